@@ -0,0 +1,178 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::ClientError;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Stops sending requests to a failing Auditor server for a while.
+///
+/// After `failure_threshold` consecutive failures the circuit opens and all requests fail fast
+/// with [`ClientError::CircuitOpen`]. Once `cooldown` has passed, a single request is let through
+/// as a probe. If it succeeds, the circuit closes again, otherwise it stays open for another
+/// `cooldown`.
+///
+/// Clones share their state.
+#[derive(Debug, Clone)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Returns an error if no request must be sent at the moment.
+    pub(crate) fn acquire(&self) -> Result<(), ClientError> {
+        let mut state = self.state.lock().unwrap();
+        match state.opened_at {
+            Some(opened_at) if opened_at.elapsed() < self.cooldown => Err(ClientError::CircuitOpen),
+            Some(_) => {
+                // Let this request through as a probe. Restarting the cool-down makes sure that
+                // concurrent requests keep failing fast until the probe has finished.
+                tracing::info!("Circuit breaker cool-down has passed, probing Auditor server");
+                state.opened_at = Some(Instant::now());
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns `true` while requests fail fast.
+    pub(crate) fn is_open(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .opened_at
+            .is_some_and(|opened_at| opened_at.elapsed() < self.cooldown)
+    }
+
+    /// Updates the state with the outcome of a request.
+    pub(crate) fn record<T>(&self, result: &Result<T, ClientError>) {
+        let mut state = self.state.lock().unwrap();
+        match result {
+            Err(e) if is_failure(e) => {
+                state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+                if state.consecutive_failures >= self.failure_threshold {
+                    if state.opened_at.is_none() {
+                        tracing::warn!(
+                            "Opening circuit breaker after {} consecutive failures",
+                            state.consecutive_failures
+                        );
+                    }
+                    state.opened_at = Some(Instant::now());
+                }
+            }
+            Err(ClientError::CircuitOpen) => {}
+            _ => {
+                if state.opened_at.is_some() {
+                    tracing::info!("Auditor server is reachable again, closing circuit breaker");
+                }
+                *state = State::default();
+            }
+        }
+    }
+}
+
+// Only errors which indicate that the server is unavailable count as failures. Client errors
+// (4xx) mean that the server is up and running.
+fn is_failure(error: &ClientError) -> bool {
+    match error {
+        ClientError::ReqwestError(e) => !e.status().is_some_and(|s| s.is_client_error()),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure() -> Result<(), ClientError> {
+        // Building a request with an invalid URL yields a `reqwest::Error` without status code.
+        Err(reqwest::Client::new()
+            .get("not a url")
+            .build()
+            .unwrap_err()
+            .into())
+    }
+
+    #[test]
+    fn opens_after_threshold_is_reached() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+
+        for _ in 0..2 {
+            assert!(breaker.acquire().is_ok());
+            breaker.record(&failure());
+        }
+        assert!(!breaker.is_open());
+
+        assert!(breaker.acquire().is_ok());
+        breaker.record(&failure());
+        assert!(breaker.is_open());
+        assert!(matches!(breaker.acquire(), Err(ClientError::CircuitOpen)));
+    }
+
+    #[test]
+    fn success_resets_consecutive_failures() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+
+        breaker.record(&failure());
+        breaker.record(&Ok(()));
+        breaker.record(&failure());
+
+        assert!(!breaker.is_open());
+        assert!(breaker.acquire().is_ok());
+    }
+
+    #[test]
+    fn non_server_errors_are_no_failures() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+
+        breaker.record::<()>(&Err(ClientError::RecordExists));
+
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn probe_after_cooldown() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+
+        breaker.record(&failure());
+        assert!(breaker.acquire().is_err());
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        // Only one probe is let through
+        assert!(breaker.acquire().is_ok());
+        assert!(breaker.acquire().is_err());
+
+        // Failing probe keeps the circuit open
+        breaker.record(&failure());
+        assert!(breaker.is_open());
+
+        std::thread::sleep(Duration::from_millis(60));
+
+        // Successful probe closes the circuit
+        assert!(breaker.acquire().is_ok());
+        breaker.record(&Ok(()));
+        assert!(!breaker.is_open());
+        assert!(breaker.acquire().is_ok());
+    }
+}
@@ -9,7 +9,8 @@ use std::fmt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use auditor::domain::{RecordAdd, RecordUpdate};
+use auditor::domain::{Component, RecordAdd, RecordUpdate, ValidMeta, ValidName};
+use chrono::{DateTime, Utc};
 
 use sqlx::{sqlite::SqliteJournalMode, QueryBuilder, Sqlite, SqlitePool};
 
@@ -20,6 +21,54 @@ fn is_path_valid(path: &Path) -> bool {
     path.to_str().is_some_and(|s| !s.is_empty()) && path.try_exists().is_ok()
 }
 
+// Records queued by older versions of the client, which could not send runtimes yet. Bincode
+// relies on the order of the fields, such that these records lack the trailing `runtime`.
+#[derive(serde::Deserialize)]
+struct LegacyRecordAdd {
+    record_id: ValidName,
+    meta: Option<ValidMeta>,
+    components: Vec<Component>,
+    start_time: DateTime<Utc>,
+    stop_time: Option<DateTime<Utc>>,
+}
+
+#[derive(serde::Deserialize)]
+struct LegacyRecordUpdate {
+    record_id: ValidName,
+    meta: Option<ValidMeta>,
+    components: Vec<Component>,
+    start_time: Option<DateTime<Utc>>,
+    stop_time: DateTime<Utc>,
+}
+
+fn decode_insert(record: &[u8]) -> RecordAdd {
+    bincode::deserialize(record).unwrap_or_else(|_| {
+        let legacy: LegacyRecordAdd = bincode::deserialize(record).unwrap();
+        RecordAdd {
+            record_id: legacy.record_id,
+            meta: legacy.meta,
+            components: legacy.components,
+            start_time: legacy.start_time,
+            stop_time: legacy.stop_time,
+            runtime: None,
+        }
+    })
+}
+
+fn decode_update(record: &[u8]) -> RecordUpdate {
+    bincode::deserialize(record).unwrap_or_else(|_| {
+        let legacy: LegacyRecordUpdate = bincode::deserialize(record).unwrap();
+        RecordUpdate {
+            record_id: legacy.record_id,
+            meta: legacy.meta,
+            components: legacy.components,
+            start_time: legacy.start_time,
+            stop_time: legacy.stop_time,
+            runtime: None,
+        }
+    })
+}
+
 /// A Wrapper around an SQLite database
 ///
 /// It manages two separate queues: one for inserts (`RecordAdd`) and one for updates
@@ -115,6 +164,22 @@ impl Database {
         Ok(())
     }
 
+    /// Delete the `count` oldest records from the "insert" queue
+    #[tracing::instrument(
+        name = "Deleting oldest records from database",
+        level = "debug",
+        skip(self)
+    )]
+    pub(crate) async fn delete_oldest_inserts(&self, count: i64) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM inserts WHERE rowid IN (SELECT rowid FROM inserts ORDER BY rowid ASC LIMIT $1)"#,
+            count
+        )
+        .execute(&self.db_pool)
+        .await?;
+        Ok(())
+    }
+
     /// Delete a single record from the "insert" queue
     #[tracing::instrument(name = "Deleting record from database", level = "debug", skip(self))]
     pub(crate) async fn delete_insert(&self, rowid: i64) -> Result<(), sqlx::Error> {
@@ -152,9 +217,7 @@ impl Database {
         .await?;
         let records = rows
             .into_iter()
-            .map(|Row { rowid, record }| {
-                (rowid, bincode::deserialize::<RecordAdd>(&record).unwrap())
-            })
+            .map(|Row { rowid, record }| (rowid, decode_insert(&record)))
             .collect();
         Ok(records)
     }
@@ -178,12 +241,7 @@ impl Database {
         .await?;
         let records = rows
             .into_iter()
-            .map(|Row { rowid, record }| {
-                (
-                    rowid,
-                    bincode::deserialize::<RecordUpdate>(&record).unwrap(),
-                )
-            })
+            .map(|Row { rowid, record }| (rowid, decode_update(&record)))
             .collect();
         Ok(records)
     }
@@ -211,16 +269,23 @@ impl Database {
         .await?;
         let records = rows
             .into_iter()
-            .map(|Row { rowid, record }| {
-                (
-                    rowid,
-                    bincode::deserialize::<RecordUpdate>(&record).unwrap(),
-                )
-            })
+            .map(|Row { rowid, record }| (rowid, decode_update(&record)))
             .collect();
         Ok(records)
     }
 
+    /// Returns the number of records in the "insert" queue
+    #[tracing::instrument(name = "Counting insert records", level = "debug", skip(self))]
+    pub(crate) async fn count_inserts(&self) -> Result<i64, sqlx::Error> {
+        struct Row {
+            count: i64,
+        }
+        let row = sqlx::query_as!(Row, r#"SELECT count(*) as count FROM inserts"#)
+            .fetch_one(&self.db_pool)
+            .await?;
+        Ok(row.count)
+    }
+
     /// Returns the highest rowid in the "update" queue, or `None` if it's empty
     #[tracing::instrument(name = "Getting highest update id", level = "debug", skip(self))]
     pub(crate) async fn get_last_update_rowid(&self) -> Result<Option<i64>, sqlx::Error> {
@@ -265,6 +330,32 @@ mod tests {
         assert_eq!(Record::from(res), Record::from(rec));
     }
 
+    #[test]
+    fn records_of_older_clients_are_decoded() {
+        #[derive(serde::Serialize)]
+        struct Legacy<'a> {
+            record_id: &'a ValidName,
+            meta: &'a Option<ValidMeta>,
+            components: &'a Vec<Component>,
+            start_time: DateTime<Utc>,
+            stop_time: Option<DateTime<Utc>>,
+        }
+
+        let rec: RecordAdd = record();
+        let legacy = bincode::serialize(&Legacy {
+            record_id: &rec.record_id,
+            meta: &rec.meta,
+            components: &rec.components,
+            start_time: rec.start_time,
+            stop_time: rec.stop_time,
+        })
+        .unwrap();
+
+        let decoded = decode_insert(&legacy);
+        assert_eq!(Record::from(decoded.clone()), Record::from(rec));
+        assert_eq!(decoded.runtime, None);
+    }
+
     #[tokio::test]
     async fn update_get() {
         let db = Database::new("sqlite://:memory:").await.unwrap();
@@ -327,6 +418,33 @@ mod tests {
             .for_each(|(a, b)| assert_eq!(Record::from(a), Record::from(b)));
     }
 
+    #[tokio::test]
+    async fn insert_many_count() {
+        let db = Database::new("sqlite://:memory:").await.unwrap();
+        let recs: Vec<RecordAdd> = (0..10).map(|_| record()).collect();
+
+        assert_eq!(db.count_inserts().await.unwrap(), 0);
+        db.insert_many(&recs).await.unwrap();
+
+        assert_eq!(db.count_inserts().await.unwrap(), 10);
+    }
+
+    #[tokio::test]
+    async fn insert_many_delete_oldest() {
+        let db = Database::new("sqlite://:memory:").await.unwrap();
+        let recs: Vec<RecordAdd> = (0..10).map(|_| record()).collect();
+
+        db.insert_many(&recs).await.unwrap();
+        db.delete_oldest_inserts(3).await.unwrap();
+        let res = db.get_inserts().await.unwrap();
+
+        assert_eq!(res.len(), 7);
+        res.into_iter()
+            .map(|(_, r)| r)
+            .zip(recs.into_iter().skip(3))
+            .for_each(|(a, b)| assert_eq!(Record::from(a), Record::from(b)));
+    }
+
     #[tokio::test]
     async fn update_delete() {
         let db = Database::new("sqlite://:memory:").await.unwrap();
@@ -354,7 +472,7 @@ mod tests {
         for r in recs.iter() {
             db.update(r).await.unwrap()
         }
-        let rowid = db.get_last_update_rowid().await.unwrap().take().unwrap();
+        let rowid = db.get_last_update_rowid().await.unwrap().unwrap();
 
         assert_eq!(rowid, 10);
     }
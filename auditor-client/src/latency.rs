@@ -0,0 +1,145 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use auditor::domain::Record;
+use chrono::Duration;
+
+/// Distribution of the ingest latency of a set of records.
+///
+/// The ingest latency of a record is the time between its `stop_time` and the time at which it
+/// was received by Auditor (`ingested_at`). It is a measure of how far collectors lag behind.
+///
+/// Percentiles are computed with the nearest-rank method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestLatency {
+    /// Number of records that were taken into account.
+    pub count: usize,
+    /// Smallest latency.
+    pub min: Duration,
+    /// Largest latency.
+    pub max: Duration,
+    /// Arithmetic mean of all latencies.
+    pub mean: Duration,
+    /// 50th percentile (median).
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 95th percentile.
+    pub p95: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+}
+
+impl IngestLatency {
+    /// Computes the ingest latency distribution of `records`.
+    ///
+    /// Records without `stop_time` or `ingested_at` are excluded.
+    /// Returns `None` if no record is left after that.
+    pub fn from_records(records: &[Record]) -> Option<Self> {
+        let mut latencies: Vec<Duration> = records
+            .iter()
+            .filter_map(|r| match (r.stop_time, r.ingested_at) {
+                (Some(stop), Some(ingested)) => Some(ingested - stop),
+                _ => None,
+            })
+            .collect();
+
+        if latencies.is_empty() {
+            return None;
+        }
+
+        latencies.sort();
+
+        let count = latencies.len();
+        let total_ms: i64 = latencies.iter().map(Duration::num_milliseconds).sum();
+
+        Some(IngestLatency {
+            count,
+            min: latencies[0],
+            max: latencies[count - 1],
+            mean: Duration::milliseconds(total_ms / count as i64),
+            p50: percentile(&latencies, 50),
+            p90: percentile(&latencies, 90),
+            p95: percentile(&latencies, 95),
+            p99: percentile(&latencies, 99),
+        })
+    }
+}
+
+// `sorted` must be sorted in ascending order and must not be empty.
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn record(id: usize, stop_offset: Option<i64>, latency: Option<i64>) -> Record {
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let stop = stop_offset.map(|o| start + Duration::seconds(o));
+        Record {
+            record_id: format!("record-{id}"),
+            meta: None,
+            components: None,
+            start_time: Some(start),
+            stop_time: stop,
+            runtime: stop_offset,
+            ingested_at: match (stop, latency) {
+                (Some(stop), Some(latency)) => Some(stop + Duration::seconds(latency)),
+                (None, Some(latency)) => Some(start + Duration::seconds(latency)),
+                _ => None,
+            },
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn distribution_of_known_records() {
+        // latencies 1..=100 seconds, in reverse order
+        let records: Vec<Record> = (1..=100)
+            .rev()
+            .map(|i| record(i as usize, Some(3600), Some(i)))
+            .collect();
+
+        let latency = IngestLatency::from_records(&records).unwrap();
+
+        assert_eq!(latency.count, 100);
+        assert_eq!(latency.min, Duration::seconds(1));
+        assert_eq!(latency.max, Duration::seconds(100));
+        assert_eq!(latency.mean, Duration::milliseconds(50_500));
+        assert_eq!(latency.p50, Duration::seconds(50));
+        assert_eq!(latency.p90, Duration::seconds(90));
+        assert_eq!(latency.p95, Duration::seconds(95));
+        assert_eq!(latency.p99, Duration::seconds(99));
+    }
+
+    #[test]
+    fn records_without_stop_time_are_excluded() {
+        let records = vec![
+            record(0, Some(10), Some(5)),
+            record(1, None, Some(1000)),
+            record(2, Some(10), None),
+        ];
+
+        let latency = IngestLatency::from_records(&records).unwrap();
+
+        assert_eq!(latency.count, 1);
+        assert_eq!(latency.min, Duration::seconds(5));
+        assert_eq!(latency.max, Duration::seconds(5));
+        assert_eq!(latency.p99, Duration::seconds(5));
+    }
+
+    #[test]
+    fn no_usable_records_returns_none() {
+        let records = vec![record(0, None, Some(10))];
+        assert!(IngestLatency::from_records(&records).is_none());
+        assert!(IngestLatency::from_records(&[]).is_none());
+    }
+}
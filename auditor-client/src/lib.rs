@@ -57,6 +57,10 @@
 //! # }
 //! ```
 //!
+//! Collectors written as shell scripts can instead export the attributes of a record as
+//! environment variables, which are read with
+//! [`RecordAdd::from_env`](auditor::domain::RecordAdd::from_env).
+//!
 //! ## Connecting to Auditor
 //!
 //! The [`AuditorClientBuilder`] is used to build an [`AuditorClient`] object
@@ -245,16 +249,27 @@
 //! - stop_time
 //! - runtime
 //! - record_id
+//! - ingested_at
+//!
+//! Records can also be sorted by the amount of a component with
+//! `SortBy::by_component("CPU", SortDirection::Descending)`. Records without this component are
+//! returned last.
 //!
 //!| Field        | Description                                                            | Operators                              | Examples (query representation)            |
 //!|--------------|------------------------------------------------------------------------|----------------------------------------|--------------------------------------------|
 //!| `record_id`  | Retrieve the exact record using `record_id`                            |                                        | `record_id-<record_id>`                    |
+//!| `record_id`  | Retrieve all records whose `record_id` starts with a prefix            | `prefix`                               | `record_id[prefix]=<prefix>`               |
 //!| `start_time` | Start time of the event (`DateTime<Utc>`)                              | `gt`, `gte`, `lt`, `lte`               | `start_time[gt]=<timestamp>`               |
 //!| `stop_time`  | Stop time of the event (`DateTime<Utc>`)                               | `gt`, `gte`, `lt`, `lte`               | `stop_time[gt]=<timestamp>`                |
+//!| `ingested_at`| Time at which Auditor received the record (`DateTime<Utc>`)            | `gt`, `gte`, `lt`, `lte`               | `ingested_at[gt]=<timestamp>`              |
+//!| `updated_since`| Records added or updated at or after a time (`DateTime<Utc>`)        |                                        | `updated_since=<timestamp>`                |
+//!| `stopped`    | Only finished (`true`) or unfinished (`false`) records (`bool`)        |                                        | `stopped=true`                             |
 //!| `runtime`    | Runtime of the event (in seconds)                                      | `gt`, `gte`, `lt`, `lte`               | `runtime[gt]=<u64>`                        |
 //!| `meta`       | Meta information (<meta_key>, MetaOperator(<meta_value>))              | `c`, `dnc`                             | `meta[<meta_key>][c]=<meta_value>`         |
 //!| `component`  | Component identifier (<component_name>, Operator(<component_amount>))  | `gt`, `gte`, `lt`, `lte`, `equals`     | `component[<component_name>][gt]=<amount>` |
+//!| `component`  | Score of a component (<component_name>, <score_name>, Operator(<value>)) | `gt`, `gte`, `lt`, `lte`, `equals`   | `component[<component_name>][score][<score_name>][gt]=<value>` |
 //!| `sort_by`    | Sort query results (SortBy(<column_name>))                             | `asc`, `desc`                          | `sort_by[desc]=<column_name>`              |
+//!| `sort_by`    | Sort query results by a component amount (missing components last)     | `asc`, `desc`                          | `sort_by[desc][component]=<name>`          |
 //!| `limit`      | limit query records (number)                                           |                                        | `limit=5000`                               |
 //!
 //! Meta field can be used to query records by specifying the meta key and [`MetaOperator`]  must be used
@@ -338,6 +353,29 @@
 //! GET records?start_time[lte]=datetime_utc_lte&start_time[gte]=datetime_utc_gte
 //! ```
 //!
+//! The same query can be written more concisely with [`QueryBuilder::started_between`].
+//! [`QueryBuilder::started_after`], [`QueryBuilder::started_before`] and
+//! [`QueryBuilder::stopped_between`] are available for similar queries.
+//!
+//! ```no_run
+//! # use auditor_client::{QueryBuilder, AuditorClientBuilder, ClientError};
+//! # use chrono::{Utc, TimeZone};
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), ClientError> {
+//! # let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+//! # let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+//! # let client = AuditorClientBuilder::new()
+//! #     .address(&"localhost", 8000)
+//! #     .timeout(20)
+//! #     .build()?;
+//! let records = QueryBuilder::new()
+//!     .started_between(datetime_utc_gte, datetime_utc_lte)
+//!     .get(client)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ### Example 3:
 //!
 //! Constructs a QueryBuilder with start time, stop time, and runtime operators,
@@ -459,6 +497,28 @@
 //! GET records?component[CPU][equals]=count
 //! ```
 //!
+//! Conditions on the scores attached to a component are nested under `score`. The following
+//! query returns all records with a CPU component whose HEPSPEC06 score is greater than 10.
+//!
+//! ```
+//! use auditor_client::{QueryBuilder, Operator, ComponentQuery};
+//!
+//! let query_string = QueryBuilder::new()
+//!     .with_component_query(ComponentQuery::new().score_operator(
+//!         "CPU".to_string(),
+//!         "HEPSPEC06".to_string(),
+//!         Operator::default().gt(10.0.into()),
+//!     ))
+//!     .build()?;
+//! # Ok::<(), auditor_client::ClientError>(())
+//! ```
+//!
+//! The query string would look like
+//!
+//! ```text
+//! GET records?component[CPU][score][HEPSPEC06][gt]=10
+//! ```
+//!
 //!//! ### Example 6:
 //!
 //! Constructs a QueryBuilder which sorts the record in descending order by stop_time and limits the query results by 500 records
@@ -543,8 +603,10 @@
 
 mod constants;
 use auditor::{
-    constants::ERR_RECORD_EXISTS,
-    domain::{Record, RecordAdd, RecordUpdate},
+    constants::{
+        ErrorCode, ERROR_CODE_HEADER, ERR_RECORD_EXISTS, NDJSON_CONTENT_TYPE, SERVER_LOAD_HEADER,
+    },
+    domain::{with_interned_names, Record, RecordAdd, RecordUpdate, RecordValidationError},
 };
 use constants::ERR_INVALID_TIME_INTERVAL;
 
@@ -552,19 +614,45 @@ use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use chrono::{DateTime, Duration, Utc};
+use futures_util::{Stream, StreamExt};
 use serde::Serialize;
-use std::collections::HashMap;
-use tokio::sync::oneshot;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::{broadcast, oneshot};
 use urlencoding::encode;
 
 mod database;
 use database::Database;
 
+mod latency;
+pub use latency::IngestLatency;
+
+pub use auditor::constants::{FORBIDDEN_CHARACTERS, MAX_NAME_LENGTH};
+pub use auditor::domain::{ComponentStatus, RecordsEnvelope, ServerStatus};
+
+mod circuit_breaker;
+use circuit_breaker::CircuitBreaker;
+
+mod record_iter;
+use record_iter::RecordIter;
+
+mod response_cache;
+use response_cache::ResponseCache;
+
+mod verify;
+pub use verify::{FieldDifference, VerifyError};
+
+mod ndjson;
+
 use reqwest::{Certificate, Identity};
 use std::fs;
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
+#[derive(serde::Deserialize)]
+struct CountResponse {
+    count: u64,
+}
+
 #[derive(Debug, thiserror::Error)]
 #[non_exhaustive]
 pub enum ClientError {
@@ -572,6 +660,14 @@ pub enum ClientError {
     InvalidTimeInterval,
     ReqwestError(reqwest::Error),
     DatabaseError(sqlx::Error),
+    TlsError(String),
+    CircuitOpen,
+    QueueFull,
+    InvalidHeader(String),
+    UnexpectedRedirect(String),
+    DeserializationError(String),
+    InvalidRecords(Vec<RecordValidationError>),
+    QuerySerialization(String),
     Other(String),
 }
 
@@ -585,15 +681,89 @@ impl std::fmt::Display for ClientError {
                 ClientError::InvalidTimeInterval => ERR_INVALID_TIME_INTERVAL.to_string(),
                 ClientError::ReqwestError(e) => format!("Reqwest Error: {e}"),
                 ClientError::DatabaseError(e) => format!("Database Error: {e}"),
+                ClientError::TlsError(s) => format!("TLS Error: {s}"),
+                ClientError::CircuitOpen => {
+                    "Circuit breaker is open, not sending request to Auditor server".to_string()
+                }
+                ClientError::QueueFull => {
+                    "Send queue is full, not queueing records for Auditor server".to_string()
+                }
+                ClientError::InvalidHeader(s) => format!("Invalid header: {s}"),
+                ClientError::UnexpectedRedirect(s) => format!("Unexpected redirect: {s}"),
+                ClientError::DeserializationError(s) => format!("Deserialization Error: {s}"),
+                ClientError::InvalidRecords(errors) => format!(
+                    "Invalid records: {}",
+                    errors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+                ClientError::QuerySerialization(s) => format!("Query serialization error: {s}"),
                 ClientError::Other(s) => format!("Other client error: {s}"),
             }
         )
     }
 }
 
+// Deserializes a list of records, sharing the memory of equal component and score names.
+fn records_from_slice(body: &[u8]) -> Result<Vec<Record>, ClientError> {
+    with_interned_names(|| serde_json::from_slice(body))
+        .map_err(|e| ClientError::DeserializationError(format!("Invalid records: {e}")))
+}
+
+// Extracts the errors of all invalid records from the response to a rejected `bulk_insert`.
+// Batches which are only rejected because of mismatching runtimes are answered with a `422`.
+fn invalid_records(status: reqwest::StatusCode, body: &str) -> Option<Vec<RecordValidationError>> {
+    if status == reqwest::StatusCode::BAD_REQUEST
+        || status == reqwest::StatusCode::UNPROCESSABLE_ENTITY
+    {
+        serde_json::from_str(body).ok()
+    } else {
+        None
+    }
+}
+
+// Reads the error code of an error response.
+fn error_code(headers: &reqwest::header::HeaderMap) -> Option<ErrorCode> {
+    ErrorCode::from_code(headers.get(ERROR_CODE_HEADER)?.to_str().ok()?)
+}
+
+// Maps the response to `add` or `bulk_insert` to an error. Relies on the error code and only
+// falls back to the body for servers which don't send one. Other server errors (5xx) are
+// returned as `status_error`, such that they count as failures of the circuit breaker and the
+// `QueuedAuditorClient` sends the records again later.
+fn insert_error(
+    status: reqwest::StatusCode,
+    code: Option<ErrorCode>,
+    body: &str,
+    status_error: Option<reqwest::Error>,
+) -> Option<ClientError> {
+    match code {
+        Some(ErrorCode::RecordExists) => Some(ClientError::RecordExists),
+        None if body == ERR_RECORD_EXISTS => Some(ClientError::RecordExists),
+        Some(ErrorCode::InvalidRecords | ErrorCode::ValidationFailed) | None => {
+            invalid_records(status, body).map(ClientError::InvalidRecords)
+        }
+        Some(_) => None,
+    }
+    .or_else(|| {
+        status_error
+            .filter(|_| status.is_server_error())
+            .map(ClientError::from)
+    })
+}
+
 impl From<reqwest::Error> for ClientError {
     fn from(error: reqwest::Error) -> Self {
-        ClientError::ReqwestError(error)
+        if error.is_redirect() {
+            // The source contains the reason given by the redirect policy
+            let reason = std::error::Error::source(&error)
+                .map_or_else(|| error.to_string(), ToString::to_string);
+            ClientError::UnexpectedRedirect(reason)
+        } else {
+            ClientError::ReqwestError(error)
+        }
     }
 }
 
@@ -651,8 +821,19 @@ pub struct AuditorClientBuilder {
     address: String,
     database_path: PathBuf,
     timeout: Duration,
+    get_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
     send_interval: Duration,
+    max_send_interval: Option<Duration>,
     tls_config: Option<TlsConfig>,
+    circuit_breaker: Option<(u32, Duration)>,
+    cache_ttl: Option<Duration>,
+    bulk_insert_sort_key: Option<BulkInsertSortKey>,
+    max_queue_size: Option<usize>,
+    queue_full_policy: QueueFullPolicy,
+    default_headers: HashMap<String, String>,
+    follow_cross_host_redirects: bool,
+    danger_accept_invalid_certs: bool,
 }
 
 impl AuditorClientBuilder {
@@ -662,8 +843,19 @@ impl AuditorClientBuilder {
             address: "http://127.0.0.1:8080".into(),
             database_path: PathBuf::from("sqlite::memory:"),
             timeout: Duration::try_seconds(30).expect("This should never fail"),
+            get_timeout: None,
+            write_timeout: None,
             send_interval: Duration::try_seconds(60).expect("This should never fail"),
+            max_send_interval: None,
             tls_config: None,
+            circuit_breaker: None,
+            cache_ttl: None,
+            bulk_insert_sort_key: None,
+            max_queue_size: None,
+            queue_full_policy: QueueFullPolicy::ReturnError,
+            default_headers: HashMap::new(),
+            follow_cross_host_redirects: false,
+            danger_accept_invalid_certs: false,
         }
     }
 
@@ -702,6 +894,36 @@ impl AuditorClientBuilder {
         self
     }
 
+    /// Set a timeout in seconds for HTTP requests retrieving records, overriding the timeout set
+    /// with [`timeout`](AuditorClientBuilder::timeout).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Timeout in seconds.
+    #[must_use]
+    pub fn with_get_timeout(mut self, timeout: i64) -> Self {
+        self.get_timeout = Some(
+            Duration::try_seconds(timeout)
+                .unwrap_or_else(|| panic!("Could not convert {} to duration", timeout)),
+        );
+        self
+    }
+
+    /// Set a timeout in seconds for HTTP requests adding or updating records, overriding the
+    /// timeout set with [`timeout`](AuditorClientBuilder::timeout).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - Timeout in seconds.
+    #[must_use]
+    pub fn with_write_timeout(mut self, timeout: i64) -> Self {
+        self.write_timeout = Some(
+            Duration::try_seconds(timeout)
+                .unwrap_or_else(|| panic!("Could not convert {} to duration", timeout)),
+        );
+        self
+    }
+
     /// Set an interval in seconds for periodic updates to AUDITOR.
     /// This setting is only relevant to the `QueuedAuditorClient`.
     ///
@@ -714,6 +936,26 @@ impl AuditorClientBuilder {
         self
     }
 
+    /// Set the maximum interval in seconds for periodic updates to AUDITOR.
+    ///
+    /// After each consecutive failure to send the queued records, the interval until the next
+    /// attempt is doubled up to `max_interval`. It is reset to the interval set with
+    /// [`send_interval`](AuditorClientBuilder::send_interval) after the records were sent
+    /// successfully. By default, the interval does not grow.
+    /// This setting is only relevant to the `QueuedAuditorClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_interval` - Maximum interval in seconds.
+    #[must_use]
+    pub fn max_send_interval(mut self, max_interval: i64) -> Self {
+        self.max_send_interval = Some(
+            Duration::try_seconds(max_interval)
+                .unwrap_or_else(|| panic!("Could not convert {} to duration", max_interval)),
+        );
+        self
+    }
+
     /// Set the file path for the persistent storage sqlite db.
     /// This setting is only relevant to the `QueuedAuditorClient`.
     ///
@@ -725,68 +967,264 @@ impl AuditorClientBuilder {
         self
     }
 
+    /// Use TLS with client authentication for the connection to the Auditor server.
+    ///
+    /// The certificates and the key are only read when the client is built. Errors while reading
+    /// or parsing them are returned as [`ClientError::TlsError`] by the `build*` methods.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_cert_path` - Path to the PEM encoded client certificate.
+    /// * `client_key_path` - Path to the PEM encoded client key.
+    /// * `ca_cert_path` - Path to the PEM encoded certificate of the CA.
+    #[must_use]
     pub fn with_tls<P: AsRef<Path>>(
         mut self,
         client_cert_path: P,
         client_key_path: P,
         ca_cert_path: P,
     ) -> Self {
-        let mut tls_config = TlsConfig::new();
+        self.tls_config = Some(TlsConfig {
+            client_cert_path: client_cert_path.as_ref().to_path_buf(),
+            client_key_path: client_key_path.as_ref().to_path_buf(),
+            ca_cert_path: ca_cert_path.as_ref().to_path_buf(),
+        });
+        self
+    }
 
-        match (fs::read(client_cert_path), fs::read(client_key_path)) {
-            (Ok(client_cert), Ok(client_key)) => {
-                match Identity::from_pem(&[client_cert, client_key].concat()) {
-                    Ok(identity) => tls_config.identity = Some(identity),
-                    Err(e) => {
-                        eprintln!("Failed to create identity from client cert and key: {}", e)
-                    }
-                }
-            }
-            (Err(e), _) | (_, Err(e)) => {
-                eprintln!("Failed to read client certificate or key: {}", e);
-            }
-        }
+    /// Enable a circuit breaker, which stops sending requests to a failing Auditor server.
+    ///
+    /// After `failure_threshold` consecutive failed requests, all requests immediately fail with
+    /// [`ClientError::CircuitOpen`] for `cooldown` seconds. Afterwards, a single request is sent
+    /// to probe the server. If it succeeds, normal operation resumes, otherwise requests keep
+    /// failing fast for another `cooldown` seconds.
+    /// Only connection errors, timeouts and server errors (5xx) count as failures.
+    ///
+    /// The `QueuedAuditorClient` pauses sending the queued records while the circuit is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `failure_threshold` - Number of consecutive failures after which the circuit opens.
+    /// * `cooldown` - Cool-down in seconds.
+    #[must_use]
+    pub fn circuit_breaker(mut self, failure_threshold: u32, cooldown: i64) -> Self {
+        self.circuit_breaker = Some((
+            failure_threshold,
+            Duration::try_seconds(cooldown)
+                .unwrap_or_else(|| panic!("Could not convert {} to duration", cooldown)),
+        ));
+        self
+    }
 
-        match fs::read(ca_cert_path) {
-            Ok(ca_cert) => match Certificate::from_pem(&ca_cert) {
-                Ok(ca_certificate) => tls_config.ca_certificate = Some(ca_certificate),
-                Err(e) => eprintln!("Failed to parse CA certificate PEM: {}", e),
-            },
-            Err(e) => eprintln!("Failed to read CA certificate file: {}", e),
-        }
+    /// Cache the records returned by `get` and `advanced_query` for `ttl`.
+    ///
+    /// Repeating a query with the same query string within `ttl` returns the cached records
+    /// without sending a request. The cache is cleared whenever the client adds, updates or
+    /// patches records. Changes made by other clients are only seen once the cached records
+    /// have expired. By default, nothing is cached.
+    ///
+    /// This setting is not used by the `AuditorClientBlocking`.
+    ///
+    /// # Arguments
+    ///
+    /// * `ttl` - Time for which the records of a query are cached.
+    #[must_use]
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Sort the records of a `bulk_insert` batch by `key` before sending them.
+    ///
+    /// Inserting records in order instead of at random positions of the database index improves
+    /// the insert performance on large tables.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - Key by which the records are sorted.
+    #[must_use]
+    pub fn bulk_insert_sort_key(mut self, key: BulkInsertSortKey) -> Self {
+        self.bulk_insert_sort_key = Some(key);
+        self
+    }
+
+    /// Limit the number of records waiting in the send queue to `max_queue_size`.
+    ///
+    /// By default, the queue grows without limit while the Auditor server is unreachable. When a
+    /// record is added to a full queue, the policy set with
+    /// [`queue_full_policy`](AuditorClientBuilder::queue_full_policy) is applied.
+    /// Only record inserts count towards the limit, record updates are always queued.
+    ///
+    /// This setting is only relevant to the `QueuedAuditorClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_queue_size` - Maximum number of queued records.
+    #[must_use]
+    pub fn max_queue_size(mut self, max_queue_size: usize) -> Self {
+        self.max_queue_size = Some(max_queue_size);
+        self
+    }
+
+    /// Set the policy which is applied when records are added to a full send queue.
+    ///
+    /// Defaults to [`QueueFullPolicy::ReturnError`]. Has no effect unless a limit is set with
+    /// [`max_queue_size`](AuditorClientBuilder::max_queue_size).
+    ///
+    /// This setting is only relevant to the `QueuedAuditorClient`.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - Policy for a full send queue.
+    #[must_use]
+    pub fn queue_full_policy(mut self, policy: QueueFullPolicy) -> Self {
+        self.queue_full_policy = policy;
+        self
+    }
+
+    /// Add a header which is sent with every request, e.g. an API key required by a gateway in
+    /// front of the Auditor server. Calling this method again with the same name replaces the
+    /// value.
+    ///
+    /// Invalid header names or values are reported when building the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the header.
+    /// * `value` - Value of the header.
+    #[must_use]
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        self.default_headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Authenticate with a bearer token, which is sent in the `Authorization` header of every
+    /// request.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - Bearer token.
+    #[must_use]
+    pub fn bearer_token(self, token: &str) -> Self {
+        self.default_header(
+            reqwest::header::AUTHORIZATION.as_str(),
+            &format!("Bearer {token}"),
+        )
+    }
+
+    /// Returns the default headers of the built client.
+    fn header_map(&self) -> Result<reqwest::header::HeaderMap, ClientError> {
+        self.default_headers
+            .iter()
+            .map(|(name, value)| {
+                let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                    .map_err(|e| ClientError::InvalidHeader(format!("{name:?}: {e}")))?;
+                let mut value = reqwest::header::HeaderValue::from_str(value)
+                    .map_err(|e| ClientError::InvalidHeader(format!("Value of {name}: {e}")))?;
+                // Keeps the token out of debug output
+                value.set_sensitive(name == reqwest::header::AUTHORIZATION);
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    /// Follow redirects to other hosts.
+    ///
+    /// By default, only redirects to the same host are followed. Redirects to other hosts are
+    /// usually caused by a misconfigured reverse proxy and fail with
+    /// [`ClientError::UnexpectedRedirect`].
+    #[must_use]
+    pub fn follow_cross_host_redirects(mut self, follow: bool) -> Self {
+        self.follow_cross_host_redirects = follow;
+        self
+    }
 
-        self.tls_config = Some(tls_config);
+    /// Disable the verification of the TLS certificate of the Auditor server.
+    ///
+    /// # Warning
+    ///
+    /// **This is insecure and must not be used in production!** Any certificate is accepted,
+    /// including self-signed, expired and those issued for other hosts, which makes the
+    /// connection vulnerable to man-in-the-middle attacks. It is only meant for development and
+    /// testing against an Auditor instance with a self-signed certificate.
+    ///
+    /// This setting is ignored if a CA certificate is configured with
+    /// [`with_tls`](AuditorClientBuilder::with_tls), in which case the certificate of the server
+    /// is always verified.
+    #[must_use]
+    pub fn danger_accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.danger_accept_invalid_certs = accept_invalid_certs;
         self
     }
 
+    /// Returns whether invalid certificates are accepted by the built client.
+    fn accept_invalid_certs(&self) -> bool {
+        if !self.danger_accept_invalid_certs {
+            return false;
+        }
+        if self.tls_config.is_some() {
+            tracing::warn!(
+                "Ignoring `danger_accept_invalid_certs`, certificates are verified with the configured CA"
+            );
+            return false;
+        }
+        tracing::warn!(
+            "TLS certificate verification is disabled. This is insecure and must not be used in production!"
+        );
+        true
+    }
+
     /// Build an [`AuditorClient`] from `AuditorClientBuilder`.
     ///
     /// # Errors
     ///
-    /// * [`ClientError::InvalidTimeInterval`] - If the timeout duration is less than zero.
+    /// * [`ClientError::InvalidTimeInterval`] - If the timeout or circuit breaker cool-down duration is less than zero.
     /// * [`ClientError::ReqwestError`] - If there was an error building the HTTP client.
+    /// * [`ClientError::TlsError`] - If the TLS certificates or key could not be read or parsed.
+    /// * [`ClientError::InvalidHeader`] - If the name or value of a default header is invalid.
     pub fn build(self) -> Result<AuditorClient, ClientError> {
+        let accept_invalid_certs = self.accept_invalid_certs();
+        let headers = self.header_map()?;
         let client = match self.tls_config {
-            Some(tls_config) => reqwest::ClientBuilder::new()
-                .identity(tls_config.identity.expect(
-                    "Error while setting up the client identity using client cert and key pem",
-                ))
-                .add_root_certificate(
-                    tls_config
-                        .ca_certificate
-                        .expect("Error while setting up the root certificate"),
-                )
-                .timeout(self.timeout.to_std()?)
-                .build()?,
+            Some(tls_config) => {
+                let (identity, ca_certificate) = tls_config.load()?;
+                reqwest::ClientBuilder::new()
+                    .default_headers(headers)
+                    .identity(identity)
+                    .add_root_certificate(ca_certificate)
+                    .timeout(self.timeout.to_std()?)
+                    .redirect(redirect_policy(self.follow_cross_host_redirects))
+                    .build()?
+            }
             None => reqwest::ClientBuilder::new()
                 .user_agent(APP_USER_AGENT)
+                .default_headers(headers)
+                .danger_accept_invalid_certs(accept_invalid_certs)
                 .timeout(self.timeout.to_std()?)
+                .redirect(redirect_policy(self.follow_cross_host_redirects))
                 .build()?,
         };
 
+        let circuit_breaker = match self.circuit_breaker {
+            Some((failure_threshold, cooldown)) => {
+                Some(CircuitBreaker::new(failure_threshold, cooldown.to_std()?))
+            }
+            None => None,
+        };
+
         Ok(AuditorClient {
             address: self.address,
             client,
+            get_timeout: self.get_timeout.map(|t| t.to_std()).transpose()?,
+            write_timeout: self.write_timeout.map(|t| t.to_std()).transpose()?,
+            circuit_breaker,
+            response_cache: self
+                .cache_ttl
+                .map(|ttl| ttl.to_std())
+                .transpose()?
+                .map(ResponseCache::new),
+            bulk_insert_sort_key: self.bulk_insert_sort_key,
         })
     }
 
@@ -794,12 +1232,21 @@ impl AuditorClientBuilder {
     ///
     /// # Errors
     ///
-    /// * [`ClientError::InvalidTimeInterval`] - If the timeout duration or send interval is less than zero.
+    /// * [`ClientError::InvalidTimeInterval`] - If the timeout duration, circuit breaker cool-down or send interval is less than zero.
     /// * [`ClientError::ReqwestError`] - If there was an error building the HTTP client.
     /// * [`ClientError::DatabaseError`] - If there was an error while opening or creating the
-    ///     database
+    ///   database
+    /// * [`ClientError::TlsError`] - If the TLS certificates or key could not be read or parsed.
+    /// * [`ClientError::InvalidHeader`] - If the name or value of a default header is invalid.
     pub async fn build_queued(self) -> Result<QueuedAuditorClient, ClientError> {
         let interval = self.send_interval;
+        let max_interval = self
+            .max_send_interval
+            .map_or(interval, |max_interval| max_interval.max(interval));
+        let queue_limit = self.max_queue_size.map(|max_size| QueueLimit {
+            max_size,
+            policy: self.queue_full_policy,
+        });
         let client = QueuedAuditorClient::new(
             Database::new(
                 self.database_path
@@ -811,7 +1258,8 @@ impl AuditorClientBuilder {
             )
             .await?,
             self.build()?,
-            interval.to_std()?,
+            SendBackoff::new(interval.to_std()?, max_interval.to_std()?),
+            queue_limit,
         );
         Ok(client)
     }
@@ -820,53 +1268,160 @@ impl AuditorClientBuilder {
     ///
     /// # Errors
     ///
-    /// * [`ClientError::InvalidTimeInterval`] - If the timeout duration is less than zero.
+    /// * [`ClientError::InvalidTimeInterval`] - If the timeout or circuit breaker cool-down duration is less than zero.
     /// * [`ClientError::ReqwestError`] - If there was an error building the HTTP client.
+    /// * [`ClientError::TlsError`] - If the TLS certificates or key could not be read or parsed.
+    /// * [`ClientError::InvalidHeader`] - If the name or value of a default header is invalid.
     ///
     /// # Panics
     ///
     /// This method panics if it is called from an async runtime.
     pub fn build_blocking(self) -> Result<AuditorClientBlocking, ClientError> {
+        let accept_invalid_certs = self.accept_invalid_certs();
+        let headers = self.header_map()?;
         let client = match self.tls_config {
-            Some(tls_config) => reqwest::blocking::ClientBuilder::new()
-                .identity(tls_config.identity.expect(
-                    "Error while setting up the client identity using client cert and key pem",
-                ))
-                .add_root_certificate(
-                    tls_config
-                        .ca_certificate
-                        .expect("Error while setting up the root certificate"),
-                )
-                .timeout(self.timeout.to_std()?)
-                .build()?,
+            Some(tls_config) => {
+                let (identity, ca_certificate) = tls_config.load()?;
+                reqwest::blocking::ClientBuilder::new()
+                    .default_headers(headers)
+                    .identity(identity)
+                    .add_root_certificate(ca_certificate)
+                    .timeout(self.timeout.to_std()?)
+                    .redirect(redirect_policy(self.follow_cross_host_redirects))
+                    .build()?
+            }
             None => reqwest::blocking::ClientBuilder::new()
                 .user_agent(APP_USER_AGENT)
+                .default_headers(headers)
+                .danger_accept_invalid_certs(accept_invalid_certs)
                 .timeout(self.timeout.to_std()?)
+                .redirect(redirect_policy(self.follow_cross_host_redirects))
                 .build()?,
         };
 
+        let circuit_breaker = match self.circuit_breaker {
+            Some((failure_threshold, cooldown)) => {
+                Some(CircuitBreaker::new(failure_threshold, cooldown.to_std()?))
+            }
+            None => None,
+        };
+
         Ok(AuditorClientBlocking {
             address: self.address,
             client,
+            get_timeout: self.get_timeout.map(|t| t.to_std()).transpose()?,
+            write_timeout: self.write_timeout.map(|t| t.to_std()).transpose()?,
+            circuit_breaker,
+            bulk_insert_sort_key: self.bulk_insert_sort_key,
         })
     }
 }
 
 #[derive(Debug, Clone)]
 struct TlsConfig {
-    identity: Option<Identity>,
-    ca_certificate: Option<Certificate>,
+    client_cert_path: PathBuf,
+    client_key_path: PathBuf,
+    ca_cert_path: PathBuf,
 }
 
 impl TlsConfig {
-    fn new() -> Self {
-        TlsConfig {
-            identity: None,
-            ca_certificate: None,
+    /// Reads and parses the client identity and the CA certificate.
+    fn load(&self) -> Result<(Identity, Certificate), ClientError> {
+        let client_cert = fs::read(&self.client_cert_path).map_err(|e| {
+            ClientError::TlsError(format!(
+                "Failed to read client certificate {:?}: {e}",
+                self.client_cert_path
+            ))
+        })?;
+        let client_key = fs::read(&self.client_key_path).map_err(|e| {
+            ClientError::TlsError(format!(
+                "Failed to read client key {:?}: {e}",
+                self.client_key_path
+            ))
+        })?;
+        let identity = Identity::from_pem(&[client_cert, client_key].concat()).map_err(|e| {
+            ClientError::TlsError(format!(
+                "Failed to create identity from client cert and key: {e}"
+            ))
+        })?;
+
+        let ca_cert = fs::read(&self.ca_cert_path).map_err(|e| {
+            ClientError::TlsError(format!(
+                "Failed to read CA certificate {:?}: {e}",
+                self.ca_cert_path
+            ))
+        })?;
+        let ca_certificate = Certificate::from_pem(&ca_cert).map_err(|e| {
+            ClientError::TlsError(format!("Failed to parse CA certificate PEM: {e}"))
+        })?;
+
+        Ok((identity, ca_certificate))
+    }
+}
+
+/// Key by which the records of a `bulk_insert` batch are sorted before they are sent.
+///
+/// See [`AuditorClientBuilder::bulk_insert_sort_key`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkInsertSortKey {
+    /// Sort by `record_id`.
+    RecordId,
+    /// Sort by `start_time`.
+    StartTime,
+}
+
+impl BulkInsertSortKey {
+    /// Returns the records sorted by this key. Records with equal keys keep their order.
+    fn sort<'a>(&self, records: &'a [RecordAdd]) -> Vec<&'a RecordAdd> {
+        let mut sorted: Vec<&RecordAdd> = records.iter().collect();
+        match self {
+            BulkInsertSortKey::RecordId => sorted.sort_by(|a, b| a.record_id.cmp(&b.record_id)),
+            BulkInsertSortKey::StartTime => sorted.sort_by_key(|r| r.start_time),
         }
+        sorted
     }
 }
 
+/// Policy which is applied when records are added to a full send queue of the
+/// [`QueuedAuditorClient`].
+///
+/// See [`AuditorClientBuilder::max_queue_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueFullPolicy {
+    /// Drop the oldest queued records to make room for the new ones.
+    DropOldest,
+    /// Drop the new records which don't fit into the queue anymore.
+    DropNewest,
+    /// Don't queue any of the new records and return [`ClientError::QueueFull`].
+    ReturnError,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct QueueLimit {
+    max_size: usize,
+    policy: QueueFullPolicy,
+}
+
+// Same limit as reqwest's default policy.
+const MAX_REDIRECTS: usize = 10;
+
+fn redirect_policy(follow_cross_host_redirects: bool) -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(move |attempt| {
+        let same_host = attempt.previous().last().is_some_and(|previous| {
+            previous.host_str() == attempt.url().host_str()
+                && previous.port_or_known_default() == attempt.url().port_or_known_default()
+        });
+        if attempt.previous().len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if same_host || follow_cross_host_redirects {
+            attempt.follow()
+        } else {
+            let error = format!("redirect to {} was not followed", attempt.url());
+            attempt.error(error)
+        }
+    })
+}
+
 impl Default for AuditorClientBuilder {
     fn default() -> Self {
         Self::new()
@@ -894,12 +1449,24 @@ impl Serialize for DateTimeUtcWrapper {
 pub struct QueryParameters {
     /// Specifies the record id to query the exact record from the database
     pub record_id: Option<String>,
+    /// Specifies the prefix of the record ids to query. Serialized as `record_id[prefix]`.
+    #[serde(skip)]
+    pub record_id_prefix: Option<String>,
     /// Specifies the start time for querying records. It uses the `Operator` enum to
     /// define time-based operations.
     pub start_time: Option<Operator>,
     /// Specifies the stop time for querying records. It uses the `Operator` enum to
     /// define time-based operations.
     pub stop_time: Option<Operator>,
+    /// Specifies the time at which the records were ingested by Auditor. It uses the `Operator`
+    /// enum to define time-based operations.
+    pub ingested_at: Option<Operator>,
+    /// Only queries records which were added or updated at or after this time. Serialized as
+    /// `updated_since=<rfc3339>`.
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only queries finished (`true`) or unfinished (`false`) records, i.e. records with or
+    /// without a stop time. Serialized as `stopped=<bool>`.
+    pub stopped: Option<bool>,
     /// Specifies the runtime for querying records. It uses the `Operator` enum to
     /// define time-based operations.
     pub runtime: Option<Operator>,
@@ -932,6 +1499,8 @@ pub enum Value {
     Runtime(u64),
     /// Represents a count value
     Count(u8),
+    /// Represents the value of a score
+    Score(f64),
 }
 
 /// Implementation of the `Serialize` trait for the `Value` enum.
@@ -944,6 +1513,7 @@ impl Serialize for Value {
             Value::Datetime(datetime) => datetime.serialize(serializer),
             Value::Runtime(runtime) => runtime.serialize(serializer),
             Value::Count(count) => count.serialize(serializer),
+            Value::Score(score) => score.serialize(serializer),
         }
     }
 }
@@ -1018,6 +1588,13 @@ impl From<u8> for Value {
     }
 }
 
+/// Conversion from f64 to Value::Score.
+impl From<f64> for Value {
+    fn from(item: f64) -> Self {
+        Value::Score(item)
+    }
+}
+
 /// The `QueryBuilder` is used to construct `QueryParameters` using the builder pattern.
 /// It is used to fetch records using query parameters such as start_time, stop_time etc.
 ///
@@ -1040,8 +1617,9 @@ impl From<u8> for Value {
 /// let query_builder = QueryBuilder::new();
 ///
 /// // Build the query string.
-/// let query_string = query_builder.build();
+/// let query_string = query_builder.build()?;
 /// println!("Generated query string: {}", query_string);
+/// # Ok::<(), auditor_client::ClientError>(())
 /// ```
 ///
 #[derive(Debug, Clone)]
@@ -1056,8 +1634,12 @@ impl QueryBuilder {
         QueryBuilder {
             query_params: QueryParameters {
                 record_id: None,
+                record_id_prefix: None,
                 start_time: None,
                 stop_time: None,
+                ingested_at: None,
+                updated_since: None,
+                stopped: None,
                 runtime: None,
                 meta: None,
                 component: None,
@@ -1068,8 +1650,21 @@ impl QueryBuilder {
     }
 
     /// Sets the exact record to be queried from the database using record id
+    ///
+    /// Replaces a prefix set with `with_record_id_prefix`.
     pub fn with_record_id(mut self, record_id: String) -> Self {
         self.query_params.record_id = Some(record_id);
+        self.query_params.record_id_prefix = None;
+        self
+    }
+
+    /// Queries all records whose record id starts with `prefix`.
+    ///
+    /// `%` and `_` in the prefix are matched literally. Replaces a record id set with
+    /// `with_record_id`.
+    pub fn with_record_id_prefix(mut self, prefix: String) -> Self {
+        self.query_params.record_id_prefix = Some(prefix);
+        self.query_params.record_id = None;
         self
     }
 
@@ -1085,6 +1680,61 @@ impl QueryBuilder {
         self
     }
 
+    /// Queries records which started between `from` and `to` (both inclusive).
+    ///
+    /// Same as `with_start_time(Operator::default().gte(from.into()).lte(to.into()))`.
+    pub fn started_between(self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.with_start_time(Operator::default().gte(from.into()).lte(to.into()))
+    }
+
+    /// Queries records which started at or after `from`.
+    ///
+    /// Same as `with_start_time(Operator::default().gte(from.into()))`.
+    pub fn started_after(self, from: DateTime<Utc>) -> Self {
+        self.with_start_time(Operator::default().gte(from.into()))
+    }
+
+    /// Queries records which started at or before `to`.
+    ///
+    /// Same as `with_start_time(Operator::default().lte(to.into()))`.
+    pub fn started_before(self, to: DateTime<Utc>) -> Self {
+        self.with_start_time(Operator::default().lte(to.into()))
+    }
+
+    /// Queries records which stopped between `from` and `to` (both inclusive).
+    ///
+    /// Same as `with_stop_time(Operator::default().gte(from.into()).lte(to.into()))`.
+    pub fn stopped_between(self, from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        self.with_stop_time(Operator::default().gte(from.into()).lte(to.into()))
+    }
+
+    /// Sets the ingestion time in the query parameters.
+    pub fn with_ingested_at(mut self, time_operator: Operator) -> Self {
+        self.query_params.ingested_at = Some(time_operator);
+        self
+    }
+
+    /// Only queries records which were added or updated at or after `timestamp`.
+    ///
+    /// Useful for incremental syncs: Remember the time of the last sync and only fetch the records
+    /// which changed since then.
+    pub fn with_updated_since(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.query_params.updated_since = Some(timestamp);
+        self
+    }
+
+    /// Only queries finished records, i.e. records with a stop time.
+    pub fn only_finished(mut self) -> Self {
+        self.query_params.stopped = Some(true);
+        self
+    }
+
+    /// Only queries unfinished records, i.e. records without a stop time.
+    pub fn only_unfinished(mut self) -> Self {
+        self.query_params.stopped = Some(false);
+        self
+    }
+
     /// Sets the runtime in the query parameters.
     pub fn with_runtime(mut self, time_operator: Operator) -> Self {
         self.query_params.runtime = Some(time_operator);
@@ -1125,20 +1775,105 @@ impl QueryBuilder {
     /// A `Result` containing the vector of records if successful, or a `ClientError` if an error occurs.
     ///
     pub async fn get(&self, client: AuditorClient) -> Result<Vec<Record>, ClientError> {
-        let query_string = self.build();
+        let query_string = self.build()?;
         client.advanced_query(query_string).await
     }
 
-    /// Builds and returns the serialized query string
-    pub fn build(&self) -> String {
-        serde_qs::to_string(&self.query_params).expect("Failed to serialize query parameters")
+    /// Executes the query like [`QueryBuilder::get`], but returns the records wrapped in a
+    /// [`RecordsEnvelope`].
+    pub async fn get_enveloped(
+        &self,
+        client: AuditorClient,
+    ) -> Result<RecordsEnvelope, ClientError> {
+        let query_string = self.build()?;
+        client.get_enveloped(query_string).await
     }
-}
 
-/// The `MetaQuery` struct represents a set of metadata queries associated with specific query IDs
-/// It is used to filter records based on metadata conditions.
-#[derive(serde::Deserialize, Debug, Default, Clone)]
-pub struct MetaQuery {
+    /// Counts the records matching the query parameters using the provided `AuditorClient`.
+    ///
+    /// `sort_by` and `limit` are ignored by the server when counting.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - An instance of the `AuditorClient` used to perform the query.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of matching records if successful, or a `ClientError` if an error occurs.
+    ///
+    pub async fn count(&self, client: AuditorClient) -> Result<u64, ClientError> {
+        let query_string = self.build()?;
+        client.count(query_string).await
+    }
+
+    /// Gets the distinct component names of the records matching the query parameters using the
+    /// provided `AuditorClient`.
+    ///
+    /// `sort_by` and `limit` are ignored by the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - An instance of the `AuditorClient` used to perform the query.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the set of component names if successful, or a `ClientError` if an error occurs.
+    ///
+    pub async fn component_names(
+        &self,
+        client: AuditorClient,
+    ) -> Result<HashSet<String>, ClientError> {
+        let query_string = self.build()?;
+        client.component_names(query_string).await
+    }
+
+    /// Builds and returns the serialized query string
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::QuerySerialization`] if the query parameters cannot be serialized.
+    pub fn build(&self) -> Result<String, ClientError> {
+        let query_string = serialize_query(&self.query_params)?;
+        match &self.query_params.record_id_prefix {
+            Some(prefix) => {
+                let prefix = serialize_query(&HashMap::from([(
+                    "record_id",
+                    HashMap::from([("prefix", prefix)]),
+                )]))?;
+                if query_string.is_empty() {
+                    Ok(prefix)
+                } else {
+                    Ok(format!("{query_string}&{prefix}"))
+                }
+            }
+            None => Ok(query_string),
+        }
+    }
+
+    /// Same as [`QueryBuilder::build`], but returns an empty query string, i.e. a query for all
+    /// records, if the query parameters cannot be serialized.
+    pub fn build_or_empty(&self) -> String {
+        self.build().unwrap_or_default()
+    }
+}
+
+fn serialize_query<T: Serialize>(query: &T) -> Result<String, ClientError> {
+    serde_qs::to_string(query).map_err(|e| ClientError::QuerySerialization(e.to_string()))
+}
+
+// Appends the query parameter requesting a `RecordsEnvelope` to `query_string`.
+fn with_envelope(query_string: &str) -> String {
+    if query_string.is_empty() {
+        "envelope=true".to_string()
+    } else {
+        format!("{query_string}&envelope=true")
+    }
+}
+
+/// The `MetaQuery` struct represents a set of metadata queries associated with specific query IDs
+/// It is used to filter records based on metadata conditions.
+#[derive(serde::Deserialize, Debug, Default, Clone)]
+pub struct MetaQuery {
     /// HashMap containing query IDs and corresponding metadata operators.
     pub meta_query: HashMap<String, Option<MetaOperator>>,
 }
@@ -1219,10 +1954,18 @@ impl MetaOperator {
 
 /// The `ComponentQuery` struct represents a set of component queries associated with specific query IDs.
 /// It is used to filter records based on component-related conditions.
+///
+/// Conditions on the amount of a component are serialized as `component[<name>][gt]=<amount>`.
+/// Conditions on the value of a score attached to a component are nested one level deeper, e.g.
+/// `component[CPU][score][HEPSPEC06][gt]=10` selects all records with a `CPU` component whose
+/// `HEPSPEC06` score is greater than 10.
 #[derive(serde::Deserialize, Debug, Default, Clone)]
 pub struct ComponentQuery {
     /// HashMap containing query IDs and corresponding component operators.
     pub component_query: HashMap<String, Option<Operator>>,
+    /// HashMap containing component names and the operators on their scores, by score name.
+    #[serde(default)]
+    pub score_query: HashMap<String, HashMap<String, Operator>>,
 }
 
 impl ComponentQuery {
@@ -1230,9 +1973,29 @@ impl ComponentQuery {
     pub fn new() -> Self {
         ComponentQuery {
             component_query: HashMap::new(),
+            score_query: HashMap::new(),
         }
     }
 
+    /// Adds an operator on the value of a score attached to a component.
+    ///
+    /// # Arguments
+    ///
+    /// * `component` - Name of the component, e.g. `CPU`.
+    /// * `score` - Name of the score, e.g. `HEPSPEC06`.
+    /// * `operator` - The operator containing the conditions on the score value.
+    ///
+    /// # Returns
+    ///
+    /// A new `ComponentQuery` instance with the added score operator.
+    pub fn score_operator(mut self, component: String, score: String, operator: Operator) -> Self {
+        self.score_query
+            .entry(component)
+            .or_default()
+            .insert(score, operator);
+        self
+    }
+
     /// Adds a new component operator to the `ComponentQuery` instance for a specific query ID.
     ///
     /// # Arguments
@@ -1257,15 +2020,93 @@ impl Serialize for ComponentQuery {
     where
         S: serde::Serializer,
     {
-        self.component_query.serialize(serializer)
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        for (name, operator) in &self.component_query {
+            map.serialize_entry(
+                name,
+                &ComponentConditions {
+                    operator: operator.as_ref(),
+                    scores: self.score_query.get(name),
+                },
+            )?;
+        }
+        for (name, scores) in &self.score_query {
+            if !self.component_query.contains_key(name) {
+                map.serialize_entry(
+                    name,
+                    &ComponentConditions {
+                        operator: None,
+                        scores: Some(scores),
+                    },
+                )?;
+            }
+        }
+        map.end()
+    }
+}
+
+// All conditions on a single component, i.e. the operator on its amount and the operators on
+// its scores nested under `score`.
+struct ComponentConditions<'a> {
+    operator: Option<&'a Operator>,
+    scores: Option<&'a HashMap<String, Operator>>,
+}
+
+impl Serialize for ComponentConditions<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(operator) = self.operator {
+            for (key, value) in [
+                ("gt", &operator.gt),
+                ("lt", &operator.lt),
+                ("gte", &operator.gte),
+                ("lte", &operator.lte),
+                ("equals", &operator.equals),
+            ] {
+                if let Some(value) = value {
+                    map.serialize_entry(key, value)?;
+                }
+            }
+        }
+        if let Some(scores) = self.scores.filter(|scores| !scores.is_empty()) {
+            map.serialize_entry("score", scores)?;
+        }
+        map.end()
+    }
+}
+
+/// Direction in which the query records are sorted, see [`SortBy::by_component`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Smallest values first.
+    Ascending,
+    /// Largest values first.
+    Descending,
+}
+
+impl SortDirection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "asc",
+            SortDirection::Descending => "desc",
+        }
     }
 }
 
 /// SortBy provides options on sorting the query records
-#[derive(serde::Deserialize, serde::Serialize, Debug, Default, Clone)]
+#[derive(serde::Deserialize, Debug, Default, Clone)]
 pub struct SortBy {
     pub asc: Option<String>,
     pub desc: Option<String>,
+    #[serde(skip)]
+    component: Option<(String, SortDirection)>,
 }
 
 impl SortBy {
@@ -1274,6 +2115,28 @@ impl SortBy {
         Self {
             asc: None,
             desc: None,
+            component: None,
+        }
+    }
+
+    /// Sorts the query records by the amount of a component.
+    ///
+    /// Records without a component of this name are always returned last, regardless of the
+    /// direction.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the component, e.g. `CPU`
+    /// * `direction` - Direction in which the records are sorted
+    ///
+    /// # Returns
+    ///
+    /// A new `SortBy` instance sorting by the component amount.
+    pub fn by_component<T: AsRef<str>>(name: T, direction: SortDirection) -> Self {
+        Self {
+            asc: None,
+            desc: None,
+            component: Some((name.as_ref().to_string(), direction)),
         }
     }
 
@@ -1281,7 +2144,8 @@ impl SortBy {
     ///
     /// # Arguments
     ///
-    /// * `column` - One of four values (`start_time`, `stop_time`, `runtime`, `record_id`)
+    /// * `column` - One of five values (`start_time`, `stop_time`, `runtime`, `record_id`,
+    ///   `ingested_at`)
     ///
     /// # Returns
     ///
@@ -1295,7 +2159,8 @@ impl SortBy {
     ///
     /// # Arguments
     ///
-    /// * `column` - One of three values (`start_time`, `stop_time`, `runtime`, `record_id`)
+    /// * `column` - One of five values (`start_time`, `stop_time`, `runtime`, `record_id`,
+    ///   `ingested_at`)
     ///
     /// # Returns
     ///
@@ -1306,6 +2171,49 @@ impl SortBy {
     }
 }
 
+/// Component sorting is serialized as `sort_by[<direction>][component]=<name>`.
+impl Serialize for SortBy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(asc) = &self.asc {
+            map.serialize_entry("asc", asc)?;
+        }
+        if let Some(desc) = &self.desc {
+            map.serialize_entry("desc", desc)?;
+        }
+        if let Some((name, direction)) = &self.component {
+            map.serialize_entry(direction.as_str(), &HashMap::from([("component", name)]))?;
+        }
+        map.end()
+    }
+}
+
+/// Metric which is summed up per group by [`AuditorClient::aggregate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AggregateMetric {
+    /// Runtime of the records in seconds.
+    Runtime,
+    /// Amount of the component with the given name.
+    ComponentAmount(String),
+}
+
+/// Sum of a metric over all records of a group, see [`AuditorClient::aggregate`].
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AggregateRow {
+    /// Value of the meta key the records are grouped by.
+    pub key: String,
+    /// Sum of the [`AggregateMetric`] over all records of the group.
+    #[serde(alias = "sum_runtime", alias = "sum_amount")]
+    pub sum: i64,
+    /// Number of records in the group.
+    pub count: i64,
+}
+
 /// The `AuditorClient` handles the interaction with the Auditor instances and allows one to add
 /// records to the database, update records in the database and retrieve the records from the
 /// database.
@@ -1315,9 +2223,99 @@ impl SortBy {
 pub struct AuditorClient {
     address: String,
     client: reqwest::Client,
+    get_timeout: Option<std::time::Duration>,
+    write_timeout: Option<std::time::Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    response_cache: Option<ResponseCache>,
+    bulk_insert_sort_key: Option<BulkInsertSortKey>,
 }
 
 impl AuditorClient {
+    /// Returns ``true`` if the circuit breaker is open, i.e. requests currently fail fast.
+    pub fn is_circuit_open(&self) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .is_some_and(CircuitBreaker::is_open)
+    }
+
+    // Sends the request unless the circuit breaker is open and keeps track of failures.
+    async fn with_circuit_breaker<T, F>(&self, request: F) -> Result<T, ClientError>
+    where
+        F: std::future::Future<Output = Result<T, ClientError>>,
+    {
+        let Some(circuit_breaker) = &self.circuit_breaker else {
+            return request.await;
+        };
+        circuit_breaker.acquire()?;
+        let result = request.await;
+        circuit_breaker.record(&result);
+        result
+    }
+
+    // Same as `with_circuit_breaker`, but clears the response cache once the request has
+    // finished, because it may have changed records on the server.
+    async fn writing<T, F>(&self, request: F) -> Result<T, ClientError>
+    where
+        F: std::future::Future<Output = Result<T, ClientError>>,
+    {
+        let result = self.with_circuit_breaker(request).await;
+        if let Some(response_cache) = &self.response_cache {
+            response_cache.clear();
+        }
+        result
+    }
+
+    // Returns the records cached for `query_string` if they are still fresh, otherwise the
+    // records are fetched with `request` and cached.
+    async fn cached<F>(&self, query_string: &str, request: F) -> Result<Vec<Record>, ClientError>
+    where
+        F: std::future::Future<Output = Result<Vec<Record>, ClientError>>,
+    {
+        let Some(response_cache) = &self.response_cache else {
+            return self.with_circuit_breaker(request).await;
+        };
+        if let Some(records) = response_cache.get(query_string) {
+            return Ok(records);
+        }
+        // A write finishing while the request is pending may have made its response stale.
+        let generation = response_cache.generation();
+        let records = self.with_circuit_breaker(request).await?;
+        response_cache.insert(query_string.to_string(), records.clone(), generation);
+        Ok(records)
+    }
+
+    // Builds a request retrieving records, with the get timeout if one is configured.
+    fn read_request(&self, url: String) -> reqwest::RequestBuilder {
+        self.read_request_with(reqwest::Method::GET, url)
+    }
+
+    // Same as `read_request` for endpoints which retrieve records with another method, e.g. `POST`
+    // to send a request body.
+    fn read_request_with(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.get_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    // Builds a request adding or updating records, with the write timeout if one is configured.
+    fn write_request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.write_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    // Applies the configured `bulk_insert` sort order, if any.
+    fn sorted_batch<'a>(&self, records: &'a [RecordAdd]) -> Vec<&'a RecordAdd> {
+        match self.bulk_insert_sort_key {
+            Some(key) => key.sort(records),
+            None => records.iter().collect(),
+        }
+    }
+
     /// Returns ``true`` if the Auditor instance is healthy, ``false`` otherwise.
     #[tracing::instrument(name = "Checking health of AUDITOR server.", skip(self))]
     pub async fn health_check(&self) -> bool {
@@ -1332,12 +2330,53 @@ impl AuditorClient {
         }
     }
 
+    /// Returns the round-trip time of a health check request to the Auditor instance.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request or the
+    ///     Auditor instance is not healthy.
+    #[tracing::instrument(name = "Pinging AUDITOR server.", skip(self))]
+    pub async fn ping(&self) -> Result<std::time::Duration, ClientError> {
+        let start = std::time::Instant::now();
+        self.client
+            .get(format!("{}/health_check", &self.address))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(start.elapsed())
+    }
+
+    /// Returns the status of the individual components of the Auditor instance.
+    ///
+    /// Unlike [`AuditorClient::health_check`], the status is also returned if the database of
+    /// the Auditor instance is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    #[tracing::instrument(name = "Getting status of AUDITOR server", skip(self))]
+    pub async fn status(&self) -> Result<ServerStatus, ClientError> {
+        let response = self
+            .client
+            .get(format!("{}/health_check/status", &self.address))
+            .send()
+            .await?;
+        if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(response.json().await?);
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+
     /// Push a record to the Auditor instance.
     ///
     /// # Errors
     ///
     /// * [`ClientError::RecordExists`] - If the record already exists in the database.
-    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, the
+    ///     server failed (`5xx`), e.g. because it is in maintenance mode (`503`), or the bearer
+    ///     token was rejected (`401`).
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Sending a record to AUDITOR server.",
         skip(self, record),
@@ -1345,45 +2384,197 @@ impl AuditorClient {
         level = "debug"
     )]
     pub async fn add(&self, record: &RecordAdd) -> Result<(), ClientError> {
-        let response = self
-            .client
-            .post(format!("{}/record", &self.address))
-            .header("Content-Type", "application/json")
-            .json(record)
-            .send()
-            .await?;
+        self.writing(async {
+            let response = self
+                .write_request(reqwest::Method::POST, format!("{}/record", &self.address))
+                .header("Content-Type", "application/json")
+                .json(record)
+                .send()
+                .await?;
+
+            // The server is temporarily unavailable, e.g. in maintenance mode, or rejected the
+            // bearer token. Returning an error makes sure that the `QueuedAuditorClient` retries
+            // later.
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::SERVICE_UNAVAILABLE | reqwest::StatusCode::UNAUTHORIZED
+            ) {
+                response.error_for_status_ref()?;
+            }
 
-        if response.text().await? == ERR_RECORD_EXISTS {
-            Err(ClientError::RecordExists)
-        } else {
+            let status = response.status();
+            let code = error_code(response.headers());
+            let status_error = response.error_for_status_ref().err();
+            match insert_error(status, code, &response.text().await?, status_error) {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })
+        .await
+    }
+
+    /// Push a record to the Auditor instance and fetch it back, to check that it is stored
+    /// unchanged.
+    ///
+    /// Meant for testing collectors against an Auditor instance, as it sends an additional
+    /// request per record. Fields which are set by Auditor (`ingested_at` and `updated_at`) are
+    /// not compared.
+    ///
+    /// # Errors
+    ///
+    /// * [`VerifyError::Client`] - If adding or fetching the record failed, see
+    ///     [`AuditorClient::add`] and [`AuditorClient::get_single_record`].
+    /// * [`VerifyError::Mismatch`] - If the fetched record differs from `record`, with all fields
+    ///     that changed.
+    #[tracing::instrument(
+        name = "Sending and verifying a record.",
+        skip(self, record),
+        fields(record_id = %record.record_id)
+    )]
+    pub async fn add_and_verify(&self, record: &RecordAdd) -> Result<(), VerifyError> {
+        self.add(record).await?;
+        let received = self.get_single_record(record.record_id.to_string()).await?;
+        let differences = verify::differences(&Record::from(record.clone()), &received);
+        if differences.is_empty() {
             Ok(())
+        } else {
+            Err(VerifyError::Mismatch {
+                record_id: received.record_id,
+                differences,
+            })
+        }
+    }
+
+    /// Push the records of a stream to the Auditor instance in batches of `batch_size` records.
+    ///
+    /// The batches are sent one after another. While the server reports a high load (see
+    /// [`SERVER_LOAD_HEADER`]), the delay between two batches doubles, up to 10 seconds. It
+    /// decreases again once the load has dropped. If the server responds with `429` or `503` and
+    /// a `Retry-After` header, the batch is sent again after the given time, up to 5 times.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`AuditorClient::bulk_insert`]. The records of the batches sent before the error
+    /// occurred have been added.
+    #[tracing::instrument(name = "Streaming records to AUDITOR server.", skip(self, records))]
+    pub async fn bulk_insert_stream<S: Stream<Item = RecordAdd>>(
+        &self,
+        records: S,
+        batch_size: usize,
+    ) -> Result<(), ClientError> {
+        let mut batches = std::pin::pin!(records.chunks(batch_size.max(1)));
+        let mut throttle = IngestThrottle::default();
+
+        while let Some(batch) = batches.next().await {
+            let mut retries = 0;
+            loop {
+                match self.send_batch(&batch).await? {
+                    BatchOutcome::Accepted(load) => {
+                        if let Some(load) = load {
+                            throttle.record_load(load);
+                        }
+                        break;
+                    }
+                    BatchOutcome::RetryLater { error, .. } if retries == MAX_INGEST_RETRIES => {
+                        return Err(error);
+                    }
+                    BatchOutcome::RetryLater { retry_after, .. } => {
+                        retries += 1;
+                        throttle.slow_down();
+                        let wait = retry_after.max(throttle.delay());
+                        tracing::warn!("Server is overloaded, sending batch again in {wait:?}");
+                        tokio::time::sleep(wait).await;
+                    }
+                }
+            }
+            if !throttle.delay().is_zero() {
+                tokio::time::sleep(throttle.delay()).await;
+            }
         }
+        Ok(())
+    }
+
+    async fn send_batch(&self, records: &[RecordAdd]) -> Result<BatchOutcome, ClientError> {
+        self.writing(async {
+            let response = self
+                .write_request(reqwest::Method::POST, format!("{}/records", &self.address))
+                .header("Content-Type", "application/json")
+                .json(&self.sorted_batch(records))
+                .send()
+                .await?;
+
+            if let Some(retry_after) = retry_after(&response) {
+                let error = response.error_for_status_ref().unwrap_err().into();
+                return Ok(BatchOutcome::RetryLater { retry_after, error });
+            }
+            // Same as in `bulk_insert`
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::SERVICE_UNAVAILABLE
+                    | reqwest::StatusCode::UNAUTHORIZED
+                    | reqwest::StatusCode::TOO_MANY_REQUESTS
+            ) {
+                response.error_for_status_ref()?;
+            }
+
+            let status = response.status();
+            let code = error_code(response.headers());
+            let load = server_load(response.headers());
+            let status_error = response.error_for_status_ref().err();
+            match insert_error(status, code, &response.text().await?, status_error) {
+                Some(e) => Err(e),
+                None => Ok(BatchOutcome::Accepted(load)),
+            }
+        })
+        .await
     }
 
     /// Push multiple record to the Auditor instance as a vec.
     ///
+    /// If a sort key was set with [`AuditorClientBuilder::bulk_insert_sort_key`], the records are
+    /// sent in that order.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::RecordExists`] - If the record already exists in the database.
-    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, the
+    ///     server failed (`5xx`), e.g. because it is in maintenance mode (`503`), or the bearer
+    ///     token was rejected (`401`).
+    /// * [`ClientError::InvalidRecords`] - If records were rejected by the server, with the
+    ///     reasons for all invalid records.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Sending multiple records to AUDITOR server.",
         skip(self, records)
     )]
-    pub async fn bulk_insert(&self, records: &Vec<RecordAdd>) -> Result<(), ClientError> {
-        let response = self
-            .client
-            .post(format!("{}/records", &self.address))
-            .header("Content-Type", "application/json")
-            .json(records)
-            .send()
-            .await?;
+    pub async fn bulk_insert(&self, records: &[RecordAdd]) -> Result<(), ClientError> {
+        self.writing(async {
+            let response = self
+                .write_request(reqwest::Method::POST, format!("{}/records", &self.address))
+                .header("Content-Type", "application/json")
+                .json(&self.sorted_batch(records))
+                .send()
+                .await?;
+
+            // The server is temporarily unavailable, e.g. in maintenance mode, or rejected the
+            // bearer token. Returning an error makes sure that the `QueuedAuditorClient` retries
+            // later.
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::SERVICE_UNAVAILABLE | reqwest::StatusCode::UNAUTHORIZED
+            ) {
+                response.error_for_status_ref()?;
+            }
 
-        if response.text().await? == ERR_RECORD_EXISTS {
-            Err(ClientError::RecordExists)
-        } else {
-            Ok(())
-        }
+            let status = response.status();
+            let code = error_code(response.headers());
+            let status_error = response.error_for_status_ref().err();
+            match insert_error(status, code, &response.text().await?, status_error) {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })
+        .await
     }
 
     /// Update an existing record in the Auditor instance.
@@ -1392,37 +2583,104 @@ impl AuditorClient {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Sending a record update to AUDITOR server.",
         skip(self, record),
         fields(record_id = %record.record_id)
     )]
     pub async fn update(&self, record: &RecordUpdate) -> Result<(), ClientError> {
-        self.client
-            .put(format!("{}/record", &self.address))
-            .header("Content-Type", "application/json")
-            .json(record)
+        self.writing(async {
+            self.write_request(reqwest::Method::PUT, format!("{}/record", &self.address))
+                .header("Content-Type", "application/json")
+                .json(record)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Change the meta information and components of an existing record with a JSON Merge Patch
+    /// (RFC 7396).
+    ///
+    /// Meta values are patched per key, keys set to `null` are removed. Components are replaced
+    /// as a whole. The server has to allow patching records (`allow_record_patch`).
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because the record does not exist or patching records is disabled.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Sending a record patch to AUDITOR server.", skip(self, patch))]
+    pub async fn patch(
+        &self,
+        record_id: &str,
+        patch: &serde_json::Value,
+    ) -> Result<(), ClientError> {
+        self.writing(async {
+            self.write_request(
+                reqwest::Method::PATCH,
+                format!("{}/record/{}", &self.address, encode(record_id)),
+            )
+            .header("Content-Type", "application/merge-patch+json")
+            .body(patch.to_string())
             .send()
             .await?
             .error_for_status()?;
-        Ok(())
+            Ok(())
+        })
+        .await
+    }
+
+    /// Deletes a record.
+    ///
+    /// If the server keeps deleted records (`soft_delete`), the record is only hidden from
+    /// queries.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because the record does not exist.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Deleting a record on AUDITOR server.", skip(self))]
+    pub async fn delete(&self, record_id: &str) -> Result<(), ClientError> {
+        self.writing(async {
+            self.write_request(
+                reqwest::Method::DELETE,
+                format!("{}/record/{}", &self.address, encode(record_id)),
+            )
+            .send()
+            .await?
+            .error_for_status()?;
+            Ok(())
+        })
+        .await
     }
 
     /// Gets all records from the Auditors database.
     ///
+    /// Equal component and score names of the records share their memory.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    /// * [`ClientError::DeserializationError`] - If the records could not be deserialized.
     #[tracing::instrument(name = "Getting all records from AUDITOR server.", skip(self))]
     pub async fn get(&self) -> Result<Vec<Record>, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/records", &self.address))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+        self.cached("", async {
+            let body = self
+                .read_request(format!("{}/records", &self.address))
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            records_from_slice(&body)
+        })
+        .await
     }
 
     /// Get all records in the database with a started timestamp after ``since``.
@@ -1430,6 +2688,7 @@ impl AuditorClient {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting all records started since a given date from AUDITOR server.",
         skip(self),
@@ -1440,20 +2699,22 @@ impl AuditorClient {
         &self,
         since: &DateTime<Utc>,
     ) -> Result<Vec<Record>, ClientError> {
-        dbg!(since.to_rfc3339());
-        let since_str = since.to_rfc3339();
-        let encoded_since = encode(&since_str);
-        Ok(self
-            .client
-            .get(format!(
-                "{}/records?start_time[gte]={}",
-                &self.address, encoded_since
-            ))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+        self.with_circuit_breaker(async {
+            dbg!(since.to_rfc3339());
+            let since_str = since.to_rfc3339();
+            let encoded_since = encode(&since_str);
+            Ok(self
+                .read_request(format!(
+                    "{}/records?start_time[gte]={}",
+                    &self.address, encoded_since
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
     }
 
     /// Get all records in the database with a stopped timestamp after ``since``.
@@ -1461,6 +2722,7 @@ impl AuditorClient {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting all records stopped since a given date from AUDITOR server.",
         skip(self),
@@ -1471,39 +2733,295 @@ impl AuditorClient {
         &self,
         since: &DateTime<Utc>,
     ) -> Result<Vec<Record>, ClientError> {
-        let since_str = since.to_rfc3339();
-        let encoded_since = encode(&since_str);
-        Ok(self
-            .client
-            .get(format!(
-                "{}/records?stop_time[gte]={}",
-                &self.address, encoded_since
-            ))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+        self.with_circuit_breaker(async {
+            let since_str = since.to_rfc3339();
+            let encoded_since = encode(&since_str);
+            Ok(self
+                .read_request(format!(
+                    "{}/records?stop_time[gte]={}",
+                    &self.address, encoded_since
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
     }
 
     /// Get records from AUDITOR server using custom query.
     ///
+    /// Equal component and score names of the records share their memory.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    /// * [`ClientError::DeserializationError`] - If the records could not be deserialized.
     #[tracing::instrument(
         name = "Getting records from AUDITOR server using custom query",
         skip(self)
     )]
     pub async fn advanced_query(&self, query_string: String) -> Result<Vec<Record>, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/records?{}", &self.address, query_string))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+        self.cached(&query_string, async {
+            let body = self
+                .read_request(format!("{}/records?{}", &self.address, query_string))
+                .send()
+                .await?
+                .error_for_status()?
+                .bytes()
+                .await?;
+            records_from_slice(&body)
+        })
+        .await
+    }
+
+    /// Get records from AUDITOR server using custom query, wrapped in a [`RecordsEnvelope`] which
+    /// also contains the query as understood by the server and whether the records were
+    /// truncated by `limit`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting enveloped records from AUDITOR server using custom query",
+        skip(self)
+    )]
+    pub async fn get_enveloped(
+        &self,
+        query_string: String,
+    ) -> Result<RecordsEnvelope, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!(
+                    "{}/records?{}",
+                    &self.address,
+                    with_envelope(&query_string)
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+
+    /// Get records from AUDITOR server using custom query and group them by the values of the
+    /// meta key `meta_key`.
+    ///
+    /// A record with several values for `meta_key` is part of each of these groups. Records
+    /// without `meta_key` are grouped under `None`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    pub async fn get_grouped_by(
+        &self,
+        query_string: String,
+        meta_key: &str,
+    ) -> Result<HashMap<Option<String>, Vec<Record>>, ClientError> {
+        let records = self.advanced_query(query_string).await?;
+        Ok(group_by_meta(records, meta_key))
+    }
+
+    /// Get records from AUDITOR server as a stream, using custom query.
+    ///
+    /// The records are requested as newline-delimited JSON and deserialized line by line while
+    /// the response is received. An empty `query_string` returns all records.
+    /// Records which cannot be deserialized are yielded as
+    /// [`ClientError::DeserializationError`] and the stream continues with the next record.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting records from AUDITOR server as NDJSON stream",
+        skip(self)
+    )]
+    pub async fn get_ndjson_stream(
+        &self,
+        query_string: String,
+    ) -> Result<impl Stream<Item = Result<Record, ClientError>>, ClientError> {
+        let response = self
+            .with_circuit_breaker(async {
+                Ok(self
+                    .read_request(format!("{}/records?{}", &self.address, query_string))
+                    .header(reqwest::header::ACCEPT, NDJSON_CONTENT_TYPE)
+                    .send()
+                    .await?
+                    .error_for_status()?)
+            })
+            .await?;
+        Ok(ndjson::record_stream(response))
+    }
+
+    /// Get the number of records matching a custom query, without downloading them.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Counting records on AUDITOR server using custom query",
+        skip(self)
+    )]
+    pub async fn count(&self, query_string: String) -> Result<u64, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!("{}/records/count?{}", &self.address, query_string))
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<CountResponse>()
+                .await?
+                .count)
+        })
+        .await
+    }
+
+    /// Get the distinct names of the components of all records matching a custom query.
+    ///
+    /// `sort_by` and `limit` are ignored by the server, as when counting records.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting component names from AUDITOR server", skip(self))]
+    pub async fn component_names(
+        &self,
+        query_string: String,
+    ) -> Result<HashSet<String>, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/components?{}",
+                    &self.address, query_string
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+
+    /// Sums up a metric of all records grouped by the values of the meta key `group_by`.
+    ///
+    /// A record with several values for `group_by` counts towards each of these groups. Records
+    /// without `group_by` are ignored. The rows are sorted by key.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Aggregating records on AUDITOR server", skip(self))]
+    pub async fn aggregate(
+        &self,
+        group_by: &str,
+        metric: AggregateMetric,
+    ) -> Result<Vec<AggregateRow>, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/aggregate?{}",
+                    &self.address,
+                    aggregate_query(group_by, &metric)
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+
+    /// Get the record with the latest stop time for every distinct value of the meta key
+    /// `meta_key`.
+    ///
+    /// Records without `meta_key` are ignored. The records are sorted by the value of
+    /// `meta_key`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting latest records from AUDITOR server", skip(self))]
+    pub async fn latest_per(&self, meta_key: &str) -> Result<Vec<Record>, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/latest?key={}",
+                    &self.address,
+                    encode(meta_key)
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+
+    /// Get the distinct values of the meta key `meta_key` over all records, sorted
+    /// alphabetically.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because `meta_key` contains characters other than ASCII letters, digits, `_`, `-` and
+    ///     `.`.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting distinct meta values from AUDITOR server", skip(self))]
+    pub async fn distinct_meta_values(&self, meta_key: &str) -> Result<Vec<String>, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/meta/{}/values",
+                    &self.address,
+                    encode(meta_key)
+                ))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+
+    /// Get the ingest latency distribution of all records which stopped in the interval
+    /// [``since``, ``until``).
+    ///
+    /// Records without a stop time are not taken into account. Returns `None` if no record is
+    /// left. See [`IngestLatency`].
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting ingest latency distribution from AUDITOR server",
+        skip(self),
+        fields(since = %since, until = %until)
+    )]
+    pub async fn ingest_latency(
+        &self,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> Result<Option<IngestLatency>, ClientError> {
+        let records = self
+            .advanced_query(ingest_latency_query(since, until)?)
+            .await?;
+        Ok(IngestLatency::from_records(&records))
     }
 
     /// Get single record from AUDITOR server using record_id.
@@ -1511,22 +3029,169 @@ impl AuditorClient {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting a single record from AUDITOR server using record_id",
         skip(self)
     )]
     pub async fn get_single_record(&self, record_id: String) -> Result<Record, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/record/{}", &self.address, record_id))
-            .send()
-            .await?
-            .error_for_status()?
-            .json()
-            .await?)
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request(format!("{}/record/{}", &self.address, record_id))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+    /// Get the records with the given record ids from AUDITOR server.
+    ///
+    /// The records are returned in the order of `record_ids`. Record ids which are unknown to
+    /// Auditor are skipped, and duplicated record ids are only returned once. The record ids are
+    /// sent in the request body, hence there is no limit on their number from the URL length.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting records by record_id from AUDITOR server",
+        skip(self, record_ids),
+        fields(count = record_ids.len())
+    )]
+    pub async fn get_records_by_ids(
+        &self,
+        record_ids: &[String],
+    ) -> Result<Vec<Record>, ClientError> {
+        self.with_circuit_breaker(async {
+            Ok(self
+                .read_request_with(
+                    reqwest::Method::POST,
+                    format!("{}/records/by-ids", &self.address),
+                )
+                .json(record_ids)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?)
+        })
+        .await
+    }
+}
+
+// Server load from which on `bulk_insert_stream` slows down.
+const HIGH_SERVER_LOAD: f64 = 0.8;
+// Bounds of the delay between two batches of `bulk_insert_stream` while slowing down.
+const MIN_INGEST_DELAY: std::time::Duration = std::time::Duration::from_millis(50);
+const MAX_INGEST_DELAY: std::time::Duration = std::time::Duration::from_secs(10);
+// How often a batch is sent again after the server asked to retry later.
+const MAX_INGEST_RETRIES: usize = 5;
+
+// Delay between two batches of `bulk_insert_stream`. It doubles while the server signals a high
+// load and halves once the load has dropped.
+#[derive(Debug, Clone, Copy, Default)]
+struct IngestThrottle {
+    delay: std::time::Duration,
+}
+
+impl IngestThrottle {
+    fn delay(&self) -> std::time::Duration {
+        self.delay
+    }
+
+    fn record_load(&mut self, load: f64) {
+        if load >= HIGH_SERVER_LOAD {
+            self.slow_down();
+        } else {
+            self.speed_up();
+        }
+    }
+
+    fn slow_down(&mut self) {
+        self.delay = self
+            .delay
+            .saturating_mul(2)
+            .clamp(MIN_INGEST_DELAY, MAX_INGEST_DELAY);
+    }
+
+    fn speed_up(&mut self) {
+        self.delay /= 2;
+        if self.delay < MIN_INGEST_DELAY {
+            self.delay = std::time::Duration::ZERO;
+        }
+    }
+}
+
+// Response of the server to a batch of `bulk_insert_stream`.
+enum BatchOutcome {
+    // The batch was added, with the load reported by the server.
+    Accepted(Option<f64>),
+    // The server is overloaded and asked to send the batch again after some time.
+    RetryLater {
+        retry_after: std::time::Duration,
+        error: ClientError,
+    },
+}
+
+fn server_load(headers: &reqwest::header::HeaderMap) -> Option<f64> {
+    headers.get(SERVER_LOAD_HEADER)?.to_str().ok()?.parse().ok()
+}
+
+// Only `Retry-After` given in seconds is supported.
+fn retry_after(response: &reqwest::Response) -> Option<std::time::Duration> {
+    if !matches!(
+        response.status(),
+        reqwest::StatusCode::TOO_MANY_REQUESTS | reqwest::StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        return None;
+    }
+    let seconds = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(std::time::Duration::from_secs(seconds))
+}
+
+// Interval of the background send task of the `QueuedAuditorClient`. It doubles on every
+// consecutive failure, up to the maximum interval.
+#[derive(Debug, Clone, Copy)]
+struct SendBackoff {
+    send_interval: std::time::Duration,
+    max_send_interval: std::time::Duration,
+    current: std::time::Duration,
+}
+
+impl SendBackoff {
+    fn new(send_interval: std::time::Duration, max_send_interval: std::time::Duration) -> Self {
+        SendBackoff {
+            send_interval,
+            max_send_interval,
+            current: send_interval,
+        }
+    }
+
+    fn interval(&self) -> std::time::Duration {
+        self.current
+    }
+
+    fn record_failure(&mut self) {
+        self.current = self.current.saturating_mul(2).min(self.max_send_interval);
+    }
+
+    fn record_success(&mut self) {
+        self.current = self.send_interval;
     }
 }
 
+// Number of background send errors buffered for each subscriber.
+const ERROR_CHANNEL_CAPACITY: usize = 64;
+
 /// The `QueuedAuditorClient` handles the interaction with the Auditor instances. All
 /// data to be sent is transparently saved in a persistent local database.
 ///
@@ -1548,7 +3213,8 @@ impl AuditorClient {
 /// - Since methods for sending records like `QueuedAuditorClient::add` only push the records to
 ///   the local queue, they can only ever raise database errors.
 ///   Errors like `ClientError::ReqwestError` or `ClientError::RecordExists` can only be triggered
-///   by the background send task and will be logged.
+///   by the background send task and will be logged. They can be received with
+///   [`QueuedAuditorClient::subscribe_errors`].
 ///
 /// # Examples
 /// ```
@@ -1572,15 +3238,24 @@ impl AuditorClient {
 pub struct QueuedAuditorClient {
     database: Database,
     client: AuditorClient,
+    queue_limit: Option<QueueLimit>,
+    error_tx: broadcast::Sender<Arc<ClientError>>,
     shutdown_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
     task_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 impl QueuedAuditorClient {
     /// Constructs the `QueuedAuditorClient` and starts the background send task
-    fn new(database: Database, client: AuditorClient, interval: std::time::Duration) -> Self {
-        let mut interval = tokio::time::interval(interval);
+    fn new(
+        database: Database,
+        client: AuditorClient,
+        mut backoff: SendBackoff,
+        queue_limit: Option<QueueLimit>,
+    ) -> Self {
+        let mut interval = tokio::time::interval(backoff.interval());
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        let (error_tx, _) = broadcast::channel(ERROR_CHANNEL_CAPACITY);
+        let _error_tx = error_tx.clone();
         let _database = database.clone();
         let _client = client.clone();
         // Note: Since the first tick on interval::tick is immediate,
@@ -1594,14 +3269,27 @@ impl QueuedAuditorClient {
                         break;
                     },
                 }
-                if let Err(e) = Self::process_queue(&_database, &_client).await {
-                    tracing::error!("Processing queue failed with error: {e}");
+                if _client.is_circuit_open() {
+                    tracing::warn!("Circuit breaker is open, pausing sending queued records");
+                    continue;
+                }
+                match Self::process_queue(&_database, &_client).await {
+                    Ok(()) => backoff.record_success(),
+                    Err(e) => {
+                        tracing::error!("Processing queue failed with error: {e}");
+                        // Sending only fails if nobody subscribed
+                        let _ = _error_tx.send(Arc::new(e));
+                        backoff.record_failure();
+                    }
                 }
+                interval.reset_after(backoff.interval());
             }
         });
         Self {
             database,
             client,
+            queue_limit,
+            error_tx,
             shutdown_tx: Arc::new(Mutex::new(Some(shutdown_tx))),
             task_handle: Arc::new(Mutex::new(Some(task_handle))),
         }
@@ -1649,6 +3337,44 @@ impl QueuedAuditorClient {
         Ok(())
     }
 
+    /// Applies the queue limit before `count` new records are queued. Returns how many of the new
+    /// records are to be queued.
+    async fn make_room(&self, count: usize) -> Result<usize, ClientError> {
+        let Some(QueueLimit { max_size, policy }) = self.queue_limit else {
+            return Ok(count);
+        };
+        let queued = usize::try_from(self.database.count_inserts().await?).unwrap_or(0);
+        let free = max_size.saturating_sub(queued);
+        if count <= free {
+            return Ok(count);
+        }
+        match policy {
+            QueueFullPolicy::ReturnError => Err(ClientError::QueueFull),
+            QueueFullPolicy::DropNewest => {
+                tracing::warn!("Send queue is full, dropping {} new records", count - free);
+                Ok(free)
+            }
+            QueueFullPolicy::DropOldest => {
+                let dropped = queued.min(count - free);
+                tracing::warn!("Send queue is full, dropping {dropped} queued records");
+                self.database
+                    .delete_oldest_inserts(i64::try_from(dropped).unwrap_or(i64::MAX))
+                    .await?;
+                Ok(count.min(max_size))
+            }
+        }
+    }
+
+    /// Returns a receiver for the errors of the background send task.
+    ///
+    /// Every error the background task encounters while sending the queued records is sent to
+    /// all receivers, in addition to being logged. Only errors occurring after subscribing are
+    /// received. If a receiver falls behind by more than 64 errors, the oldest ones are skipped
+    /// and [`broadcast::error::RecvError::Lagged`] is returned.
+    pub fn subscribe_errors(&self) -> broadcast::Receiver<Arc<ClientError>> {
+        self.error_tx.subscribe()
+    }
+
     /// Stops the background sync task
     #[tracing::instrument(name = "Stop QueuedAuditorClient task", skip(self))]
     pub async fn stop(&mut self) -> anyhow::Result<()> {
@@ -1677,31 +3403,68 @@ impl QueuedAuditorClient {
         self.client.health_check().await
     }
 
+    /// Same as [`AuditorClient::ping`]
+    pub async fn ping(&self) -> Result<std::time::Duration, ClientError> {
+        self.client.ping().await
+    }
+
+    /// Same as [`AuditorClient::status`]
+    pub async fn status(&self) -> Result<ServerStatus, ClientError> {
+        self.client.status().await
+    }
+
+    /// Same as [`AuditorClient::is_circuit_open`]
+    pub fn is_circuit_open(&self) -> bool {
+        self.client.is_circuit_open()
+    }
+
     /// Push a record to the Auditor instance.
     ///
+    /// If the send queue is full, the policy set with
+    /// [`AuditorClientBuilder::queue_full_policy`] is applied.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::DatabaseError`] - If there was an error inserting into the database
+    /// * [`ClientError::QueueFull`] - If the send queue is full and the policy is
+    ///     [`QueueFullPolicy::ReturnError`]
     #[tracing::instrument(
         name = "Pushing record to client send queue.",
         skip(self, record),
         fields(record_id = %record.record_id)
     )]
     pub async fn add(&self, record: &RecordAdd) -> Result<(), ClientError> {
-        self.database.insert(record).await?;
+        if self.make_room(1).await? == 1 {
+            self.database.insert(record).await?;
+        }
         Ok(())
     }
 
     /// Push multiple records to the Auditor instance as a vec.
     ///
+    /// If the records don't fit into the send queue, the policy set with
+    /// [`AuditorClientBuilder::queue_full_policy`] is applied. With
+    /// [`QueueFullPolicy::DropOldest`] and more records than the queue can hold, only the last
+    /// records of `records` are queued.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::DatabaseError`] - If there was an error inserting into the database
+    /// * [`ClientError::QueueFull`] - If the records don't fit into the send queue and the policy
+    ///     is [`QueueFullPolicy::ReturnError`]
     #[tracing::instrument(
         name = "Pushing multiple records to client send queue.",
         skip(self, records)
     )]
     pub async fn bulk_insert(&self, records: &[RecordAdd]) -> Result<(), ClientError> {
+        let fitting = self.make_room(records.len()).await?;
+        let records = match self.queue_limit {
+            Some(QueueLimit {
+                policy: QueueFullPolicy::DropOldest,
+                ..
+            }) => &records[records.len() - fitting..],
+            _ => &records[..fitting],
+        };
         self.database.insert_many(records).await?;
         Ok(())
     }
@@ -1721,6 +3484,20 @@ impl QueuedAuditorClient {
         Ok(())
     }
 
+    /// Same as [`AuditorClient::patch`]
+    pub async fn patch(
+        &self,
+        record_id: &str,
+        patch: &serde_json::Value,
+    ) -> Result<(), ClientError> {
+        self.client.patch(record_id, patch).await
+    }
+
+    /// Same as [`AuditorClient::delete`]
+    pub async fn delete(&self, record_id: &str) -> Result<(), ClientError> {
+        self.client.delete(record_id).await
+    }
+
     /// Same as [`AuditorClient::get`]
     pub async fn get(&self) -> Result<Vec<Record>, ClientError> {
         self.client.get().await
@@ -1731,10 +3508,75 @@ impl QueuedAuditorClient {
         self.client.advanced_query(query_string).await
     }
 
+    /// Same as [`AuditorClient::get_enveloped`]
+    pub async fn get_enveloped(
+        &self,
+        query_string: String,
+    ) -> Result<RecordsEnvelope, ClientError> {
+        self.client.get_enveloped(query_string).await
+    }
+
+    /// Same as [`AuditorClient::get_grouped_by`]
+    pub async fn get_grouped_by(
+        &self,
+        query_string: String,
+        meta_key: &str,
+    ) -> Result<HashMap<Option<String>, Vec<Record>>, ClientError> {
+        self.client.get_grouped_by(query_string, meta_key).await
+    }
+
+    /// Same as [`AuditorClient::get_ndjson_stream`]
+    pub async fn get_ndjson_stream(
+        &self,
+        query_string: String,
+    ) -> Result<impl Stream<Item = Result<Record, ClientError>>, ClientError> {
+        self.client.get_ndjson_stream(query_string).await
+    }
+
+    /// Same as [`AuditorClient::count`]
+    pub async fn count(&self, query_string: String) -> Result<u64, ClientError> {
+        self.client.count(query_string).await
+    }
+
+    /// Same as [`AuditorClient::component_names`]
+    pub async fn component_names(
+        &self,
+        query_string: String,
+    ) -> Result<HashSet<String>, ClientError> {
+        self.client.component_names(query_string).await
+    }
+
+    /// Same as [`AuditorClient::aggregate`]
+    pub async fn aggregate(
+        &self,
+        group_by: &str,
+        metric: AggregateMetric,
+    ) -> Result<Vec<AggregateRow>, ClientError> {
+        self.client.aggregate(group_by, metric).await
+    }
+
+    /// Same as [`AuditorClient::latest_per`]
+    pub async fn latest_per(&self, meta_key: &str) -> Result<Vec<Record>, ClientError> {
+        self.client.latest_per(meta_key).await
+    }
+
+    /// Same as [`AuditorClient::distinct_meta_values`]
+    pub async fn distinct_meta_values(&self, meta_key: &str) -> Result<Vec<String>, ClientError> {
+        self.client.distinct_meta_values(meta_key).await
+    }
+
     /// Same as [`AuditorClient::get_single_record`]
     pub async fn get_single_record(&self, record_id: String) -> Result<Record, ClientError> {
         self.client.get_single_record(record_id).await
     }
+
+    /// Same as [`AuditorClient::get_records_by_ids`]
+    pub async fn get_records_by_ids(
+        &self,
+        record_ids: &[String],
+    ) -> Result<Vec<Record>, ClientError> {
+        self.client.get_records_by_ids(record_ids).await
+    }
 }
 
 // There is no async drop, so error messages are the best we can do here
@@ -1759,9 +3601,74 @@ impl std::ops::Drop for QueuedAuditorClient {
 pub struct AuditorClientBlocking {
     address: String,
     client: reqwest::blocking::Client,
+    get_timeout: Option<std::time::Duration>,
+    write_timeout: Option<std::time::Duration>,
+    circuit_breaker: Option<CircuitBreaker>,
+    bulk_insert_sort_key: Option<BulkInsertSortKey>,
 }
 
 impl AuditorClientBlocking {
+    /// Returns ``true`` if the circuit breaker is open, i.e. requests currently fail fast.
+    pub fn is_circuit_open(&self) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .is_some_and(CircuitBreaker::is_open)
+    }
+
+    // Sends the request unless the circuit breaker is open and keeps track of failures.
+    fn with_circuit_breaker<T, F>(&self, request: F) -> Result<T, ClientError>
+    where
+        F: FnOnce() -> Result<T, ClientError>,
+    {
+        let Some(circuit_breaker) = &self.circuit_breaker else {
+            return request();
+        };
+        circuit_breaker.acquire()?;
+        let result = request();
+        circuit_breaker.record(&result);
+        result
+    }
+
+    // Builds a request retrieving records, with the get timeout if one is configured.
+    fn read_request(&self, url: String) -> reqwest::blocking::RequestBuilder {
+        self.read_request_with(reqwest::Method::GET, url)
+    }
+
+    // Same as `read_request` for endpoints which retrieve records with another method, e.g. `POST`
+    // to send a request body.
+    fn read_request_with(
+        &self,
+        method: reqwest::Method,
+        url: String,
+    ) -> reqwest::blocking::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.get_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    // Builds a request adding or updating records, with the write timeout if one is configured.
+    fn write_request(
+        &self,
+        method: reqwest::Method,
+        url: String,
+    ) -> reqwest::blocking::RequestBuilder {
+        let request = self.client.request(method, url);
+        match self.write_timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    // Applies the configured `bulk_insert` sort order, if any.
+    fn sorted_batch<'a>(&self, records: &'a [RecordAdd]) -> Vec<&'a RecordAdd> {
+        match self.bulk_insert_sort_key {
+            Some(key) => key.sort(records),
+            None => records.iter().collect(),
+        }
+    }
+
     /// Returns ``true`` if the Auditor instance is healthy, ``false`` otherwise.
     #[tracing::instrument(name = "Checking health of AUDITOR server.", skip(self))]
     pub fn health_check(&self) -> bool {
@@ -1775,89 +3682,239 @@ impl AuditorClientBlocking {
         }
     }
 
-    /// Push a record to the Auditor instance.
+    /// Returns the round-trip time of a health check request to the Auditor instance.
     ///
     /// # Errors
     ///
-    /// * [`ClientError::RecordExists`] - If the record already exists in the database.
-    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
-    #[tracing::instrument(
-        name = "Sending a record to AUDITOR server.",
-        skip(self, record),
-        fields(record_id = %record.record_id)
-    )]
-    pub fn add(&self, record: &RecordAdd) -> Result<(), ClientError> {
-        let response = self
-            .client
-            .post(format!("{}/record", &self.address))
-            .header("Content-Type", "application/json")
-            .json(record)
-            .send()?;
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request or the
+    ///     Auditor instance is not healthy.
+    #[tracing::instrument(name = "Pinging AUDITOR server.", skip(self))]
+    pub fn ping(&self) -> Result<std::time::Duration, ClientError> {
+        let start = std::time::Instant::now();
+        self.client
+            .get(format!("{}/health_check", &self.address))
+            .send()?
+            .error_for_status()?;
+        Ok(start.elapsed())
+    }
 
-        if response.text()? == ERR_RECORD_EXISTS {
-            Err(ClientError::RecordExists)
-        } else {
-            Ok(())
+    /// Returns the status of the individual components of the Auditor instance.
+    ///
+    /// Unlike [`AuditorClient::health_check`], the status is also returned if the database of
+    /// the Auditor instance is unavailable.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    #[tracing::instrument(name = "Getting status of AUDITOR server", skip(self))]
+    pub fn status(&self) -> Result<ServerStatus, ClientError> {
+        let response = self
+            .client
+            .get(format!("{}/health_check/status", &self.address))
+            .send()?;
+        if response.status() == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(response.json()?);
         }
+        Ok(response.error_for_status()?.json()?)
+    }
+
+    /// Push a record to the Auditor instance.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::RecordExists`] - If the record already exists in the database.
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, the
+    ///     server failed (`5xx`), e.g. because it is in maintenance mode (`503`), or the bearer
+    ///     token was rejected (`401`).
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Sending a record to AUDITOR server.",
+        skip(self, record),
+        fields(record_id = %record.record_id)
+    )]
+    pub fn add(&self, record: &RecordAdd) -> Result<(), ClientError> {
+        self.with_circuit_breaker(|| {
+            let response = self
+                .write_request(reqwest::Method::POST, format!("{}/record", &self.address))
+                .header("Content-Type", "application/json")
+                .json(record)
+                .send()?;
+
+            // The server is temporarily unavailable, e.g. in maintenance mode, or rejected the
+            // bearer token. Returning an error makes sure that the `QueuedAuditorClient` retries
+            // later.
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::SERVICE_UNAVAILABLE | reqwest::StatusCode::UNAUTHORIZED
+            ) {
+                response.error_for_status_ref()?;
+            }
+
+            let status = response.status();
+            let code = error_code(response.headers());
+            let status_error = response.error_for_status_ref().err();
+            match insert_error(status, code, &response.text()?, status_error) {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })
     }
 
     /// Push multiple records to the Auditor instance as vec.
     ///
+    /// If a sort key was set with [`AuditorClientBuilder::bulk_insert_sort_key`], the records are
+    /// sent in that order.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::RecordExists`] - If the record already exists in the database.
-    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, the
+    ///     server failed (`5xx`), e.g. because it is in maintenance mode (`503`), or the bearer
+    ///     token was rejected (`401`).
+    /// * [`ClientError::InvalidRecords`] - If records were rejected by the server, with the
+    ///     reasons for all invalid records.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Sending multiple records to AUDITOR server.",
         skip(self, records)
     )]
-    pub fn bulk_insert(&self, records: &Vec<RecordAdd>) -> Result<(), ClientError> {
-        let response = self
-            .client
-            .post(format!("{}/records", &self.address))
-            .header("Content-Type", "application/json")
-            .json(records)
-            .send()?;
+    pub fn bulk_insert(&self, records: &[RecordAdd]) -> Result<(), ClientError> {
+        self.with_circuit_breaker(|| {
+            let response = self
+                .write_request(reqwest::Method::POST, format!("{}/records", &self.address))
+                .header("Content-Type", "application/json")
+                .json(&self.sorted_batch(records))
+                .send()?;
+
+            // The server is temporarily unavailable, e.g. in maintenance mode, or rejected the
+            // bearer token. Returning an error makes sure that the `QueuedAuditorClient` retries
+            // later.
+            if matches!(
+                response.status(),
+                reqwest::StatusCode::SERVICE_UNAVAILABLE | reqwest::StatusCode::UNAUTHORIZED
+            ) {
+                response.error_for_status_ref()?;
+            }
 
-        if response.text()? == ERR_RECORD_EXISTS {
-            Err(ClientError::RecordExists)
-        } else {
-            Ok(())
-        }
+            let status = response.status();
+            let code = error_code(response.headers());
+            let status_error = response.error_for_status_ref().err();
+            match insert_error(status, code, &response.text()?, status_error) {
+                Some(e) => Err(e),
+                None => Ok(()),
+            }
+        })
     }
     /// Update an existing record in the Auditor instance.
     ///
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Sending a record update to AUDITOR server.",
         skip(self, record),
         fields(record_id = %record.record_id)
     )]
     pub fn update(&self, record: &RecordUpdate) -> Result<(), ClientError> {
-        self.client
-            .put(format!("{}/record", &self.address))
-            .header("Content-Type", "application/json")
-            .json(record)
+        self.with_circuit_breaker(|| {
+            self.write_request(reqwest::Method::PUT, format!("{}/record", &self.address))
+                .header("Content-Type", "application/json")
+                .json(record)
+                .send()?
+                .error_for_status()?;
+            Ok(())
+        })
+    }
+
+    /// Change the meta information and components of an existing record with a JSON Merge Patch
+    /// (RFC 7396).
+    ///
+    /// Meta values are patched per key, keys set to `null` are removed. Components are replaced
+    /// as a whole. The server has to allow patching records (`allow_record_patch`).
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because the record does not exist or patching records is disabled.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Sending a record patch to AUDITOR server.", skip(self, patch))]
+    pub fn patch(&self, record_id: &str, patch: &serde_json::Value) -> Result<(), ClientError> {
+        self.with_circuit_breaker(|| {
+            self.write_request(
+                reqwest::Method::PATCH,
+                format!("{}/record/{}", &self.address, encode(record_id)),
+            )
+            .header("Content-Type", "application/merge-patch+json")
+            .body(patch.to_string())
             .send()?
             .error_for_status()?;
-        Ok(())
+            Ok(())
+        })
+    }
+
+    /// Deletes a record.
+    ///
+    /// If the server keeps deleted records (`soft_delete`), the record is only hidden from
+    /// queries.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because the record does not exist.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Deleting a record on AUDITOR server.", skip(self))]
+    pub fn delete(&self, record_id: &str) -> Result<(), ClientError> {
+        self.with_circuit_breaker(|| {
+            self.write_request(
+                reqwest::Method::DELETE,
+                format!("{}/record/{}", &self.address, encode(record_id)),
+            )
+            .send()?
+            .error_for_status()?;
+            Ok(())
+        })
     }
 
     /// Gets all records from the Auditors database.
     ///
+    /// Equal component and score names of the records share their memory.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    /// * [`ClientError::DeserializationError`] - If a record could not be deserialized.
     #[tracing::instrument(name = "Getting all records from AUDITOR server.", skip(self))]
     pub fn get(&self) -> Result<Vec<Record>, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/records", &self.address))
-            .send()?
-            .error_for_status()?
-            .json()?)
+        with_interned_names(|| self.get_iter()?.collect())
+    }
+
+    /// Gets all records from the Auditors database as an iterator.
+    ///
+    /// The records are deserialized lazily while iterating over the response.
+    /// Records which cannot be deserialized are yielded as
+    /// [`ClientError::DeserializationError`]. If the response is not valid JSON, the iteration
+    /// ends after the error.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting all records from AUDITOR server as iterator.",
+        skip(self)
+    )]
+    pub fn get_iter(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<Record, ClientError>>, ClientError> {
+        let response = self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!("{}/records", &self.address))
+                .send()?
+                .error_for_status()?)
+        })?;
+        Ok(RecordIter::new(std::io::BufReader::new(response)))
     }
 
     /// Get all records in the database with a started timestamp after ``since``.
@@ -1865,6 +3922,7 @@ impl AuditorClientBlocking {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting all records started since a given date from AUDITOR server.",
         skip(self),
@@ -1872,18 +3930,19 @@ impl AuditorClientBlocking {
     )]
     #[deprecated(since = "0.4.0", note = "please use `advanced_query` instead")]
     pub fn get_started_since(&self, since: &DateTime<Utc>) -> Result<Vec<Record>, ClientError> {
-        dbg!(since.to_rfc3339());
-        let since_str = since.to_rfc3339();
-        let encoded_since = encode(&since_str);
-        Ok(self
-            .client
-            .get(format!(
-                "{}/records?start_time[gte]={}",
-                &self.address, encoded_since
-            ))
-            .send()?
-            .error_for_status()?
-            .json()?)
+        self.with_circuit_breaker(|| {
+            dbg!(since.to_rfc3339());
+            let since_str = since.to_rfc3339();
+            let encoded_since = encode(&since_str);
+            Ok(self
+                .read_request(format!(
+                    "{}/records?start_time[gte]={}",
+                    &self.address, encoded_since
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
     }
 
     /// Get all records in the database with a stopped timestamp after ``since``.
@@ -1891,6 +3950,7 @@ impl AuditorClientBlocking {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting all records stopped since a given date from AUDITOR server.",
         skip(self),
@@ -1898,31 +3958,217 @@ impl AuditorClientBlocking {
     )]
     #[deprecated(since = "0.4.0", note = "please use `advanced_query` instead")]
     pub fn get_stopped_since(&self, since: &DateTime<Utc>) -> Result<Vec<Record>, ClientError> {
-        let since_str = since.to_rfc3339();
-        let encoded_since = encode(&since_str);
-        Ok(self
-            .client
-            .get(format!(
-                "{}/records?stop_time[gte]={}",
-                &self.address, encoded_since
-            ))
-            .send()?
-            .error_for_status()?
-            .json()?)
+        self.with_circuit_breaker(|| {
+            let since_str = since.to_rfc3339();
+            let encoded_since = encode(&since_str);
+            Ok(self
+                .read_request(format!(
+                    "{}/records?stop_time[gte]={}",
+                    &self.address, encoded_since
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
     }
 
     /// Get records from AUDITOR server using custom filters.
     ///
+    /// Equal component and score names of the records share their memory.
+    ///
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    /// * [`ClientError::DeserializationError`] - If the records could not be deserialized.
     pub fn advanced_query(&self, query_params: String) -> Result<Vec<Record>, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/records?{}", &self.address, query_params))
-            .send()?
-            .error_for_status()?
-            .json()?)
+        self.with_circuit_breaker(|| {
+            let body = self
+                .read_request(format!("{}/records?{}", &self.address, query_params))
+                .send()?
+                .error_for_status()?
+                .bytes()?;
+            records_from_slice(&body)
+        })
+    }
+
+    /// Get records from AUDITOR server using custom filters, wrapped in a [`RecordsEnvelope`].
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    pub fn get_enveloped(&self, query_params: String) -> Result<RecordsEnvelope, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!(
+                    "{}/records?{}",
+                    &self.address,
+                    with_envelope(&query_params)
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Get records from AUDITOR server using custom query and group them by the values of the
+    /// meta key `meta_key`.
+    ///
+    /// A record with several values for `meta_key` is part of each of these groups. Records
+    /// without `meta_key` are grouped under `None`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    pub fn get_grouped_by(
+        &self,
+        query_params: String,
+        meta_key: &str,
+    ) -> Result<HashMap<Option<String>, Vec<Record>>, ClientError> {
+        let records = self.advanced_query(query_params)?;
+        Ok(group_by_meta(records, meta_key))
+    }
+
+    /// Get the number of records matching a custom query, without downloading them.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    pub fn count(&self, query_params: String) -> Result<u64, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!("{}/records/count?{}", &self.address, query_params))
+                .send()?
+                .error_for_status()?
+                .json::<CountResponse>()?
+                .count)
+        })
+    }
+
+    /// Get the distinct names of the components of all records matching a custom query.
+    ///
+    /// `sort_by` and `limit` are ignored by the server, as when counting records.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting component names from AUDITOR server", skip(self))]
+    pub fn component_names(&self, query_string: String) -> Result<HashSet<String>, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/components?{}",
+                    &self.address, query_string
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Sums up a metric of all records grouped by the values of the meta key `group_by`.
+    ///
+    /// A record with several values for `group_by` counts towards each of these groups. Records
+    /// without `group_by` are ignored. The rows are sorted by key.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Aggregating records on AUDITOR server", skip(self))]
+    pub fn aggregate(
+        &self,
+        group_by: &str,
+        metric: AggregateMetric,
+    ) -> Result<Vec<AggregateRow>, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/aggregate?{}",
+                    &self.address,
+                    aggregate_query(group_by, &metric)
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Get the record with the latest stop time for every distinct value of the meta key
+    /// `meta_key`.
+    ///
+    /// Records without `meta_key` are ignored. The records are sorted by the value of
+    /// `meta_key`.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting latest records from AUDITOR server", skip(self))]
+    pub fn latest_per(&self, meta_key: &str) -> Result<Vec<Record>, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/latest?key={}",
+                    &self.address,
+                    encode(meta_key)
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Get the distinct values of the meta key `meta_key` over all records, sorted
+    /// alphabetically.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request, e.g.
+    ///     because `meta_key` contains characters other than ASCII letters, digits, `_`, `-` and
+    ///     `.`.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(name = "Getting distinct meta values from AUDITOR server", skip(self))]
+    pub fn distinct_meta_values(&self, meta_key: &str) -> Result<Vec<String>, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!(
+                    "{}/records/meta/{}/values",
+                    &self.address,
+                    encode(meta_key)
+                ))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Get the ingest latency distribution of all records which stopped in the interval
+    /// [``since``, ``until``).
+    ///
+    /// Records without a stop time are not taken into account. Returns `None` if no record is
+    /// left. See [`IngestLatency`].
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting ingest latency distribution from AUDITOR server",
+        skip(self),
+        fields(since = %since, until = %until)
+    )]
+    pub fn ingest_latency(
+        &self,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+    ) -> Result<Option<IngestLatency>, ClientError> {
+        let records = self.advanced_query(ingest_latency_query(since, until)?)?;
+        Ok(IngestLatency::from_records(&records))
     }
 
     /// Get single record from AUDITOR server using record_id.
@@ -1930,17 +4176,89 @@ impl AuditorClientBlocking {
     /// # Errors
     ///
     /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
     #[tracing::instrument(
         name = "Getting a single record from AUDITOR server using record_id",
         skip(self)
     )]
     pub fn get_single_record(&self, record_id: &str) -> Result<Record, ClientError> {
-        Ok(self
-            .client
-            .get(format!("{}/record/{}", &self.address, record_id))
-            .send()?
-            .error_for_status()?
-            .json()?)
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request(format!("{}/record/{}", &self.address, record_id))
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+
+    /// Get the records with the given record ids from AUDITOR server.
+    ///
+    /// The records are returned in the order of `record_ids`. Record ids which are unknown to
+    /// Auditor are skipped, and duplicated record ids are only returned once. The record ids are
+    /// sent in the request body, hence there is no limit on their number from the URL length.
+    ///
+    /// # Errors
+    ///
+    /// * [`ClientError::ReqwestError`] - If there was an error sending the HTTP request.
+    /// * [`ClientError::CircuitOpen`] - If the circuit breaker is open.
+    #[tracing::instrument(
+        name = "Getting records by record_id from AUDITOR server",
+        skip(self, record_ids),
+        fields(count = record_ids.len())
+    )]
+    pub fn get_records_by_ids(&self, record_ids: &[String]) -> Result<Vec<Record>, ClientError> {
+        self.with_circuit_breaker(|| {
+            Ok(self
+                .read_request_with(
+                    reqwest::Method::POST,
+                    format!("{}/records/by-ids", &self.address),
+                )
+                .json(record_ids)
+                .send()?
+                .error_for_status()?
+                .json()?)
+        })
+    }
+}
+
+fn ingest_latency_query(
+    since: &DateTime<Utc>,
+    until: &DateTime<Utc>,
+) -> Result<String, ClientError> {
+    QueryBuilder::new()
+        .with_stop_time(Operator::default().gte((*since).into()).lt((*until).into()))
+        .build()
+}
+
+fn group_by_meta(records: Vec<Record>, meta_key: &str) -> HashMap<Option<String>, Vec<Record>> {
+    let mut groups: HashMap<Option<String>, Vec<Record>> = HashMap::new();
+    for record in records {
+        let values = record
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(meta_key))
+            .filter(|values| !values.is_empty())
+            .cloned();
+        match values {
+            Some(values) => {
+                for value in values {
+                    groups.entry(Some(value)).or_default().push(record.clone());
+                }
+            }
+            None => groups.entry(None).or_default().push(record),
+        }
+    }
+    groups
+}
+
+fn aggregate_query(group_by: &str, metric: &AggregateMetric) -> String {
+    match metric {
+        AggregateMetric::Runtime => format!("group_by={}&sum=runtime", encode(group_by)),
+        AggregateMetric::ComponentAmount(component) => format!(
+            "group_by={}&sum=component_amount&component={}",
+            encode(group_by),
+            encode(component)
+        ),
     }
 }
 
@@ -1949,8 +4267,9 @@ mod tests {
     use super::*;
     use auditor::domain::RecordTest;
     use chrono::TimeZone;
-    use claim::assert_err;
+    use claim::{assert_err, assert_ok};
     use fake::{Fake, Faker};
+    use futures_util::{StreamExt, TryStreamExt};
     use tokio::time::sleep;
     use wiremock::matchers::{any, body_json, header, method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -1989,96 +4308,826 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn blocking_get_succeeds() {
+    async fn get_ndjson_stream_succeeds() {
         let mock_server = MockServer::start().await;
-        let uri = mock_server.uri();
-        let client = tokio::task::spawn_blocking(move || {
-            AuditorClientBuilder::new()
-                .connection_string(&uri)
-                .build_blocking()
-                .unwrap()
-        })
-        .await
-        .unwrap();
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        let body: Vec<Record> = vec![record()];
+        let body: Vec<Record> = (0..3).map(|_| record()).collect();
+        let ndjson = body
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap() + "\n")
+            .collect::<String>();
 
         Mock::given(method("GET"))
             .and(path("/records"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .and(header("Accept", "application/x-ndjson"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(ndjson, "application/x-ndjson"))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = tokio::task::spawn_blocking(move || client.get().unwrap())
+        let received: Vec<Record> = client
+            .get_ndjson_stream(String::new())
+            .await
+            .unwrap()
+            .try_collect()
             .await
             .unwrap();
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        assert_eq!(received, body);
     }
 
     #[tokio::test]
-    async fn health_check_succeeds() {
+    async fn get_ndjson_stream_reports_invalid_records_and_continues() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        Mock::given(method("GET"))
-            .and(path("/health_check"))
-            .respond_with(ResponseTemplate::new(200))
-            .expect(1)
-            .mount(&mock_server)
-            .await;
-
-        assert!(client.health_check().await);
-    }
-
-    #[tokio::test]
-    async fn blocking_health_check_succeeds() {
-        let mock_server = MockServer::start().await;
-        let uri = mock_server.uri();
-        let client = tokio::task::spawn_blocking(move || {
-            AuditorClientBuilder::new()
-                .connection_string(&uri)
-                .build_blocking()
-                .unwrap()
-        })
-        .await
-        .unwrap();
+        let valid: Vec<Record> = (0..2).map(|_| record()).collect();
+        // The last line has no trailing newline
+        let ndjson = format!(
+            "{}\n{{\"record_id\": 5}}\n\n{}",
+            serde_json::to_string(&valid[0]).unwrap(),
+            serde_json::to_string(&valid[1]).unwrap()
+        );
 
         Mock::given(method("GET"))
-            .and(path("/health_check"))
-            .respond_with(ResponseTemplate::new(200))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(ndjson, "application/x-ndjson"))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = tokio::task::spawn_blocking(move || client.health_check())
+        let received: Vec<_> = client
+            .get_ndjson_stream(String::new())
             .await
-            .unwrap();
+            .unwrap()
+            .collect()
+            .await;
 
-        assert!(response);
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[0].as_ref().unwrap(), &valid[0]);
+        assert!(matches!(
+            received[1],
+            Err(ClientError::DeserializationError(_))
+        ));
+        assert_eq!(received[2].as_ref().unwrap(), &valid[1]);
     }
 
     #[tokio::test]
-    async fn health_check_fails_on_timeout() {
+    async fn circuit_breaker_fails_fast_and_recovers() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
-            .timeout(1)
+            .circuit_breaker(3, 1)
             .build()
             .unwrap();
 
+        let body: Vec<Record> = vec![record()];
+
         Mock::given(method("GET"))
-            .and(path("/health_check"))
-            .respond_with(
-                ResponseTemplate::new(200).set_delay(
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(500))
+            .up_to_n_times(3)
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        for _ in 0..3 {
+            assert!(matches!(
+                client.get().await,
+                Err(ClientError::ReqwestError(_))
+            ));
+        }
+        assert!(client.is_circuit_open());
+
+        // No request reaches the server during the cool-down
+        for _ in 0..5 {
+            assert!(matches!(client.get().await, Err(ClientError::CircuitOpen)));
+        }
+
+        sleep(std::time::Duration::from_millis(1100)).await;
+
+        let response = client.get().await.unwrap();
+        assert!(!client.is_circuit_open());
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_ignores_client_errors() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .circuit_breaker(1, 60)
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.advanced_query("invalid=1".to_string()).await);
+        assert!(!client.is_circuit_open());
+        assert_err!(client.advanced_query("invalid=1".to_string()).await);
+    }
+
+    #[tokio::test]
+    async fn blocking_get_iter_reports_invalid_record() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let valid: Record = record();
+        let body = format!(
+            "[{}, {{\"record_id\": \"r2\", \"start_time\": \"not a date\"}}]",
+            serde_json::to_string(&valid).unwrap()
+        );
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let (received, result) = tokio::task::spawn_blocking(move || {
+            (client.get_iter().unwrap().collect::<Vec<_>>(), client.get())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].as_ref().unwrap(), &valid);
+        assert!(matches!(
+            received[1],
+            Err(ClientError::DeserializationError(_))
+        ));
+        assert!(matches!(result, Err(ClientError::DeserializationError(_))));
+    }
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(body).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_decompresses_gzip_response() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = (0..3).map(|_| record()).collect();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(header("Accept-Encoding", "gzip"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .set_body_raw(
+                        gzip(&serde_json::to_vec(&body).unwrap()),
+                        "application/json",
+                    ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(client.get().await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn blocking_get_iter_decompresses_gzip_response() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let body: Vec<Record> = (0..3).map(|_| record()).collect();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(header("Accept-Encoding", "gzip"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .set_body_raw(
+                        gzip(&serde_json::to_vec(&body).unwrap()),
+                        "application/json",
+                    ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let received = tokio::task::spawn_blocking(move || {
+            client
+                .get_iter()
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(received, body);
+    }
+
+    #[tokio::test]
+    async fn get_fails_on_cross_host_redirect() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("Location", "http://example.invalid/records"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let result = client.get().await;
+        assert!(
+            matches!(&result, Err(ClientError::UnexpectedRedirect(s)) if s.contains("example.invalid"))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_follows_same_host_redirect() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = (0..3).map(|_| record()).collect();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(301).insert_header("Location", "/moved/records"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/moved/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(client.get().await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn blocking_get_fails_on_cross_host_redirect() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(307)
+                    .insert_header("Location", "http://example.invalid/records"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let result = tokio::task::spawn_blocking(move || client.get())
+            .await
+            .unwrap();
+        assert!(matches!(result, Err(ClientError::UnexpectedRedirect(_))));
+    }
+
+    fn large_record() -> Record {
+        let values = (0..1000)
+            .map(|i| format!("value_{i:05}"))
+            .collect::<Vec<_>>();
+        let record = Record::try_from(
+            Faker
+                .fake::<RecordTest>()
+                .with_meta(HashMap::from([("key".to_string(), values)])),
+        )
+        .unwrap();
+        assert!(serde_json::to_vec(&record).unwrap().len() > 10000);
+        record
+    }
+
+    fn cached_client(mock_server: &MockServer, ttl: Duration) -> AuditorClient {
+        AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .cache_ttl(ttl)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn identical_queries_within_cache_ttl_are_sent_once() {
+        let mock_server = MockServer::start().await;
+        let client = cached_client(&mock_server, Duration::try_minutes(1).unwrap());
+
+        let body: Vec<Record> = vec![record(), record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("limit", "3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        for _ in 0..3 {
+            assert_eq!(
+                client.advanced_query("limit=2".to_string()).await.unwrap(),
+                body
+            );
+        }
+        // Clones share the cache
+        assert_eq!(
+            client
+                .clone()
+                .advanced_query("limit=2".to_string())
+                .await
+                .unwrap(),
+            body
+        );
+        // Different queries are cached separately
+        assert_eq!(
+            client.advanced_query("limit=3".to_string()).await.unwrap(),
+            body
+        );
+    }
+
+    #[tokio::test]
+    async fn cached_records_expire_after_ttl() {
+        let mock_server = MockServer::start().await;
+        let client = cached_client(&mock_server, Duration::try_milliseconds(50).unwrap());
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(client.get().await.unwrap(), body);
+        assert_eq!(client.get().await.unwrap(), body);
+        sleep(std::time::Duration::from_millis(60)).await;
+        assert_eq!(client.get().await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn cache_is_cleared_by_writes() {
+        let mock_server = MockServer::start().await;
+        let client = cached_client(&mock_server, Duration::try_minutes(1).unwrap());
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(5)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PATCH"))
+            .and(path("/record/r1"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/record/r1"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client.get().await.unwrap();
+        client.get().await.unwrap();
+
+        client.add(&record()).await.unwrap();
+        client.get().await.unwrap();
+
+        client.update(&record()).await.unwrap();
+        client.get().await.unwrap();
+
+        client
+            .patch("r1", &serde_json::json!({ "meta": {} }))
+            .await
+            .unwrap();
+        client.get().await.unwrap();
+
+        client.delete("r1").await.unwrap();
+        client.get().await.unwrap();
+        client.get().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn responses_of_queries_overlapping_writes_are_not_cached() {
+        let mock_server = MockServer::start().await;
+        let client = cached_client(&mock_server, Duration::try_minutes(1).unwrap());
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(&body)
+                    .set_delay(std::time::Duration::from_millis(200)),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/record/r1"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let (records, deleted) = tokio::join!(client.get(), async {
+            sleep(std::time::Duration::from_millis(50)).await;
+            client.delete("r1").await
+        });
+        records.unwrap();
+        deleted.unwrap();
+
+        // The response may predate the delete, hence it must not have been cached
+        client.get().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_queries_are_not_cached() {
+        let mock_server = MockServer::start().await;
+        let client = cached_client(&mock_server, Duration::try_minutes(1).unwrap());
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.get().await);
+        assert_err!(client.get().await);
+    }
+
+    #[tokio::test]
+    async fn get_succeeds_for_large_records() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![large_record(), large_record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(client.get().await.unwrap(), body);
+        assert_eq!(client.advanced_query(String::new()).await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn get_shares_component_names() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = (0..3)
+            .map(|_| {
+                let mut record: Record = record();
+                record.components = Some(vec![auditor::domain::Component::new("CPU", 1).unwrap()]);
+                record
+            })
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        for records in [
+            client.get().await.unwrap(),
+            client.advanced_query(String::new()).await.unwrap(),
+        ] {
+            assert_eq!(records, body);
+            let names = records
+                .iter()
+                .map(|r| r.components.as_ref().unwrap()[0].name.as_ref().as_ptr())
+                .collect::<std::collections::HashSet<_>>();
+            assert_eq!(names.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn blocking_get_succeeds_for_large_records() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let body: Vec<Record> = vec![large_record(), large_record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response = tokio::task::spawn_blocking(move || client.get().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response, body);
+    }
+
+    #[tokio::test]
+    async fn blocking_get_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response = tokio::task::spawn_blocking(move || client.get().unwrap())
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn health_check_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(client.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn status_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check/status"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "database": "ok",
+                "tls": "disabled",
+                "maintenance_mode": true,
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(
+            client.status().await.unwrap(),
+            ServerStatus {
+                database: ComponentStatus::Ok,
+                tls: ComponentStatus::Disabled,
+                maintenance_mode: true,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn status_reports_unavailable_database() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check/status"))
+            .respond_with(ResponseTemplate::new(503).set_body_json(serde_json::json!({
+                "database": "unavailable",
+                "tls": "ok",
+                "maintenance_mode": false,
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(
+            client.status().await.unwrap(),
+            ServerStatus {
+                database: ComponentStatus::Unavailable,
+                tls: ComponentStatus::Ok,
+                maintenance_mode: false,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn status_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.status().await);
+    }
+
+    #[tokio::test]
+    async fn blocking_health_check_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response = tokio::task::spawn_blocking(move || client.health_check())
+            .await
+            .unwrap();
+
+        assert!(response);
+    }
+
+    #[tokio::test]
+    async fn health_check_fails_on_timeout() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .timeout(1)
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_delay(
+                    Duration::try_seconds(180)
+                        .expect("This should never fail")
+                        .to_std()
+                        .expect("This should never fail"),
+                ),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(!client.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn blocking_health_check_fails_on_timeout() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .timeout(1)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_delay(
                     Duration::try_seconds(180)
                         .expect("This should never fail")
                         .to_std()
@@ -2089,17 +5138,1801 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        assert!(!client.health_check().await);
+        let response = tokio::task::spawn_blocking(move || client.health_check())
+            .await
+            .unwrap();
+
+        assert!(!response);
+    }
+
+    #[tokio::test]
+    async fn ping_returns_round_trip_time() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(
+                ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let rtt = client.ping().await.unwrap();
+
+        assert!(rtt >= std::time::Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn ping_fails_when_server_is_down() {
+        // Nothing is listening on this port anymore
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let client = AuditorClientBuilder::new()
+            .address(&"127.0.0.1", port)
+            .build()
+            .unwrap();
+
+        assert_err!(client.ping().await);
+    }
+
+    #[tokio::test]
+    async fn health_check_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .timeout(1)
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(!client.health_check().await);
+    }
+
+    #[tokio::test]
+    async fn blocking_health_check_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .timeout(1)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/health_check"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response = tokio::task::spawn_blocking(move || client.health_check())
+            .await
+            .unwrap();
+
+        assert!(!response);
+    }
+
+    #[tokio::test]
+    async fn add_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = client.add(&record).await;
+    }
+
+    async fn mock_add_and_get(mock_server: &MockServer, received: &Record) {
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("/record/{}", received.record_id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(received))
+            .expect(1)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn add_and_verify_succeeds_for_unchanged_record() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record = RecordAdd::new("r1", HashMap::new(), vec![], Utc::now())
+            .unwrap()
+            .with_stop_time(Utc::now());
+        let mut received = Record::from(record.clone());
+        received.updated_at = Some(Utc::now());
+        mock_add_and_get(&mock_server, &received).await;
+
+        assert_ok!(client.add_and_verify(&record).await);
+    }
+
+    #[tokio::test]
+    async fn add_and_verify_reports_mangled_fields() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record = RecordAdd::new(
+            "r1",
+            HashMap::from([("site_id", vec!["site-1"])]),
+            vec![],
+            Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+        )
+        .unwrap();
+        // The server mangled the meta value
+        let mut received = Record::from(record.clone());
+        let mut meta = auditor::domain::Meta::new();
+        meta.insert("site_id".to_string(), vec!["site_1".to_string()]);
+        received.meta = Some(meta);
+        mock_add_and_get(&mock_server, &received).await;
+
+        match client.add_and_verify(&record).await {
+            Err(VerifyError::Mismatch {
+                record_id,
+                differences,
+            }) => {
+                assert_eq!(record_id, "r1");
+                assert_eq!(differences.len(), 1);
+                assert_eq!(differences[0].field, "meta");
+                assert!(differences[0].sent.contains("site-1"));
+                assert!(differences[0].received.contains("site_1"));
+            }
+            other => panic!("Expected a mismatch, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn add_and_verify_fails_if_record_cannot_be_added() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let record: RecordAdd = record();
+        assert!(matches!(
+            client.add_and_verify(&record).await,
+            Err(VerifyError::Client(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn get_and_add_use_their_own_timeouts() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .timeout(1)
+            .with_get_timeout(10)
+            .with_write_timeout(1)
+            .build()
+            .unwrap();
+        let delay = std::time::Duration::from_secs(2);
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(Vec::<Record>::new())
+                    .set_delay(delay),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200).set_delay(delay))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(client.get().await.unwrap().is_empty());
+        match client.add(&record()).await {
+            Err(ClientError::ReqwestError(e)) => assert!(e.is_timeout()),
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    // ATM a send is triggered on creation of `QueuedAuditorClient`,
+    // so we don't *need* waits as long as `QueuedAuditorClient::stop` is called.
+    // This is however highly implementation specific (number of awaits in each
+    // code path and usage of tokios `rt` runtime).
+    // So we set a low `send_interval` and wait after client.add.
+    // Same is true for the other queued tests.
+    #[tokio::test]
+    async fn queued_add_succeeds() {
+        let mock_server = MockServer::start().await;
+        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        let mut client = client_builder.build_queued().await.unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = client.add(&record).await;
+        sleep(std::time::Duration::from_millis(100)).await;
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_client_opens_circuit_when_server_is_down() {
+        // Nothing is listening on this port anymore
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let mut client_builder = AuditorClientBuilder::new()
+            .address(&"127.0.0.1", port)
+            .circuit_breaker(1, 60);
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        let mut client = client_builder.build_queued().await.unwrap();
+
+        assert!(!client.is_circuit_open());
+
+        client.add(&record()).await.unwrap();
+        sleep(std::time::Duration::from_millis(200)).await;
+
+        assert!(client.is_circuit_open());
+        client.stop().await.unwrap();
+    }
+
+    // Builds a queued client whose records stay in the queue because the server is down.
+    async fn queue_limited_client(policy: QueueFullPolicy) -> QueuedAuditorClient {
+        // Nothing is listening on this port anymore
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        AuditorClientBuilder::new()
+            .address(&"127.0.0.1", port)
+            .max_queue_size(3)
+            .queue_full_policy(policy)
+            .build_queued()
+            .await
+            .unwrap()
+    }
+
+    async fn queued_record_ids(client: &QueuedAuditorClient) -> Vec<String> {
+        client
+            .database
+            .get_inserts()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(_, r)| r.record_id.to_string())
+            .collect()
+    }
+
+    fn record_ids(records: &[RecordAdd]) -> Vec<String> {
+        records.iter().map(|r| r.record_id.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn queued_client_drops_oldest_records_when_queue_is_full() {
+        let mut client = queue_limited_client(QueueFullPolicy::DropOldest).await;
+        let records: Vec<RecordAdd> = (0..6).map(|_| record()).collect();
+
+        for r in &records[..4] {
+            client.add(r).await.unwrap();
+        }
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[1..4]));
+
+        client.bulk_insert(&records[4..]).await.unwrap();
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[3..6]));
+
+        client.bulk_insert(&records).await.unwrap();
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[3..6]));
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_client_drops_newest_records_when_queue_is_full() {
+        let mut client = queue_limited_client(QueueFullPolicy::DropNewest).await;
+        let records: Vec<RecordAdd> = (0..6).map(|_| record()).collect();
+
+        client.bulk_insert(&records[..2]).await.unwrap();
+        client.bulk_insert(&records[2..4]).await.unwrap();
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[..3]));
+
+        client.add(&records[4]).await.unwrap();
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[..3]));
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_client_returns_error_when_queue_is_full() {
+        let mut client = queue_limited_client(QueueFullPolicy::ReturnError).await;
+        let records: Vec<RecordAdd> = (0..4).map(|_| record()).collect();
+
+        client.bulk_insert(&records[..2]).await.unwrap();
+        assert!(matches!(
+            client.bulk_insert(&records[2..]).await,
+            Err(ClientError::QueueFull)
+        ));
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[..2]));
+
+        client.add(&records[2]).await.unwrap();
+        assert!(matches!(
+            client.add(&records[3]).await,
+            Err(ClientError::QueueFull)
+        ));
+        assert_eq!(queued_record_ids(&client).await, record_ids(&records[..3]));
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn queued_client_sends_background_errors_to_subscribers() {
+        let mock_server = MockServer::start().await;
+        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        let mut client = client_builder.build_queued().await.unwrap();
+        let mut errors = client.subscribe_errors();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&mock_server)
+            .await;
+
+        client.add(&record()).await.unwrap();
+        let error = tokio::time::timeout(std::time::Duration::from_secs(1), errors.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        match error.as_ref() {
+            ClientError::ReqwestError(e) => {
+                assert_eq!(e.status(), Some(reqwest::StatusCode::SERVICE_UNAVAILABLE))
+            }
+            e => panic!("Unexpected error: {e:?}"),
+        }
+        client.stop().await.unwrap();
+    }
+
+    #[test]
+    fn send_backoff_grows_up_to_max_interval_and_resets() {
+        let mut backoff = SendBackoff::new(
+            std::time::Duration::from_secs(10),
+            std::time::Duration::from_secs(60),
+        );
+
+        let mut intervals = vec![];
+        for _ in 0..4 {
+            backoff.record_failure();
+            intervals.push(backoff.interval().as_secs());
+        }
+        assert_eq!(intervals, vec![20, 40, 60, 60]);
+
+        backoff.record_success();
+        assert_eq!(backoff.interval().as_secs(), 10);
+    }
+
+    // Waits until the mock server received `count` requests and returns when that happened.
+    async fn wait_for_requests(mock_server: &MockServer, count: usize) -> tokio::time::Instant {
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while mock_server.received_requests().await.unwrap().len() < count {
+                sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::Instant::now()
+    }
+
+    #[tokio::test]
+    async fn queued_client_backs_off_on_failures_and_recovers() {
+        let mock_server = MockServer::start().await;
+        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        client_builder.max_send_interval = chrono::Duration::try_milliseconds(1600);
+        let mut client = client_builder.build_queued().await.unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(3)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&mock_server)
+            .await;
+
+        let start = tokio::time::Instant::now();
+        client.add(&record()).await.unwrap();
+        // Fails after 50ms, then retries after 100ms, 200ms and 400ms
+        let recovered = wait_for_requests(&mock_server, 4).await;
+        assert!(recovered - start >= std::time::Duration::from_millis(700));
+
+        // Without reset, the next attempt would happen after 800ms
+        client.add(&record()).await.unwrap();
+        let sent = wait_for_requests(&mock_server, 5).await;
+        assert!(sent - recovered < std::time::Duration::from_millis(400));
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn default_headers_are_sent_with_every_request() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .default_header("X-Api-Key", "secret")
+            .default_header("X-Tenant", "atlas")
+            .build()
+            .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("X-Api-Key", "secret"))
+            .and(header("X-Tenant", "atlas"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(header("X-Api-Key", "secret"))
+            .and(header("X-Tenant", "atlas"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Vec::<Record>::new()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client.add(&record()).await.unwrap();
+        assert!(client.get().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn blocking_client_sends_default_headers() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .default_header("X-Api-Key", "secret")
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("X-Api-Key", "secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        tokio::task::spawn_blocking(move || client.add(&record()))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn bearer_token_is_sent_with_every_request() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .bearer_token("secret")
+            .build()
+            .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("Authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(header("Authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Vec::<Record>::new()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client.add(&record()).await.unwrap();
+        assert!(client.get().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn add_fails_on_401() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .bearer_token("wrong")
+            .build()
+            .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(
+                ResponseTemplate::new(401).insert_header(ERROR_CODE_HEADER, "UNAUTHORIZED"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        match client.add(&record()).await {
+            Err(ClientError::ReqwestError(e)) => {
+                assert_eq!(e.status(), Some(reqwest::StatusCode::UNAUTHORIZED))
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_default_headers_are_rejected() {
+        for (name, value) in [("X Api Key", "secret"), ("X-Api-Key", "sec\nret")] {
+            let result = AuditorClientBuilder::new()
+                .default_header(name, value)
+                .build();
+            assert!(
+                matches!(result, Err(ClientError::InvalidHeader(_))),
+                "Header: {name}: {value}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn add_fails_on_503() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("MAINTENANCE_MODE"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        match client.add(&record()).await {
+            Err(ClientError::ReqwestError(e)) => {
+                assert_eq!(e.status(), Some(reqwest::StatusCode::SERVICE_UNAVAILABLE))
+            }
+            result => panic!("Unexpected result: {result:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn queued_add_retries_on_503() {
+        let mock_server = MockServer::start().await;
+        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        let mut client = client_builder.build_queued().await.unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(503).set_body_string("MAINTENANCE_MODE"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client.add(&record).await.unwrap();
+        sleep(std::time::Duration::from_millis(200)).await;
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocking_add_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = tokio::task::spawn_blocking(move || client.add(&record))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn add_fails_on_existing_record() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500).set_body_string(ERR_RECORD_EXISTS))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.add(&record).await);
+    }
+
+    #[tokio::test]
+    async fn blocking_add_fails_on_existing_record() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500).set_body_string(ERR_RECORD_EXISTS))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = tokio::task::spawn_blocking(move || client.add(&record))
+            .await
+            .unwrap();
+        assert_err!(res);
+    }
+
+    #[tokio::test]
+    async fn add_maps_error_code_regardless_of_body() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(any())
+            .respond_with(
+                ResponseTemplate::new(500)
+                    .insert_header(ERROR_CODE_HEADER, "RECORD_EXISTS")
+                    .set_body_string("A record with this ID already exists"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(matches!(
+            client.add(&record).await,
+            Err(ClientError::RecordExists)
+        ));
+    }
+
+    #[tokio::test]
+    async fn add_prefers_error_code_over_body() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(any())
+            .respond_with(
+                ResponseTemplate::new(500)
+                    .insert_header(ERROR_CODE_HEADER, "UNEXPECTED_ERROR")
+                    .set_body_string(ERR_RECORD_EXISTS),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(!matches!(
+            client.add(&record).await,
+            Err(ClientError::RecordExists)
+        ));
+    }
+
+    #[tokio::test]
+    async fn blocking_bulk_insert_maps_error_code_regardless_of_body() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let records: Vec<RecordAdd> = (0..2).map(|_| record()).collect();
+
+        Mock::given(any())
+            .respond_with(
+                ResponseTemplate::new(500)
+                    .insert_header(ERROR_CODE_HEADER, "RECORD_EXISTS")
+                    .set_body_string("Duplicate record"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = tokio::task::spawn_blocking(move || client.bulk_insert(&records))
+            .await
+            .unwrap();
+        assert!(matches!(res, Err(ClientError::RecordExists)));
+    }
+
+    #[tokio::test]
+    async fn update_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordUpdate = record();
+
+        Mock::given(method("PUT"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = client.update(&record).await;
+    }
+
+    #[tokio::test]
+    async fn queued_update_succeeds() {
+        let mock_server = MockServer::start().await;
+        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
+        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
+        let mut client = client_builder.build_queued().await.unwrap();
+
+        let record: RecordUpdate = record();
+
+        Mock::given(method("PUT"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = client.update(&record).await;
+        sleep(std::time::Duration::from_millis(100)).await;
+        client.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocking_update_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record: RecordUpdate = record();
+
+        Mock::given(method("PUT"))
+            .and(path("/record"))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&record))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let _res = tokio::task::spawn_blocking(move || client.update(&record))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn patch_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let patch = serde_json::json!({ "meta": { "site_id": ["site2"] } });
+
+        Mock::given(method("PATCH"))
+            .and(path("/record/r1"))
+            .and(header("Content-Type", "application/merge-patch+json"))
+            .and(body_json(&patch))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        client.patch("r1", &patch).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn blocking_patch_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let patch = serde_json::json!({ "meta": { "site_id": ["site2"] } });
+
+        Mock::given(method("PATCH"))
+            .and(path("/record/r1"))
+            .and(header("Content-Type", "application/merge-patch+json"))
+            .and(body_json(&patch))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        tokio::task::spawn_blocking(move || client.patch("r1", &patch))
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn patch_fails_if_disabled() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("PATCH"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = client
+            .patch("r1", &serde_json::json!({ "components": [] }))
+            .await;
+
+        assert_err!(res);
+    }
+
+    #[tokio::test]
+    async fn update_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordUpdate = record();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.update(&record).await);
+    }
+
+    #[tokio::test]
+    async fn add_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert!(matches!(
+            client.add(&record).await,
+            Err(ClientError::ReqwestError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn blocking_bulk_insert_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let records: Vec<RecordAdd> = (0..2).map(|_| record()).collect();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = tokio::task::spawn_blocking(move || client.bulk_insert(&records))
+            .await
+            .unwrap();
+        assert!(matches!(res, Err(ClientError::ReqwestError(_))));
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_counts_failed_inserts() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .circuit_breaker(2, 60)
+            .build()
+            .unwrap();
+
+        let record: RecordAdd = record();
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        for _ in 0..2 {
+            assert_err!(client.add(&record).await);
+        }
+        assert!(client.is_circuit_open());
+        assert!(matches!(
+            client.add(&record).await,
+            Err(ClientError::CircuitOpen)
+        ));
+    }
+
+    #[tokio::test]
+    async fn blocking_update_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record: RecordUpdate = record();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = tokio::task::spawn_blocking(move || client.update(&record))
+            .await
+            .unwrap();
+        assert_err!(res);
+    }
+
+    #[tokio::test]
+    async fn get_advanced_queries_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_start_time(Operator::default().gte(datetime_utc.into()))
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_start_time_and_stop_time_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("stop_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_start_time(Operator::default().gte(datetime_utc.into()))
+            .with_stop_time(Operator::default().gte(datetime_utc.into()))
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_start_time_gte_and_start_time_lte_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_start_time(
+                Operator::default()
+                    .gte(datetime_utc_gte.into())
+                    .lte(datetime_utc_lte.into()),
+            )
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_start_time_gte_and_start_time_lte_runtime_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
+            .and(query_param("runtime[gte]", "100000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+        let runtime: u64 = 100000;
+        let response = QueryBuilder::new()
+            .with_start_time(
+                Operator::default()
+                    .gte(datetime_utc_gte.into())
+                    .lte(datetime_utc_lte.into()),
+            )
+            .with_runtime(Operator::default().gte(runtime.into()))
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_start_time_stop_time_and_runtime_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
+            .and(query_param("runtime[gte]", "100000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+        let runtime_gte: u64 = 100000;
+        let runtime_lte: u64 = 200000;
+        let response = QueryBuilder::new()
+            .with_start_time(
+                Operator::default()
+                    .gte(datetime_utc_gte.into())
+                    .lte(datetime_utc_lte.into()),
+            )
+            .with_stop_time(
+                Operator::default()
+                    .gte(datetime_utc_gte.into())
+                    .lte(datetime_utc_lte.into()),
+            )
+            .with_runtime(
+                Operator::default()
+                    .gte(runtime_gte.into())
+                    .lte(runtime_lte.into()),
+            )
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_ingested_at_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("ingested_at[gte]", "2022-08-03T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_ingested_at(Operator::default().gte(datetime_utc.into()))
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
+    }
+
+    #[tokio::test]
+    async fn get_record_query_with_updated_since_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("updated_since", "2022-08-03T09:47:00Z"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_updated_since(datetime_utc)
+            .get(client)
+            .await
+            .unwrap();
+
+        assert_eq!(response, body);
+    }
+
+    #[test]
+    fn updated_since_is_serialized_as_rfc3339() {
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let query_string = QueryBuilder::new()
+            .with_updated_since(datetime_utc)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            urlencoding::decode(&query_string).unwrap(),
+            "updated_since=2022-08-03T09:47:00Z"
+        );
+    }
+
+    #[test]
+    fn only_finished_is_serialized_as_stopped() {
+        assert_eq!(
+            QueryBuilder::new().only_finished().build().unwrap(),
+            "stopped=true"
+        );
+        assert_eq!(
+            QueryBuilder::new().only_unfinished().build().unwrap(),
+            "stopped=false"
+        );
+        assert_eq!(
+            QueryBuilder::new()
+                .only_unfinished()
+                .only_finished()
+                .limit(5)
+                .build()
+                .unwrap(),
+            "stopped=true&limit=5"
+        );
+    }
+
+    #[tokio::test]
+    async fn ingest_latency_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let stop_time = Utc.with_ymd_and_hms(2022, 8, 3, 10, 0, 0).unwrap();
+        let body: Vec<Record> = (1..=4)
+            .map(|i| {
+                let mut r = record::<Record>();
+                r.stop_time = Some(stop_time);
+                r.ingested_at = Some(stop_time + Duration::seconds(i * 10));
+                r
+            })
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("stop_time[gte]", "2022-08-03T09:00:00+00:00"))
+            .and(query_param("stop_time[lt]", "2022-08-03T11:00:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let since = Utc.with_ymd_and_hms(2022, 8, 3, 9, 0, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2022, 8, 3, 11, 0, 0).unwrap();
+        let latency = client
+            .ingest_latency(&since, &until)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(latency.count, 4);
+        assert_eq!(latency.min, Duration::seconds(10));
+        assert_eq!(latency.max, Duration::seconds(40));
+        assert_eq!(latency.p50, Duration::seconds(20));
+    }
+
+    #[tokio::test]
+    async fn get_advanced_queries_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+
+        assert_err!(
+            QueryBuilder::new()
+                .with_stop_time(Operator::default().gte(datetime_utc_gte.into()))
+                .get(client)
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn count_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/count"))
+            .and(query_param("stop_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(HashMap::from([("count", 42)])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let query_string = QueryBuilder::new()
+            .with_stop_time(Operator::default().gte(datetime_utc.into()))
+            .build()
+            .unwrap();
+
+        assert_eq!(client.count(query_string).await.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn get_enveloped_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let expected: Record = record();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("limit", "1"))
+            .and(query_param("envelope", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "query": { "limit": 1, "envelope": true },
+                "count": 1,
+                "records": [&expected],
+                "truncated": true,
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let envelope = QueryBuilder::new()
+            .limit(1)
+            .get_enveloped(client)
+            .await
+            .unwrap();
+
+        assert_eq!(envelope.count, 1);
+        assert_eq!(envelope.records, vec![expected]);
+        assert!(envelope.truncated);
+        assert_eq!(envelope.query.limit.map(|limit| *limit.as_ref()), Some(1));
+    }
+
+    #[tokio::test]
+    async fn component_names_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/components"))
+            .and(query_param("meta[site_id][c]", "site1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec!["CPU", "GPU", "Memory"]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let query = QueryBuilder::new().with_meta_query(MetaQuery::new().meta_operator(
+            "site_id".to_string(),
+            MetaOperator::default().contains("site1".to_string()),
+        ));
+        let names = query.component_names(client).await.unwrap();
+
+        assert_eq!(
+            names,
+            HashSet::from(["CPU", "GPU", "Memory"].map(String::from))
+        );
+    }
+
+    #[tokio::test]
+    async fn blocking_component_names_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/components"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(vec!["CPU", "GPU"]))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let names = tokio::task::spawn_blocking(move || client.component_names(String::new()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(names, HashSet::from(["CPU", "GPU"].map(String::from)));
+    }
+
+    #[tokio::test]
+    async fn component_names_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.component_names(String::new()).await);
+    }
+
+    #[test]
+    fn started_between_equals_manual_operator() {
+        let from = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+
+        assert_eq!(
+            QueryBuilder::new()
+                .started_between(from, to)
+                .build()
+                .unwrap(),
+            QueryBuilder::new()
+                .with_start_time(Operator::default().gte(from.into()).lte(to.into()))
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn started_after_and_before_equal_manual_operator() {
+        let time = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+
+        assert_eq!(
+            QueryBuilder::new().started_after(time).build().unwrap(),
+            QueryBuilder::new()
+                .with_start_time(Operator::default().gte(time.into()))
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            QueryBuilder::new().started_before(time).build().unwrap(),
+            QueryBuilder::new()
+                .with_start_time(Operator::default().lte(time.into()))
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn stopped_between_equals_manual_operator() {
+        let from = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let to = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+
+        assert_eq!(
+            QueryBuilder::new()
+                .stopped_between(from, to)
+                .limit(10)
+                .build()
+                .unwrap(),
+            QueryBuilder::new()
+                .with_stop_time(Operator::default().gte(from.into()).lte(to.into()))
+                .limit(10)
+                .build()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn record_id_prefix_is_serialized() {
+        assert_eq!(
+            QueryBuilder::new()
+                .with_record_id_prefix("site1-".to_string())
+                .build()
+                .unwrap(),
+            "record_id[prefix]=site1-"
+        );
+        assert_eq!(
+            QueryBuilder::new()
+                .with_record_id_prefix("site1-".to_string())
+                .limit(10)
+                .build()
+                .unwrap(),
+            "limit=10&record_id[prefix]=site1-"
+        );
+    }
+
+    #[test]
+    fn names_are_checked_against_the_shared_limits() {
+        let start_time = Utc::now();
+        let record_add =
+            |record_id: String| RecordAdd::new(record_id, HashMap::new(), vec![], start_time);
+
+        assert_ok!(record_add("a".repeat(MAX_NAME_LENGTH)));
+        assert_err!(record_add("a".repeat(MAX_NAME_LENGTH + 1)));
+        for c in FORBIDDEN_CHARACTERS {
+            assert_err!(record_add(format!("record{c}1")));
+        }
+    }
+
+    #[test]
+    fn query_serialization_error_is_returned() {
+        // Query strings can only be serialized from structs and maps
+        let result = serialize_query(&42);
+
+        assert!(matches!(result, Err(ClientError::QuerySerialization(_))));
+    }
+
+    #[test]
+    fn build_or_empty_equals_build() {
+        let query = QueryBuilder::new()
+            .with_record_id_prefix("site1-".to_string())
+            .limit(10);
+
+        assert_eq!(query.build_or_empty(), query.build().unwrap());
+        assert_eq!(QueryBuilder::new().build_or_empty(), "");
+    }
+
+    #[test]
+    fn record_id_and_prefix_replace_each_other() {
+        assert_eq!(
+            QueryBuilder::new()
+                .with_record_id("record-1".to_string())
+                .with_record_id_prefix("site1-".to_string())
+                .build()
+                .unwrap(),
+            "record_id[prefix]=site1-"
+        );
+        assert_eq!(
+            QueryBuilder::new()
+                .with_record_id_prefix("site1-".to_string())
+                .with_record_id("record-1".to_string())
+                .build()
+                .unwrap(),
+            "record_id=record-1"
+        );
     }
 
     #[tokio::test]
-    async fn blocking_health_check_fails_on_timeout() {
+    async fn advanced_query_with_record_id_prefix_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body: Vec<Record> = vec![record()];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("record_id[prefix]", "site_1%-"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let query_string = QueryBuilder::new()
+            .with_record_id_prefix("site_1%-".to_string())
+            .build()
+            .unwrap();
+
+        assert_eq!(client.advanced_query(query_string).await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn count_meta_query_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/count"))
+            .and(query_param("meta[site_id][c]", "group_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(HashMap::from([("count", 3)])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let count = QueryBuilder::new()
+            .with_meta_query(MetaQuery::new().meta_operator(
+                "site_id".to_string(),
+                MetaOperator::default().contains("group_1".to_string()),
+            ))
+            .count(client)
+            .await
+            .unwrap();
+
+        assert_eq!(count, 3);
+    }
+
+    fn truncated_records_body() -> String {
+        let body = serde_json::to_string(&vec![record::<Record>(), record::<Record>()]).unwrap();
+        body[..body.len() / 2].to_string()
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn get_queries_fail_on_truncated_response() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw(truncated_records_body(), "application/json"),
+            )
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let since = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+
+        assert_err!(client.get().await);
+        assert_err!(client.advanced_query(String::new()).await);
+        assert_err!(client.get_started_since(&since).await);
+        assert_err!(client.get_stopped_since(&since).await);
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
+    async fn blocking_get_queries_fail_on_truncated_response() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
             AuditorClientBuilder::new()
                 .connection_string(&uri)
-                .timeout(1)
                 .build_blocking()
                 .unwrap()
         })
@@ -2107,126 +6940,346 @@ mod tests {
         .unwrap();
 
         Mock::given(method("GET"))
-            .and(path("/health_check"))
+            .and(path("/records"))
             .respond_with(
-                ResponseTemplate::new(200).set_delay(
-                    Duration::try_seconds(180)
-                        .expect("This should never fail")
-                        .to_std()
-                        .expect("This should never fail"),
-                ),
+                ResponseTemplate::new(200)
+                    .set_body_raw(truncated_records_body(), "application/json"),
+            )
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let since = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+
+        tokio::task::spawn_blocking(move || {
+            assert_err!(client.get());
+            assert_err!(client.advanced_query(String::new()));
+            assert_err!(client.get_started_since(&since));
+            assert_err!(client.get_stopped_since(&since));
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn count_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.count(String::new()).await);
+    }
+
+    #[tokio::test]
+    async fn aggregate_runtime_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/aggregate"))
+            .and(query_param("group_by", "group_id"))
+            .and(query_param("sum", "runtime"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "key": "atlas", "sum_runtime": 12345, "count": 42 },
+                { "key": "cms", "sum_runtime": 10, "count": 1 },
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let rows = client
+            .aggregate("group_id", AggregateMetric::Runtime)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                AggregateRow {
+                    key: "atlas".to_string(),
+                    sum: 12345,
+                    count: 42
+                },
+                AggregateRow {
+                    key: "cms".to_string(),
+                    sum: 10,
+                    count: 1
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_component_amount_succeeds() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/aggregate"))
+            .and(query_param("group_by", "site id"))
+            .and(query_param("sum", "component_amount"))
+            .and(query_param("component", "CPU"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "key": "site1", "sum_amount": 64, "count": 8 },
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let rows = client
+            .aggregate(
+                "site id",
+                AggregateMetric::ComponentAmount("CPU".to_string()),
             )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![AggregateRow {
+                key: "site1".to_string(),
+                sum: 64,
+                count: 8
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn aggregate_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = tokio::task::spawn_blocking(move || client.health_check())
+        assert_err!(client.aggregate("group_id", AggregateMetric::Runtime).await);
+    }
+
+    #[tokio::test]
+    async fn blocking_aggregate_runtime_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        Mock::given(method("GET"))
+            .and(path("/records/aggregate"))
+            .and(query_param("group_by", "group_id"))
+            .and(query_param("sum", "runtime"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                { "key": "atlas", "sum_runtime": 12345, "count": 42 },
+            ])))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let rows = tokio::task::spawn_blocking(move || {
+            client.aggregate("group_id", AggregateMetric::Runtime)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(
+            rows,
+            vec![AggregateRow {
+                key: "atlas".to_string(),
+                sum: 12345,
+                count: 42
+            }]
+        );
+    }
+
+    // Records without sites have no meta information at all
+    fn record_with_sites(record_id: &str, sites: &[&str]) -> Record {
+        let mut meta = HashMap::new();
+        if !sites.is_empty() {
+            meta.insert("site_id", sites.to_vec());
+        }
+        Record::try_from(
+            Faker
+                .fake::<RecordTest>()
+                .with_record_id(record_id)
+                .with_meta(meta),
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn get_grouped_by_groups_records_by_meta_value() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let body = vec![
+            record_with_sites("r1", &["site1"]),
+            record_with_sites("r2", &["site2"]),
+            record_with_sites("r3", &["site1", "site2"]),
+            record_with_sites("r4", &[]),
+        ];
+
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("meta[group_id][c][0]", "atlas"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let groups = client
+            .get_grouped_by("meta[group_id][c][0]=atlas".to_string(), "site_id")
             .await
             .unwrap();
 
-        assert!(!response);
+        let record_ids = |key: Option<&str>| -> Vec<String> {
+            groups[&key.map(String::from)]
+                .iter()
+                .map(|r| r.record_id.clone())
+                .collect()
+        };
+        assert_eq!(groups.len(), 3);
+        assert_eq!(record_ids(Some("site1")), vec!["r1", "r3"]);
+        assert_eq!(record_ids(Some("site2")), vec!["r2", "r3"]);
+        assert_eq!(record_ids(None), vec!["r4"]);
     }
 
     #[tokio::test]
-    async fn health_check_fails_on_500() {
+    async fn latest_per_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
-            .timeout(1)
             .build()
             .unwrap();
 
+        let body: Vec<Record> = vec![record(), record()];
+
         Mock::given(method("GET"))
-            .and(path("/health_check"))
+            .and(path("/records/latest"))
+            .and(query_param("key", "group_id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_eq!(client.latest_per("group_id").await.unwrap(), body);
+    }
+
+    #[tokio::test]
+    async fn latest_per_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        Mock::given(any())
             .respond_with(ResponseTemplate::new(500))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        assert!(!client.health_check().await);
+        assert_err!(client.latest_per("group_id").await);
     }
 
     #[tokio::test]
-    async fn blocking_health_check_fails_on_500() {
+    async fn blocking_latest_per_succeeds() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
             AuditorClientBuilder::new()
                 .connection_string(&uri)
-                .timeout(1)
                 .build_blocking()
                 .unwrap()
         })
         .await
         .unwrap();
 
+        let body: Vec<Record> = vec![record()];
+
         Mock::given(method("GET"))
-            .and(path("/health_check"))
-            .respond_with(ResponseTemplate::new(500))
+            .and(path("/records/latest"))
+            .and(query_param("key", "site id"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = tokio::task::spawn_blocking(move || client.health_check())
+        let response = tokio::task::spawn_blocking(move || client.latest_per("site id"))
             .await
+            .unwrap()
             .unwrap();
 
-        assert!(!response);
+        assert_eq!(response, body);
     }
 
     #[tokio::test]
-    async fn add_succeeds() {
+    async fn distinct_meta_values_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let record: RecordAdd = record();
+        let body = vec!["site1".to_string(), "site2".to_string()];
 
-        Mock::given(method("POST"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records/meta/site_id/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = client.add(&record).await;
+        assert_eq!(client.distinct_meta_values("site_id").await.unwrap(), body);
     }
 
-    // ATM a send is triggered on creation of `QueuedAuditorClient`,
-    // so we don't *need* waits as long as `QueuedAuditorClient::stop` is called.
-    // This is however highly implementation specific (number of awaits in each
-    // code path and usage of tokios `rt` runtime).
-    // So we set a low `send_interval` and wait after client.add.
-    // Same is true for the other queued tests.
     #[tokio::test]
-    async fn queued_add_succeeds() {
+    async fn distinct_meta_values_fails_on_400() {
         let mock_server = MockServer::start().await;
-        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
-        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
-        let mut client = client_builder.build_queued().await.unwrap();
-
-        let record: RecordAdd = record();
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        Mock::given(method("POST"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records/meta/site%20id/values"))
+            .respond_with(ResponseTemplate::new(400))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = client.add(&record).await;
-        sleep(std::time::Duration::from_millis(100)).await;
-        client.stop().await.unwrap();
+        assert_err!(client.distinct_meta_values("site id").await);
     }
 
     #[tokio::test]
-    async fn blocking_add_succeeds() {
+    async fn blocking_distinct_meta_values_succeeds() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
@@ -2238,115 +7291,196 @@ mod tests {
         .await
         .unwrap();
 
-        let record: RecordAdd = record();
+        let body = vec!["atlas".to_string()];
 
-        Mock::given(method("POST"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records/meta/group_id/values"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = tokio::task::spawn_blocking(move || client.add(&record))
+        let response = tokio::task::spawn_blocking(move || client.distinct_meta_values("group_id"))
             .await
+            .unwrap()
             .unwrap();
+
+        assert_eq!(response, body);
     }
 
     #[tokio::test]
-    async fn add_fails_on_existing_record() {
+    async fn get_meta_queries_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let record: RecordAdd = record();
+        let body: Vec<Record> = vec![record()];
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500).set_body_string(ERR_RECORD_EXISTS))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("meta[site_id][c]", "group_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        assert_err!(client.add(&record).await);
+        let response = QueryBuilder::new()
+            .with_meta_query(MetaQuery::new().meta_operator(
+                "site_id".to_string(),
+                MetaOperator::default().contains("group_1".to_string()),
+            ))
+            .get(client)
+            .await
+            .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
     }
 
     #[tokio::test]
-    async fn blocking_add_fails_on_existing_record() {
+    async fn get_meta_queries_and_start_time_succeeds() {
         let mock_server = MockServer::start().await;
-        let uri = mock_server.uri();
-        let client = tokio::task::spawn_blocking(move || {
-            AuditorClientBuilder::new()
-                .connection_string(&uri)
-                .build_blocking()
-                .unwrap()
-        })
-        .await
-        .unwrap();
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        let record: RecordAdd = record();
+        let body: Vec<Record> = vec![record()];
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500).set_body_string(ERR_RECORD_EXISTS))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("meta[site_id][c]", "group_1"))
+            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let res = tokio::task::spawn_blocking(move || client.add(&record))
+        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+        let response = QueryBuilder::new()
+            .with_meta_query(MetaQuery::new().meta_operator(
+                "site_id".to_string(),
+                MetaOperator::default().contains("group_1".to_string()),
+            ))
+            .with_start_time(Operator::default().lte(datetime_utc_lte.into()))
+            .get(client)
             .await
             .unwrap();
-        assert_err!(res);
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
     }
 
     #[tokio::test]
-    async fn update_succeeds() {
+    async fn get_component_queries_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let record: RecordUpdate = record();
+        let body: Vec<Record> = vec![record()];
 
-        Mock::given(method("PUT"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("component[cpu][equals]", "4"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = client.update(&record).await;
+        let count: u8 = 4;
+        let response =
+            QueryBuilder::new()
+                .with_component_query(ComponentQuery::new().component_operator(
+                    "cpu".to_string(),
+                    Operator::default().equals(count.into()),
+                ))
+                .get(client)
+                .await
+                .unwrap();
+
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
     }
 
     #[tokio::test]
-    async fn queued_update_succeeds() {
+    async fn get_component_score_queries_succeeds() {
         let mock_server = MockServer::start().await;
-        let mut client_builder = AuditorClientBuilder::new().connection_string(&mock_server.uri());
-        client_builder.send_interval = chrono::Duration::try_milliseconds(50).unwrap();
-        let mut client = client_builder.build_queued().await.unwrap();
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        let record: RecordUpdate = record();
+        let body: Vec<Record> = vec![record()];
 
-        Mock::given(method("PUT"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("component[CPU][score][HEPSPEC06][gt]", "10.5"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = client.update(&record).await;
-        sleep(std::time::Duration::from_millis(100)).await;
-        client.stop().await.unwrap();
+        let response = QueryBuilder::new()
+            .with_component_query(ComponentQuery::new().score_operator(
+                "CPU".to_string(),
+                "HEPSPEC06".to_string(),
+                Operator::default().gt(10.5.into()),
+            ))
+            .get(client)
+            .await
+            .unwrap();
+
+        assert_eq!(response, body);
+    }
+
+    #[test]
+    fn component_amount_and_score_are_serialized_together() {
+        let count: u8 = 4;
+        let query_string = QueryBuilder::new()
+            .with_component_query(
+                ComponentQuery::new()
+                    .component_operator("CPU".to_string(), Operator::default().equals(count.into()))
+                    .score_operator(
+                        "CPU".to_string(),
+                        "HEPSPEC06".to_string(),
+                        Operator::default().gte(10.0.into()).lt(20.0.into()),
+                    ),
+            )
+            .build()
+            .unwrap();
+
+        let mut params = urlencoding::decode(&query_string)
+            .unwrap()
+            .split('&')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        params.sort();
+
+        assert_eq!(
+            params,
+            vec![
+                "component[CPU][equals]=4",
+                "component[CPU][score][HEPSPEC06][gte]=10",
+                "component[CPU][score][HEPSPEC06][lt]=20",
+            ]
+        );
     }
 
     #[tokio::test]
-    async fn blocking_update_succeeds() {
+    async fn blocking_count_succeeds() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
@@ -2358,43 +7492,59 @@ mod tests {
         .await
         .unwrap();
 
-        let record: RecordUpdate = record();
-
-        Mock::given(method("PUT"))
-            .and(path("/record"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&record))
-            .respond_with(ResponseTemplate::new(200))
+        Mock::given(method("GET"))
+            .and(path("/records/count"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(HashMap::from([("count", 7)])))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = tokio::task::spawn_blocking(move || client.update(&record))
+        let count = tokio::task::spawn_blocking(move || client.count(String::new()))
             .await
+            .unwrap()
             .unwrap();
+
+        assert_eq!(count, 7);
     }
 
     #[tokio::test]
-    async fn update_fails_on_500() {
+    async fn blocking_get_enveloped_succeeds() {
         let mock_server = MockServer::start().await;
-        let client = AuditorClientBuilder::new()
-            .connection_string(&mock_server.uri())
-            .build()
-            .unwrap();
-
-        let record: RecordUpdate = record();
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("envelope", "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "query": { "envelope": true },
+                "count": 0,
+                "records": [],
+                "truncated": false,
+            })))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        assert_err!(client.update(&record).await);
+        let envelope = tokio::task::spawn_blocking(move || client.get_enveloped(String::new()))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(envelope.count, 0);
+        assert!(envelope.records.is_empty());
+        assert!(!envelope.truncated);
     }
 
     #[tokio::test]
-    async fn blocking_update_fails_on_500() {
+    async fn blocking_advanced_queries_succeeds() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
@@ -2406,22 +7556,37 @@ mod tests {
         .await
         .unwrap();
 
-        let record: RecordUpdate = record();
+        let body: Vec<Record> = vec![record()];
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500))
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(query_param("stop_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let res = tokio::task::spawn_blocking(move || client.update(&record))
+        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        let query_string = QueryBuilder::new()
+            .with_stop_time(Operator::default().gte(datetime_utc.into()))
+            .build()
+            .unwrap();
+
+        let response = tokio::task::spawn_blocking(move || client.advanced_query(query_string))
             .await
+            .unwrap()
             .unwrap();
-        assert_err!(res);
+
+        println!("{:?}", &response);
+        response
+            .into_iter()
+            .zip(body)
+            .map(|(rr, br)| assert_eq!(rr, br))
+            .count();
     }
 
     #[tokio::test]
-    async fn get_advanced_queries_succeeds() {
+    async fn get_sort_by_query_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
@@ -2432,15 +7597,14 @@ mod tests {
 
         Mock::given(method("GET"))
             .and(path("/records"))
-            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("sort_by[asc]", "start_time"))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
         let response = QueryBuilder::new()
-            .with_start_time(Operator::default().gte(datetime_utc.into()))
+            .sort_by(SortBy::new().ascending("start_time".to_string()))
             .get(client)
             .await
             .unwrap();
@@ -2453,7 +7617,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn get_record_query_with_start_time_and_stop_time_succeeds() {
+    async fn get_sort_by_component_query_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
@@ -2464,30 +7628,38 @@ mod tests {
 
         Mock::given(method("GET"))
             .and(path("/records"))
-            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
-            .and(query_param("stop_time[gte]", "2022-08-03T09:47:00+00:00"))
+            .and(query_param("sort_by[desc][component]", "GPU"))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
         let response = QueryBuilder::new()
-            .with_start_time(Operator::default().gte(datetime_utc.into()))
-            .with_stop_time(Operator::default().gte(datetime_utc.into()))
+            .sort_by(SortBy::by_component("GPU", SortDirection::Descending))
             .get(client)
             .await
             .unwrap();
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        assert_eq!(response, body);
+    }
+
+    #[test]
+    fn sort_by_component_is_serialized_per_direction() {
+        for (direction, expected) in [
+            (SortDirection::Ascending, "sort_by[asc][component]=CPU"),
+            (SortDirection::Descending, "sort_by[desc][component]=CPU"),
+        ] {
+            let query_string = QueryBuilder::new()
+                .sort_by(SortBy::by_component("CPU", direction))
+                .build()
+                .unwrap();
+
+            assert_eq!(urlencoding::decode(&query_string).unwrap(), expected);
+        }
     }
 
     #[tokio::test]
-    async fn get_record_query_with_start_time_gte_and_start_time_lte_succeeds() {
+    async fn limit_get_query_records_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
@@ -2498,21 +7670,16 @@ mod tests {
 
         Mock::given(method("GET"))
             .and(path("/records"))
-            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
-            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
+            .and(query_param("limit", "500"))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
-        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
+        let number: u64 = 500;
         let response = QueryBuilder::new()
-            .with_start_time(
-                Operator::default()
-                    .gte(datetime_utc_gte.into())
-                    .lte(datetime_utc_lte.into()),
-            )
+            .sort_by(SortBy::new().ascending("start_time".to_string()))
+            .limit(number)
             .get(client)
             .await
             .unwrap();
@@ -2525,7 +7692,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn get_record_query_with_start_time_gte_and_start_time_lte_runtime_succeeds() {
+    async fn get_exact_record_using_record_id_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
@@ -2536,24 +7703,14 @@ mod tests {
 
         Mock::given(method("GET"))
             .and(path("/records"))
-            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
-            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
-            .and(query_param("runtime[gte]", "100000"))
+            .and(query_param("record_id", "r1"))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
-        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
-        let runtime: u64 = 100000;
         let response = QueryBuilder::new()
-            .with_start_time(
-                Operator::default()
-                    .gte(datetime_utc_gte.into())
-                    .lte(datetime_utc_lte.into()),
-            )
-            .with_runtime(Operator::default().gte(runtime.into()))
+            .with_record_id("r1".to_string())
             .get(client)
             .await
             .unwrap();
@@ -2566,403 +7723,477 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn get_record_query_with_start_time_stop_time_and_runtime_succeeds() {
+    async fn get_single_record_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
+        let record_id: &str = "r3";
+
+        let body: Record = record();
 
         Mock::given(method("GET"))
-            .and(path("/records"))
-            .and(query_param("start_time[gte]", "2022-08-03T09:47:00+00:00"))
-            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
-            .and(query_param("runtime[gte]", "100000"))
+            .and(path("/record/r3"))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
-        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
-        let runtime_gte: u64 = 100000;
-        let runtime_lte: u64 = 200000;
-        let response = QueryBuilder::new()
-            .with_start_time(
-                Operator::default()
-                    .gte(datetime_utc_gte.into())
-                    .lte(datetime_utc_lte.into()),
-            )
-            .with_stop_time(
-                Operator::default()
-                    .gte(datetime_utc_gte.into())
-                    .lte(datetime_utc_lte.into()),
-            )
-            .with_runtime(
-                Operator::default()
-                    .gte(runtime_gte.into())
-                    .lte(runtime_lte.into()),
-            )
-            .get(client)
+        let response = client
+            .get_single_record(record_id.to_string())
             .await
             .unwrap();
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        assert_eq!(body, response)
     }
 
     #[tokio::test]
-    async fn get_advanced_queries_fails_on_500() {
+    async fn blocking_get_single_record_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record_id: &str = "r3";
+
+        let body: Record = record();
+
+        Mock::given(method("GET"))
+            .and(path("/record/r3"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response =
+            tokio::task::spawn_blocking(move || client.get_single_record(record_id).unwrap())
+                .await
+                .unwrap();
+
+        assert_eq!(body, response)
+    }
+
+    #[tokio::test]
+    async fn get_single_record_fails_on_500() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
+        let record_id: &str = "r3";
+
         Mock::given(any())
             .respond_with(ResponseTemplate::new(500))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc_gte = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
+        assert_err!(client.get_single_record(record_id.to_string()).await);
+    }
 
-        assert_err!(
-            QueryBuilder::new()
-                .with_stop_time(Operator::default().gte(datetime_utc_gte.into()))
-                .get(client)
-                .await
-        );
+    #[tokio::test]
+    async fn blocking_get_single_record_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record_id: &str = "r3";
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let res = tokio::task::spawn_blocking(move || client.get_single_record(record_id))
+            .await
+            .unwrap();
+        assert_err!(res);
     }
 
     #[tokio::test]
-    async fn get_meta_queries_succeeds() {
+    async fn get_records_by_ids_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
+        let record_ids = vec!["r2".to_string(), "r1".to_string()];
 
-        Mock::given(method("GET"))
-            .and(path("/records"))
-            .and(query_param("meta[site_id][c]", "group_1"))
+        let body: Vec<Record> = vec![record(), record()];
+
+        Mock::given(method("POST"))
+            .and(path("/records/by-ids"))
+            .and(body_json(&record_ids))
             .respond_with(ResponseTemplate::new(200).set_body_json(&body))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = QueryBuilder::new()
-            .with_meta_query(MetaQuery::new().meta_operator(
-                "site_id".to_string(),
-                MetaOperator::default().contains("group_1".to_string()),
-            ))
-            .get(client)
-            .await
+        let response = client.get_records_by_ids(&record_ids).await.unwrap();
+
+        assert_eq!(body, response)
+    }
+
+    #[tokio::test]
+    async fn blocking_get_records_by_ids_succeeds() {
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
+
+        let record_ids = vec!["r2".to_string(), "r1".to_string()];
+
+        let body: Vec<Record> = vec![record(), record()];
+
+        Mock::given(method("POST"))
+            .and(path("/records/by-ids"))
+            .and(body_json(&record_ids))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let response =
+            tokio::task::spawn_blocking(move || client.get_records_by_ids(&record_ids).unwrap())
+                .await
+                .unwrap();
+
+        assert_eq!(body, response)
+    }
+
+    #[tokio::test]
+    async fn get_records_by_ids_fails_on_500() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
             .unwrap();
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        assert_err!(client.get_records_by_ids(&["r1".to_string()]).await);
     }
 
     #[tokio::test]
-    async fn get_meta_queries_and_start_time_succeeds() {
+    async fn bulk_insert_succeeds() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
+        let records: Vec<RecordAdd> = (0..10).map(|_| record()).collect();
 
-        Mock::given(method("GET"))
+        Mock::given(method("POST"))
             .and(path("/records"))
-            .and(query_param("meta[site_id][c]", "group_1"))
-            .and(query_param("start_time[lte]", "2022-08-04T09:47:00+00:00"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .and(header("Content-Type", "application/json"))
+            .and(body_json(&records))
+            .respond_with(ResponseTemplate::new(200))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc_lte = Utc.with_ymd_and_hms(2022, 8, 4, 9, 47, 0).unwrap();
-        let response = QueryBuilder::new()
-            .with_meta_query(MetaQuery::new().meta_operator(
-                "site_id".to_string(),
-                MetaOperator::default().contains("group_1".to_string()),
-            ))
-            .with_start_time(Operator::default().lte(datetime_utc_lte.into()))
-            .get(client)
-            .await
-            .unwrap();
+        let _res = client.bulk_insert(&records).await;
+    }
+
+    #[test]
+    fn ingest_throttle_slows_down_under_load_and_recovers() {
+        let mut throttle = IngestThrottle::default();
+        assert!(throttle.delay().is_zero());
+
+        throttle.record_load(0.9);
+        assert_eq!(throttle.delay(), MIN_INGEST_DELAY);
+        throttle.record_load(1.0);
+        assert_eq!(throttle.delay(), MIN_INGEST_DELAY * 2);
+        for _ in 0..20 {
+            throttle.record_load(1.0);
+        }
+        assert_eq!(throttle.delay(), MAX_INGEST_DELAY);
+
+        throttle.record_load(0.5);
+        assert_eq!(throttle.delay(), MAX_INGEST_DELAY / 2);
+        for _ in 0..20 {
+            throttle.record_load(0.0);
+        }
+        assert!(throttle.delay().is_zero());
+    }
+
+    // Responds with the given server load and remembers when the requests were received.
+    struct LoadResponder {
+        load: &'static str,
+        received: Arc<Mutex<Vec<std::time::Instant>>>,
+    }
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+    impl wiremock::Respond for LoadResponder {
+        fn respond(&self, _request: &wiremock::Request) -> ResponseTemplate {
+            self.received
+                .lock()
+                .unwrap()
+                .push(std::time::Instant::now());
+            ResponseTemplate::new(200).insert_header(SERVER_LOAD_HEADER, self.load)
+        }
     }
 
     #[tokio::test]
-    async fn get_component_queries_succeeds() {
+    async fn bulk_insert_stream_sends_all_records_in_batches() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
-
-        Mock::given(method("GET"))
-            .and(path("/records"))
-            .and(query_param("component[cpu][equals]", "4"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
-            .expect(1)
-            .mount(&mock_server)
-            .await;
+        let records: Vec<RecordAdd> = (0..5).map(|_| record()).collect();
 
-        let count: u8 = 4;
-        let response =
-            QueryBuilder::new()
-                .with_component_query(ComponentQuery::new().component_operator(
-                    "cpu".to_string(),
-                    Operator::default().equals(count.into()),
-                ))
-                .get(client)
-                .await
-                .unwrap();
+        for batch in records.chunks(2) {
+            Mock::given(method("POST"))
+                .and(path("/records"))
+                .and(body_json(batch))
+                .respond_with(ResponseTemplate::new(200))
+                .expect(1)
+                .mount(&mock_server)
+                .await;
+        }
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        client
+            .bulk_insert_stream(futures_util::stream::iter(records), 2)
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
-    async fn blocking_advanced_queries_succeeds() {
+    async fn bulk_insert_stream_slows_down_while_server_signals_load() {
         let mock_server = MockServer::start().await;
-        let uri = mock_server.uri();
-        let client = tokio::task::spawn_blocking(move || {
-            AuditorClientBuilder::new()
-                .connection_string(&uri)
-                .build_blocking()
-                .unwrap()
-        })
-        .await
-        .unwrap();
-
-        let body: Vec<Record> = vec![record()];
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        Mock::given(method("GET"))
+        let received = Arc::new(Mutex::new(vec![]));
+        Mock::given(method("POST"))
             .and(path("/records"))
-            .and(query_param("stop_time[gte]", "2022-08-03T09:47:00+00:00"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
-            .expect(1)
+            .respond_with(LoadResponder {
+                load: "1.00",
+                received: received.clone(),
+            })
+            .expect(4)
             .mount(&mock_server)
             .await;
 
-        let datetime_utc = Utc.with_ymd_and_hms(2022, 8, 3, 9, 47, 0).unwrap();
-        let query_string = QueryBuilder::new()
-            .with_stop_time(Operator::default().gte(datetime_utc.into()))
-            .build();
-
-        let response = tokio::task::spawn_blocking(move || client.advanced_query(query_string))
+        let records: Vec<RecordAdd> = (0..8).map(|_| record()).collect();
+        client
+            .bulk_insert_stream(futures_util::stream::iter(records), 2)
             .await
-            .unwrap()
             .unwrap();
 
-        println!("{:?}", &response);
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        let received = received.lock().unwrap();
+        let gaps: Vec<std::time::Duration> = received
+            .windows(2)
+            .map(|w| w[1].duration_since(w[0]))
+            .collect();
+        assert_eq!(gaps.len(), 3);
+        assert!(gaps[0] >= MIN_INGEST_DELAY);
+        assert!(gaps[2] > gaps[0], "Send rate did not decrease: {gaps:?}");
     }
 
     #[tokio::test]
-    async fn get_sort_by_query_succeeds() {
+    async fn bulk_insert_stream_does_not_slow_down_without_load() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
-
-        Mock::given(method("GET"))
+        let received = Arc::new(Mutex::new(vec![]));
+        Mock::given(method("POST"))
             .and(path("/records"))
-            .and(query_param("sort_by[asc]", "start_time"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
-            .expect(1)
+            .respond_with(LoadResponder {
+                load: "0.10",
+                received: received.clone(),
+            })
+            .expect(4)
             .mount(&mock_server)
             .await;
 
-        let response = QueryBuilder::new()
-            .sort_by(SortBy::new().ascending("start_time".to_string()))
-            .get(client)
+        let records: Vec<RecordAdd> = (0..8).map(|_| record()).collect();
+        let start = std::time::Instant::now();
+        client
+            .bulk_insert_stream(futures_util::stream::iter(records), 2)
             .await
             .unwrap();
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        // The throttled client would wait at least 50 + 100 + 200 ms
+        assert!(start.elapsed() < MIN_INGEST_DELAY * 7);
     }
 
     #[tokio::test]
-    async fn limit_get_query_records_succeeds() {
+    async fn bulk_insert_stream_sends_batch_again_after_retry_after() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
-
-        Mock::given(method("GET"))
+        Mock::given(method("POST"))
             .and(path("/records"))
-            .and(query_param("limit", "500"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .up_to_n_times(1)
+            .with_priority(1)
             .expect(1)
             .mount(&mock_server)
             .await;
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
 
-        let number: u64 = 500;
-        let response = QueryBuilder::new()
-            .sort_by(SortBy::new().ascending("start_time".to_string()))
-            .limit(number)
-            .get(client)
+        let records: Vec<RecordAdd> = (0..4).map(|_| record()).collect();
+        client
+            .bulk_insert_stream(futures_util::stream::iter(records), 2)
             .await
             .unwrap();
-
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
     }
 
     #[tokio::test]
-    async fn get_exact_record_using_record_id_succeeds() {
+    async fn bulk_insert_stream_gives_up_if_server_stays_overloaded() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let body: Vec<Record> = vec![record()];
-
-        Mock::given(method("GET"))
+        Mock::given(method("POST"))
             .and(path("/records"))
-            .and(query_param("record_id", "r1"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
-            .expect(1)
+            .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "0"))
+            .expect(MAX_INGEST_RETRIES as u64 + 1)
             .mount(&mock_server)
             .await;
 
-        let response = QueryBuilder::new()
-            .with_record_id("r1".to_string())
-            .get(client)
-            .await
-            .unwrap();
+        let records: Vec<RecordAdd> = (0..2).map(|_| record()).collect();
+        let res = client
+            .bulk_insert_stream(futures_util::stream::iter(records), 2)
+            .await;
 
-        response
-            .into_iter()
-            .zip(body)
-            .map(|(rr, br)| assert_eq!(rr, br))
-            .count();
+        assert_err!(res);
     }
 
     #[tokio::test]
-    async fn get_single_record_succeeds() {
+    async fn bulk_insert_sorts_by_record_id() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
+            .bulk_insert_sort_key(BulkInsertSortKey::RecordId)
             .build()
             .unwrap();
 
-        let record_id: &str = "r3";
-
-        let body: Record = record();
+        let records: Vec<RecordAdd> = (0..10).map(|_| record()).collect();
+        let mut sorted = records.clone();
+        sorted.sort_by(|a, b| a.record_id.cmp(&b.record_id));
 
-        Mock::given(method("GET"))
-            .and(path("/record/r3"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .and(body_json(&sorted))
+            .respond_with(ResponseTemplate::new(200))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response = client
-            .get_single_record(record_id.to_string())
-            .await
-            .unwrap();
-
-        assert_eq!(body, response)
+        client.bulk_insert(&records).await.unwrap();
     }
 
     #[tokio::test]
-    async fn blocking_get_single_record_succeeds() {
+    async fn bulk_insert_returns_all_invalid_records() {
         let mock_server = MockServer::start().await;
-        let uri = mock_server.uri();
-        let client = tokio::task::spawn_blocking(move || {
-            AuditorClientBuilder::new()
-                .connection_string(&uri)
-                .build_blocking()
-                .unwrap()
-        })
-        .await
-        .unwrap();
-
-        let record_id: &str = "r3";
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
 
-        let body: Record = record();
+        let records: Vec<RecordAdd> = (0..3).map(|_| record()).collect();
+        let errors = vec![
+            RecordValidationError {
+                index: 0,
+                reason: "Parsing 'a/b' failed".to_string(),
+            },
+            RecordValidationError {
+                index: 2,
+                reason: "missing field `start_time`".to_string(),
+            },
+        ];
 
-        Mock::given(method("GET"))
-            .and(path("/record/r3"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(&body))
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(&errors))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let response =
-            tokio::task::spawn_blocking(move || client.get_single_record(record_id).unwrap())
-                .await
-                .unwrap();
+        let res = client.bulk_insert(&records).await;
 
-        assert_eq!(body, response)
+        assert!(matches!(res, Err(ClientError::InvalidRecords(received)) if received == errors));
     }
 
     #[tokio::test]
-    async fn get_single_record_fails_on_500() {
+    async fn bulk_insert_returns_records_with_mismatching_runtime() {
         let mock_server = MockServer::start().await;
         let client = AuditorClientBuilder::new()
             .connection_string(&mock_server.uri())
             .build()
             .unwrap();
 
-        let record_id: &str = "r3";
+        let records: Vec<RecordAdd> = (0..2).map(|_| record()).collect();
+        let errors = vec![RecordValidationError {
+            index: 1,
+            reason: "Runtime 42 does not match the expected runtime 3600".to_string(),
+        }];
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500))
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .respond_with(
+                ResponseTemplate::new(422)
+                    .insert_header(ERROR_CODE_HEADER, "VALIDATION_FAILED")
+                    .set_body_json(&errors),
+            )
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        assert_err!(client.get_single_record(record_id.to_string()).await);
+        let res = client.bulk_insert(&records).await;
+
+        assert!(matches!(res, Err(ClientError::InvalidRecords(received)) if received == errors));
     }
 
     #[tokio::test]
-    async fn blocking_get_single_record_fails_on_500() {
+    async fn blocking_bulk_insert_returns_all_invalid_records() {
         let mock_server = MockServer::start().await;
         let uri = mock_server.uri();
         let client = tokio::task::spawn_blocking(move || {
@@ -2974,40 +8205,62 @@ mod tests {
         .await
         .unwrap();
 
-        let record_id: &str = "r3";
+        let records: Vec<RecordAdd> = (0..3).map(|_| record()).collect();
+        let errors = vec![
+            RecordValidationError {
+                index: 1,
+                reason: "Parsing 'a/b' failed".to_string(),
+            },
+            RecordValidationError {
+                index: 2,
+                reason: "missing field `components`".to_string(),
+            },
+        ];
 
-        Mock::given(any())
-            .respond_with(ResponseTemplate::new(500))
+        Mock::given(method("POST"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(&errors))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let res = tokio::task::spawn_blocking(move || client.get_single_record(record_id))
+        let res = tokio::task::spawn_blocking(move || client.bulk_insert(&records))
             .await
             .unwrap();
-        assert_err!(res);
+
+        assert!(matches!(res, Err(ClientError::InvalidRecords(received)) if received == errors));
     }
 
     #[tokio::test]
-    async fn bulk_insert_succeeds() {
+    async fn blocking_bulk_insert_sorts_by_start_time() {
         let mock_server = MockServer::start().await;
-        let client = AuditorClientBuilder::new()
-            .connection_string(&mock_server.uri())
-            .build()
-            .unwrap();
+        let uri = mock_server.uri();
+        let client = tokio::task::spawn_blocking(move || {
+            AuditorClientBuilder::new()
+                .connection_string(&uri)
+                .bulk_insert_sort_key(BulkInsertSortKey::StartTime)
+                .build_blocking()
+                .unwrap()
+        })
+        .await
+        .unwrap();
 
         let records: Vec<RecordAdd> = (0..10).map(|_| record()).collect();
+        let mut sorted = records.clone();
+        sorted.sort_by_key(|r| r.start_time);
 
         Mock::given(method("POST"))
             .and(path("/records"))
-            .and(header("Content-Type", "application/json"))
-            .and(body_json(&records))
+            .and(body_json(&sorted))
             .respond_with(ResponseTemplate::new(200))
             .expect(1)
             .mount(&mock_server)
             .await;
 
-        let _res = client.bulk_insert(&records).await;
+        tokio::task::spawn_blocking(move || client.bulk_insert(&records))
+            .await
+            .unwrap()
+            .unwrap();
     }
 
     /*
@@ -3125,4 +8378,95 @@ mod tests {
             .unwrap();
         assert_err!(res);
     }
+
+    fn test_cert_path(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../scripts/certs")
+            .join(name)
+    }
+
+    #[test]
+    fn build_with_missing_ca_cert_returns_tls_error() {
+        let result = AuditorClientBuilder::new()
+            .with_tls(
+                test_cert_path("client-cert.pem"),
+                test_cert_path("client-key.pem"),
+                test_cert_path("does-not-exist.pem"),
+            )
+            .build();
+
+        assert!(matches!(result, Err(ClientError::TlsError(_))));
+    }
+
+    #[test]
+    fn build_with_missing_client_cert_returns_tls_error() {
+        let result = AuditorClientBuilder::new()
+            .with_tls(
+                test_cert_path("does-not-exist.pem"),
+                test_cert_path("client-key.pem"),
+                test_cert_path("rootCA.pem"),
+            )
+            .build();
+
+        assert!(matches!(result, Err(ClientError::TlsError(_))));
+    }
+
+    #[tokio::test]
+    async fn blocking_build_with_missing_ca_cert_returns_tls_error() {
+        let result = tokio::task::spawn_blocking(|| {
+            AuditorClientBuilder::new()
+                .with_tls(
+                    test_cert_path("client-cert.pem"),
+                    test_cert_path("client-key.pem"),
+                    test_cert_path("does-not-exist.pem"),
+                )
+                .build_blocking()
+        })
+        .await
+        .unwrap();
+
+        assert!(matches!(result, Err(ClientError::TlsError(_))));
+    }
+
+    #[test]
+    fn danger_accept_invalid_certs_is_disabled_by_default() {
+        assert!(!AuditorClientBuilder::new().accept_invalid_certs());
+    }
+
+    #[test]
+    fn danger_accept_invalid_certs_is_applied_without_tls() {
+        let builder = AuditorClientBuilder::new()
+            .connection_string(&"https://localhost:8443")
+            .danger_accept_invalid_certs(true);
+        assert!(builder.accept_invalid_certs());
+
+        assert!(builder.build().is_ok());
+    }
+
+    #[test]
+    fn danger_accept_invalid_certs_is_overridden_by_configured_ca() {
+        let builder = AuditorClientBuilder::new()
+            .with_tls(
+                test_cert_path("client-cert.pem"),
+                test_cert_path("client-key.pem"),
+                test_cert_path("rootCA.pem"),
+            )
+            .danger_accept_invalid_certs(true);
+
+        assert!(!builder.accept_invalid_certs());
+    }
+
+    #[tokio::test]
+    async fn blocking_build_with_danger_accept_invalid_certs_succeeds() {
+        let result = tokio::task::spawn_blocking(|| {
+            AuditorClientBuilder::new()
+                .connection_string(&"https://localhost:8443")
+                .danger_accept_invalid_certs(true)
+                .build_blocking()
+        })
+        .await
+        .unwrap();
+
+        assert!(result.is_ok());
+    }
 }
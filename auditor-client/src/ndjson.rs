@@ -0,0 +1,75 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::ClientError;
+use auditor::domain::Record;
+use futures_util::{stream, Stream};
+
+/// Deserializes the records of a newline-delimited JSON response line by line.
+///
+/// A line which cannot be deserialized into a [`Record`] yields an error, and the stream continues
+/// with the next line. An error while reading the response ends the stream.
+pub(crate) fn record_stream(
+    response: reqwest::Response,
+) -> impl Stream<Item = Result<Record, ClientError>> {
+    let state = LineReader {
+        response,
+        buffer: Vec::new(),
+        finished: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(line) = state.next_line() {
+                if !is_blank(&line) {
+                    return Some((parse_line(&line), state));
+                }
+                continue;
+            }
+            if state.finished {
+                return None;
+            }
+            match state.response.chunk().await {
+                Ok(Some(chunk)) => state.buffer.extend_from_slice(&chunk),
+                Ok(None) => state.finished = true,
+                Err(e) => {
+                    state.finished = true;
+                    state.buffer.clear();
+                    return Some((Err(e.into()), state));
+                }
+            }
+        }
+    })
+}
+
+struct LineReader {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+    finished: bool,
+}
+
+impl LineReader {
+    // Returns the next complete line, or the remainder of the buffer once the response has ended.
+    fn next_line(&mut self) -> Option<Vec<u8>> {
+        match self.buffer.iter().position(|b| *b == b'\n') {
+            Some(i) => Some(self.buffer.drain(..=i).collect()),
+            None if self.finished && !self.buffer.is_empty() => {
+                Some(std::mem::take(&mut self.buffer))
+            }
+            None => None,
+        }
+    }
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+fn parse_line(line: &[u8]) -> Result<Record, ClientError> {
+    serde_json::from_slice(line)
+        .map_err(|e| ClientError::DeserializationError(format!("Invalid record: {e}")))
+}
@@ -0,0 +1,171 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::ClientError;
+use auditor::domain::Record;
+use serde::Deserialize;
+use std::io::BufRead;
+
+/// Lazily deserializes the records of a JSON array read from `reader`.
+///
+/// A record which is valid JSON but cannot be deserialized into a [`Record`] yields an error, and
+/// iteration continues with the next record. Malformed JSON yields an error and ends the iteration.
+pub(crate) struct RecordIter<R> {
+    reader: R,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: BufRead> RecordIter<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        RecordIter {
+            reader,
+            started: false,
+            finished: false,
+        }
+    }
+
+    // Skips whitespace and returns the next byte without consuming it.
+    fn peek(&mut self) -> Result<Option<u8>, ClientError> {
+        loop {
+            let buf = self.reader.fill_buf().map_err(malformed)?;
+            if buf.is_empty() {
+                return Ok(None);
+            }
+            match buf.iter().position(|b| !b.is_ascii_whitespace()) {
+                Some(i) => {
+                    let byte = buf[i];
+                    self.reader.consume(i);
+                    return Ok(Some(byte));
+                }
+                None => {
+                    let len = buf.len();
+                    self.reader.consume(len);
+                }
+            }
+        }
+    }
+
+    // The outer error is fatal, the inner one only concerns a single record.
+    fn next_record(&mut self) -> Result<Option<Result<Record, ClientError>>, ClientError> {
+        if !self.started {
+            self.started = true;
+            match self.peek()? {
+                Some(b'[') => self.reader.consume(1),
+                _ => return Err(malformed("expected a JSON array")),
+            }
+            if self.peek()? == Some(b']') {
+                return Ok(None);
+            }
+        } else {
+            match self.peek()? {
+                Some(b',') => self.reader.consume(1),
+                Some(b']') => return Ok(None),
+                Some(byte) => {
+                    return Err(malformed(format!(
+                        "unexpected character '{}' after record",
+                        byte as char
+                    )))
+                }
+                None => return Err(malformed("unexpected end of JSON array")),
+            }
+        }
+
+        let value = serde_json::Value::deserialize(&mut serde_json::Deserializer::from_reader(
+            &mut self.reader,
+        ))
+        .map_err(malformed)?;
+
+        Ok(Some(Record::deserialize(value).map_err(|e| {
+            ClientError::DeserializationError(format!("Invalid record: {e}"))
+        })))
+    }
+}
+
+impl<R: BufRead> Iterator for RecordIter<R> {
+    type Item = Result<Record, ClientError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.next_record() {
+            Ok(Some(record)) => Some(record),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                // The position in the stream is unknown after malformed JSON
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+fn malformed<E: std::fmt::Display>(error: E) -> ClientError {
+    ClientError::DeserializationError(format!("Malformed JSON: {error}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auditor::domain::RecordTest;
+    use fake::{Fake, Faker};
+
+    fn records(n: usize) -> Vec<Record> {
+        (0..n)
+            .map(|_| Record::try_from(Faker.fake::<RecordTest>()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn yields_all_records() {
+        let expected = records(3);
+        let json = serde_json::to_vec(&expected).unwrap();
+
+        let received = RecordIter::new(json.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(expected, received);
+    }
+
+    #[test]
+    fn empty_array_yields_nothing() {
+        assert_eq!(RecordIter::new(" [ ] ".as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn invalid_record_is_reported_and_iteration_continues() {
+        let valid = records(2);
+        let json = format!(
+            "[{}, {{\"record_id\": 5}}, {}]",
+            serde_json::to_string(&valid[0]).unwrap(),
+            serde_json::to_string(&valid[1]).unwrap()
+        );
+
+        let received: Vec<_> = RecordIter::new(json.as_bytes()).collect();
+
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[0].as_ref().unwrap(), &valid[0]);
+        assert!(matches!(
+            received[1],
+            Err(ClientError::DeserializationError(_))
+        ));
+        assert_eq!(received[2].as_ref().unwrap(), &valid[1]);
+    }
+
+    #[test]
+    fn malformed_json_ends_iteration() {
+        let received: Vec<_> = RecordIter::new("[{\"record_id\": ".as_bytes()).collect();
+
+        assert_eq!(received.len(), 1);
+        assert!(received[0].is_err());
+    }
+}
@@ -0,0 +1,141 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use auditor::domain::Record;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Keeps the records returned for a query string for `ttl`, such that repeating the same query
+/// neither sends a request nor parses the response again.
+///
+/// Clones share their entries.
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    ttl: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Incremented by `clear`, such that responses of requests which were sent before records
+    // changed on the server are not cached afterwards.
+    generation: u64,
+    entries: HashMap<String, Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    cached_at: Instant,
+    records: Vec<Record>,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(ttl: Duration) -> Self {
+        ResponseCache {
+            ttl,
+            state: Arc::new(Mutex::new(State::default())),
+        }
+    }
+
+    /// Returns the records cached for `query_string`, unless they have expired.
+    pub(crate) fn get(&self, query_string: &str) -> Option<Vec<Record>> {
+        self.state
+            .lock()
+            .unwrap()
+            .entries
+            .get(query_string)
+            .filter(|entry| entry.cached_at.elapsed() < self.ttl)
+            .map(|entry| entry.records.clone())
+    }
+
+    /// Returns the current generation, which has to be passed to [`ResponseCache::insert`].
+    pub(crate) fn generation(&self) -> u64 {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Caches `records` for `query_string`, unless the cache was cleared since `generation` was
+    /// retrieved. Expired entries are removed on the way.
+    pub(crate) fn insert(&self, query_string: String, records: Vec<Record>, generation: u64) {
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+            return;
+        }
+        state
+            .entries
+            .retain(|_, entry| entry.cached_at.elapsed() < self.ttl);
+        state.entries.insert(
+            query_string,
+            Entry {
+                cached_at: Instant::now(),
+                records,
+            },
+        );
+    }
+
+    /// Removes all entries, e.g. after records were changed on the server.
+    pub(crate) fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.generation += 1;
+        state.entries.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auditor::domain::RecordTest;
+    use fake::{Fake, Faker};
+
+    fn records() -> Vec<Record> {
+        vec![Faker.fake::<RecordTest>().try_into().unwrap()]
+    }
+
+    #[test]
+    fn returns_fresh_entries() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let records = records();
+
+        cache.insert("limit=1".to_string(), records.clone(), cache.generation());
+
+        assert_eq!(cache.get("limit=1"), Some(records));
+        assert_eq!(cache.get("limit=2"), None);
+    }
+
+    #[test]
+    fn expired_entries_are_not_returned() {
+        let cache = ResponseCache::new(Duration::from_millis(50));
+
+        cache.insert(String::new(), records(), cache.generation());
+        std::thread::sleep(Duration::from_millis(60));
+
+        assert_eq!(cache.get(""), None);
+    }
+
+    #[test]
+    fn clones_share_entries_and_clear_removes_them() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        let clone = cache.clone();
+
+        cache.insert(String::new(), records(), cache.generation());
+        assert!(clone.get("").is_some());
+
+        clone.clear();
+        assert_eq!(cache.get(""), None);
+    }
+
+    #[test]
+    fn responses_requested_before_clear_are_not_cached() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+
+        let generation = cache.generation();
+        cache.clear();
+        cache.insert(String::new(), records(), generation);
+
+        assert_eq!(cache.get(""), None);
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::ClientError;
+use auditor::domain::Record;
+use chrono::{DateTime, SubsecRound, Utc};
+use std::fmt::Debug;
+
+/// Error returned by [`AuditorClient::add_and_verify`](crate::AuditorClient::add_and_verify).
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum VerifyError {
+    /// Adding or fetching the record failed.
+    #[error(transparent)]
+    Client(#[from] ClientError),
+    /// The record returned by Auditor differs from the record that was added.
+    #[error("Record {record_id} was changed by Auditor: {}", join(.differences))]
+    Mismatch {
+        record_id: String,
+        differences: Vec<FieldDifference>,
+    },
+}
+
+/// A field which differs between the record that was sent to Auditor and the record that was
+/// returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDifference {
+    /// Name of the field, e.g. `meta`.
+    pub field: &'static str,
+    /// Sent value, formatted with `Debug`.
+    pub sent: String,
+    /// Returned value, formatted with `Debug`.
+    pub received: String,
+}
+
+impl std::fmt::Display for FieldDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} sent as {} but received as {}",
+            self.field, self.sent, self.received
+        )
+    }
+}
+
+fn join(differences: &[FieldDifference]) -> String {
+    differences
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Compares all fields of `sent` and `received` which are not set by the server.
+///
+/// Timestamps are compared with microsecond precision, as Auditor does not store more, and
+/// missing meta information or components are the same as empty ones.
+pub(crate) fn differences(sent: &Record, received: &Record) -> Vec<FieldDifference> {
+    let mut differences = vec![];
+    compare(
+        &mut differences,
+        "record_id",
+        &sent.record_id,
+        &received.record_id,
+    );
+    compare(
+        &mut differences,
+        "meta",
+        &sent.meta.clone().unwrap_or_default(),
+        &received.meta.clone().unwrap_or_default(),
+    );
+    compare(
+        &mut differences,
+        "components",
+        &sent.components.clone().unwrap_or_default(),
+        &received.components.clone().unwrap_or_default(),
+    );
+    compare(
+        &mut differences,
+        "start_time",
+        &sent.start_time.map(microseconds),
+        &received.start_time.map(microseconds),
+    );
+    compare(
+        &mut differences,
+        "stop_time",
+        &sent.stop_time.map(microseconds),
+        &received.stop_time.map(microseconds),
+    );
+    compare(
+        &mut differences,
+        "runtime",
+        &sent.runtime,
+        &received.runtime,
+    );
+    differences
+}
+
+fn compare<T: PartialEq + Debug>(
+    differences: &mut Vec<FieldDifference>,
+    field: &'static str,
+    sent: &T,
+    received: &T,
+) {
+    if sent != received {
+        differences.push(FieldDifference {
+            field,
+            sent: format!("{sent:?}"),
+            received: format!("{received:?}"),
+        });
+    }
+}
+
+fn microseconds(time: DateTime<Utc>) -> DateTime<Utc> {
+    time.trunc_subsecs(6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auditor::domain::{Component, Meta};
+    use chrono::{Duration, TimeZone};
+
+    fn record() -> Record {
+        let mut meta = Meta::new();
+        meta.insert("site_id".to_string(), vec!["site1".to_string()]);
+        Record {
+            record_id: "r1".to_string(),
+            meta: Some(meta),
+            components: Some(vec![Component::new("CPU", 4).unwrap()]),
+            start_time: Some(Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap()),
+            stop_time: Some(Utc.with_ymd_and_hms(2024, 1, 1, 13, 0, 0).unwrap()),
+            runtime: Some(3600),
+            ingested_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn fields_set_by_the_server_are_ignored() {
+        let sent = record();
+        let mut received = record();
+        received.ingested_at = Some(Utc::now());
+        received.updated_at = Some(Utc::now());
+
+        assert!(differences(&sent, &received).is_empty());
+    }
+
+    #[test]
+    fn timestamps_are_compared_with_microsecond_precision() {
+        let mut sent = record();
+        sent.start_time = sent
+            .start_time
+            .map(|t| t + Duration::nanoseconds(1_000_999));
+        let mut received = record();
+        received.start_time = received
+            .start_time
+            .map(|t| t + Duration::microseconds(1_000));
+
+        assert!(differences(&sent, &received).is_empty());
+
+        received.start_time = received.start_time.map(|t| t + Duration::microseconds(1));
+        assert_eq!(differences(&sent, &received)[0].field, "start_time");
+    }
+
+    #[test]
+    fn missing_and_empty_collections_are_the_same() {
+        let mut sent = record();
+        sent.meta = None;
+        sent.components = None;
+        let mut received = record();
+        received.meta = Some(Meta::new());
+        received.components = Some(vec![]);
+
+        assert!(differences(&sent, &received).is_empty());
+    }
+
+    #[test]
+    fn all_changed_fields_are_reported() {
+        let sent = record();
+        let mut received = record();
+        received.meta = Some(Meta::new());
+        received.runtime = Some(0);
+
+        let differences = differences(&sent, &received);
+
+        assert_eq!(
+            differences
+                .iter()
+                .map(|difference| difference.field)
+                .collect::<Vec<_>>(),
+            vec!["meta", "runtime"]
+        );
+        assert_eq!(differences[1].sent, "Some(3600)");
+        assert_eq!(differences[1].received, "Some(0)");
+    }
+}
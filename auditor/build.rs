@@ -6,6 +6,10 @@
 // copied, modified, or distributed except according to those terms.
 
 fn main() {
+    // Rebuild if migrations change, as they are embedded into the binary
+    println!("cargo:rerun-if-changed=../migrations");
+    println!("cargo:rerun-if-env-changed=DOCS_RS");
+
     // When building in docs.rs, we want to set SQLX_OFFLINE mode to true
     if std::env::var_os("DOCS_RS").is_some() {
         println!("cargo:rustc-env=SQLX_OFFLINE=true");
@@ -0,0 +1,71 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Retention of records, which removes records from the database some time after they have
+//! finished.
+
+use crate::configuration::RetentionSettings;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+/// Background task which periodically deletes expired records, see
+/// [`delete_expired_records`].
+#[derive(Clone)]
+pub struct RetentionTask {
+    db_pool: PgPool,
+    retention_period: Duration,
+    interval: Duration,
+}
+
+impl RetentionTask {
+    pub fn new(pool: PgPool, settings: &RetentionSettings) -> RetentionTask {
+        RetentionTask {
+            db_pool: pool,
+            retention_period: settings.retention_period,
+            interval: settings.interval,
+        }
+    }
+
+    /// Deletes expired records every `interval`. Failures are logged and retried at the next
+    /// interval.
+    #[tracing::instrument(name = "Running retention task", skip(self))]
+    pub async fn run(&self) -> Result<(), anyhow::Error> {
+        let mut interval = tokio::time::interval(self.interval.to_std()?);
+        loop {
+            interval.tick().await;
+            match delete_expired_records(&self.db_pool, Utc::now(), self.retention_period).await {
+                Ok(deleted) => tracing::info!(deleted, "Deleted expired records"),
+                Err(e) => tracing::error!("Deleting expired records failed: {e}"),
+            }
+        }
+    }
+}
+
+/// Deletes all records whose `stop_time` is more than `retention_period` before `now`, including
+/// records which are marked as deleted. Unfinished records are never deleted. Returns the number
+/// of deleted records.
+#[tracing::instrument(name = "Deleting expired records", skip(pool))]
+pub async fn delete_expired_records(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    retention_period: Duration,
+) -> Result<u64, anyhow::Error> {
+    if retention_period <= Duration::zero() {
+        anyhow::bail!("Retention period has to be positive");
+    }
+    let result = sqlx::query(
+        r#"
+        DELETE FROM auditor_accounting
+        WHERE stop_time IS NOT NULL AND stop_time < $1
+        "#,
+    )
+    .bind(now - retention_period)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
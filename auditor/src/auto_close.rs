@@ -0,0 +1,105 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Closing of orphaned records, i.e. records which were never updated with a `stop_time`, for
+//! instance because the epilog of a job did not run.
+
+use crate::configuration::{AutoCloseSettings, AutoCloseStopTime};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+
+/// Meta key which marks records that were closed by Auditor instead of a collector. Its value is
+/// `["true"]`.
+pub const AUTO_CLOSED_META_KEY: &str = "auto_closed";
+
+/// Background task which periodically closes orphaned records, see
+/// [`close_orphaned_records`].
+#[derive(Clone)]
+pub struct AutoCloseTask {
+    db_pool: PgPool,
+    max_runtime: Duration,
+    stop_time: AutoCloseStopTime,
+    interval: Duration,
+}
+
+impl AutoCloseTask {
+    pub fn new(pool: PgPool, settings: &AutoCloseSettings) -> AutoCloseTask {
+        AutoCloseTask {
+            db_pool: pool,
+            max_runtime: settings.max_runtime,
+            stop_time: settings.stop_time,
+            interval: settings.interval,
+        }
+    }
+
+    /// Closes orphaned records every `interval`. Failures are logged and retried at the next
+    /// interval.
+    #[tracing::instrument(name = "Running auto-close task", skip(self))]
+    pub async fn run(&self) -> Result<(), anyhow::Error> {
+        let mut interval = tokio::time::interval(self.interval.to_std()?);
+        loop {
+            interval.tick().await;
+            match close_orphaned_records(
+                &self.db_pool,
+                Utc::now(),
+                self.max_runtime,
+                self.stop_time,
+            )
+            .await
+            {
+                Ok(closed) => tracing::info!(closed, "Closed orphaned records"),
+                Err(e) => tracing::error!("Closing orphaned records failed: {e}"),
+            }
+        }
+    }
+}
+
+/// Closes all unfinished records which started more than `max_runtime` before `now`.
+///
+/// The `stop_time` of these records is set according to `stop_time`, their runtime is updated
+/// accordingly and they are marked with the meta key [`AUTO_CLOSED_META_KEY`]. Records which are
+/// marked as deleted are left untouched. Returns the number of closed records.
+#[tracing::instrument(name = "Closing orphaned records", skip(pool))]
+pub async fn close_orphaned_records(
+    pool: &PgPool,
+    now: DateTime<Utc>,
+    max_runtime: Duration,
+    stop_time: AutoCloseStopTime,
+) -> Result<u64, anyhow::Error> {
+    if max_runtime <= Duration::zero() {
+        anyhow::bail!("Maximum runtime has to be positive");
+    }
+    // The new `stop_time` is computed in a subquery, because `SET` only sees the old values.
+    // Records without meta information store a JSON `null`, which would become an array.
+    let result = sqlx::query(
+        r#"
+        UPDATE auditor_accounting
+        SET stop_time = closed.stop_time,
+            runtime = EXTRACT(EPOCH FROM closed.stop_time - auditor_accounting.start_time)::BIGINT,
+            meta = COALESCE(NULLIF(meta, 'null'::jsonb), '{}'::jsonb) || jsonb_build_object($4::TEXT, jsonb_build_array('true')),
+            updated_at = $5
+        FROM (
+            SELECT id,
+                   CASE WHEN $3 THEN GREATEST(updated_at, start_time)
+                        ELSE start_time + make_interval(secs => $2)
+                   END AS stop_time
+            FROM auditor_accounting
+            WHERE stop_time IS NULL AND deleted_at IS NULL AND start_time < $1
+        ) AS closed
+        WHERE auditor_accounting.id = closed.id
+        "#,
+    )
+    .bind(now - max_runtime)
+    .bind(max_runtime.num_milliseconds() as f64 / 1000.0)
+    .bind(stop_time == AutoCloseStopTime::LastSeen)
+    .bind(AUTO_CLOSED_META_KEY)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
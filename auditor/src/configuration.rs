@@ -5,12 +5,19 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::middleware::{BearerAuth, RateLimiter};
+use crate::routes::{
+    ConsumerHorizon, MaxRecordAge, RecordPatchEnabled, RuntimeTolerance, SoftDelete, StreamLimit,
+};
+use crate::slow_query_log::SlowQueryLog;
 use crate::telemetry::deserialize_log_level;
 use rustls::ServerConfig;
 use secrecy::{ExposeSecret, Secret};
+use serde::{de, Deserialize};
 use serde_aux::field_attributes::deserialize_number_from_string;
-use sqlx::postgres::{PgConnectOptions, PgSslMode};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions, PgSslMode};
 use sqlx::ConnectOptions;
+use std::str::FromStr;
 use tracing_subscriber::filter::LevelFilter;
 
 #[derive(serde::Deserialize, Debug)]
@@ -23,6 +30,41 @@ pub struct Settings {
     #[serde(deserialize_with = "deserialize_log_level")]
     pub log_level: LevelFilter,
     pub tls_config: Option<TLSConfig>,
+    #[serde(default)]
+    pub auth: AuthSettings,
+    /// Publishes inserted records to a message bus. Disabled if not set.
+    #[serde(default)]
+    pub publisher: Option<PublisherSettings>,
+    /// Periodically deletes finished records after the retention period. Disabled if not set.
+    #[serde(default)]
+    pub retention: Option<RetentionSettings>,
+    /// Periodically closes records which are still unfinished after a maximum runtime. Disabled
+    /// if not set.
+    #[serde(default)]
+    pub auto_close: Option<AutoCloseSettings>,
+}
+
+#[derive(serde::Deserialize, Debug, Default)]
+pub struct AuthSettings {
+    /// Accepted bearer tokens. Bearer token authentication is disabled if empty.
+    #[serde(default)]
+    pub bearer_tokens: Vec<Secret<String>>,
+    /// Bearer tokens accepted by the admin endpoints. The admin endpoints are disabled if empty.
+    #[serde(default)]
+    pub admin_tokens: Vec<Secret<String>>,
+    /// Bearer tokens which may add records older than `max_record_age`, e.g. for backfills.
+    /// They are accepted by all other endpoints as well.
+    #[serde(default)]
+    pub backfill_tokens: Vec<Secret<String>>,
+}
+
+impl AuthSettings {
+    /// Returns the bearer token authentication configuration.
+    pub fn bearer_auth(&self) -> BearerAuth {
+        BearerAuth::new(self.bearer_tokens.clone())
+            .with_admin_tokens(self.admin_tokens.clone())
+            .with_backfill_tokens(self.backfill_tokens.clone())
+    }
 }
 
 // Set the default values for TLSConfig options
@@ -76,12 +118,111 @@ fn default_log_level() -> LevelFilter {
     LevelFilter::INFO
 }
 
+#[serde_with::serde_as]
 #[derive(serde::Deserialize, Debug)]
 pub struct AuditorSettings {
     #[serde(default = "default_addr")]
     pub addr: String,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     pub port: u16,
+    /// Record queries taking longer than this are logged. Disabled if not set.
+    #[serde(default)]
+    pub slow_query_threshold_ms: Option<u64>,
+    /// Redact meta values in the slow query log.
+    #[serde(default)]
+    pub slow_query_redact_meta: bool,
+    /// Maximum number of concurrent streamed record responses. Unlimited if not set.
+    #[serde(default)]
+    pub max_streaming_responses: Option<usize>,
+    /// Allow changing meta and components of records with `PATCH /record/{record_id}`.
+    #[serde(default)]
+    pub allow_record_patch: bool,
+    /// Keep deleted records as tombstones instead of removing them from the database.
+    #[serde(default = "default_soft_delete")]
+    pub soft_delete: bool,
+    /// Maximum difference in seconds between the runtime sent with a record and the time between
+    /// its start and stop time.
+    #[serde(default = "default_runtime_tolerance_secs")]
+    pub runtime_tolerance_secs: u64,
+    /// Time in seconds after their `stop_time` after which records are only accepted with one of
+    /// the backfill tokens. Disabled if not set.
+    #[serde_as(as = "Option<serde_with::DurationSeconds<i64>>")]
+    pub max_record_age: Option<chrono::Duration>,
+    /// Time in seconds after their ingestion after which records are handed out to consumers, see
+    /// [`ConsumerHorizon`]. Has to be longer than it takes to insert records.
+    #[serde(default = "default_consumer_horizon_secs")]
+    pub consumer_horizon_secs: u64,
+    /// Limits the rate of requests per client. Disabled if not set.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitSettings>,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct RateLimitSettings {
+    /// Sustained number of requests per second and client.
+    pub requests_per_second: f64,
+    /// Number of requests a client may send at once before being limited.
+    pub burst: u32,
+}
+
+impl AuditorSettings {
+    /// Returns the slow query log configuration, if enabled.
+    pub fn slow_query_log(&self) -> Option<SlowQueryLog> {
+        self.slow_query_threshold_ms
+            .map(|threshold_ms| SlowQueryLog {
+                threshold: std::time::Duration::from_millis(threshold_ms),
+                redact_meta: self.slow_query_redact_meta,
+            })
+    }
+
+    /// Returns the limit of concurrent streamed record responses.
+    pub fn stream_limit(&self) -> StreamLimit {
+        StreamLimit::new(self.max_streaming_responses)
+    }
+
+    /// Returns whether records may be patched.
+    pub fn record_patch(&self) -> RecordPatchEnabled {
+        RecordPatchEnabled(self.allow_record_patch)
+    }
+
+    /// Returns whether deleted records are kept as tombstones.
+    pub fn soft_delete(&self) -> SoftDelete {
+        SoftDelete(self.soft_delete)
+    }
+
+    /// Returns the tolerance for runtimes sent with records.
+    pub fn runtime_tolerance(&self) -> RuntimeTolerance {
+        RuntimeTolerance(self.runtime_tolerance_secs)
+    }
+
+    /// Returns the maximum age of records which are accepted without a backfill token.
+    pub fn max_record_age(&self) -> MaxRecordAge {
+        MaxRecordAge(self.max_record_age)
+    }
+
+    /// Returns how long records are held back from consumers after their ingestion.
+    pub fn consumer_horizon(&self) -> ConsumerHorizon {
+        ConsumerHorizon(std::time::Duration::from_secs(self.consumer_horizon_secs))
+    }
+
+    /// Returns the rate limiter, if enabled.
+    pub fn rate_limiter(&self) -> Option<RateLimiter> {
+        self.rate_limit
+            .as_ref()
+            .map(|limit| RateLimiter::new(limit.requests_per_second, limit.burst))
+    }
+}
+
+fn default_soft_delete() -> bool {
+    true
+}
+
+fn default_runtime_tolerance_secs() -> u64 {
+    1
+}
+
+fn default_consumer_horizon_secs() -> u64 {
+    10
 }
 
 fn default_addr() -> String {
@@ -97,6 +238,39 @@ pub struct DatabaseSettings {
     pub host: String,
     pub database_name: String,
     pub require_ssl: bool,
+    /// SSL mode of the connection to the database, e.g. `verify-full`. Overrides `require_ssl`.
+    #[serde(default, deserialize_with = "deserialize_ssl_mode")]
+    pub ssl_mode: Option<PgSslMode>,
+    /// CA certificate used to verify the certificate of the database server.
+    pub ssl_root_cert_path: Option<String>,
+    /// Client certificate for authenticating against the database server.
+    pub ssl_client_cert_path: Option<String>,
+    /// Private key of the client certificate.
+    pub ssl_client_key_path: Option<String>,
+    /// Maximum number of connections in the connection pool.
+    #[serde(default = "default_max_connections")]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_connections: u32,
+    /// Number of connections the connection pool maintains at all times.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub min_connections: u32,
+    /// Create the database and apply pending migrations on startup.
+    #[serde(default)]
+    pub auto_migrate: bool,
+}
+
+fn default_max_connections() -> u32 {
+    10
+}
+
+fn deserialize_ssl_mode<'de, D>(deserializer: D) -> Result<Option<PgSslMode>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| PgSslMode::from_str(&s).map_err(de::Error::custom))
+        .transpose()
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -117,6 +291,99 @@ fn default_db_metrics_frequency() -> chrono::Duration {
     chrono::Duration::try_seconds(30).expect("This should never fail")
 }
 
+#[serde_with::serde_as]
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct RetentionSettings {
+    /// Time in seconds after their `stop_time` after which records are deleted.
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub retention_period: chrono::Duration,
+    /// Time in seconds between two runs of the retention task.
+    #[serde(default = "default_task_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub interval: chrono::Duration,
+}
+
+impl RetentionSettings {
+    /// Checks that the retention period and the interval are positive.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.retention_period <= chrono::Duration::zero() {
+            return Err("retention.retention_period has to be positive");
+        }
+        if self.interval <= chrono::Duration::zero() {
+            return Err("retention.interval has to be positive");
+        }
+        Ok(())
+    }
+}
+
+fn default_task_interval() -> chrono::Duration {
+    chrono::Duration::try_hours(1).expect("This should never fail")
+}
+
+#[serde_with::serde_as]
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct AutoCloseSettings {
+    /// Time in seconds after their `start_time` after which unfinished records are closed.
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub max_runtime: chrono::Duration,
+    /// The `stop_time` which is set for closed records.
+    #[serde(default)]
+    pub stop_time: AutoCloseStopTime,
+    /// Time in seconds between two runs of the auto-close task.
+    #[serde(default = "default_task_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub interval: chrono::Duration,
+}
+
+impl AutoCloseSettings {
+    /// Checks that the maximum runtime and the interval are positive.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.max_runtime <= chrono::Duration::zero() {
+            return Err("auto_close.max_runtime has to be positive");
+        }
+        if self.interval <= chrono::Duration::zero() {
+            return Err("auto_close.interval has to be positive");
+        }
+        Ok(())
+    }
+}
+
+/// `stop_time` of records which are closed by the auto-close task.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoCloseStopTime {
+    /// `start_time` plus the maximum runtime.
+    #[default]
+    MaxRuntime,
+    /// Time at which the record was last added or updated (`updated_at`).
+    LastSeen,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct PublisherSettings {
+    #[serde(flatten)]
+    pub target: PublisherTarget,
+    /// How often publishing a record is retried before it is given up.
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_publisher_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+}
+
+/// Message bus which records are published to, selected with `kind`.
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum PublisherTarget {
+    /// Publishes to a NATS subject. Requires the `nats` feature.
+    Nats { url: String, subject: String },
+    /// Publishes to a Kafka topic, keyed by the record id. Requires the `kafka` feature.
+    Kafka { brokers: String, topic: String },
+}
+
+fn default_publisher_retry_delay_ms() -> u64 {
+    1000
+}
+
 fn default_metrics() -> MetricsSettings {
     MetricsSettings {
         database: DatabaseMetricsSettings {
@@ -129,17 +396,59 @@ fn default_metrics() -> MetricsSettings {
 impl DatabaseSettings {
     /// Returns the connection options for the PostgreSQL database without database name
     pub fn without_db(&self) -> PgConnectOptions {
-        let ssl_mode = if self.require_ssl {
-            PgSslMode::Require
-        } else {
-            PgSslMode::Prefer
+        let ssl_mode = match self.ssl_mode {
+            Some(ssl_mode) => ssl_mode,
+            None if self.require_ssl => PgSslMode::Require,
+            None => PgSslMode::Prefer,
         };
-        PgConnectOptions::new()
+        let mut options = PgConnectOptions::new()
             .host(&self.host)
             .username(&self.username)
             .password(self.password.expose_secret())
             .port(self.port)
-            .ssl_mode(ssl_mode)
+            .ssl_mode(ssl_mode);
+        if let Some(path) = &self.ssl_root_cert_path {
+            options = options.ssl_root_cert(path);
+        }
+        if let Some(path) = &self.ssl_client_cert_path {
+            options = options.ssl_client_cert(path);
+        }
+        if let Some(path) = &self.ssl_client_key_path {
+            options = options.ssl_client_key(path);
+        }
+        options
+    }
+
+    /// Checks that the client certificate and key are either both set or both unset.
+    pub fn validate_ssl(&self) -> Result<(), &'static str> {
+        match (&self.ssl_client_cert_path, &self.ssl_client_key_path) {
+            (Some(_), None) => {
+                Err("ssl_client_key_path is required when ssl_client_cert_path is set")
+            }
+            (None, Some(_)) => {
+                Err("ssl_client_cert_path is required when ssl_client_key_path is set")
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the size of the connection pool is valid.
+    pub fn validate_pool_size(&self) -> Result<(), &'static str> {
+        if self.max_connections == 0 {
+            return Err("max_connections must be greater than zero");
+        }
+        if self.max_connections < self.min_connections {
+            return Err("max_connections must not be less than min_connections");
+        }
+        Ok(())
+    }
+
+    /// Returns the options for the connection pool of the PostgreSQL database
+    pub fn pool_options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_secs(2))
+            .max_connections(self.max_connections)
+            .min_connections(self.min_connections)
     }
 
     /// Returns the connection options for the PostgreSQL database with database name
@@ -151,6 +460,14 @@ impl DatabaseSettings {
 }
 
 /// Loads the configuration from a file `configuration.{yaml,json,toml,...}`
+///
+/// The settings are layered with the following precedence (highest first):
+/// 1. Environment variables prefixed with `AUDITOR_`, nested keys are separated by `__`, e.g.
+///    `AUDITOR_DATABASE__HOST` overrides `database.host`
+/// 2. The configuration file passed as first argument
+/// 3. `configuration/{local,production}`, depending on `AUDITOR_ENVIRONMENT`
+/// 4. `configuration/base`
+/// 5. Default values
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let configuration_directory = base_path.join("configuration");
@@ -183,13 +500,22 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
         None => settings,
     };
 
-    let settings = settings.add_source(
-        config::Environment::with_prefix("AUDITOR")
-            .separator("__")
-            .prefix_separator("_"),
-    );
+    with_environment(settings, environment_overrides())
+}
+
+/// Environment variables which override the settings, e.g. `AUDITOR_DATABASE__HOST`.
+fn environment_overrides() -> config::Environment {
+    config::Environment::with_prefix("AUDITOR")
+        .separator("__")
+        .prefix_separator("_")
+}
 
-    settings.build()?.try_deserialize()
+/// Adds `environment` as source with the highest precedence and deserializes the settings.
+fn with_environment(
+    settings: config::ConfigBuilder<config::builder::DefaultState>,
+    environment: config::Environment,
+) -> Result<Settings, config::ConfigError> {
+    settings.add_source(environment).build()?.try_deserialize()
 }
 
 // The possible runtime environment for AUDITOR.
@@ -219,3 +545,221 @@ impl TryFrom<String> for Environment {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database_settings(pool_size: &str) -> Result<DatabaseSettings, config::ConfigError> {
+        let yaml = format!(
+            "host: localhost\n\
+             port: 5432\n\
+             username: postgres\n\
+             password: password\n\
+             database_name: auditor\n\
+             require_ssl: false\n\
+             {pool_size}"
+        );
+        config::Config::builder()
+            .add_source(config::File::from_str(&yaml, config::FileFormat::Yaml))
+            .build()?
+            .try_deserialize()
+    }
+
+    fn settings_from(
+        yaml: &str,
+        variables: &[(&str, &str)],
+    ) -> Result<Settings, config::ConfigError> {
+        let environment = environment_overrides().source(Some(
+            variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ));
+        with_environment(
+            config::Config::builder()
+                .add_source(config::File::from_str(yaml, config::FileFormat::Yaml)),
+            environment,
+        )
+    }
+
+    const SETTINGS: &str = r#"
+application:
+  port: 8000
+database:
+  host: localhost
+  port: 5432
+  username: postgres
+  password: password
+  database_name: auditor
+  require_ssl: false
+"#;
+
+    #[test]
+    fn environment_variable_overrides_nested_file_value() {
+        let settings = settings_from(
+            SETTINGS,
+            &[
+                ("AUDITOR_DATABASE__HOST", "db.example.org"),
+                ("AUDITOR_DATABASE__PORT", "6543"),
+                ("AUDITOR_DATABASE__MAX_CONNECTIONS", "20"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(settings.database.host, "db.example.org");
+        assert_eq!(settings.database.port, 6543);
+        assert_eq!(settings.database.max_connections, 20);
+        // Values which are not overridden are taken from the file
+        assert_eq!(settings.database.username, "postgres");
+        assert_eq!(settings.application.port, 8000);
+    }
+
+    #[test]
+    fn environment_variable_without_prefix_is_ignored() {
+        let settings = settings_from(SETTINGS, &[("DATABASE__HOST", "db.example.org")]).unwrap();
+
+        assert_eq!(settings.database.host, "localhost");
+    }
+
+    #[test]
+    fn rate_limit_is_opt_in() {
+        let settings = settings_from(SETTINGS, &[]).unwrap();
+        assert!(settings.application.rate_limit.is_none());
+        assert!(settings.application.rate_limiter().is_none());
+
+        let yaml = SETTINGS.replace(
+            "  port: 8000\n",
+            "  port: 8000\n  rate_limit:\n    requests_per_second: 2.5\n    burst: 10\n",
+        );
+        let settings = settings_from(&yaml, &[]).unwrap();
+        let rate_limit = settings.application.rate_limit.unwrap();
+        assert_eq!(rate_limit.requests_per_second, 2.5);
+        assert_eq!(rate_limit.burst, 10);
+    }
+
+    #[test]
+    fn max_record_age_is_opt_in() {
+        let settings = settings_from(SETTINGS, &[]).unwrap();
+        assert!(settings.application.max_record_age.is_none());
+        assert!(settings.auth.backfill_tokens.is_empty());
+
+        let yaml = SETTINGS.replace(
+            "  port: 8000\n",
+            "  port: 8000\n  max_record_age: 2592000\n",
+        );
+        let settings = settings_from(&yaml, &[]).unwrap();
+        assert_eq!(
+            settings.application.max_record_age,
+            Some(chrono::Duration::days(30))
+        );
+    }
+
+    #[test]
+    fn retention_is_opt_in_and_validated() {
+        let settings = settings_from(SETTINGS, &[]).unwrap();
+        assert!(settings.retention.is_none());
+
+        let yaml = format!("{SETTINGS}retention:\n  retention_period: 86400\n");
+        let retention = settings_from(&yaml, &[]).unwrap().retention.unwrap();
+        assert_eq!(retention.retention_period, chrono::Duration::days(1));
+        assert_eq!(retention.interval, chrono::Duration::hours(1));
+        assert!(retention.validate().is_ok());
+
+        let yaml = format!("{SETTINGS}retention:\n  retention_period: 0\n");
+        let retention = settings_from(&yaml, &[]).unwrap().retention.unwrap();
+        assert!(retention.validate().is_err());
+    }
+
+    #[test]
+    fn auto_close_is_opt_in_and_validated() {
+        let settings = settings_from(SETTINGS, &[]).unwrap();
+        assert!(settings.auto_close.is_none());
+
+        let yaml = format!("{SETTINGS}auto_close:\n  max_runtime: 604800\n");
+        let auto_close = settings_from(&yaml, &[]).unwrap().auto_close.unwrap();
+        assert_eq!(auto_close.max_runtime, chrono::Duration::days(7));
+        assert_eq!(auto_close.stop_time, AutoCloseStopTime::MaxRuntime);
+        assert_eq!(auto_close.interval, chrono::Duration::hours(1));
+        assert!(auto_close.validate().is_ok());
+
+        let yaml = format!(
+            "{SETTINGS}auto_close:\n  max_runtime: 604800\n  stop_time: last_seen\n  interval: 0\n"
+        );
+        let auto_close = settings_from(&yaml, &[]).unwrap().auto_close.unwrap();
+        assert_eq!(auto_close.stop_time, AutoCloseStopTime::LastSeen);
+        assert!(auto_close.validate().is_err());
+    }
+
+    #[test]
+    fn pool_size_defaults_are_valid() {
+        let settings = database_settings("").unwrap();
+        assert_eq!(settings.max_connections, 10);
+        assert_eq!(settings.min_connections, 0);
+        assert!(settings.validate_pool_size().is_ok());
+    }
+
+    #[test]
+    fn pool_size_is_parsed() {
+        let settings = database_settings("max_connections: 32\nmin_connections: 4").unwrap();
+        assert_eq!(settings.max_connections, 32);
+        assert_eq!(settings.min_connections, 4);
+        assert!(settings.validate_pool_size().is_ok());
+
+        let options = settings.pool_options();
+        assert_eq!(options.get_max_connections(), 32);
+        assert_eq!(options.get_min_connections(), 4);
+    }
+
+    #[test]
+    fn ssl_mode_defaults_to_require_ssl() {
+        let settings = database_settings("").unwrap();
+        assert!(settings.ssl_mode.is_none());
+        assert!(matches!(
+            settings.without_db().get_ssl_mode(),
+            PgSslMode::Prefer
+        ));
+    }
+
+    #[test]
+    fn ssl_options_are_applied() {
+        let settings = database_settings(
+            "ssl_mode: verify-full\n\
+             ssl_root_cert_path: /path/rootCA.pem\n\
+             ssl_client_cert_path: /path/client-cert.pem\n\
+             ssl_client_key_path: /path/client-key.pem",
+        )
+        .unwrap();
+        assert!(settings.validate_ssl().is_ok());
+        assert_eq!(
+            settings.ssl_root_cert_path.as_deref(),
+            Some("/path/rootCA.pem")
+        );
+
+        let options = settings.with_db();
+        assert!(matches!(options.get_ssl_mode(), PgSslMode::VerifyFull));
+    }
+
+    #[test]
+    fn invalid_ssl_mode_is_rejected() {
+        assert!(database_settings("ssl_mode: encrypted").is_err());
+    }
+
+    #[test]
+    fn client_cert_without_key_is_rejected() {
+        let settings = database_settings("ssl_client_cert_path: /path/client-cert.pem").unwrap();
+        assert!(settings.validate_ssl().is_err());
+
+        let settings = database_settings("ssl_client_key_path: /path/client-key.pem").unwrap();
+        assert!(settings.validate_ssl().is_err());
+    }
+
+    #[test]
+    fn pool_size_with_max_below_min_is_rejected() {
+        let settings = database_settings("max_connections: 2\nmin_connections: 4").unwrap();
+        assert!(settings.validate_pool_size().is_err());
+
+        let settings = database_settings("max_connections: 0").unwrap();
+        assert!(settings.validate_pool_size().is_err());
+    }
+}
@@ -5,6 +5,140 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+/// Characters which are not allowed in names, i.e. in record ids, meta keys and values as well as
+/// in component and score names.
 pub const FORBIDDEN_CHARACTERS: [char; 9] = ['/', '(', ')', '"', '<', '>', '\\', '{', '}'];
+/// Maximum number of graphemes of a name. Longer names are rejected by the server, clients can
+/// use it to check records before sending them.
+pub const MAX_NAME_LENGTH: usize = 256;
 pub const ERR_RECORD_EXISTS: &str = "RECORD_EXISTS";
 pub const ERR_UNEXPECTED_ERROR: &str = "UNEXPECTED_ERROR";
+pub const ERR_MAINTENANCE_MODE: &str = "MAINTENANCE_MODE";
+pub const ERR_INVALID_RECORDS: &str = "INVALID_RECORDS";
+pub const ERR_UNKNOWN_RECORD: &str = "UNKNOWN_RECORD";
+pub const ERR_INVALID_QUERY: &str = "INVALID_QUERY";
+pub const ERR_VALIDATION_FAILED: &str = "VALIDATION_FAILED";
+pub const ERR_TOO_MANY_STREAMS: &str = "TOO_MANY_STREAMS";
+pub const ERR_UNAUTHORIZED: &str = "UNAUTHORIZED";
+pub const ERR_PATCH_DISABLED: &str = "PATCH_DISABLED";
+pub const ERR_RATE_LIMITED: &str = "RATE_LIMITED";
+
+/// Content type of newline-delimited JSON responses, e.g. of `GET /records`.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Response header which contains the [`ErrorCode`] of an error response.
+pub const ERROR_CODE_HEADER: &str = "x-auditor-error-code";
+
+/// Response header which contains the load of the server as a number between `0` and `1`, i.e.
+/// the share of database connections in use. Clients ingesting many records should slow down if
+/// the load is high.
+pub const SERVER_LOAD_HEADER: &str = "x-auditor-server-load";
+
+/// Machine readable codes of the errors returned by the Auditor server.
+///
+/// Every error response carries its code in the [`ERROR_CODE_HEADER`] header. Unlike the
+/// response body, which is meant for humans, the codes are stable and can be relied upon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// A record with the same `record_id` already exists (`RECORD_EXISTS`).
+    RecordExists,
+    /// At least one record of a batch is invalid (`INVALID_RECORDS`).
+    InvalidRecords,
+    /// The record to be updated does not exist (`UNKNOWN_RECORD`).
+    UnknownRecord,
+    /// The query parameters are invalid (`INVALID_QUERY`).
+    InvalidQuery,
+    /// A value does not pass validation (`VALIDATION_FAILED`).
+    ValidationFailed,
+    /// The server is in maintenance mode and rejects writes (`MAINTENANCE_MODE`).
+    MaintenanceMode,
+    /// The maximum number of concurrent streamed responses is reached (`TOO_MANY_STREAMS`).
+    TooManyStreams,
+    /// The request lacks a valid bearer token (`UNAUTHORIZED`).
+    Unauthorized,
+    /// Patching records is disabled in the configuration (`PATCH_DISABLED`).
+    PatchDisabled,
+    /// The client sent more requests than allowed by the rate limit (`RATE_LIMITED`).
+    RateLimited,
+    /// Any other error (`UNEXPECTED_ERROR`).
+    UnexpectedError,
+}
+
+impl ErrorCode {
+    /// All error codes.
+    pub const ALL: [ErrorCode; 11] = [
+        ErrorCode::RecordExists,
+        ErrorCode::InvalidRecords,
+        ErrorCode::UnknownRecord,
+        ErrorCode::InvalidQuery,
+        ErrorCode::ValidationFailed,
+        ErrorCode::MaintenanceMode,
+        ErrorCode::TooManyStreams,
+        ErrorCode::Unauthorized,
+        ErrorCode::PatchDisabled,
+        ErrorCode::RateLimited,
+        ErrorCode::UnexpectedError,
+    ];
+
+    /// Returns the code as it is sent by the server.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::RecordExists => ERR_RECORD_EXISTS,
+            ErrorCode::InvalidRecords => ERR_INVALID_RECORDS,
+            ErrorCode::UnknownRecord => ERR_UNKNOWN_RECORD,
+            ErrorCode::InvalidQuery => ERR_INVALID_QUERY,
+            ErrorCode::ValidationFailed => ERR_VALIDATION_FAILED,
+            ErrorCode::MaintenanceMode => ERR_MAINTENANCE_MODE,
+            ErrorCode::TooManyStreams => ERR_TOO_MANY_STREAMS,
+            ErrorCode::Unauthorized => ERR_UNAUTHORIZED,
+            ErrorCode::PatchDisabled => ERR_PATCH_DISABLED,
+            ErrorCode::RateLimited => ERR_RATE_LIMITED,
+            ErrorCode::UnexpectedError => ERR_UNEXPECTED_ERROR,
+        }
+    }
+
+    /// Parses a code sent by the server. Returns `None` for unknown codes.
+    pub fn from_code(code: &str) -> Option<ErrorCode> {
+        ErrorCode::ALL.into_iter().find(|c| c.as_str() == code)
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_codes_are_stable() {
+        let codes: Vec<&str> = ErrorCode::ALL.iter().map(ErrorCode::as_str).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "RECORD_EXISTS",
+                "INVALID_RECORDS",
+                "UNKNOWN_RECORD",
+                "INVALID_QUERY",
+                "VALIDATION_FAILED",
+                "MAINTENANCE_MODE",
+                "TOO_MANY_STREAMS",
+                "UNAUTHORIZED",
+                "PATCH_DISABLED",
+                "RATE_LIMITED",
+                "UNEXPECTED_ERROR",
+            ]
+        );
+    }
+
+    #[test]
+    fn error_codes_round_trip() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_code(code.as_str()), Some(code));
+        }
+        assert_eq!(ErrorCode::from_code("Record exists"), None);
+    }
+}
@@ -5,8 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::{Score, ScoreTest, ValidAmount, ValidName};
-use anyhow::{Context, Error};
+use super::{interner::deserialize_name, Score, ScoreTest, ValidAmount, ValidName};
+use anyhow::{bail, Context, Error};
 use fake::{Dummy, Fake, Faker, StringFaker};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -37,6 +37,7 @@ use sqlx::{
 #[sqlx(type_name = "component")]
 pub struct Component {
     /// Name of the component.
+    #[serde(deserialize_with = "deserialize_name")]
     pub name: ValidName,
     /// Amount of the component (how many or how much of this component is to be accounted for).
     pub amount: ValidAmount,
@@ -50,7 +51,7 @@ impl Component {
     /// # Errors
     ///
     /// * [`anyhow::Error`] - If there was an invalid character (`/()"<>\{}`) in the `name`
-    ///     or if a negative `amount` was given.
+    ///   or if a negative `amount` was given.
     pub fn new<T: AsRef<str>>(name: T, amount: i64) -> Result<Self, Error> {
         Ok(Component {
             name: ValidName::parse(name.as_ref().to_string())
@@ -61,16 +62,85 @@ impl Component {
     }
 
     /// Attach a [`Score`] to the component.
+    ///
+    /// Does not check whether a score with the same name is already attached, see
+    /// [`Component::try_with_score`].
     pub fn with_score(mut self, score: Score) -> Self {
         self.scores.push(score);
         self
     }
 
+    /// Attach a [`Score`] to the component.
+    ///
+    /// # Errors
+    ///
+    /// * [`anyhow::Error`] - If a score with the same name is already attached to the component.
+    pub fn try_with_score(mut self, score: Score) -> Result<Self, Error> {
+        self.push_unique_score(score)?;
+        Ok(self)
+    }
+
     /// Attach multiple [`Score`]s to the component.
-    pub fn with_scores(mut self, mut scores: Vec<Score>) -> Self {
-        self.scores.append(&mut scores);
+    ///
+    /// # Errors
+    ///
+    /// * [`anyhow::Error`] - If two scores share the same name, either within `scores` or with a
+    ///   score that is already attached to the component.
+    pub fn with_scores(mut self, scores: Vec<Score>) -> Result<Self, Error> {
+        for score in scores {
+            self.push_unique_score(score)?;
+        }
+        Ok(self)
+    }
+
+    /// Append a [`Score`] to the component.
+    ///
+    /// Returns the component again, so that multiple scores can be chained. Does not check whether
+    /// a score with the same name is already attached, see [`Component::try_add_score`].
+    ///
+    /// ```
+    /// # use auditor::domain::{Component, Score};
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// let component = Component::new("CPU", 10)?
+    ///     .add_score(Score::new("HEPSPEC06", 9.2)?)
+    ///     .add_score(Score::new("HEPscore23", 10.1)?);
+    /// assert_eq!(component.scores.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_score(mut self, score: Score) -> Self {
+        self.scores.push(score);
         self
     }
+
+    /// Append a [`Score`] to the component, unless a score with the same name is already
+    /// attached.
+    ///
+    /// # Errors
+    ///
+    /// * [`anyhow::Error`] - If a score with the same name is already attached to the component.
+    pub fn try_add_score(mut self, score: Score) -> Result<Self, Error> {
+        self.push_unique_score(score)?;
+        Ok(self)
+    }
+
+    // Attaches `score`, unless a score with the same name is already attached. Otherwise, only
+    // the first of them would be used when the scores are looked up by name.
+    fn push_unique_score(&mut self, score: Score) -> Result<(), Error> {
+        if self
+            .scores
+            .iter()
+            .any(|other| other.name.as_ref() == score.name.as_ref())
+        {
+            bail!(
+                "Duplicate score {} in component {}",
+                score.name.as_ref(),
+                self.name.as_ref()
+            );
+        }
+        self.scores.push(score);
+        Ok(())
+    }
 }
 
 // manual impl of decode because of a compiler bug. See:
@@ -192,7 +262,7 @@ impl Dummy<Faker> for ComponentTest {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use claim::assert_ok;
+    use claim::{assert_err, assert_ok};
 
     impl quickcheck::Arbitrary for ComponentTest {
         fn arbitrary(_g: &mut quickcheck::Gen) -> Self {
@@ -204,4 +274,99 @@ mod tests {
     fn a_valid_name_is_parsed_successfully(component: ComponentTest) {
         assert_ok!(Component::try_from(component));
     }
+
+    #[test]
+    fn chained_scores_accumulate() {
+        let component = Component::new("CPU", 10)
+            .unwrap()
+            .with_score(Score::new("first", 1.0).unwrap())
+            .add_score(Score::new("second", 2.0).unwrap())
+            .add_score(Score::new("third", 3.0).unwrap());
+
+        let names: Vec<&str> = component.scores.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn scores_with_distinct_names_are_attached() {
+        let component = Component::new("CPU", 10)
+            .unwrap()
+            .with_score(Score::new("first", 1.0).unwrap())
+            .with_scores(vec![
+                Score::new("second", 2.0).unwrap(),
+                Score::new("third", 3.0).unwrap(),
+            ])
+            .unwrap();
+
+        let names: Vec<&str> = component.scores.iter().map(|s| s.name.as_ref()).collect();
+        assert_eq!(names, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn duplicate_score_names_are_rejected() {
+        let result = Component::new("CPU", 10).unwrap().with_scores(vec![
+            Score::new("HEPSPEC06", 9.2).unwrap(),
+            Score::new("HEPscore23", 10.1).unwrap(),
+            Score::new("HEPSPEC06", 10.0).unwrap(),
+        ]);
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Duplicate score HEPSPEC06 in component CPU"
+        );
+    }
+
+    #[test]
+    fn score_names_colliding_with_attached_scores_are_rejected() {
+        let result = Component::new("CPU", 10)
+            .unwrap()
+            .with_score(Score::new("HEPSPEC06", 9.2).unwrap())
+            .with_scores(vec![Score::new("HEPSPEC06", 10.0).unwrap()]);
+
+        assert_err!(result);
+    }
+
+    #[test]
+    fn try_with_score_rejects_duplicate_score_names() {
+        let component = Component::new("CPU", 10)
+            .unwrap()
+            .try_with_score(Score::new("HEPSPEC06", 9.2).unwrap())
+            .unwrap();
+        assert_eq!(component.scores.len(), 1);
+
+        let result = component.try_with_score(Score::new("HEPSPEC06", 10.0).unwrap());
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Duplicate score HEPSPEC06 in component CPU"
+        );
+    }
+
+    #[test]
+    fn try_add_score_rejects_duplicate_score_names() {
+        let component = Component::new("CPU", 10)
+            .unwrap()
+            .try_add_score(Score::new("HEPSPEC06", 9.2).unwrap())
+            .unwrap()
+            .try_add_score(Score::new("HEPscore23", 10.1).unwrap())
+            .unwrap();
+        assert_eq!(component.scores.len(), 2);
+
+        let result = component.try_add_score(Score::new("HEPscore23", 10.0).unwrap());
+
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Duplicate score HEPscore23 in component CPU"
+        );
+    }
+
+    #[test]
+    fn infallible_methods_do_not_check_score_names() {
+        let component = Component::new("CPU", 10)
+            .unwrap()
+            .with_score(Score::new("HEPSPEC06", 9.2).unwrap())
+            .add_score(Score::new("HEPSPEC06", 10.0).unwrap());
+
+        assert_eq!(component.scores.len(), 2);
+    }
 }
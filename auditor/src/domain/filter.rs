@@ -0,0 +1,294 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::Record;
+use chrono::{DateTime, Utc};
+
+/// Selects records on the client side, e.g. in plugins and collectors.
+///
+/// Filters are composed with [`RecordFilter::and`], [`RecordFilter::or`] and
+/// [`RecordFilter::not`]. Closures taking a `&Record` are filters as well.
+///
+/// ```
+/// use auditor::domain::{HasComponent, MetaContains, RecordFilter};
+///
+/// let filter = MetaContains::new("group_id", "atlas").and(HasComponent::new("GPU").not());
+/// ```
+pub trait RecordFilter {
+    /// Returns `true` if `record` is selected by this filter.
+    fn matches(&self, record: &Record) -> bool;
+
+    /// Selects records matching both filters.
+    fn and<F: RecordFilter>(self, other: F) -> And<Self, F>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Selects records matching at least one of the filters.
+    fn or<F: RecordFilter>(self, other: F) -> Or<Self, F>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Selects records not matching this filter.
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+
+    /// Keeps the records matching this filter.
+    fn apply(&self, records: Vec<Record>) -> Vec<Record> {
+        records.into_iter().filter(|r| self.matches(r)).collect()
+    }
+}
+
+impl<F: Fn(&Record) -> bool> RecordFilter for F {
+    fn matches(&self, record: &Record) -> bool {
+        self(record)
+    }
+}
+
+/// Filter created by [`RecordFilter::and`].
+#[derive(Debug, Clone)]
+pub struct And<A, B>(A, B);
+
+impl<A: RecordFilter, B: RecordFilter> RecordFilter for And<A, B> {
+    fn matches(&self, record: &Record) -> bool {
+        self.0.matches(record) && self.1.matches(record)
+    }
+}
+
+/// Filter created by [`RecordFilter::or`].
+#[derive(Debug, Clone)]
+pub struct Or<A, B>(A, B);
+
+impl<A: RecordFilter, B: RecordFilter> RecordFilter for Or<A, B> {
+    fn matches(&self, record: &Record) -> bool {
+        self.0.matches(record) || self.1.matches(record)
+    }
+}
+
+/// Filter created by [`RecordFilter::not`].
+#[derive(Debug, Clone)]
+pub struct Not<A>(A);
+
+impl<A: RecordFilter> RecordFilter for Not<A> {
+    fn matches(&self, record: &Record) -> bool {
+        !self.0.matches(record)
+    }
+}
+
+/// Selects records whose meta values for `key` contain `value`.
+#[derive(Debug, Clone)]
+pub struct MetaContains {
+    key: String,
+    value: String,
+}
+
+impl MetaContains {
+    /// Constructor.
+    pub fn new<K: AsRef<str>, V: AsRef<str>>(key: K, value: V) -> Self {
+        MetaContains {
+            key: key.as_ref().to_string(),
+            value: value.as_ref().to_string(),
+        }
+    }
+}
+
+impl RecordFilter for MetaContains {
+    fn matches(&self, record: &Record) -> bool {
+        record
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(&self.key))
+            .is_some_and(|values| values.contains(&self.value))
+    }
+}
+
+/// Selects records which have a component with the given name.
+#[derive(Debug, Clone)]
+pub struct HasComponent {
+    name: String,
+}
+
+impl HasComponent {
+    /// Constructor.
+    pub fn new<T: AsRef<str>>(name: T) -> Self {
+        HasComponent {
+            name: name.as_ref().to_string(),
+        }
+    }
+}
+
+impl RecordFilter for HasComponent {
+    fn matches(&self, record: &Record) -> bool {
+        record
+            .components
+            .as_ref()
+            .is_some_and(|components| components.iter().any(|c| c.name.as_ref() == self.name))
+    }
+}
+
+/// Selects records which ran entirely within a time window.
+///
+/// Both bounds are inclusive and optional. Records without `start_time` or `stop_time` are only
+/// selected if the corresponding bound is not set.
+#[derive(Debug, Clone, Default)]
+pub struct TimeWindow {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+impl TimeWindow {
+    /// Selects records which started at or after `from` and stopped at or before `to`.
+    pub fn new(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        TimeWindow {
+            from: Some(from),
+            to: Some(to),
+        }
+    }
+
+    /// Selects records which started at or after `from`.
+    pub fn since(from: DateTime<Utc>) -> Self {
+        TimeWindow {
+            from: Some(from),
+            to: None,
+        }
+    }
+
+    /// Selects records which stopped at or before `to`.
+    pub fn until(to: DateTime<Utc>) -> Self {
+        TimeWindow {
+            from: None,
+            to: Some(to),
+        }
+    }
+}
+
+impl RecordFilter for TimeWindow {
+    fn matches(&self, record: &Record) -> bool {
+        let after_from = match (self.from, record.start_time) {
+            (Some(from), Some(start_time)) => start_time >= from,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let before_to = match (self.to, record.stop_time) {
+            (Some(to), Some(stop_time)) => stop_time <= to,
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        after_from && before_to
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Component, RecordAdd};
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn time(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 10, 1, hour, 0, 0).unwrap()
+    }
+
+    fn record(group: &str, components: &[&str], start: u32, stop: u32) -> Record {
+        let components = components
+            .iter()
+            .map(|name| Component::new(*name, 1).unwrap())
+            .collect();
+        RecordAdd::new(
+            "r1",
+            HashMap::from([("group_id", vec![group])]),
+            components,
+            time(start),
+        )
+        .unwrap()
+        .with_stop_time(time(stop))
+        .into()
+    }
+
+    #[test]
+    fn meta_contains_matches_meta_values() {
+        let record = record("atlas", &[], 1, 2);
+
+        assert!(MetaContains::new("group_id", "atlas").matches(&record));
+        assert!(!MetaContains::new("group_id", "cms").matches(&record));
+        assert!(!MetaContains::new("site_id", "atlas").matches(&record));
+    }
+
+    #[test]
+    fn has_component_matches_component_names() {
+        let record = record("atlas", &["CPU", "GPU"], 1, 2);
+
+        assert!(HasComponent::new("GPU").matches(&record));
+        assert!(!HasComponent::new("Memory").matches(&record));
+    }
+
+    #[test]
+    fn time_window_includes_bounds() {
+        let record = record("atlas", &[], 1, 2);
+
+        assert!(TimeWindow::new(time(1), time(2)).matches(&record));
+        assert!(!TimeWindow::new(time(1), time(1)).matches(&record));
+        assert!(TimeWindow::since(time(0)).matches(&record));
+        assert!(!TimeWindow::since(time(2)).matches(&record));
+        assert!(TimeWindow::until(time(3)).matches(&record));
+        assert!(TimeWindow::default().matches(&record));
+    }
+
+    #[test]
+    fn time_window_rejects_running_records() {
+        let mut record = record("atlas", &[], 1, 2);
+        record.stop_time = None;
+
+        assert!(TimeWindow::since(time(1)).matches(&record));
+        assert!(!TimeWindow::until(time(3)).matches(&record));
+    }
+
+    #[test]
+    fn combinators_compose_filters() {
+        let atlas = MetaContains::new("group_id", "atlas");
+        let gpu = HasComponent::new("GPU");
+
+        let cpu_atlas = record("atlas", &["CPU"], 1, 2);
+        let gpu_atlas = record("atlas", &["CPU", "GPU"], 1, 2);
+        let gpu_cms = record("cms", &["GPU"], 1, 2);
+
+        assert!(!atlas.clone().and(gpu.clone()).matches(&cpu_atlas));
+        assert!(atlas.clone().and(gpu.clone()).matches(&gpu_atlas));
+        assert!(atlas.clone().or(gpu.clone()).matches(&gpu_cms));
+        assert!(atlas.clone().not().matches(&gpu_cms));
+        assert!(!gpu.not().matches(&gpu_atlas));
+    }
+
+    #[test]
+    fn composed_filter_selects_records() {
+        let filter = MetaContains::new("group_id", "atlas")
+            .and(HasComponent::new("GPU").not())
+            .and(TimeWindow::since(time(2)))
+            .or(|r: &Record| r.runtime == Some(3600 * 10));
+
+        let records = vec![
+            record("atlas", &["CPU"], 2, 3),
+            record("atlas", &["GPU"], 2, 3),
+            record("atlas", &["CPU"], 1, 3),
+            record("cms", &["CPU"], 2, 3),
+            record("cms", &["GPU"], 0, 10),
+        ];
+
+        let selected = filter.apply(records.clone());
+
+        assert_eq!(selected, vec![records[0].clone(), records[4].clone()]);
+    }
+}
@@ -0,0 +1,268 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::{Component, RecordAdd, Score};
+use anyhow::{anyhow, bail, Context, Error};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+impl RecordAdd {
+    /// Constructs a record from environment variables, which allows writing collectors as shell
+    /// scripts.
+    ///
+    /// The variables are prefixed with `prefix`, followed by an underscore:
+    ///
+    /// | Variable              | Required | Example                                   |
+    /// |-----------------------|----------|-------------------------------------------|
+    /// | `{PREFIX}_RECORD_ID`  | yes      | `slurm-1234`                              |
+    /// | `{PREFIX}_START`      | yes      | `2023-01-01T12:00:00Z` or `1672574400`    |
+    /// | `{PREFIX}_STOP`       | no       | `2023-01-01T13:00:00Z` or `1672578000`    |
+    /// | `{PREFIX}_META`       | no       | `site_id=site1;group_id=group1,group2`    |
+    /// | `{PREFIX}_COMPONENTS` | no       | `CPU=4:HEPSPEC06=10.5,HEPscore23=9;MEM=16` |
+    ///
+    /// Times are given either in RFC 3339 format or as seconds since the Unix epoch. Meta entries
+    /// and components are separated by `;`. Each component consists of its name and amount,
+    /// optionally followed by `:` and a comma separated list of scores.
+    ///
+    /// # Errors
+    ///
+    /// * [`anyhow::Error`] - If a required variable is missing or a variable cannot be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use auditor::domain::RecordAdd;
+    /// #
+    /// # fn main() -> Result<(), anyhow::Error> {
+    /// std::env::set_var("MYCOLLECTOR_RECORD_ID", "job-1");
+    /// std::env::set_var("MYCOLLECTOR_START", "2023-01-01T12:00:00Z");
+    /// std::env::set_var("MYCOLLECTOR_COMPONENTS", "CPU=4:HEPSPEC06=10.5");
+    ///
+    /// let record = RecordAdd::from_env("MYCOLLECTOR")?;
+    /// assert_eq!(record.components[0].amount.as_ref(), &4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env<T: AsRef<str>>(prefix: T) -> Result<Self, Error> {
+        let prefix = prefix.as_ref();
+        let var = |name: &str| {
+            let name = format!("{prefix}_{name}");
+            match std::env::var(&name) {
+                Ok(value) => Ok((name, Some(value))),
+                Err(std::env::VarError::NotPresent) => Ok((name, None)),
+                Err(e) => Err(anyhow!("Invalid environment variable {name}: {e}")),
+            }
+        };
+        let required = |name: &str| match var(name)? {
+            (name, Some(value)) => Ok((name, value)),
+            (name, None) => Err(anyhow!("Missing required environment variable {name}")),
+        };
+
+        let (_, record_id) = required("RECORD_ID")?;
+        let (name, start_time) = required("START")?;
+        let start_time = parse_time(&start_time).with_context(|| format!("Invalid {name}"))?;
+        let meta = match var("META")? {
+            (name, Some(meta)) => parse_meta(&meta).with_context(|| format!("Invalid {name}"))?,
+            (_, None) => HashMap::new(),
+        };
+        let components = match var("COMPONENTS")? {
+            (name, Some(components)) => {
+                parse_components(&components).with_context(|| format!("Invalid {name}"))?
+            }
+            (_, None) => vec![],
+        };
+
+        let meta = meta
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.iter().map(String::as_str).collect()))
+            .collect();
+        let record = RecordAdd::new(record_id.as_str(), meta, components, start_time)?;
+
+        match var("STOP")? {
+            (name, Some(stop_time)) => Ok(record.with_stop_time(
+                parse_time(&stop_time).with_context(|| format!("Invalid {name}"))?,
+            )),
+            (_, None) => Ok(record),
+        }
+    }
+}
+
+// Entries which are empty after trimming are skipped, e.g. because of a trailing separator.
+fn entries(input: &str, separator: char) -> impl Iterator<Item = &str> {
+    input
+        .split(separator)
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+fn parse_time(input: &str) -> Result<DateTime<Utc>, Error> {
+    let input = input.trim();
+    if let Ok(seconds) = input.parse::<i64>() {
+        return DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| anyhow!("Timestamp {seconds} is out of range"));
+    }
+    Ok(DateTime::parse_from_rfc3339(input)
+        .with_context(|| format!("Cannot parse {input} as RFC 3339 or Unix timestamp"))?
+        .with_timezone(&Utc))
+}
+
+fn parse_meta(input: &str) -> Result<HashMap<String, Vec<String>>, Error> {
+    entries(input, ';')
+        .map(|entry| {
+            let Some((key, values)) = entry.split_once('=') else {
+                bail!("Meta entry {entry} is not of the form key=value1,value2");
+            };
+            Ok((
+                key.trim().to_string(),
+                entries(values, ',').map(str::to_string).collect(),
+            ))
+        })
+        .collect()
+}
+
+fn parse_components(input: &str) -> Result<Vec<Component>, Error> {
+    entries(input, ';').map(parse_component).collect()
+}
+
+fn parse_component(input: &str) -> Result<Component, Error> {
+    let (component, scores) = match input.split_once(':') {
+        Some((component, scores)) => (component, scores),
+        None => (input, ""),
+    };
+    let Some((name, amount)) = component.split_once('=') else {
+        bail!("Component {input} is not of the form name=amount");
+    };
+    let amount = amount
+        .trim()
+        .parse()
+        .with_context(|| format!("Amount of component {name} is not an integer"))?;
+    let scores = entries(scores, ',')
+        .map(|score| {
+            let Some((name, value)) = score.split_once('=') else {
+                bail!("Score {score} is not of the form name=value");
+            };
+            let value = value
+                .trim()
+                .parse()
+                .with_context(|| format!("Value of score {name} is not a number"))?;
+            Score::new(name.trim(), value)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Component::new(name.trim(), amount)?.with_scores(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Meta;
+    use chrono::TimeZone;
+
+    // Every test uses its own prefix, as tests run in parallel and share the environment
+    fn set_vars(prefix: &str, vars: &[(&str, &str)]) {
+        for (name, value) in vars {
+            std::env::set_var(format!("{prefix}_{name}"), value);
+        }
+    }
+
+    #[test]
+    fn record_is_constructed_from_env() {
+        set_vars(
+            "FROM_ENV_FULL",
+            &[
+                ("RECORD_ID", "job-1"),
+                ("START", "2023-01-01T12:00:00Z"),
+                ("STOP", "1672578000"),
+                ("META", "site_id=site1; group_id=group1,group2;"),
+                ("COMPONENTS", "CPU=4:HEPSPEC06=10.5,HEPscore23=9;MEM=16"),
+            ],
+        );
+
+        let record = RecordAdd::from_env("FROM_ENV_FULL").unwrap();
+
+        assert_eq!(record.record_id.as_ref(), "job-1");
+        assert_eq!(
+            record.start_time,
+            Utc.with_ymd_and_hms(2023, 1, 1, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            record.stop_time,
+            Some(Utc.with_ymd_and_hms(2023, 1, 1, 13, 0, 0).unwrap())
+        );
+
+        let meta = Meta::from(record.meta.unwrap());
+        assert_eq!(meta.get("site_id").unwrap(), &vec!["site1"]);
+        assert_eq!(meta.get("group_id").unwrap(), &vec!["group1", "group2"]);
+
+        assert_eq!(
+            record.components,
+            vec![
+                Component::new("CPU", 4)
+                    .unwrap()
+                    .with_score(Score::new("HEPSPEC06", 10.5).unwrap())
+                    .with_score(Score::new("HEPscore23", 9.0).unwrap()),
+                Component::new("MEM", 16).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn optional_vars_can_be_omitted() {
+        set_vars(
+            "FROM_ENV_MINIMAL",
+            &[("RECORD_ID", "job-1"), ("START", "1672574400")],
+        );
+
+        let record = RecordAdd::from_env("FROM_ENV_MINIMAL").unwrap();
+
+        assert_eq!(record.meta, None);
+        assert!(record.components.is_empty());
+        assert_eq!(record.stop_time, None);
+    }
+
+    #[test]
+    fn missing_required_var_is_reported() {
+        set_vars("FROM_ENV_MISSING", &[("RECORD_ID", "job-1")]);
+
+        let error = RecordAdd::from_env("FROM_ENV_MISSING").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Missing required environment variable FROM_ENV_MISSING_START"
+        );
+    }
+
+    #[test]
+    fn invalid_vars_are_reported() {
+        let cases = [
+            ("START", "yesterday"),
+            ("META", "site_id"),
+            ("COMPONENTS", "CPU"),
+            ("COMPONENTS", "CPU=four"),
+            ("COMPONENTS", "CPU=4:HEPSPEC06"),
+            ("COMPONENTS", "CPU/1=4"),
+        ];
+        for (i, (name, value)) in cases.into_iter().enumerate() {
+            let prefix = format!("FROM_ENV_INVALID_{i}");
+            set_vars(
+                &prefix,
+                &[
+                    ("RECORD_ID", "job-1"),
+                    ("START", "1672574400"),
+                    (name, value),
+                ],
+            );
+
+            let error = RecordAdd::from_env(&prefix).unwrap_err();
+
+            assert!(
+                format!("{error:#}").contains(&format!("{prefix}_{name}")),
+                "{name}={value}: {error:#}"
+            );
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use super::ValidName;
+use serde::{Deserialize, Deserializer};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+thread_local! {
+    static NAMES: RefCell<Option<HashSet<ValidName>>> = const { RefCell::new(None) };
+}
+
+/// Shares the memory of equal component and score names which are deserialized by `f`.
+///
+/// Records usually contain only a handful of different component and score names (e.g. `CPU`
+/// and `HEPSPEC06`). When deserializing a large number of records, storing each of these names
+/// only once considerably reduces the memory needed to hold the records.
+///
+/// Only names deserialized on the current thread while `f` is running are interned. As `f` is
+/// synchronous, the interned names are dropped at the end of the call and never held across an
+/// `.await` point. The Auditor client deserializes the records of `get` and `advanced_query`
+/// with this function.
+///
+/// # Example
+///
+/// ```
+/// # use auditor::domain::{with_interned_names, Record};
+/// # fn main() -> Result<(), serde_json::Error> {
+/// # let body = b"[]";
+/// let records: Vec<Record> = with_interned_names(|| serde_json::from_slice(body))?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn with_interned_names<T>(f: impl FnOnce() -> T) -> T {
+    if NAMES.with(|names| names.borrow().is_some()) {
+        // Already interning, share the names with the outer call
+        return f();
+    }
+
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            NAMES.with(|names| names.borrow_mut().take());
+        }
+    }
+
+    NAMES.with(|names| *names.borrow_mut() = Some(HashSet::new()));
+    let _reset = Reset;
+    f()
+}
+
+fn intern(name: ValidName) -> ValidName {
+    NAMES.with(|names| match names.borrow_mut().as_mut() {
+        Some(names) => match names.get(&name) {
+            Some(interned) => interned.clone(),
+            None => {
+                names.insert(name.clone());
+                name
+            }
+        },
+        None => name,
+    })
+}
+
+/// Deserializes a component or score name, see [`with_interned_names`].
+pub(super) fn deserialize_name<'de, D>(deserializer: D) -> Result<ValidName, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    ValidName::deserialize(deserializer).map(intern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Component, Record, RecordTest, Score};
+    use fake::{Fake, Faker};
+
+    fn records_json(n: usize) -> Vec<u8> {
+        let records = (0..n)
+            .map(|_| {
+                let mut record = Record::try_from(Faker.fake::<RecordTest>()).unwrap();
+                record.components = Some(vec![Component::new("CPU", 8)
+                    .unwrap()
+                    .with_score(Score::new("HEPSPEC06", 10.0).unwrap())]);
+                record
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_vec(&records).unwrap()
+    }
+
+    // Number of distinct allocations holding component and score names
+    fn name_allocations(records: &[Record]) -> usize {
+        records
+            .iter()
+            .flat_map(|r| r.components.as_ref().unwrap())
+            .flat_map(|c| {
+                std::iter::once(c.name.as_ref().as_ptr())
+                    .chain(c.scores.iter().map(|s| s.name.as_ref().as_ptr()))
+            })
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[test]
+    fn names_are_not_shared_by_default() {
+        let json = records_json(100);
+
+        let records: Vec<Record> = serde_json::from_slice(&json).unwrap();
+
+        assert_eq!(name_allocations(&records), 200);
+    }
+
+    #[test]
+    fn interned_names_are_shared() {
+        let json = records_json(100);
+
+        let records: Vec<Record> = with_interned_names(|| serde_json::from_slice(&json)).unwrap();
+
+        assert_eq!(name_allocations(&records), 2);
+        assert_eq!(
+            records,
+            serde_json::from_slice::<Vec<Record>>(&json).unwrap()
+        );
+    }
+
+    #[test]
+    fn interning_ends_with_the_call() {
+        let json = records_json(10);
+
+        let _: Vec<Record> = with_interned_names(|| serde_json::from_slice(&json)).unwrap();
+        let records: Vec<Record> = serde_json::from_slice(&json).unwrap();
+
+        assert!(NAMES.with(|names| names.borrow().is_none()));
+        assert_eq!(name_allocations(&records), 20);
+    }
+}
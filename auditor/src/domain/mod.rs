@@ -6,22 +6,39 @@
 // copied, modified, or distributed except according to those terms.
 
 mod component;
+mod filter;
+mod from_env;
+mod interner;
 mod meta;
+mod query;
 mod record;
+mod sanitize;
 mod score;
+mod server_status;
 mod validamount;
 mod validname;
 mod validvalue;
 
-use actix_web::{http::StatusCode, ResponseError};
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
 pub use component::{Component, ComponentTest};
+pub use filter::{And, HasComponent, MetaContains, Not, Or, RecordFilter, TimeWindow};
+pub use interner::with_interned_names;
 pub use meta::{Meta, ValidMeta};
-pub use record::{Record, RecordAdd, RecordDatabase, RecordTest, RecordUpdate};
+pub use query::{
+    ComponentOperator, Filters, MetaOperator, Operator, RecordIdFilter, RecordsEnvelope, SortField,
+    SortOption,
+};
+pub use record::{
+    Record, RecordAdd, RecordDatabase, RecordTest, RecordTooOld, RecordUpdate, RuntimeMismatch,
+};
+pub use sanitize::SanitizationMode;
 pub use score::{Score, ScoreTest};
+pub use server_status::{ComponentStatus, ServerStatus};
 pub use validamount::ValidAmount;
 pub use validname::ValidName;
 pub use validvalue::ValidValue;
 
+use crate::constants::{ErrorCode, ERROR_CODE_HEADER};
 use crate::error::error_chain_fmt;
 
 #[derive(thiserror::Error)]
@@ -43,4 +60,26 @@ impl ResponseError for ValidationError {
     fn status_code(&self) -> StatusCode {
         StatusCode::BAD_REQUEST
     }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header((ERROR_CODE_HEADER, ErrorCode::ValidationFailed.as_str()))
+            .content_type("text/plain; charset=utf-8")
+            .body(self.to_string())
+    }
+}
+
+/// Reason why a record of a batch sent to `POST /records` was rejected.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecordValidationError {
+    /// Position of the record in the batch.
+    pub index: usize,
+    /// Why the record is invalid.
+    pub reason: String,
+}
+
+impl std::fmt::Display for RecordValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "record {}: {}", self.index, self.reason)
+    }
 }
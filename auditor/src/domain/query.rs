@@ -0,0 +1,194 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Filters of record queries (`GET /records`), shared by the server and the client.
+
+use super::{Record, ValidAmount, ValidName};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Filters {
+    pub record_id: Option<RecordIdFilter>,
+    pub start_time: Option<Operator<DateTime<Utc>>>,
+    pub stop_time: Option<Operator<DateTime<Utc>>>,
+    pub ingested_at: Option<Operator<DateTime<Utc>>>,
+    pub updated_since: Option<DateTime<Utc>>,
+    /// Only selects finished (`true`) or unfinished (`false`) records, i.e. records with or
+    /// without a `stop_time`.
+    pub stopped: Option<bool>,
+    /// Also selects records which were soft-deleted.
+    pub include_deleted: Option<bool>,
+    pub runtime: Option<Operator<ValidAmount>>,
+    pub meta: Option<HashMap<ValidName, MetaOperator>>,
+    pub component: Option<HashMap<ValidName, ComponentOperator>>,
+    pub sort_by: Option<SortOption>,
+    pub limit: Option<ValidAmount>,
+    /// Wraps the records in a [`RecordsEnvelope`] instead of
+    /// returning a bare list.
+    pub envelope: Option<bool>,
+}
+
+impl Filters {
+    pub fn is_all_none(&self) -> bool {
+        self.record_id.is_none()
+            && self.start_time.is_none()
+            && self.stop_time.is_none()
+            && self.ingested_at.is_none()
+            && self.updated_since.is_none()
+            && self.stopped.is_none()
+            && self.include_deleted.is_none()
+            && self.runtime.is_none()
+            && self.meta.is_none()
+            && self.component.is_none()
+            && self.sort_by.is_none()
+            && self.limit.is_none()
+            && self.envelope.is_none()
+    }
+}
+
+/// Filter on the `record_id`, given as `record_id=<id>` or `record_id[prefix]=<prefix>`.
+#[derive(Debug, Clone)]
+pub enum RecordIdFilter {
+    Equals(ValidName),
+    Prefix(ValidName),
+}
+
+impl<'de> serde::Deserialize<'de> for RecordIdFilter {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct RecordIdFilterVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for RecordIdFilterVisitor {
+            type Value = RecordIdFilter;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a record id or a map with the key `prefix`")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                ValidName::parse(v.to_string())
+                    .map(RecordIdFilter::Equals)
+                    .map_err(E::custom)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                let mut filter = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "prefix" if filter.is_none() => {
+                            filter = Some(RecordIdFilter::Prefix(map.next_value()?));
+                        }
+                        "prefix" => return Err(serde::de::Error::duplicate_field("prefix")),
+                        key => return Err(serde::de::Error::unknown_field(key, &["prefix"])),
+                    }
+                }
+                filter.ok_or_else(|| serde::de::Error::missing_field("prefix"))
+            }
+        }
+
+        deserializer.deserialize_any(RecordIdFilterVisitor)
+    }
+}
+
+impl serde::Serialize for RecordIdFilter {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            RecordIdFilter::Equals(record_id) => serde::Serialize::serialize(record_id, serializer),
+            RecordIdFilter::Prefix(prefix) => {
+                serde::Serialize::serialize(&HashMap::from([("prefix", prefix)]), serializer)
+            }
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Operator<T> {
+    pub gt: Option<T>,
+    pub lt: Option<T>,
+    pub gte: Option<T>,
+    pub lte: Option<T>,
+    pub equals: Option<T>,
+}
+
+/// Filter on a component, given as `component[<name>][gt]=<amount>` for the amount and as
+/// `component[<name>][score][<score_name>][gt]=<value>` for the value of an attached score.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct ComponentOperator {
+    pub gt: Option<ValidAmount>,
+    pub lt: Option<ValidAmount>,
+    pub gte: Option<ValidAmount>,
+    pub lte: Option<ValidAmount>,
+    pub equals: Option<ValidAmount>,
+    pub score: Option<HashMap<ValidName, Operator<f64>>>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MetaOperator {
+    pub c: Option<ValidName>,
+    pub dnc: Option<ValidName>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOption {
+    ASC(SortField),
+    DESC(SortField),
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum SortField {
+    #[serde(rename = "start_time")]
+    StartTime,
+    #[serde(rename = "stop_time")]
+    StopTime,
+    #[serde(rename = "runtime")]
+    Runtime,
+    #[serde(rename = "record_id")]
+    RecordId,
+    #[serde(rename = "ingested_at")]
+    IngestedAt,
+    /// Amount of the component with the given name. Records without this component are sorted
+    /// last.
+    #[serde(rename = "component")]
+    Component(ValidName),
+}
+
+impl Display for SortField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortField::StartTime => write!(f, "start_time"),
+            SortField::StopTime => write!(f, "stop_time"),
+            SortField::Runtime => write!(f, "runtime"),
+            SortField::RecordId => write!(f, "record_id"),
+            SortField::IngestedAt => write!(f, "ingested_at"),
+            SortField::Component(name) => write!(f, "component[{name}]"),
+        }
+    }
+}
+
+/// Response of `GET /records?envelope=true`.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct RecordsEnvelope {
+    /// Filters as understood by the server.
+    pub query: Filters,
+    /// Number of returned records.
+    pub count: usize,
+    pub records: Vec<Record>,
+    /// Whether more records match the query than were returned because of `limit`.
+    pub truncated: bool,
+}
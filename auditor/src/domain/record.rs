@@ -11,7 +11,7 @@ use std::collections::HashMap;
 
 use super::{Component, ComponentTest, Meta, ScoreTest, ValidMeta, ValidName};
 use anyhow::{Context, Error};
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use fake::{Dummy, Fake, Faker, StringFaker};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -115,6 +115,10 @@ pub struct RecordAdd {
     pub start_time: DateTime<Utc>,
     /// Stop time of the record.
     pub stop_time: Option<DateTime<Utc>>,
+    /// Runtime of the record in seconds. Optional, as Auditor computes the runtime from start and
+    /// stop time itself. If set, Auditor rejects the record if it does not match.
+    #[serde(default)]
+    pub runtime: Option<i64>,
 }
 
 /// `RecordUpdate` represents a single accountable unit that is used to set the `stop_time` of a
@@ -177,6 +181,52 @@ pub struct RecordUpdate {
     pub start_time: Option<DateTime<Utc>>,
     /// Stop time of the record.
     pub stop_time: DateTime<Utc>,
+    /// Runtime of the record in seconds. Optional, as Auditor computes the runtime from start and
+    /// stop time itself. If set, Auditor rejects the update if it does not match.
+    #[serde(default)]
+    pub runtime: Option<i64>,
+}
+
+/// A runtime which does not match the time between start and stop time of a record.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("runtime of {runtime}s does not match the {expected}s between start and stop time")]
+pub struct RuntimeMismatch {
+    /// Runtime sent with the record.
+    pub runtime: i64,
+    /// Runtime computed from start and stop time.
+    pub expected: i64,
+}
+
+impl RuntimeMismatch {
+    // Checks `runtime` against the duration between `start_time` and `stop_time`, allowing a
+    // difference of up to `tolerance` seconds.
+    fn check(
+        start_time: DateTime<Utc>,
+        stop_time: DateTime<Utc>,
+        runtime: i64,
+        tolerance: u64,
+    ) -> Result<(), RuntimeMismatch> {
+        let expected = (stop_time - start_time).num_seconds();
+        if runtime.abs_diff(expected) <= tolerance {
+            Ok(())
+        } else {
+            Err(RuntimeMismatch { runtime, expected })
+        }
+    }
+}
+
+/// A record whose `stop_time` is older than the maximum record age accepted by Auditor.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "stop_time {stop_time} is older than the maximum record age of {max_age_secs}s. \
+    Check the stop_time of the record or, if it is meant to be backfilled, add it with one of \
+    the backfill tokens"
+)]
+pub struct RecordTooOld {
+    /// `stop_time` of the record.
+    pub stop_time: DateTime<Utc>,
+    /// Maximum record age in seconds.
+    pub max_age_secs: i64,
 }
 
 /// A `Record` represents a single accountable unit.
@@ -221,6 +271,18 @@ pub struct Record {
     pub stop_time: Option<DateTime<Utc>>,
     /// Runtime of the record, i.e. the difference between stop and start time.
     pub runtime: Option<i64>,
+    /// Time at which the record was first received by Auditor. Set by the server on insert and
+    /// never changed by updates.
+    pub ingested_at: Option<DateTime<Utc>>,
+    /// Time at which the record was last added or updated in Auditor. Set by the server.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Record {
+    /// Returns the time between start and stop time, if both are known.
+    pub fn duration(&self) -> Option<Duration> {
+        Some(self.stop_time? - self.start_time?)
+    }
 }
 
 #[doc(hidden)]
@@ -232,6 +294,8 @@ pub struct RecordDatabase {
     pub start_time: Option<DateTime<Utc>>,
     pub stop_time: Option<DateTime<Utc>>,
     pub runtime: Option<i64>,
+    pub ingested_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
 }
 
 #[doc(hidden)]
@@ -250,7 +314,7 @@ impl RecordAdd {
     /// # Errors
     ///
     /// * [`anyhow::Error`] - If there was an invalid character (`/()"<>\{}`) in the `record_id` or the
-    ///     `meta` information.
+    ///   `meta` information.
     pub fn new<T: AsRef<str>>(
         record_id: T,
         meta: HashMap<T, Vec<T>>,
@@ -270,6 +334,7 @@ impl RecordAdd {
             components,
             start_time,
             stop_time: None,
+            runtime: None,
         })
     }
 
@@ -279,6 +344,35 @@ impl RecordAdd {
         self.stop_time = Some(stop_time);
         self
     }
+
+    /// Set the runtime of the record in seconds, which Auditor checks against start and stop time.
+    #[must_use]
+    pub fn with_runtime(mut self, runtime: i64) -> Self {
+        self.runtime = Some(runtime);
+        self
+    }
+
+    /// Checks that the runtime, if set together with the stop time, matches the time between start
+    /// and stop time within `tolerance` seconds.
+    pub fn check_runtime(&self, tolerance: u64) -> Result<(), RuntimeMismatch> {
+        match (self.stop_time, self.runtime) {
+            (Some(stop_time), Some(runtime)) => {
+                RuntimeMismatch::check(self.start_time, stop_time, runtime, tolerance)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the stop time, if set, is not older than `max_age` at `now`.
+    pub fn check_age(&self, max_age: Duration, now: DateTime<Utc>) -> Result<(), RecordTooOld> {
+        match self.stop_time {
+            Some(stop_time) if stop_time < now - max_age => Err(RecordTooOld {
+                stop_time,
+                max_age_secs: max_age.num_seconds(),
+            }),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl RecordUpdate {
@@ -287,7 +381,7 @@ impl RecordUpdate {
     /// # Errors
     ///
     /// * [`anyhow::Error`] - If there was an invalid character (`/()"<>\{}`) in the `record_id` or the
-    ///     `meta` information.
+    ///   `meta` information.
     pub fn new<T: AsRef<str>>(
         record_id: T,
         meta: HashMap<T, Vec<T>>,
@@ -307,8 +401,24 @@ impl RecordUpdate {
             components,
             start_time: None,
             stop_time,
+            runtime: None,
         })
     }
+
+    /// Checks that the runtime, if set, matches the time between `start_time` and the stop time
+    /// of the update within `tolerance` seconds.
+    ///
+    /// `start_time` is the start time stored by Auditor, as the start time of an update is ignored.
+    pub fn check_runtime(
+        &self,
+        start_time: DateTime<Utc>,
+        tolerance: u64,
+    ) -> Result<(), RuntimeMismatch> {
+        match self.runtime {
+            Some(runtime) => RuntimeMismatch::check(start_time, self.stop_time, runtime, tolerance),
+            None => Ok(()),
+        }
+    }
 }
 
 impl RecordTest {
@@ -433,6 +543,8 @@ impl PartialEq<Record> for RecordTest {
             start_time: o_start,
             stop_time: o_stop,
             runtime: _,
+            ingested_at: _,
+            updated_at: _,
         } = other;
 
         // Can't be equal if record ID and start_time are not set in `RecordTest`.
@@ -452,14 +564,14 @@ impl PartialEq<Record> for RecordTest {
         let stop = match (s_stop, o_stop) {
             (Some(s), Some(o)) => {
                 let stop_diff = if s > o { *s - *o } else { *o - *s };
-                stop_diff < chrono::Duration::try_milliseconds(1).expect("This should never fail")
+                stop_diff < Duration::try_milliseconds(1).expect("This should never fail")
             }
             (None, None) => true,
             _ => false,
         };
 
         s_rid.as_ref().unwrap() == o_rid
-            && start_diff < chrono::Duration::try_milliseconds(1).expect("This should never fail")
+            && start_diff < Duration::try_milliseconds(1).expect("This should never fail")
             && stop
             && ((s_comp.is_none() && o_comp.is_none())
                 || (
@@ -512,6 +624,7 @@ impl TryFrom<RecordTest> for RecordAdd {
                 .collect::<Result<Vec<_>, _>>()?,
             start_time: value.start_time.unwrap(),
             stop_time: value.stop_time,
+            runtime: None,
         })
     }
 }
@@ -533,6 +646,7 @@ impl TryFrom<Record> for RecordAdd {
                 .start_time
                 .ok_or_else(|| anyhow::anyhow!("No start time"))?,
             stop_time: value.stop_time,
+            runtime: None,
         })
     }
 }
@@ -556,6 +670,7 @@ impl TryFrom<RecordTest> for RecordUpdate {
                 .collect::<Result<Vec<_>, _>>()?,
             start_time: value.start_time,
             stop_time: value.stop_time.unwrap(),
+            runtime: None,
         })
     }
 }
@@ -574,6 +689,8 @@ impl From<RecordAdd> for Record {
             start_time: Some(r.start_time),
             stop_time: r.stop_time,
             runtime,
+            ingested_at: None,
+            updated_at: None,
         }
     }
 }
@@ -592,6 +709,8 @@ impl From<RecordUpdate> for Record {
             start_time: r.start_time,
             stop_time: Some(r.stop_time),
             runtime,
+            ingested_at: None,
+            updated_at: None,
         }
     }
 }
@@ -611,6 +730,7 @@ impl TryFrom<Record> for RecordUpdate {
                 .collect::<Result<Vec<_>, _>>()?,
             start_time: value.start_time,
             stop_time: value.stop_time.unwrap(),
+            runtime: None,
         })
     }
 }
@@ -646,6 +766,8 @@ impl TryFrom<RecordTest> for Record {
             } else {
                 None
             },
+            ingested_at: None,
+            updated_at: None,
         })
     }
 }
@@ -661,6 +783,8 @@ impl TryFrom<RecordDatabase> for Record {
             start_time,
             stop_time,
             runtime,
+            ingested_at,
+            updated_at,
         } = other;
         let meta = if let Some(meta) = meta {
             serde_json::from_value(meta).ok()
@@ -680,6 +804,173 @@ impl TryFrom<RecordDatabase> for Record {
             start_time,
             stop_time,
             runtime,
+            ingested_at,
+            updated_at,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn record_without_updated_at_can_be_deserialized() {
+        let record: Record = serde_json::from_str(
+            r#"{
+                "record_id": "r1",
+                "meta": null,
+                "components": null,
+                "start_time": "2022-10-01T12:00:00Z",
+                "stop_time": null,
+                "runtime": null
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(record.updated_at, None);
+        assert_eq!(record.ingested_at, None);
+    }
+
+    #[test]
+    fn large_runtime_is_serialized_as_integer() {
+        let mut record = Record::from(
+            RecordAdd::new(
+                "r1",
+                HashMap::new(),
+                vec![],
+                Utc.with_ymd_and_hms(2022, 10, 1, 12, 0, 0).unwrap(),
+            )
+            .unwrap(),
+        );
+        record.runtime = Some(i64::MAX - 1);
+
+        let json = serde_json::to_string(&record).unwrap();
+        assert!(json.contains(r#""runtime":9223372036854775806"#));
+
+        let deserialized: Record = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.runtime, Some(i64::MAX - 1));
+    }
+
+    #[test]
+    fn updated_at_is_serialized() {
+        let updated_at = Utc.with_ymd_and_hms(2022, 10, 1, 13, 0, 0).unwrap();
+        let mut record = Record::from(
+            RecordAdd::new(
+                "r1",
+                HashMap::new(),
+                vec![],
+                Utc.with_ymd_and_hms(2022, 10, 1, 12, 0, 0).unwrap(),
+            )
+            .unwrap(),
+        );
+        record.updated_at = Some(updated_at);
+
+        let value = serde_json::to_value(&record).unwrap();
+        assert_eq!(value["updated_at"], "2022-10-01T13:00:00Z");
+
+        let deserialized: Record = serde_json::from_value(value).unwrap();
+        assert_eq!(deserialized, record);
+    }
+
+    fn record_add(start: u32, stop: u32) -> RecordAdd {
+        RecordAdd::new(
+            "r1",
+            HashMap::new(),
+            vec![],
+            Utc.with_ymd_and_hms(2022, 10, 1, start, 0, 0).unwrap(),
+        )
+        .unwrap()
+        .with_stop_time(Utc.with_ymd_and_hms(2022, 10, 1, stop, 0, 0).unwrap())
+    }
+
+    #[test]
+    fn duration_is_time_between_start_and_stop() {
+        let record = Record::from(record_add(12, 14));
+        assert_eq!(record.duration(), Some(Duration::hours(2)));
+
+        let mut record = record;
+        record.stop_time = None;
+        assert_eq!(record.duration(), None);
+    }
+
+    #[test]
+    fn runtime_is_checked_within_tolerance() {
+        assert_eq!(record_add(12, 14).check_runtime(0), Ok(()));
+        assert_eq!(
+            record_add(12, 14).with_runtime(7200).check_runtime(0),
+            Ok(())
+        );
+        assert_eq!(
+            record_add(12, 14).with_runtime(7202).check_runtime(2),
+            Ok(())
+        );
+        assert_eq!(
+            record_add(12, 14).with_runtime(7198).check_runtime(2),
+            Ok(())
+        );
+        assert_eq!(
+            record_add(12, 14).with_runtime(7203).check_runtime(2),
+            Err(RuntimeMismatch {
+                runtime: 7203,
+                expected: 7200
+            })
+        );
+    }
+
+    #[test]
+    fn runtime_is_not_checked_without_stop_time() {
+        let mut record = record_add(12, 14).with_runtime(1);
+        record.stop_time = None;
+
+        assert_eq!(record.check_runtime(0), Ok(()));
+    }
+
+    #[test]
+    fn age_is_checked_against_stop_time() {
+        let now = Utc.with_ymd_and_hms(2022, 10, 2, 14, 0, 0).unwrap();
+
+        assert_eq!(record_add(12, 14).check_age(Duration::days(1), now), Ok(()));
+        assert_eq!(
+            record_add(12, 13).check_age(Duration::days(1), now),
+            Err(RecordTooOld {
+                stop_time: Utc.with_ymd_and_hms(2022, 10, 1, 13, 0, 0).unwrap(),
+                max_age_secs: 86400
+            })
+        );
+    }
+
+    #[test]
+    fn age_is_not_checked_without_stop_time() {
+        let mut record = record_add(12, 14);
+        record.stop_time = None;
+
+        assert_eq!(
+            record.check_age(Duration::hours(1), Utc::now() + Duration::days(365)),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn runtime_of_update_is_checked_against_given_start_time() {
+        let stop_time = Utc.with_ymd_and_hms(2022, 10, 1, 14, 0, 0).unwrap();
+        let mut update = RecordUpdate::new("r1", HashMap::new(), vec![], stop_time).unwrap();
+        update.runtime = Some(3600);
+
+        let start_time = Utc.with_ymd_and_hms(2022, 10, 1, 13, 0, 0).unwrap();
+        assert_eq!(update.check_runtime(start_time, 0), Ok(()));
+        let start_time = Utc.with_ymd_and_hms(2022, 10, 1, 12, 0, 0).unwrap();
+        assert!(update.check_runtime(start_time, 0).is_err());
+    }
+
+    #[test]
+    fn runtime_is_optional_when_deserializing() {
+        let mut json = serde_json::to_value(record_add(12, 14)).unwrap();
+        assert!(json["runtime"].is_null());
+        json.as_object_mut().unwrap().remove("runtime");
+
+        let record: RecordAdd = serde_json::from_value(json).unwrap();
+        assert_eq!(record.runtime, None);
+    }
+}
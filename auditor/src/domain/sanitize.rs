@@ -0,0 +1,103 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::constants::FORBIDDEN_CHARACTERS;
+use std::string::FromUtf8Error;
+
+/// Determines how the [`FORBIDDEN_CHARACTERS`] are removed from strings, e.g. by collectors
+/// before they are used as names or meta values.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SanitizationMode {
+    /// Remove the forbidden characters. The original string cannot be restored.
+    #[default]
+    Strip,
+    /// Percent-encode the forbidden characters and `%`, e.g. `/` becomes `%2F`. The original
+    /// string can be restored with [`SanitizationMode::restore`] or any URL decoder.
+    UrlEncode,
+}
+
+impl SanitizationMode {
+    /// Returns `input` without forbidden characters.
+    pub fn sanitize<T: AsRef<str>>(&self, input: T) -> String {
+        let input = input.as_ref();
+        match self {
+            SanitizationMode::Strip => input.replace(&FORBIDDEN_CHARACTERS[..], ""),
+            SanitizationMode::UrlEncode => {
+                let mut encoded = String::with_capacity(input.len());
+                for c in input.chars() {
+                    if c == '%' || FORBIDDEN_CHARACTERS.contains(&c) {
+                        // All of these characters are ASCII, hence a single byte
+                        encoded.push_str(&format!("%{:02X}", c as u8));
+                    } else {
+                        encoded.push(c);
+                    }
+                }
+                encoded
+            }
+        }
+    }
+
+    /// Restores a string sanitized with this mode. Strings sanitized with
+    /// [`SanitizationMode::Strip`] are returned unchanged.
+    pub fn restore<T: AsRef<str>>(&self, input: T) -> Result<String, FromUtf8Error> {
+        match self {
+            SanitizationMode::Strip => Ok(input.as_ref().to_string()),
+            SanitizationMode::UrlEncode => {
+                urlencoding::decode(input.as_ref()).map(|decoded| decoded.into_owned())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ValidName;
+
+    #[test]
+    fn strip_removes_forbidden_characters() {
+        assert_eq!(
+            SanitizationMode::Strip.sanitize("/DC=org/CN=Jane (Doe)"),
+            "DC=orgCN=Jane Doe"
+        );
+    }
+
+    #[test]
+    fn url_encode_encodes_forbidden_characters() {
+        assert_eq!(
+            SanitizationMode::UrlEncode.sanitize("/DC=org/CN=Jane (Doe) 100%"),
+            "%2FDC=org%2FCN=Jane %28Doe%29 100%25"
+        );
+    }
+
+    #[test]
+    fn url_encode_round_trips() {
+        let mode = SanitizationMode::UrlEncode;
+        for input in [
+            "/DC=org/CN=Jane (Doe)",
+            r#"{"a": "<b>\c"}"#,
+            "%2F",
+            "ünïcödé/",
+        ] {
+            let sanitized = mode.sanitize(input);
+            assert!(ValidName::parse(sanitized.clone()).is_ok(), "{sanitized}");
+            assert_eq!(mode.restore(&sanitized).unwrap(), input);
+        }
+    }
+
+    #[quickcheck]
+    fn url_encode_round_trips_arbitrary_strings(input: String) -> bool {
+        let mode = SanitizationMode::UrlEncode;
+        let sanitized = mode.sanitize(&input);
+        !sanitized.contains(&FORBIDDEN_CHARACTERS[..]) && mode.restore(&sanitized).unwrap() == input
+    }
+
+    #[test]
+    fn strip_restore_is_identity() {
+        assert_eq!(SanitizationMode::Strip.restore("abc%2F").unwrap(), "abc%2F");
+    }
+}
@@ -5,7 +5,7 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use super::{ValidName, ValidValue};
+use super::{interner::deserialize_name, ValidName, ValidValue};
 use anyhow::{Context, Error};
 use fake::{Dummy, Fake, Faker, StringFaker};
 use rand::Rng;
@@ -35,6 +35,7 @@ use std::cmp::Ordering;
 #[sqlx(type_name = "score")]
 #[sqlx(no_pg_array)]
 pub struct Score {
+    #[serde(deserialize_with = "deserialize_name")]
     pub name: ValidName,
     pub value: ValidValue,
 }
@@ -45,7 +46,7 @@ impl Score {
     /// # Errors
     ///
     /// * [`anyhow::Error`] - If there was an invalid character (`/()"<>\{}`) in the `name`
-    ///     or if a negative `value` was given.
+    ///   or if a negative, infinite or `NaN` `value` was given.
     pub fn new<T: AsRef<str>>(name: T, value: f64) -> Result<Self, Error> {
         Ok(Score {
             name: ValidName::parse(name.as_ref().to_string())
@@ -105,7 +106,7 @@ impl Eq for Score {}
 
 impl PartialOrd for Score {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.name.as_ref().cmp(other.name.as_ref()))
+        Some(self.cmp(other))
     }
 }
 
@@ -142,12 +143,7 @@ impl ScoreTest {
 
 impl PartialOrd for ScoreTest {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(
-            self.name
-                .as_ref()
-                .unwrap()
-                .cmp(other.name.as_ref().unwrap()),
-        )
+        Some(self.cmp(other))
     }
 }
 
@@ -241,7 +237,7 @@ impl Dummy<Faker> for ScoreTest {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use claim::assert_ok;
+    use claim::{assert_err, assert_ok};
 
     impl quickcheck::Arbitrary for ScoreTest {
         fn arbitrary(_g: &mut quickcheck::Gen) -> Self {
@@ -249,8 +245,35 @@ mod tests {
         }
     }
 
+    #[derive(Debug, Clone)]
+    struct InvalidValue(f64);
+
+    impl quickcheck::Arbitrary for InvalidValue {
+        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
+            Self(
+                *g.choose(&[
+                    f64::NAN,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    (f64::MIN..-f64::EPSILON).fake(),
+                ])
+                .unwrap(),
+            )
+        }
+    }
+
     #[quickcheck]
     fn a_valid_name_is_parsed_successfully(score: ScoreTest) {
         assert_ok!(Score::try_from(score));
     }
+
+    #[quickcheck]
+    fn a_valid_score_is_created_successfully(score: ScoreTest) {
+        assert_ok!(Score::new(score.name.unwrap(), score.value.unwrap()));
+    }
+
+    #[quickcheck]
+    fn an_invalid_value_is_rejected(value: InvalidValue) {
+        assert_err!(Score::new("HEPSPEC06", value.0));
+    }
 }
@@ -5,19 +5,28 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::constants::FORBIDDEN_CHARACTERS;
+use crate::constants::{FORBIDDEN_CHARACTERS, MAX_NAME_LENGTH};
 use crate::domain::ValidationError;
 use anyhow::Context;
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    postgres::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef},
+    Decode, Encode, Postgres, Type,
+};
 use std::fmt;
+use std::sync::Arc;
 use unicode_segmentation::UnicodeSegmentation;
 
 // never turn this into `ValidName(pub String)`. By keeping the inner field private, it is not
 // possible to create this type outside of this module, hence enforcing the use of `parse`. This
 // ensures that every string stored in this type satisfies the validation criteria checked by
 // `parse`.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
-#[sqlx(transparent)]
-pub struct ValidName(String);
+//
+// The string is reference counted, such that clones of a name share their memory (see
+// `with_interned_names`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ValidName(Arc<str>);
 
 impl ValidName {
     /// Returns `ValidName` only if input satisfies validation criteria, otherwise panics.
@@ -25,13 +34,13 @@ impl ValidName {
         // remove trailing whitespace and check if string is then empty
         let is_empty_or_whitespace = s.trim().is_empty();
         // count characters
-        let is_too_long = s.graphemes(true).count() > 256;
+        let is_too_long = s.graphemes(true).count() > MAX_NAME_LENGTH;
         // check for forbidden characters
         let contains_forbidden_characters = s.chars().any(|g| FORBIDDEN_CHARACTERS.contains(&g));
         if is_empty_or_whitespace || is_too_long || contains_forbidden_characters {
             Err(ValidationError(format!("Invalid Name: {s}")))
         } else {
-            Ok(Self(s))
+            Ok(Self(s.into()))
         }
     }
 }
@@ -66,6 +75,35 @@ impl<'de> serde::Deserialize<'de> for ValidName {
     }
 }
 
+// manual impls instead of `#[sqlx(transparent)]`, because sqlx does not support `Arc<str>`.
+impl Type<Postgres> for ValidName {
+    fn type_info() -> PgTypeInfo {
+        <&str as Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for ValidName {
+    fn array_type_info() -> PgTypeInfo {
+        <&str as PgHasArrayType>::array_type_info()
+    }
+}
+
+impl Encode<'_, Postgres> for ValidName {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <&str as Encode<Postgres>>::encode(&*self.0, buf)
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for ValidName {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(Self(<&str as Decode<Postgres>>::decode(value)?.into()))
+    }
+}
+
 impl fmt::Display for ValidName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0)
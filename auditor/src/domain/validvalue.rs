@@ -20,7 +20,8 @@ pub struct ValidValue(f64);
 impl ValidValue {
     /// Returns `ValidValue` only if input satisfies validation criteria, otherwise panics.
     pub fn parse(s: f64) -> Result<ValidValue, ValidationError> {
-        if s < 0.0 {
+        // `NaN` and infinite values would propagate through all computations based on them
+        if !s.is_finite() || s < 0.0 {
             Err(ValidationError(format!("Invalid value: {s}")))
         } else {
             Ok(Self(s))
@@ -100,6 +101,13 @@ mod tests {
         assert_err!(ValidValue::parse(value.0));
     }
 
+    #[test]
+    fn a_nan_or_infinite_value_is_rejected() {
+        assert_err!(ValidValue::parse(f64::NAN));
+        assert_err!(ValidValue::parse(f64::INFINITY));
+        assert_err!(ValidValue::parse(f64::NEG_INFINITY));
+    }
+
     #[test]
     fn a_zero_value_is_valid() {
         assert_ok!(ValidValue::parse(0.0));
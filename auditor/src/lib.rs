@@ -9,6 +9,10 @@
 #[macro_use(quickcheck)]
 extern crate quickcheck_macros;
 
+#[cfg(feature = "server")]
+pub mod archive;
+#[cfg(feature = "server")]
+pub mod auto_close;
 #[cfg(feature = "server")]
 pub mod configuration;
 pub mod constants;
@@ -16,10 +20,16 @@ pub mod domain;
 pub mod error;
 #[cfg(feature = "server")]
 pub mod metrics;
+#[cfg(feature = "server")]
+pub mod middleware;
 #[macro_use]
 mod macros;
 #[cfg(feature = "server")]
+pub mod publisher;
+#[cfg(feature = "server")]
 pub mod routes;
 #[cfg(feature = "server")]
+pub mod slow_query_log;
+#[cfg(feature = "server")]
 pub mod startup;
 pub mod telemetry;
@@ -40,13 +40,23 @@ macro_rules! error_for_error {
 
 #[macro_export]
 macro_rules! responseerror_for_error {
-    ($error_type:ident, $($field:ident => $code:ident;)*) => {
+    ($error_type:ident, $($field:ident => $code:ident, $error_code:ident;)*) => {
         impl actix_web::ResponseError for $error_type {
             fn status_code(&self) -> actix_web::http::StatusCode {
                 match self {
                     $($error_type::$field(_) => actix_web::http::StatusCode::$code),*
                 }
             }
+
+            fn error_response(&self) -> actix_web::HttpResponse {
+                let error_code = match self {
+                    $($error_type::$field(_) => $crate::constants::ErrorCode::$error_code),*
+                };
+                actix_web::HttpResponse::build(self.status_code())
+                    .insert_header(($crate::constants::ERROR_CODE_HEADER, error_code.as_str()))
+                    .content_type("text/plain; charset=utf-8")
+                    .body(self.to_string())
+            }
         }
     };
 }
@@ -5,11 +5,12 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use auditor::archive::RetentionTask;
+use auditor::auto_close::AutoCloseTask;
 use auditor::configuration::{get_configuration, TLSParams};
 use auditor::metrics::DatabaseMetricsWatcher;
-use auditor::startup::run;
+use auditor::startup::{migrate_database, run, ServerOptions};
 use auditor::telemetry::{get_subscriber, init_subscriber};
-use sqlx::postgres::PgPoolOptions;
 use std::net::TcpListener;
 
 use rustls::{pki_types::PrivateKeyDer, server::WebPkiClientVerifier, RootCertStore, ServerConfig};
@@ -29,8 +30,20 @@ async fn main() -> Result<(), anyhow::Error> {
     init_subscriber(subscriber);
 
     // Create a connection pool for the PostgreSQL database
-    let connection_pool = PgPoolOptions::new()
-        .acquire_timeout(std::time::Duration::from_secs(2))
+    configuration
+        .database
+        .validate_pool_size()
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    configuration
+        .database
+        .validate_ssl()
+        .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+    if configuration.database.auto_migrate {
+        migrate_database(&configuration.database).await?;
+    }
+    let connection_pool = configuration
+        .database
+        .pool_options()
         .connect_lazy_with(configuration.database.with_db());
 
     // Start background task
@@ -41,6 +54,30 @@ async fn main() -> Result<(), anyhow::Error> {
         db_metrics_watcher_task.monitor().await.unwrap();
     });
 
+    if let Some(retention) = &configuration.retention {
+        retention
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+        let retention_task = RetentionTask::new(connection_pool.clone(), retention);
+        tokio::spawn(async move {
+            if let Err(e) = retention_task.run().await {
+                tracing::error!("Retention task stopped: {e}");
+            }
+        });
+    }
+
+    if let Some(auto_close) = &configuration.auto_close {
+        auto_close
+            .validate()
+            .map_err(|e| anyhow::anyhow!("Configuration error: {}", e))?;
+        let auto_close_task = AutoCloseTask::new(connection_pool.clone(), auto_close);
+        tokio::spawn(async move {
+            if let Err(e) = auto_close_task.run().await {
+                tracing::error!("Auto-close task stopped: {e}");
+            }
+        });
+    }
+
     // Create a TcpListener for a given address and port
     let address = format!(
         "{}:{}",
@@ -48,9 +85,15 @@ async fn main() -> Result<(), anyhow::Error> {
     );
     let listener = TcpListener::bind(address)?;
 
-    if let Some(tls) = configuration.tls_config {
-        // tls config if the use_tls option is set to true
-        if tls.use_tls {
+    let publisher = match &configuration.publisher {
+        Some(settings) => Some(auditor::publisher::connect(settings).await?),
+        None => None,
+    };
+    let options = ServerOptions::from_settings(&configuration).with_publisher(publisher);
+
+    // tls config if the use_tls option is set to true
+    let tls_params = match configuration.tls_config {
+        Some(tls) if tls.use_tls => {
             let mut cert_store = RootCertStore::empty();
 
             //rustls::crypto::default_provider().install_default().expect("failed to install default crypto provider");
@@ -99,28 +142,25 @@ async fn main() -> Result<(), anyhow::Error> {
                 .unwrap();
             let config = config.with_single_cert(cert_chain, keys.remove(0)).unwrap();
 
-            let tls_params = TLSParams {
+            Some(TLSParams {
                 config,
                 https_addr: tls.https_addr,
                 https_port: tls.https_port,
                 use_tls: tls.use_tls,
-            };
-
-            run(
-                listener,
-                connection_pool,
-                db_metrics_watcher,
-                Some(tls_params),
-            )?
-            .await?;
-        } else {
-            // Start server
-            run(listener, connection_pool, db_metrics_watcher, None)?.await?;
+            })
         }
-    } else {
-        // Start server
-        run(listener, connection_pool, db_metrics_watcher, None)?.await?;
-    }
+        _ => None,
+    };
+
+    // Start server
+    run(
+        listener,
+        connection_pool,
+        db_metrics_watcher,
+        tls_params,
+        options,
+    )?
+    .await?;
 
     Ok(())
 }
@@ -9,11 +9,14 @@ use opentelemetry_sdk::metrics::SdkMeterProvider;
 use prometheus::Registry;
 
 mod database;
+mod requests;
 pub use database::*;
+pub use requests::*;
 
 pub struct PrometheusExporterConfig {
     pub provider: SdkMeterProvider,
     pub prom_registry: Registry,
+    pub request_latency: RequestLatency,
 }
 
 pub struct PrometheusExporterBuilder {
@@ -38,6 +41,9 @@ impl PrometheusExporterBuilder {
             prom_registry.register(std::boxed::Box::new(db_watcher))?;
         }
 
+        let request_latency = RequestLatency::new()?;
+        prom_registry.register(std::boxed::Box::new(request_latency.histogram()))?;
+
         let metrics_exporter = opentelemetry_prometheus::exporter()
             .with_registry(prom_registry.clone())
             .build()?;
@@ -49,6 +55,7 @@ impl PrometheusExporterBuilder {
         Ok(PrometheusExporterConfig {
             provider,
             prom_registry,
+            request_latency,
         })
     }
 }
@@ -0,0 +1,74 @@
+// Copyright 2021-2022 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use prometheus::{HistogramOpts, HistogramVec};
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets in seconds. Reaches up to several seconds, as queries of
+/// many records are bound by the database.
+pub const REQUEST_LATENCY_BUCKETS: [f64; 13] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+
+/// Histogram of the request durations per route, method and status code.
+#[derive(Clone)]
+pub struct RequestLatency {
+    histogram: HistogramVec,
+}
+
+impl RequestLatency {
+    pub fn new() -> Result<RequestLatency, prometheus::Error> {
+        let histogram = HistogramVec::new(
+            HistogramOpts::new(
+                "auditor_request_duration_seconds",
+                "Duration of the requests handled by Auditor",
+            )
+            .buckets(REQUEST_LATENCY_BUCKETS.to_vec()),
+            &["route", "method", "status"],
+        )?;
+        Ok(RequestLatency { histogram })
+    }
+
+    /// Returns the histogram, such that it can be registered with a Prometheus registry.
+    pub fn histogram(&self) -> HistogramVec {
+        self.histogram.clone()
+    }
+
+    /// Records the duration of a request. `route` is the pattern of the matched route, such
+    /// that the number of label values stays bounded.
+    pub fn observe(&self, route: &str, method: &str, status: u16, duration: Duration) {
+        self.histogram
+            .with_label_values(&[route, method, &status.to_string()])
+            .observe(duration.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::core::Collector;
+
+    #[test]
+    fn durations_are_recorded_per_label() {
+        let latency = RequestLatency::new().unwrap();
+
+        latency.observe("/record", "POST", 200, Duration::from_millis(20));
+        latency.observe("/record", "POST", 200, Duration::from_secs(3));
+        latency.observe("/record", "POST", 500, Duration::from_millis(20));
+
+        let families = latency.histogram().collect();
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].get_name(), "auditor_request_duration_seconds");
+        assert_eq!(families[0].get_metric().len(), 2);
+
+        let ok = latency
+            .histogram()
+            .with_label_values(&["/record", "POST", "200"])
+            .get_sample_count();
+        assert_eq!(ok, 2);
+    }
+}
@@ -0,0 +1,488 @@
+// Copyright 2021-2022 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::constants::{ErrorCode, ERROR_CODE_HEADER, SERVER_LOAD_HEADER};
+use crate::metrics::RequestLatency;
+use crate::routes::MaintenanceMode;
+use actix_tls::accept::rustls_0_23::TlsStream;
+use actix_web::body::MessageBody;
+use actix_web::dev::{Extensions, ServiceRequest, ServiceResponse};
+use actix_web::http::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, RETRY_AFTER, WWW_AUTHENTICATE,
+};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::rt::net::TcpStream;
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
+use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Rejects writing requests with `503 SERVICE UNAVAILABLE` while the maintenance mode is enabled.
+/// Requests to the admin endpoints under `/admin` are always let through, such that the
+/// maintenance mode can be disabled again with `PUT /admin/maintenance`. `/records/by-ids` only
+/// reads records despite being a `POST` request and is let through as well.
+pub async fn reject_writes_in_maintenance(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let in_maintenance = req
+        .app_data::<web::Data<MaintenanceMode>>()
+        .is_some_and(|maintenance| maintenance.is_enabled());
+    let is_write = matches!(
+        *req.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    );
+
+    let is_exempt = req.path() == "/records/by-ids" || req.path().starts_with("/admin/");
+
+    if in_maintenance && is_write && !is_exempt {
+        let response = HttpResponse::ServiceUnavailable()
+            .insert_header((ERROR_CODE_HEADER, ErrorCode::MaintenanceMode.as_str()))
+            .body(ErrorCode::MaintenanceMode.as_str());
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Bearer tokens accepted by [`require_bearer_token`]. Authentication is disabled if there are
+/// no tokens.
+///
+/// The admin endpoints are only accepted with one of the admin tokens, see
+/// [`require_admin_token`]. Records older than the maximum record age are only accepted with one
+/// of the backfill tokens, see [`is_backfill_request`].
+#[derive(Debug, Clone, Default)]
+pub struct BearerAuth {
+    tokens: Vec<Secret<String>>,
+    admin_tokens: Vec<Secret<String>>,
+    backfill_tokens: Vec<Secret<String>>,
+}
+
+impl BearerAuth {
+    /// Constructor.
+    pub fn new(tokens: Vec<Secret<String>>) -> Self {
+        BearerAuth {
+            tokens,
+            admin_tokens: vec![],
+            backfill_tokens: vec![],
+        }
+    }
+
+    /// Sets the tokens which are accepted by the admin endpoints. The admin endpoints are
+    /// disabled if there are no admin tokens.
+    pub fn with_admin_tokens(mut self, admin_tokens: Vec<Secret<String>>) -> Self {
+        self.admin_tokens = admin_tokens;
+        self
+    }
+
+    /// Sets the tokens which allow adding records older than the maximum record age. They are
+    /// accepted by all other endpoints as well.
+    pub fn with_backfill_tokens(mut self, backfill_tokens: Vec<Secret<String>>) -> Self {
+        self.backfill_tokens = backfill_tokens;
+        self
+    }
+
+    /// Returns `true` if requests have to be authenticated.
+    pub fn is_enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    fn accepts(&self, token: &str) -> bool {
+        contains_token(&self.tokens, token) || self.accepts_backfill(token)
+    }
+
+    fn accepts_admin(&self, token: &str) -> bool {
+        contains_token(&self.admin_tokens, token)
+    }
+
+    fn accepts_backfill(&self, token: &str) -> bool {
+        contains_token(&self.backfill_tokens, token)
+    }
+}
+
+fn contains_token(tokens: &[Secret<String>], token: &str) -> bool {
+    tokens
+        .iter()
+        .any(|t| constant_time_eq(t.expose_secret().as_bytes(), token.as_bytes()))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+}
+
+fn unauthorized(req: ServiceRequest) -> ServiceResponse {
+    let response = HttpResponse::Unauthorized()
+        .insert_header((ERROR_CODE_HEADER, ErrorCode::Unauthorized.as_str()))
+        .insert_header((WWW_AUTHENTICATE, "Bearer"))
+        .body(ErrorCode::Unauthorized.as_str());
+    req.into_response(response)
+}
+
+// Compares without returning early, such that the tokens cannot be guessed from the timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Rejects requests without a valid `Authorization: Bearer <token>` header with
+/// `401 UNAUTHORIZED` if bearer token authentication is enabled. Requests to `/health_check` are
+/// always let through, such that the server can be probed, as are requests to `/admin`, which
+/// are checked by [`require_admin_token`].
+pub async fn require_bearer_token(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let is_exempt = req.path().starts_with("/health_check") || req.path().starts_with("/admin/");
+    let authorized = match req.app_data::<web::Data<BearerAuth>>() {
+        Some(auth) if auth.is_enabled() && !is_exempt => {
+            bearer_token(req.headers()).is_some_and(|token| auth.accepts(token))
+        }
+        _ => true,
+    };
+
+    if !authorized {
+        return Ok(unauthorized(req).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Returns `true` if the request carries one of the backfill tokens in the
+/// `Authorization: Bearer <token>` header and may therefore add records older than the maximum
+/// record age.
+pub fn is_backfill_request(req: &HttpRequest) -> bool {
+    req.app_data::<web::Data<BearerAuth>>().is_some_and(|auth| {
+        bearer_token(req.headers()).is_some_and(|token| auth.accepts_backfill(token))
+    })
+}
+
+/// Returns `true` if the request carries one of the admin tokens in the
+/// `Authorization: Bearer <token>` header and may therefore also retrieve soft-deleted records.
+pub fn is_admin_request(req: &HttpRequest) -> bool {
+    req.app_data::<web::Data<BearerAuth>>().is_some_and(|auth| {
+        bearer_token(req.headers()).is_some_and(|token| auth.accepts_admin(token))
+    })
+}
+
+/// Rejects requests without one of the admin tokens in the `Authorization: Bearer <token>` header
+/// with `401 UNAUTHORIZED`. Unlike [`require_bearer_token`], requests are always rejected if no
+/// admin tokens are configured. Registered for the `/admin` scope.
+pub async fn require_admin_token(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let authorized = req.app_data::<web::Data<BearerAuth>>().is_some_and(|auth| {
+        bearer_token(req.headers()).is_some_and(|token| auth.accepts_admin(token))
+    });
+
+    if !authorized {
+        return Ok(unauthorized(req).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Maximum number of clients whose buckets are tracked. Once it is reached, buckets that are full
+/// again are dropped, and if none are, the bucket of the least recently seen client.
+const MAX_RATE_LIMITED_CLIENTS: usize = 10_000;
+
+/// Token bucket rate limiter used by [`limit_request_rate`].
+///
+/// Every client has a bucket holding up to `burst` tokens, which is refilled with
+/// `requests_per_second` tokens per second. Each request takes one token and is rejected if the
+/// bucket is empty.
+#[derive(Debug)]
+pub struct RateLimiter {
+    requests_per_second: f64,
+    burst: u32,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Constructor.
+    pub fn new(requests_per_second: f64, burst: u32) -> Self {
+        RateLimiter {
+            requests_per_second,
+            burst,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Takes a token from the bucket of `client`. Returns the time until the next token is
+    /// available if the bucket is empty.
+    pub fn try_acquire(&self, client: &str, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() >= MAX_RATE_LIMITED_CLIENTS && !buckets.contains_key(client) {
+            buckets.retain(|_, bucket| self.refill(*bucket, now).tokens < f64::from(self.burst));
+            if buckets.len() >= MAX_RATE_LIMITED_CLIENTS {
+                let least_recently_seen = buckets
+                    .iter()
+                    .min_by_key(|(_, bucket)| bucket.updated)
+                    .map(|(client, _)| client.clone());
+                if let Some(client) = least_recently_seen {
+                    buckets.remove(&client);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: f64::from(self.burst),
+            updated: now,
+        });
+        *bucket = self.refill(*bucket, now);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if self.requests_per_second > 0.0 {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / self.requests_per_second,
+            ))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+
+    fn refill(&self, bucket: Bucket, now: Instant) -> Bucket {
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        Bucket {
+            tokens: (bucket.tokens + elapsed * self.requests_per_second).min(f64::from(self.burst)),
+            updated: now,
+        }
+    }
+}
+
+/// DER encoded certificate presented by the client of a mutual TLS connection, see
+/// [`store_client_certificate`].
+#[derive(Debug, Clone)]
+pub struct ClientCertificate(pub Vec<u8>);
+
+/// Stores the certificate of the client in the connection data, such that requests can be
+/// attributed to it. Registered with [`actix_web::HttpServer::on_connect`].
+pub fn store_client_certificate(connection: &dyn Any, data: &mut Extensions) {
+    if let Some(stream) = connection.downcast_ref::<TlsStream<TcpStream>>() {
+        if let Some(certificate) = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certificates| certificates.first())
+        {
+            data.insert(ClientCertificate(certificate.to_vec()));
+        }
+    }
+}
+
+// Identifies the client by its TLS certificate, its bearer token or its IP address, in this order.
+// Only tokens accepted by `BearerAuth` are used, otherwise clients could evade the rate limit by
+// sending a different made-up token with every request. Certificates and tokens are hashed, such
+// that they are not kept in memory.
+fn client_identity(req: &ServiceRequest) -> String {
+    let hash = |value: &[u8]| {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    if let Some(certificate) = req.conn_data::<ClientCertificate>() {
+        return format!("cert:{:016x}", hash(&certificate.0));
+    }
+    let accepted_token = req.app_data::<web::Data<BearerAuth>>().and_then(|auth| {
+        bearer_token(req.headers()).filter(|token| auth.accepts(token) || auth.accepts_admin(token))
+    });
+    if let Some(token) = accepted_token {
+        return format!("token:{:016x}", hash(token.as_bytes()));
+    }
+    match req.peer_addr() {
+        Some(addr) => format!("ip:{}", addr.ip()),
+        None => "unknown".to_string(),
+    }
+}
+
+/// Rejects requests with `429 TOO MANY REQUESTS` if the client exceeds the rate limit, if rate
+/// limiting is enabled. Requests to `/health_check` are always let through, such that the server
+/// can be probed.
+pub async fn limit_request_rate(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let limited = match req
+        .app_data::<web::Data<Option<RateLimiter>>>()
+        .and_then(|limiter| limiter.get_ref().as_ref())
+    {
+        Some(limiter) if !req.path().starts_with("/health_check") => limiter
+            .try_acquire(&client_identity(&req), Instant::now())
+            .err(),
+        _ => None,
+    };
+
+    if let Some(retry_after) = limited {
+        let retry_after = retry_after.as_secs_f64().ceil().min(u32::MAX as f64) as u32;
+        let response = HttpResponse::TooManyRequests()
+            .insert_header((ERROR_CODE_HEADER, ErrorCode::RateLimited.as_str()))
+            .insert_header((RETRY_AFTER, retry_after.max(1).to_string()))
+            .body(ErrorCode::RateLimited.as_str());
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+/// Adds the current load of the server to every response, see [`SERVER_LOAD_HEADER`].
+pub async fn report_server_load(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let pool = req.app_data::<web::Data<PgPool>>().cloned();
+
+    let mut res = next.call(req).await?;
+
+    if let Some(pool) = pool {
+        let load = format!("{:.2}", server_load(&pool));
+        if let Ok(value) = HeaderValue::from_str(&load) {
+            res.headers_mut()
+                .insert(HeaderName::from_static(SERVER_LOAD_HEADER), value);
+        }
+    }
+    Ok(res)
+}
+
+/// Records the duration of every request in the [`RequestLatency`] histogram, labeled by the
+/// pattern of the matched route, the method and the status code. Requests which do not match any
+/// route are recorded as `unmatched`.
+pub async fn record_request_latency(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let latency = req.app_data::<web::Data<RequestLatency>>().cloned();
+    let start = Instant::now();
+
+    let res = next.call(req).await?;
+
+    if let Some(latency) = latency {
+        let route = res
+            .request()
+            .match_pattern()
+            .unwrap_or_else(|| "unmatched".to_string());
+        latency.observe(
+            &route,
+            res.request().method().as_str(),
+            res.status().as_u16(),
+            start.elapsed(),
+        );
+    }
+    Ok(res)
+}
+
+// Share of the database connections which are currently in use.
+fn server_load(pool: &PgPool) -> f64 {
+    let max_connections = pool.options().get_max_connections();
+    if max_connections == 0 {
+        return 0.0;
+    }
+    let in_use = (pool.size() as usize).saturating_sub(pool.num_idle());
+    (in_use as f64 / max_connections as f64).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_allows_burst_and_refills() {
+        let limiter = RateLimiter::new(2.0, 3);
+        let now = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("client", now).is_ok());
+        }
+        assert_eq!(
+            limiter.try_acquire("client", now),
+            Err(Duration::from_millis(500))
+        );
+
+        // Other clients have their own bucket
+        assert!(limiter.try_acquire("other", now).is_ok());
+
+        // One token is added every 500 ms
+        let later = now + Duration::from_millis(500);
+        assert!(limiter.try_acquire("client", later).is_ok());
+        assert!(limiter.try_acquire("client", later).is_err());
+
+        // The bucket does not grow beyond the burst
+        let much_later = now + Duration::from_secs(60);
+        for _ in 0..3 {
+            assert!(limiter.try_acquire("client", much_later).is_ok());
+        }
+        assert!(limiter.try_acquire("client", much_later).is_err());
+    }
+
+    #[test]
+    fn rate_limiter_tracks_a_limited_number_of_clients() {
+        // Buckets are never refilled, hence none of them can be dropped for being full
+        let limiter = RateLimiter::new(0.0, 1);
+        let now = Instant::now();
+
+        for i in 0..MAX_RATE_LIMITED_CLIENTS {
+            let seen = now + Duration::from_millis(i as u64);
+            assert!(limiter.try_acquire(&format!("client{i}"), seen).is_ok());
+        }
+
+        let later = now + Duration::from_secs(60);
+        assert!(limiter.try_acquire("new", later).is_ok());
+        assert_eq!(
+            limiter.buckets.lock().unwrap().len(),
+            MAX_RATE_LIMITED_CLIENTS
+        );
+
+        // Only the least recently seen client was dropped
+        assert!(limiter.try_acquire("client1", later).is_err());
+        assert!(limiter.try_acquire("new", later).is_err());
+        assert!(limiter.try_acquire("client0", later).is_ok());
+    }
+
+    #[test]
+    fn client_identity_only_uses_accepted_tokens() {
+        let auth = BearerAuth::new(vec![Secret::new("token".to_string())])
+            .with_admin_tokens(vec![Secret::new("admin".to_string())]);
+        let identity = |token: &str| {
+            let req = actix_web::test::TestRequest::default()
+                .insert_header((AUTHORIZATION, format!("Bearer {token}")))
+                .peer_addr("127.0.0.1:8000".parse().unwrap())
+                .app_data(web::Data::new(auth.clone()))
+                .to_srv_request();
+            client_identity(&req)
+        };
+
+        assert!(identity("token").starts_with("token:"));
+        assert!(identity("admin").starts_with("token:"));
+        assert_ne!(identity("token"), identity("admin"));
+        assert_eq!(identity("made-up"), "ip:127.0.0.1");
+    }
+}
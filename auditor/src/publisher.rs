@@ -0,0 +1,273 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! Publishing of inserted records to a message bus (Kafka or NATS).
+
+use crate::configuration::{PublisherSettings, PublisherTarget};
+use crate::domain::Record;
+use futures_util::future::BoxFuture;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Sends serialized records to a message bus.
+pub trait Publish: Send + Sync {
+    /// Publishes `payload`, a record serialized as JSON. `key` is the `record_id` of the record.
+    fn publish(&self, key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>>;
+}
+
+/// Publishes records once they are stored in the database.
+///
+/// Records are published in the background. Failed deliveries are logged and retried, but never
+/// fail the insert.
+#[derive(Clone)]
+pub struct RecordPublisher {
+    publisher: Arc<dyn Publish>,
+    retries: u32,
+    retry_delay: Duration,
+}
+
+impl RecordPublisher {
+    /// Creates a `RecordPublisher` which tries to publish every record up to `retries + 1` times.
+    pub fn new(publisher: impl Publish + 'static, retries: u32, retry_delay: Duration) -> Self {
+        RecordPublisher {
+            publisher: Arc::new(publisher),
+            retries,
+            retry_delay,
+        }
+    }
+
+    /// Publishes `records` in the background. These are the records as stored in the database,
+    /// including `ingested_at` and `updated_at`, in the same format as returned by `GET /records`.
+    pub fn publish(&self, records: &[Record]) {
+        for record in records {
+            let record_id = record.record_id.clone();
+            let payload = match serde_json::to_vec(record) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    tracing::error!(record_id, "Failed to serialize record for publishing: {e}");
+                    continue;
+                }
+            };
+            let publisher = self.clone();
+            tokio::spawn(async move { publisher.deliver(record_id, payload).await });
+        }
+    }
+
+    // Returns whether the record was delivered.
+    async fn deliver(&self, record_id: String, payload: Vec<u8>) -> bool {
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                tokio::time::sleep(self.retry_delay).await;
+            }
+            match self
+                .publisher
+                .publish(record_id.clone(), payload.clone())
+                .await
+            {
+                Ok(()) => return true,
+                Err(e) => tracing::warn!(record_id, attempt, "Failed to publish record: {e}"),
+            }
+        }
+        tracing::error!(record_id, "Giving up publishing record");
+        false
+    }
+}
+
+/// Connects to the message bus configured in `settings`.
+#[tracing::instrument(name = "Connecting to message bus", skip(settings))]
+pub async fn connect(settings: &PublisherSettings) -> Result<RecordPublisher, anyhow::Error> {
+    let retry_delay = Duration::from_millis(settings.retry_delay_ms);
+    match &settings.target {
+        PublisherTarget::Nats { url, subject } => Ok(RecordPublisher::new(
+            nats::NatsPublisher::connect(url, subject).await?,
+            settings.retries,
+            retry_delay,
+        )),
+        PublisherTarget::Kafka { brokers, topic } => Ok(RecordPublisher::new(
+            kafka::KafkaPublisher::connect(brokers, topic)?,
+            settings.retries,
+            retry_delay,
+        )),
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use super::Publish;
+    use futures_util::future::BoxFuture;
+
+    pub struct NatsPublisher {
+        client: async_nats::Client,
+        subject: String,
+    }
+
+    impl NatsPublisher {
+        pub async fn connect(url: &str, subject: &str) -> Result<Self, anyhow::Error> {
+            Ok(NatsPublisher {
+                client: async_nats::connect(url).await?,
+                subject: subject.to_string(),
+            })
+        }
+    }
+
+    impl Publish for NatsPublisher {
+        fn publish(
+            &self,
+            _key: String,
+            payload: Vec<u8>,
+        ) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                self.client
+                    .publish(self.subject.clone(), payload.into())
+                    .await?;
+                // Publishing only buffers the message, flushing makes sure it reached the server.
+                self.client.flush().await?;
+                Ok(())
+            })
+        }
+    }
+}
+
+#[cfg(not(feature = "nats"))]
+mod nats {
+    use super::Publish;
+    use futures_util::future::BoxFuture;
+
+    pub enum NatsPublisher {}
+
+    impl NatsPublisher {
+        pub async fn connect(_url: &str, _subject: &str) -> Result<Self, anyhow::Error> {
+            anyhow::bail!("Publishing to NATS requires AUDITOR to be built with the `nats` feature")
+        }
+    }
+
+    impl Publish for NatsPublisher {
+        fn publish(&self, _: String, _: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use super::Publish;
+    use futures_util::future::BoxFuture;
+    use rdkafka::producer::{FutureProducer, FutureRecord};
+    use rdkafka::util::Timeout;
+    use rdkafka::ClientConfig;
+    use std::time::Duration;
+
+    // How long sending a message may wait for room in the queue of the producer.
+    const QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub struct KafkaPublisher {
+        producer: FutureProducer,
+        topic: String,
+    }
+
+    impl KafkaPublisher {
+        pub fn connect(brokers: &str, topic: &str) -> Result<Self, anyhow::Error> {
+            Ok(KafkaPublisher {
+                producer: ClientConfig::new()
+                    .set("bootstrap.servers", brokers)
+                    .create()?,
+                topic: topic.to_string(),
+            })
+        }
+    }
+
+    impl Publish for KafkaPublisher {
+        fn publish(
+            &self,
+            key: String,
+            payload: Vec<u8>,
+        ) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                // Records with the same id end up in the same partition
+                let record = FutureRecord::to(&self.topic).key(&key).payload(&payload);
+                self.producer
+                    .send(record, Timeout::After(QUEUE_TIMEOUT))
+                    .await
+                    .map_err(|(e, _)| e)?;
+                Ok(())
+            })
+        }
+    }
+}
+
+#[cfg(not(feature = "kafka"))]
+mod kafka {
+    use super::Publish;
+    use futures_util::future::BoxFuture;
+
+    pub enum KafkaPublisher {}
+
+    impl KafkaPublisher {
+        pub fn connect(_brokers: &str, _topic: &str) -> Result<Self, anyhow::Error> {
+            anyhow::bail!(
+                "Publishing to Kafka requires AUDITOR to be built with the `kafka` feature"
+            )
+        }
+    }
+
+    impl Publish for KafkaPublisher {
+        fn publish(&self, _: String, _: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+            match *self {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    // Fails the first `failures` attempts
+    struct FlakyPublisher {
+        failures: u32,
+        attempts: Arc<AtomicU32>,
+    }
+
+    impl Publish for FlakyPublisher {
+        fn publish(&self, _: String, _: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                    anyhow::bail!("Message bus unavailable")
+                }
+                Ok(())
+            })
+        }
+    }
+
+    fn publisher(failures: u32, retries: u32) -> (RecordPublisher, Arc<AtomicU32>) {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let publisher = FlakyPublisher {
+            failures,
+            attempts: attempts.clone(),
+        };
+        (
+            RecordPublisher::new(publisher, retries, Duration::from_millis(1)),
+            attempts,
+        )
+    }
+
+    #[tokio::test]
+    async fn failed_deliveries_are_retried() {
+        let (publisher, attempts) = publisher(2, 3);
+
+        assert!(publisher.deliver("r1".to_string(), vec![]).await);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn delivery_is_given_up_after_all_retries() {
+        let (publisher, attempts) = publisher(5, 2);
+
+        assert!(!publisher.deliver("r1".to_string(), vec![]).await);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
@@ -5,16 +5,50 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::constants::{ERR_RECORD_EXISTS, ERR_UNEXPECTED_ERROR};
-use crate::domain::RecordAdd;
-use actix_web::{web, HttpResponse, ResponseError};
-use chrono::Utc;
+use crate::constants::{ErrorCode, ERROR_CODE_HEADER};
+use crate::domain::{
+    Record, RecordAdd, RecordDatabase, RecordTooOld, RecordValidationError, RuntimeMismatch,
+};
+use crate::middleware::is_backfill_request;
+use crate::publisher::RecordPublisher;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::PgPool;
 
+/// Maximum difference in seconds between the runtime sent with a record and the time between its
+/// start and stop time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuntimeTolerance(pub u64);
+
+/// Maximum age of the `stop_time` of records which are accepted without a backfill token.
+/// Disabled if `None`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MaxRecordAge(pub Option<chrono::Duration>);
+
+impl MaxRecordAge {
+    /// Checks the age of `record` at `now`, unless the request carries a backfill token.
+    pub fn check(
+        &self,
+        record: &RecordAdd,
+        request: &HttpRequest,
+        now: DateTime<Utc>,
+    ) -> Result<(), RecordTooOld> {
+        match self.0 {
+            Some(max_age) if !is_backfill_request(request) => record.check_age(max_age, now),
+            _ => Ok(()),
+        }
+    }
+}
+
 #[derive(thiserror::Error)]
 pub enum AddError {
     RecordExists,
+    InvalidRecords(Vec<RecordValidationError>),
+    RuntimeMismatch(RuntimeMismatch),
+    /// All invalid records of a batch were only rejected because of their runtime.
+    RuntimeMismatches(Vec<RecordValidationError>),
+    RecordTooOld(RecordTooOld),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
     // UnexpectedError,
@@ -23,16 +57,22 @@ pub enum AddError {
 debug_for_error!(AddError);
 // responseerror_for_error!(AddError, UnexpectedError => INTERNAL_SERVER_ERROR;);
 
+impl AddError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            AddError::RecordExists => ErrorCode::RecordExists,
+            AddError::InvalidRecords(_) => ErrorCode::InvalidRecords,
+            AddError::RuntimeMismatch(_)
+            | AddError::RuntimeMismatches(_)
+            | AddError::RecordTooOld(_) => ErrorCode::ValidationFailed,
+            AddError::UnexpectedError(_) => ErrorCode::UnexpectedError,
+        }
+    }
+}
+
 impl std::fmt::Display for AddError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                AddError::RecordExists => ERR_RECORD_EXISTS,
-                AddError::UnexpectedError(_) => ERR_UNEXPECTED_ERROR,
-            }
-        )
+        write!(f, "{}", self.error_code())
     }
 }
 
@@ -41,29 +81,50 @@ impl ResponseError for AddError {
         match self {
             AddError::UnexpectedError(_) => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
             AddError::RecordExists => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
+            AddError::InvalidRecords(_) => actix_web::http::StatusCode::BAD_REQUEST,
+            AddError::RuntimeMismatch(_) | AddError::RuntimeMismatches(_) => {
+                actix_web::http::StatusCode::UNPROCESSABLE_ENTITY
+            }
+            AddError::RecordTooOld(_) => actix_web::http::StatusCode::BAD_REQUEST,
         }
     }
 
     fn error_response(&self) -> HttpResponse {
-        let message = match self {
-            AddError::UnexpectedError(_) => ERR_UNEXPECTED_ERROR,
-            AddError::RecordExists => ERR_RECORD_EXISTS,
-        };
-
-        HttpResponse::build(self.status_code()).body(message)
+        let mut response = HttpResponse::build(self.status_code());
+        response.insert_header((ERROR_CODE_HEADER, self.error_code().as_str()));
+        match self {
+            AddError::UnexpectedError(_) | AddError::RecordExists => {
+                response.body(self.error_code().as_str())
+            }
+            AddError::InvalidRecords(errors) | AddError::RuntimeMismatches(errors) => {
+                response.json(errors)
+            }
+            AddError::RuntimeMismatch(mismatch) => response.body(mismatch.to_string()),
+            AddError::RecordTooOld(too_old) => response.body(too_old.to_string()),
+        }
     }
 }
 
 #[tracing::instrument(
     name = "Adding a record to the database",
-    skip(record, pool),
+    skip(request, record, pool, runtime_tolerance, max_record_age, publisher),
     fields(record_id = %record.record_id)
 )]
 pub async fn add(
+    request: HttpRequest,
     record: web::Json<RecordAdd>,
     pool: web::Data<PgPool>,
+    runtime_tolerance: web::Data<RuntimeTolerance>,
+    max_record_age: web::Data<MaxRecordAge>,
+    publisher: web::Data<Option<RecordPublisher>>,
 ) -> Result<HttpResponse, AddError> {
-    add_record(&record, &pool)
+    record
+        .check_runtime(runtime_tolerance.0)
+        .map_err(AddError::RuntimeMismatch)?;
+    max_record_age
+        .check(&record, &request, Utc::now())
+        .map_err(AddError::RecordTooOld)?;
+    let record = add_record(&record, &pool)
         .await
         .map_err(|e| match e.0.as_database_error() {
             Some(db_err) => match db_err.code().as_ref() {
@@ -75,11 +136,14 @@ pub async fn add(
             },
             _ => AddError::UnexpectedError(e.into()),
         })?;
+    if let Some(publisher) = publisher.as_ref() {
+        publisher.publish(std::slice::from_ref(&record));
+    }
     Ok(HttpResponse::Ok().finish())
 }
 
 #[tracing::instrument(name = "Inserting record into database", skip(record, pool))]
-pub async fn add_record(record: &RecordAdd, pool: &PgPool) -> Result<(), AddRecordError> {
+pub async fn add_record(record: &RecordAdd, pool: &PgPool) -> Result<Record, AddRecordError> {
     let runtime = match record.stop_time.as_ref() {
         Some(&stop) => Some((stop - record.start_time).num_seconds()),
         _ => None,
@@ -90,13 +154,16 @@ pub async fn add_record(record: &RecordAdd, pool: &PgPool) -> Result<(), AddReco
         Err(e) => return Err(AddRecordError(e)),
     };
 
-    sqlx::query_unchecked!(
+    let now = Utc::now();
+
+    let record = sqlx::query_as_unchecked!(
+        RecordDatabase,
         r#"
         INSERT INTO auditor_accounting (
-            record_id, start_time, stop_time, meta, components, runtime, updated_at
+            record_id, start_time, stop_time, meta, components, runtime, updated_at, ingested_at
         )
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        RETURNING id;
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING record_id, meta, components, start_time, stop_time, runtime, ingested_at, updated_at;
         "#,
         record.record_id.as_ref(),
         record.start_time,
@@ -104,42 +171,126 @@ pub async fn add_record(record: &RecordAdd, pool: &PgPool) -> Result<(), AddReco
         serde_json::to_value(&record.meta).unwrap_or_else(|_| serde_json::Value::Null),
         serde_json::to_value(&record.components).unwrap_or_else(|_| serde_json::Value::Null),
         runtime,
-        Utc::now()
+        now,
+        now
     )
     .fetch_optional(&mut *transaction)
     .await
     .map_err(AddRecordError)?
     .ok_or_else(|| AddRecordError(sqlx::Error::RowNotFound))?;
+    let record =
+        Record::try_from(record).map_err(|e| AddRecordError(sqlx::Error::Decode(e.into())))?;
 
     if let Err(e) = transaction.commit().await {
         Err(AddRecordError(e))
     } else {
-        Ok(())
+        Ok(record)
     }
 }
 
-#[tracing::instrument(name = "Adding multiple records to the database", skip(records, pool))]
+#[tracing::instrument(
+    name = "Adding multiple records to the database",
+    skip(request, records, pool, runtime_tolerance, max_record_age, publisher)
+)]
 pub async fn bulk_add(
-    records: web::Json<Vec<RecordAdd>>,
+    request: HttpRequest,
+    records: web::Json<Vec<Value>>,
     pool: web::Data<PgPool>,
+    runtime_tolerance: web::Data<RuntimeTolerance>,
+    max_record_age: web::Data<MaxRecordAge>,
+    publisher: web::Data<Option<RecordPublisher>>,
 ) -> Result<HttpResponse, AddError> {
-    bulk_insert(&records, &pool)
-        .await
-        .map_err(|e| match e.0.as_database_error() {
-            Some(db_err) => match db_err.code().as_ref() {
-                Some(code) => match code.as_ref() {
-                    "23505" => AddError::RecordExists,
+    let now = Utc::now();
+    let records = parse_records(records.into_inner(), |record| {
+        record
+            .check_runtime(runtime_tolerance.0)
+            .map_err(Rejection::RuntimeMismatch)?;
+        max_record_age
+            .check(record, &request, now)
+            .map_err(|e| Rejection::Invalid(e.to_string()))
+    })?;
+    let records =
+        bulk_insert(&records, &pool)
+            .await
+            .map_err(|e| match e.0.as_database_error() {
+                Some(db_err) => match db_err.code().as_ref() {
+                    Some(code) => match code.as_ref() {
+                        "23505" => AddError::RecordExists,
+                        _ => AddError::UnexpectedError(e.into()),
+                    },
                     _ => AddError::UnexpectedError(e.into()),
                 },
                 _ => AddError::UnexpectedError(e.into()),
-            },
-            _ => AddError::UnexpectedError(e.into()),
-        })?;
+            })?;
+    if let Some(publisher) = publisher.as_ref() {
+        publisher.publish(&records);
+    }
     Ok(HttpResponse::Ok().finish())
 }
 
+// Reason why a record of a batch was rejected. Runtime mismatches are kept apart, such that a
+// batch which is only rejected because of them is answered like a single record (`422`).
+enum Rejection {
+    RuntimeMismatch(RuntimeMismatch),
+    Invalid(String),
+}
+
+impl std::fmt::Display for Rejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Rejection::RuntimeMismatch(mismatch) => write!(f, "{mismatch}"),
+            Rejection::Invalid(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// Deserializes all records of a batch and validates them with `check`. If any of them are
+/// invalid, the errors of all invalid records are returned at once, instead of only the first one.
+fn parse_records(
+    values: Vec<Value>,
+    check: impl Fn(&RecordAdd) -> Result<(), Rejection>,
+) -> Result<Vec<RecordAdd>, AddError> {
+    let mut records = Vec::with_capacity(values.len());
+    let mut rejections = Vec::new();
+
+    for (index, value) in values.into_iter().enumerate() {
+        let record = serde_json::from_value::<RecordAdd>(value)
+            .map_err(|e| Rejection::Invalid(e.to_string()))
+            .and_then(|record| {
+                check(&record)?;
+                Ok(record)
+            });
+        match record {
+            Ok(record) => records.push(record),
+            Err(rejection) => rejections.push((index, rejection)),
+        }
+    }
+
+    if rejections.is_empty() {
+        return Ok(records);
+    }
+    let only_runtime_mismatches = rejections
+        .iter()
+        .all(|(_, rejection)| matches!(rejection, Rejection::RuntimeMismatch(_)));
+    let errors = rejections
+        .into_iter()
+        .map(|(index, rejection)| RecordValidationError {
+            index,
+            reason: rejection.to_string(),
+        })
+        .collect();
+    if only_runtime_mismatches {
+        Err(AddError::RuntimeMismatches(errors))
+    } else {
+        Err(AddError::InvalidRecords(errors))
+    }
+}
+
 #[tracing::instrument(name = "Inserting bulk records into database", skip(records, pool))]
-pub async fn bulk_insert(records: &[RecordAdd], pool: &PgPool) -> Result<(), AddRecordError> {
+pub async fn bulk_insert(
+    records: &[RecordAdd],
+    pool: &PgPool,
+) -> Result<Vec<Record>, AddRecordError> {
     let mut transaction = match pool.begin().await {
         Ok(transaction) => transaction,
         Err(e) => return Err(AddRecordError(e)),
@@ -155,7 +306,9 @@ pub async fn bulk_insert(records: &[RecordAdd], pool: &PgPool) -> Result<(), Add
         .iter()
         .map(|r| r.stop_time.map(|stop| (stop - r.start_time).num_seconds()))
         .collect();
-    let updated_at_vec: Vec<_> = std::iter::repeat(Utc::now()).take(records.len()).collect();
+    let now = Utc::now();
+    let updated_at_vec: Vec<_> = std::iter::repeat_n(now, records.len()).collect();
+    let ingested_at_vec: Vec<_> = std::iter::repeat_n(now, records.len()).collect();
 
     let meta_values: Vec<Value> = records
         .iter()
@@ -166,13 +319,14 @@ pub async fn bulk_insert(records: &[RecordAdd], pool: &PgPool) -> Result<(), Add
         .map(|r| serde_json::to_value(&r.components).unwrap_or(serde_json::Value::Null))
         .collect();
 
-    sqlx::query_unchecked!(
+    let records = sqlx::query_as_unchecked!(
+        RecordDatabase,
         r#"
         INSERT INTO auditor_accounting (
-            record_id, start_time, stop_time, meta, components, runtime, updated_at
+            record_id, start_time, stop_time, meta, components, runtime, updated_at, ingested_at
         )
-        SELECT * FROM UNNEST($1::text[], $2::timestamptz[], $3::timestamptz[], $4::jsonb[], $5::jsonb[],  $6::bigint[], $7::timestamptz[])
-        RETURNING id;
+        SELECT * FROM UNNEST($1::text[], $2::timestamptz[], $3::timestamptz[], $4::jsonb[], $5::jsonb[],  $6::bigint[], $7::timestamptz[], $8::timestamptz[])
+        RETURNING record_id, meta, components, start_time, stop_time, runtime, ingested_at, updated_at;
         "#,
         &record_ids[..],
         &start_times[..],
@@ -181,15 +335,20 @@ pub async fn bulk_insert(records: &[RecordAdd], pool: &PgPool) -> Result<(), Add
         &component_values[..],
         &runtimes[..],
         &updated_at_vec[..],
+        &ingested_at_vec[..],
     )
     .fetch_all(&mut *transaction)
     .await
-    .map_err(AddRecordError)?;
+    .map_err(AddRecordError)?
+    .into_iter()
+    .map(Record::try_from)
+    .collect::<Result<Vec<Record>, anyhow::Error>>()
+    .map_err(|e| AddRecordError(sqlx::Error::Decode(e.into())))?;
 
     if let Err(e) = transaction.commit().await {
         return Err(AddRecordError(e));
     } else {
-        return Ok(());
+        return Ok(records);
     }
 }
 
@@ -0,0 +1,77 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use std::time::Instant;
+
+#[derive(serde::Deserialize, Debug)]
+pub struct AnalyzeQuery {
+    /// Rebuild the indexes of the table in addition to refreshing its statistics.
+    #[serde(default)]
+    pub reindex: bool,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct AnalyzeResponse {
+    pub reindexed: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(thiserror::Error)]
+pub enum AdminError {
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(AdminError);
+responseerror_for_error!(
+    AdminError,
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Refreshes the statistics of the accounting table, e.g. after bulk loading records, such that
+/// the query planner picks suitable plans again. With `reindex=true`, its indexes are rebuilt as
+/// well, which blocks writes until it is finished.
+#[tracing::instrument(name = "Analyzing accounting table", skip(pool))]
+pub async fn analyze(
+    query: web::Query<AnalyzeQuery>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, AdminError> {
+    let start = Instant::now();
+    if query.reindex {
+        reindex_accounting_table(&pool).await?;
+    }
+    analyze_accounting_table(&pool).await?;
+
+    let duration_ms = start.elapsed().as_millis() as u64;
+    tracing::info!(
+        reindexed = query.reindex,
+        duration_ms,
+        "Analyzed accounting table"
+    );
+    Ok(HttpResponse::Ok().json(AnalyzeResponse {
+        reindexed: query.reindex,
+        duration_ms,
+    }))
+}
+
+#[tracing::instrument(name = "Running ANALYZE on accounting table", skip(pool))]
+async fn analyze_accounting_table(pool: &PgPool) -> Result<(), anyhow::Error> {
+    sqlx::query("ANALYZE auditor_accounting")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[tracing::instrument(name = "Running REINDEX on accounting table", skip(pool))]
+async fn reindex_accounting_table(pool: &PgPool) -> Result<(), anyhow::Error> {
+    sqlx::query("REINDEX TABLE auditor_accounting")
+        .execute(pool)
+        .await?;
+    Ok(())
+}
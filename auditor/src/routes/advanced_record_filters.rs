@@ -5,80 +5,43 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::domain::{Record, RecordDatabase, ValidAmount, ValidName};
+use crate::domain::{
+    ComponentOperator, Filters, Operator, Record, RecordDatabase, RecordIdFilter, SortField,
+    SortOption, ValidAmount, ValidName,
+};
+use actix_web::web::Bytes;
 use chrono::{DateTime, Utc};
-use core::fmt::Debug;
-use sqlx::{PgPool, QueryBuilder, Row};
-use std::collections::HashMap;
-use std::fmt::Display;
-
-#[derive(serde::Deserialize, Debug, Clone)]
-pub struct Filters {
-    pub record_id: Option<ValidName>,
-    pub start_time: Option<Operator<DateTime<Utc>>>,
-    pub stop_time: Option<Operator<DateTime<Utc>>>,
-    pub runtime: Option<Operator<ValidAmount>>,
-    pub meta: Option<HashMap<ValidName, MetaOperator>>,
-    pub component: Option<HashMap<ValidName, Operator<ValidAmount>>>,
-    pub sort_by: Option<SortOption>,
-    pub limit: Option<ValidAmount>,
-}
-
-impl Filters {
-    pub fn is_all_none(&self) -> bool {
-        self.record_id.is_none()
-            && self.start_time.is_none()
-            && self.stop_time.is_none()
-            && self.runtime.is_none()
-            && self.meta.is_none()
-            && self.component.is_none()
-            && self.sort_by.is_none()
-            && self.limit.is_none()
-    }
-}
-
-#[derive(serde::Deserialize, Debug, Clone)]
-pub struct Operator<T> {
-    pub gt: Option<T>,
-    pub lt: Option<T>,
-    pub gte: Option<T>,
-    pub lte: Option<T>,
-    pub equals: Option<T>,
-}
+use futures_util::{stream, Stream, TryStreamExt};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row};
+use tokio::sync::mpsc;
 
-#[derive(serde::Deserialize, Debug, Clone)]
-pub struct MetaOperator {
-    pub c: Option<ValidName>,
-    pub dnc: Option<ValidName>,
-}
+// Number of NDJSON lines which are buffered while the client is reading the response.
+const NDJSON_BUFFER_SIZE: usize = 64;
 
-#[derive(serde::Deserialize, Debug, Clone)]
-#[serde(rename_all = "lowercase")]
-pub enum SortOption {
-    ASC(SortField),
-    DESC(SortField),
-}
-
-#[derive(serde::Deserialize, Debug, PartialEq, Eq, Clone)]
-#[serde(rename_all = "lowercase")]
-pub enum SortField {
-    #[serde(rename = "start_time")]
-    StartTime,
-    #[serde(rename = "stop_time")]
-    StopTime,
-    #[serde(rename = "runtime")]
-    Runtime,
-    #[serde(rename = "record_id")]
-    RecordId,
+impl RecordIdFilter {
+    // Pattern for `LIKE` matching all record ids starting with the prefix.
+    fn like_pattern(prefix: &ValidName) -> String {
+        let mut pattern = String::new();
+        for c in prefix.as_ref().chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+        }
+        pattern.push('%');
+        pattern
+    }
 }
 
-impl Display for SortField {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SortField::StartTime => write!(f, "start_time"),
-            SortField::StopTime => write!(f, "stop_time"),
-            SortField::Runtime => write!(f, "runtime"),
-            SortField::RecordId => write!(f, "record_id"),
+impl ComponentOperator {
+    fn amount(&self) -> Operator<ValidAmount> {
+        Operator {
+            gt: self.gt,
+            lt: self.lt,
+            gte: self.gte,
+            lte: self.lte,
+            equals: self.equals,
         }
     }
 }
@@ -88,30 +51,215 @@ pub async fn advanced_record_filtering(
     filters: Filters,
     pool: &PgPool,
 ) -> Result<Vec<Record>, anyhow::Error> {
+    let rows = records_query(&filters)
+        .build()
+        .fetch_all(pool)
+        .await
+        .map_err(GetRecordError)?;
+
+    let result: Vec<Record> = rows.iter().map(record_from_row).collect();
+
+    Ok(result)
+}
+
+/// Streams the records matching `filters` as newline-delimited JSON, one record per line.
+///
+/// The records are read from a database cursor instead of being collected first. If the query
+/// fails, the stream ends with the error.
+pub fn advanced_record_ndjson_stream(
+    filters: Filters,
+    pool: PgPool,
+) -> impl Stream<Item = Result<Bytes, anyhow::Error>> {
+    let (tx, rx) = mpsc::channel(NDJSON_BUFFER_SIZE);
+
+    tokio::spawn(async move {
+        let mut query = records_query(&filters);
+        let mut rows = query.build().fetch(&pool);
+        loop {
+            let line = match rows.try_next().await {
+                Ok(Some(row)) => ndjson_line(&record_from_row(&row)),
+                Ok(None) => break,
+                Err(e) => Err(GetRecordError(e).into()),
+            };
+            let failed = line.is_err();
+            // Sending fails if the client has disconnected
+            if tx.send(line).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|line| (line, rx))
+    })
+}
+
+fn ndjson_line(record: &Record) -> Result<Bytes, anyhow::Error> {
+    let mut line = serde_json::to_vec(record)?;
+    line.push(b'\n');
+    Ok(Bytes::from(line))
+}
+
+fn records_query(filters: &Filters) -> QueryBuilder<'_, Postgres> {
     let mut query = QueryBuilder::new(
         "SELECT record_id,
                   meta,
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
                ",
     );
 
+    push_filter_conditions(&mut query, filters);
+
+    if let Some(sort_by) = &filters.sort_by {
+        let (field, direction) = match sort_by {
+            SortOption::ASC(field) => (field, "ASC"),
+            SortOption::DESC(field) => (field, "DESC"),
+        };
+        if let SortField::Component(name) = field {
+            push_component_order(&mut query, name, direction);
+        } else {
+            query.push(format!(" ORDER BY {field} {direction}"));
+        }
+    } else {
+        query.push(" ORDER BY stop_time ".to_string());
+    }
+
+    if let Some(limit) = &filters.limit {
+        query.push(" LIMIT ".to_string());
+        query.push_bind(limit);
+    }
+
+    query
+}
+
+// Orders by the summed amount of the component `name`. Records without this component have no
+// amount and are always sorted last, the record id breaks ties between them.
+fn push_component_order<'a>(
+    query: &mut QueryBuilder<'a, Postgres>,
+    name: &'a ValidName,
+    direction: &str,
+) {
+    query.push(
+        " ORDER BY (
+            SELECT SUM((component ->> 'amount')::BIGINT)
+            FROM jsonb_array_elements(
+                CASE WHEN jsonb_typeof(components) = 'array' THEN components ELSE '[]' END
+            ) AS component
+            WHERE component ->> 'name' = ",
+    );
+    query.push_bind(name.as_ref());
+    query.push(format!(") {direction} NULLS LAST, record_id"));
+}
+
+fn record_from_row(row: &PgRow) -> Record {
+    Record {
+        record_id: row.try_get("record_id").unwrap(),
+        meta: row
+            .try_get("meta")
+            .ok()
+            .and_then(|value| serde_json::from_value(value).ok()),
+        components: row
+            .try_get("components")
+            .ok()
+            .and_then(|value| serde_json::from_value(value).ok()),
+        start_time: row.try_get("start_time").ok().unwrap_or(None),
+        stop_time: row.try_get("stop_time").ok().unwrap_or(None),
+        runtime: row.try_get("runtime").ok().unwrap_or(None),
+        ingested_at: row.try_get("ingested_at").ok().unwrap_or(None),
+        updated_at: row.try_get("updated_at").ok().unwrap_or(None),
+    }
+}
+
+#[tracing::instrument(name = "Counting records using custom query", skip(filters, pool))]
+pub async fn advanced_record_count(filters: Filters, pool: &PgPool) -> Result<u64, anyhow::Error> {
+    // `sort_by` and `limit` have no effect on the number of matching records and are ignored.
+    let mut query = QueryBuilder::new("SELECT COUNT(*) FROM auditor_accounting ");
+
+    push_filter_conditions(&mut query, &filters);
+
+    let count: i64 = query
+        .build()
+        .fetch_one(pool)
+        .await
+        .map_err(GetRecordError)?
+        .try_get(0)
+        .map_err(GetRecordError)?;
+
+    Ok(count as u64)
+}
+
+#[tracing::instrument(
+    name = "Getting distinct component names using custom query",
+    skip(filters, pool)
+)]
+pub async fn advanced_component_names(
+    filters: Filters,
+    pool: &PgPool,
+) -> Result<Vec<String>, anyhow::Error> {
+    // Like counting, `sort_by` and `limit` are ignored.
+    let mut query = QueryBuilder::new(
+        "SELECT DISTINCT component ->> 'name' AS name
+           FROM auditor_accounting,
+                jsonb_array_elements(
+                    CASE WHEN jsonb_typeof(components) = 'array' THEN components ELSE '[]' END
+                ) AS component
+               ",
+    );
+
+    push_filter_conditions(&mut query, &filters);
+    query.push(" ORDER BY name");
+
+    let names = query
+        .build()
+        .fetch_all(pool)
+        .await
+        .map_err(GetRecordError)?
+        .iter()
+        .map(|row| row.try_get("name"))
+        .collect::<Result<Vec<String>, _>>()
+        .map_err(GetRecordError)?;
+
+    Ok(names)
+}
+
+// Appends the WHERE clause corresponding to `filters` to `query`.
+fn push_filter_conditions<'a>(query: &mut QueryBuilder<'a, Postgres>, filters: &'a Filters) {
+    let exclude_deleted = filters.include_deleted != Some(true);
+
     if filters.start_time.is_some()
         || filters.stop_time.is_some()
+        || filters.ingested_at.is_some()
+        || filters.updated_since.is_some()
+        || filters.stopped.is_some()
         || filters.runtime.is_some()
         || filters.meta.is_some()
         || filters.component.is_some()
         || filters.record_id.is_some()
     {
         query.push(" WHERE ".to_string());
-        if let Some(record_id) = &filters.record_id {
-            // query string -> a.record_id = '{}' and
-            query.push(" record_id = ".to_string());
-            query.push_bind(record_id);
-            query.push(" and ".to_string());
+        if exclude_deleted {
+            query.push(" deleted_at IS NULL and ".to_string());
+        }
+        match &filters.record_id {
+            Some(RecordIdFilter::Equals(record_id)) => {
+                // query string -> a.record_id = '{}' and
+                query.push(" record_id = ".to_string());
+                query.push_bind(record_id);
+                query.push(" and ".to_string());
+            }
+            Some(RecordIdFilter::Prefix(prefix)) => {
+                // query string -> a.record_id LIKE '{}%' and
+                query.push(" record_id LIKE ".to_string());
+                query.push_bind(RecordIdFilter::like_pattern(prefix));
+                query.push(" ESCAPE '\\' and ".to_string());
+            }
+            None => {}
         }
 
         if let Some(start_time_filters) = &filters.start_time {
@@ -132,10 +280,45 @@ pub async fn advanced_record_filtering(
                     query.push(format!(" stop_time {} ", operator.0));
                     query.push_bind(operator.1);
                     query.push(" and ".to_string());
+                    // Partitions are pruned on `start_time` only. Records never stop before they
+                    // start, hence an upper bound on `stop_time` also bounds `start_time`.
+                    if matches!(operator.0, "<" | "<=") {
+                        // query string -> a.start_time {} '{}' and
+                        query.push(format!(" start_time {} ", operator.0));
+                        query.push_bind(operator.1);
+                        query.push(" and ".to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(ingested_at_filters) = &filters.ingested_at {
+            if let Some(operators) = get_operator(ingested_at_filters) {
+                for operator in operators {
+                    // query string -> a.ingested_at {} '{}' and
+                    query.push(format!(" ingested_at {} ", operator.0));
+                    query.push_bind(operator.1);
+                    query.push(" and ".to_string());
                 }
             }
         }
 
+        if let Some(updated_since) = &filters.updated_since {
+            // query string -> a.updated_at >= '{}' and
+            query.push(" updated_at >= ".to_string());
+            query.push_bind(updated_since);
+            query.push(" and ".to_string());
+        }
+
+        if let Some(stopped) = filters.stopped {
+            // query string -> stop_time IS NOT NULL and / stop_time IS NULL and
+            if stopped {
+                query.push(" stop_time IS NOT NULL and ".to_string());
+            } else {
+                query.push(" stop_time IS NULL and ".to_string());
+            }
+        }
+
         if let Some(meta_filters) = &filters.meta {
             for (key, meta_operator) in meta_filters {
                 if let Some(c) = &meta_operator.c {
@@ -163,7 +346,7 @@ pub async fn advanced_record_filtering(
 
         if let Some(component_filters) = &filters.component {
             for (key, component_operator) in component_filters {
-                if let Some(operators) = get_operator(component_operator) {
+                if let Some(operators) = get_operator(&component_operator.amount()) {
                     for operator in operators {
                         // query string -> components->0->>'name' = "CPU" AND
                         // (components->0->>'amount')::int >10  and
@@ -174,11 +357,17 @@ pub async fn advanced_record_filtering(
                             " AND (components->0->>'amount')::int {} ",
                             &operator.0
                         ));
-                        query.push_bind(operator.1);
+                        query.push_bind(*operator.1);
 
                         query.push(" and ".to_string());
                     }
                 }
+
+                for (score_name, score_operator) in component_operator.score.iter().flatten() {
+                    if let Some(operators) = get_operator(score_operator) {
+                        push_score_condition(query, key, score_name, operators);
+                    }
+                }
             }
         }
 
@@ -195,93 +384,92 @@ pub async fn advanced_record_filtering(
                     query.push(" and ".to_string());
                 }
             }
+        } else if filters.stopped == Some(false) {
+            // Unfinished records have no runtime, hence they must not be excluded here.
+            query.push(" TRUE".to_string());
         } else {
             query.push(" runtime IS NOT NULL".to_string());
         }
+    } else if exclude_deleted {
+        query.push(" WHERE deleted_at IS NULL".to_string());
     }
+}
 
-    if let Some(sort_by) = &filters.sort_by {
-        if let SortOption::ASC(asc) = sort_by {
-            query.push(format!(" ORDER BY {} ASC", &asc.to_string()));
-        }
-        if let SortOption::DESC(desc) = sort_by {
-            query.push(format!(" ORDER BY {} DESC", &desc.to_string()));
-        }
-    } else {
-        query.push(" ORDER BY stop_time ".to_string());
+// Appends a condition selecting records which have a component `component` with a score
+// `score_name` whose value satisfies all `operators`.
+fn push_score_condition<'a>(
+    query: &mut QueryBuilder<'a, Postgres>,
+    component: &'a ValidName,
+    score_name: &'a ValidName,
+    operators: Vec<(&str, &'a f64)>,
+) {
+    // query string -> EXISTS (SELECT 1 FROM <components> AS component, <scores> AS score
+    // WHERE component->>'name' = "CPU" AND score->>'name' = "HEPSPEC06"
+    // AND (score->>'value')::double precision > 10) and
+    query.push(
+        " EXISTS (
+            SELECT 1
+            FROM jsonb_array_elements(
+                     CASE WHEN jsonb_typeof(components) = 'array' THEN components ELSE '[]' END
+                 ) AS component,
+                 jsonb_array_elements(
+                     CASE WHEN jsonb_typeof(component -> 'scores') = 'array'
+                          THEN component -> 'scores' ELSE '[]' END
+                 ) AS score
+            WHERE component ->> 'name' = ",
+    );
+    query.push_bind(component);
+    query.push(" AND score ->> 'name' = ");
+    query.push_bind(score_name);
+    for operator in operators {
+        query.push(format!(
+            " AND (score ->> 'value')::double precision {} ",
+            operator.0
+        ));
+        query.push_bind(operator.1);
     }
+    query.push(") and ".to_string());
+}
 
-    if let Some(limit) = &filters.limit {
-        query.push(" LIMIT ".to_string());
-        query.push_bind(limit);
-    }
+fn get_operator<T>(operator: &Operator<T>) -> Option<Vec<(&str, &T)>>
+where
+    T: 'static,
+{
+    let mut operators: Vec<(&str, &T)> = Vec::new();
 
-    fn get_operator<T>(operator: &Operator<T>) -> Option<Vec<(&str, &T)>>
-    where
-        T: 'static,
+    if operator.gt.is_some() && operator.gte.is_some()
+        || operator.lt.is_some() && operator.lte.is_some()
     {
-        let mut operators: Vec<(&str, &T)> = Vec::new();
-
-        if operator.gt.is_some() && operator.gte.is_some()
-            || operator.lt.is_some() && operator.lte.is_some()
-        {
-            return None;
-        }
+        return None;
+    }
 
-        if let Some(gt) = &operator.gt {
-            operators.push((">", gt));
-        }
-        if let Some(lt) = &operator.lt {
-            operators.push(("<", lt));
-        }
-        if let Some(gte) = &operator.gte {
-            operators.push((">=", gte));
-        }
-        if let Some(lte) = &operator.lte {
-            operators.push(("<=", lte));
-        }
-        if let Some(equals) = &operator.equals {
-            if !is_datetime::<T>() {
-                operators.push(("=", equals));
-            }
-        }
-        if !operators.is_empty() {
-            Some(operators)
-        } else {
-            None
+    if let Some(gt) = &operator.gt {
+        operators.push((">", gt));
+    }
+    if let Some(lt) = &operator.lt {
+        operators.push(("<", lt));
+    }
+    if let Some(gte) = &operator.gte {
+        operators.push((">=", gte));
+    }
+    if let Some(lte) = &operator.lte {
+        operators.push(("<=", lte));
+    }
+    if let Some(equals) = &operator.equals {
+        if !is_datetime::<T>() {
+            operators.push(("=", equals));
         }
     }
-
-    // Helper function to check if T is Datetime
-    fn is_datetime<T: 'static>() -> bool {
-        std::any::TypeId::of::<T>() == std::any::TypeId::of::<DateTime<Utc>>()
+    if !operators.is_empty() {
+        Some(operators)
+    } else {
+        None
     }
+}
 
-    let rows = query
-        .build()
-        .fetch_all(pool)
-        .await
-        .map_err(GetRecordError)?;
-
-    let result: Vec<Record> = rows
-        .iter()
-        .map(|row| Record {
-            record_id: row.try_get("record_id").unwrap(),
-            meta: row
-                .try_get("meta")
-                .ok()
-                .and_then(|value| serde_json::from_value(value).ok()),
-            components: row
-                .try_get("components")
-                .ok()
-                .and_then(|value| serde_json::from_value(value).ok()),
-            start_time: row.try_get("start_time").ok().unwrap_or(None),
-            stop_time: row.try_get("stop_time").ok().unwrap_or(None),
-            runtime: row.try_get("runtime").ok().unwrap_or(None),
-        })
-        .collect();
-
-    Ok(result)
+// Helper function to check if T is Datetime
+fn is_datetime<T: 'static>() -> bool {
+    std::any::TypeId::of::<T>() == std::any::TypeId::of::<DateTime<Utc>>()
 }
 
 #[tracing::instrument(name = "Getting one record using record_id", skip(record_id, pool))]
@@ -291,19 +479,20 @@ pub async fn get_one_record(
 ) -> Result<Option<Record>, anyhow::Error> {
     let is_valid_record_id = ValidName::parse(record_id.clone().to_string());
     if is_valid_record_id.is_ok() {
-        Ok(sqlx::query_as!(
-            RecordDatabase,
+        Ok(sqlx::query_as::<_, RecordDatabase>(
             r#"SELECT record_id,
                   meta,
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
-           WHERE record_id = $1
+           WHERE record_id = $1 AND deleted_at IS NULL
         "#,
-            &record_id,
         )
+        .bind(&record_id)
         .fetch_one(pool)
         .await
         .map(Record::try_from)
@@ -321,3 +510,43 @@ display_for_error!(
     GetRecordError,
     "A database error was encountered while trying to get a record from the database"
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sql_for(query_string: &str) -> String {
+        let filters: Filters = serde_qs::from_str(query_string).unwrap();
+        records_query(&filters).sql().to_string()
+    }
+
+    #[test]
+    fn start_time_bound_is_kept_as_is() {
+        let sql =
+            sql_for("start_time[gte]=2024-01-01T00:00:00Z&start_time[lt]=2024-02-01T00:00:00Z");
+
+        assert!(sql.contains(" start_time < $"));
+        assert!(sql.contains(" start_time >= $"));
+    }
+
+    #[test]
+    fn stop_time_upper_bound_also_bounds_start_time() {
+        let sql = sql_for("stop_time[lt]=2024-02-01T00:00:00Z");
+
+        assert!(sql.contains(" stop_time < $1 "));
+        assert!(sql.contains(" start_time < $2 "));
+
+        let sql = sql_for("stop_time[lte]=2024-02-01T00:00:00Z");
+
+        assert!(sql.contains(" start_time <= $2 "));
+    }
+
+    #[test]
+    fn stop_time_lower_bound_does_not_bound_start_time() {
+        let sql = sql_for("stop_time[gte]=2024-01-01T00:00:00Z");
+
+        assert!(sql.contains(" stop_time >= $1 "));
+        assert!(!sql.contains("start_time >"));
+        assert!(!sql.contains("start_time <"));
+    }
+}
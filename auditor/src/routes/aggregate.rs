@@ -0,0 +1,162 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::domain::ValidName;
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+
+#[derive(thiserror::Error)]
+pub enum AggregateError {
+    #[error("Invalid query parameters: {0}")]
+    InvalidQuery(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(AggregateError);
+responseerror_for_error!(
+    AggregateError,
+    InvalidQuery => BAD_REQUEST, InvalidQuery;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Metric which is summed up per group.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateMetric {
+    /// Runtime of the records in seconds.
+    Runtime,
+    /// Amount of the component given by `component`.
+    ComponentAmount,
+}
+
+impl AggregateMetric {
+    /// Name of the field holding the sum in the response.
+    fn sum_field(&self) -> &'static str {
+        match self {
+            AggregateMetric::Runtime => "sum_runtime",
+            AggregateMetric::ComponentAmount => "sum_amount",
+        }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct AggregateQuery {
+    /// Meta key whose values define the groups.
+    pub group_by: String,
+    pub sum: AggregateMetric,
+    /// Component whose amount is summed up, required for `sum=component_amount`.
+    pub component: Option<String>,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct AggregateRow {
+    key: String,
+    sum: i64,
+    count: i64,
+}
+
+impl AggregateRow {
+    fn to_json(&self, metric: AggregateMetric) -> serde_json::Value {
+        let mut row = serde_json::Map::new();
+        row.insert("key".to_string(), self.key.clone().into());
+        row.insert(metric.sum_field().to_string(), self.sum.into());
+        row.insert("count".to_string(), self.count.into());
+        row.into()
+    }
+}
+
+/// Sums up a metric of all records grouped by the values of a meta key.
+///
+/// A record with several values for the meta key counts towards each of these groups. Records
+/// without the meta key are ignored.
+#[tracing::instrument(name = "Aggregating records", skip(request, pool))]
+pub async fn aggregate_records(
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, AggregateError> {
+    let query: AggregateQuery = serde_qs::from_str(request.query_string())
+        .map_err(|e| AggregateError::InvalidQuery(e.to_string()))?;
+    let group_by = ValidName::parse(query.group_by)
+        .map_err(|e| AggregateError::InvalidQuery(e.to_string()))?;
+
+    let rows = match (query.sum, query.component) {
+        (AggregateMetric::Runtime, None) => sum_runtime(&group_by, &pool).await?,
+        (AggregateMetric::ComponentAmount, Some(component)) => {
+            let component = ValidName::parse(component)
+                .map_err(|e| AggregateError::InvalidQuery(e.to_string()))?;
+            sum_component_amount(&group_by, &component, &pool).await?
+        }
+        (AggregateMetric::Runtime, Some(_)) => {
+            return Err(AggregateError::InvalidQuery(
+                "`component` requires `sum=component_amount`".to_string(),
+            ))
+        }
+        (AggregateMetric::ComponentAmount, None) => {
+            return Err(AggregateError::InvalidQuery(
+                "`sum=component_amount` requires `component`".to_string(),
+            ))
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(
+        rows.iter()
+            .map(|row| row.to_json(query.sum))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+async fn sum_runtime(
+    group_by: &ValidName,
+    pool: &PgPool,
+) -> Result<Vec<AggregateRow>, anyhow::Error> {
+    Ok(sqlx::query_as(
+        r#"
+        SELECT value AS "key",
+               COALESCE(SUM(runtime), 0)::BIGINT AS sum,
+               COUNT(*) AS count
+        FROM auditor_accounting,
+             jsonb_array_elements_text(meta -> $1) AS value
+        WHERE deleted_at IS NULL
+        GROUP BY value
+        ORDER BY value
+        "#,
+    )
+    .bind(group_by.as_ref())
+    .fetch_all(pool)
+    .await?)
+}
+
+async fn sum_component_amount(
+    group_by: &ValidName,
+    component: &ValidName,
+    pool: &PgPool,
+) -> Result<Vec<AggregateRow>, anyhow::Error> {
+    Ok(sqlx::query_as(
+        r#"
+        SELECT value AS "key",
+               COALESCE(SUM(components.amount), 0)::BIGINT AS sum,
+               COUNT(*) AS count
+        FROM auditor_accounting a
+        CROSS JOIN jsonb_array_elements_text(a.meta -> $1) AS value
+        LEFT JOIN LATERAL (
+            SELECT SUM((component ->> 'amount')::BIGINT) AS amount
+            FROM jsonb_array_elements(
+                CASE WHEN jsonb_typeof(a.components) = 'array' THEN a.components ELSE '[]' END
+            ) AS component
+            WHERE component ->> 'name' = $2
+        ) AS components ON true
+        WHERE a.deleted_at IS NULL
+        GROUP BY value
+        ORDER BY value
+        "#,
+    )
+    .bind(group_by.as_ref())
+    .bind(component.as_ref())
+    .fetch_all(pool)
+    .await?)
+}
@@ -0,0 +1,65 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::domain::{Record, RecordDatabase};
+use actix_web::{web, HttpResponse};
+use sqlx::{FromRow, PgPool};
+
+#[derive(thiserror::Error)]
+pub enum ByIdsError {
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(ByIdsError);
+responseerror_for_error!(
+    ByIdsError,
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Returns the records with the record ids given as JSON list in the body.
+///
+/// The records are returned in the order of the requested ids. Unknown or deleted ids are
+/// skipped, and ids which are requested multiple times are only returned once. This is a `POST`
+/// request, such that long lists of ids are not limited by the maximum length of URLs.
+#[tracing::instrument(name = "Retrieving records by record ids", skip(record_ids, pool))]
+pub async fn records_by_ids(
+    record_ids: web::Json<Vec<String>>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ByIdsError> {
+    Ok(HttpResponse::Ok().json(get_records_by_ids(&record_ids, &pool).await?))
+}
+
+async fn get_records_by_ids(
+    record_ids: &[String],
+    pool: &PgPool,
+) -> Result<Vec<Record>, anyhow::Error> {
+    if record_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    sqlx::query(
+        r#"
+        SELECT record_id,
+               meta,
+               components,
+               start_time,
+               stop_time,
+               runtime,
+               ingested_at,
+               updated_at
+        FROM auditor_accounting
+        WHERE record_id = ANY($1) AND deleted_at IS NULL
+        ORDER BY array_position($1, record_id)
+        "#,
+    )
+    .bind(record_ids)
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| Record::try_from(RecordDatabase::from_row(row)?))
+    .collect()
+}
@@ -0,0 +1,200 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::domain::{Record, RecordDatabase, ValidName};
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool, Row};
+use std::time::Duration;
+
+/// Number of records returned by `GET /consumers/{name}/next` if no `limit` is given.
+const DEFAULT_LIMIT: u32 = 1000;
+
+/// Time after their ingestion after which records are handed out to consumers.
+///
+/// Inserts can commit in a different order than their records were ingested. Records are
+/// therefore held back until all inserts which started before them have committed, as otherwise
+/// the cursor could move past records which only become visible later. Hence the horizon has to
+/// be longer than the slowest insert, including the clock skew between Auditor instances.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsumerHorizon(pub Duration);
+
+#[derive(thiserror::Error)]
+pub enum ConsumerError {
+    #[error("Invalid consumer name: {0}")]
+    InvalidName(String),
+    #[error("Invalid limit: {0}")]
+    InvalidLimit(u32),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(ConsumerError);
+responseerror_for_error!(
+    ConsumerError,
+    InvalidName => BAD_REQUEST, InvalidQuery;
+    InvalidLimit => BAD_REQUEST, InvalidQuery;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+#[derive(serde::Deserialize, Debug)]
+pub struct NextQuery {
+    pub limit: Option<u32>,
+}
+
+/// Records which have not been acknowledged by a consumer yet.
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct ConsumerBatch {
+    /// Records in the order in which they were ingested.
+    pub records: Vec<Record>,
+    /// Cursor to acknowledge once all `records` have been processed.
+    pub cursor: ConsumerCursor,
+}
+
+/// Position of a consumer in the order in which records were ingested. Acknowledging a cursor
+/// acknowledges all records up to and including the record it points to.
+///
+/// Records are ordered by `ingested_at`, and by their `sequence` number if they were ingested at
+/// the same time. New consumers start at the default cursor, which precedes all records.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsumerCursor {
+    pub ingested_at: DateTime<Utc>,
+    pub sequence: i64,
+}
+
+#[tracing::instrument(name = "Getting next records of consumer", skip(pool))]
+pub async fn consumer_next(
+    name: web::Path<String>,
+    query: web::Query<NextQuery>,
+    pool: web::Data<PgPool>,
+    horizon: web::Data<ConsumerHorizon>,
+) -> Result<HttpResponse, ConsumerError> {
+    let name = parse_name(name.into_inner())?;
+    let limit = match query.limit.unwrap_or(DEFAULT_LIMIT) {
+        0 => return Err(ConsumerError::InvalidLimit(0)),
+        limit => limit,
+    };
+
+    let until = chrono::Duration::from_std(horizon.0)
+        .ok()
+        .and_then(|horizon| Utc::now().checked_sub_signed(horizon))
+        .ok_or_else(|| anyhow::anyhow!("Invalid consumer horizon: {:?}", horizon.0))?;
+
+    let cursor = get_cursor(&name, &pool).await?;
+    let batch = get_next_records(cursor, until, limit, &pool).await?;
+
+    Ok(HttpResponse::Ok().json(batch))
+}
+
+#[tracing::instrument(name = "Acknowledging records of consumer", skip(pool))]
+pub async fn consumer_ack(
+    name: web::Path<String>,
+    ack: web::Json<ConsumerCursor>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, ConsumerError> {
+    let name = parse_name(name.into_inner())?;
+
+    let cursor = set_cursor(&name, ack.into_inner(), &pool).await?;
+
+    Ok(HttpResponse::Ok().json(cursor))
+}
+
+fn parse_name(name: String) -> Result<ValidName, ConsumerError> {
+    ValidName::parse(name.clone()).map_err(|_| ConsumerError::InvalidName(name))
+}
+
+// Consumers which have never acknowledged anything start at the beginning.
+async fn get_cursor(name: &ValidName, pool: &PgPool) -> Result<ConsumerCursor, anyhow::Error> {
+    let cursor = sqlx::query("SELECT ingested_at, sequence FROM auditor_consumers WHERE name = $1")
+        .bind(name.as_ref())
+        .fetch_optional(pool)
+        .await?;
+    Ok(match cursor {
+        Some(row) => ConsumerCursor {
+            ingested_at: row.try_get("ingested_at")?,
+            sequence: row.try_get("sequence")?,
+        },
+        None => ConsumerCursor::default(),
+    })
+}
+
+// The cursor never moves backwards, such that a late acknowledgement of an older batch has no
+// effect. Returns the resulting cursor.
+async fn set_cursor(
+    name: &ValidName,
+    cursor: ConsumerCursor,
+    pool: &PgPool,
+) -> Result<ConsumerCursor, anyhow::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO auditor_consumers (name, ingested_at, sequence, updated_at)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (name) DO UPDATE
+        SET ingested_at = EXCLUDED.ingested_at,
+            sequence = EXCLUDED.sequence,
+            updated_at = EXCLUDED.updated_at
+        WHERE (EXCLUDED.ingested_at, EXCLUDED.sequence)
+            > (auditor_consumers.ingested_at, auditor_consumers.sequence)
+        "#,
+    )
+    .bind(name.as_ref())
+    .bind(cursor.ingested_at)
+    .bind(cursor.sequence)
+    .bind(Utc::now())
+    .execute(pool)
+    .await?;
+    get_cursor(name, pool).await
+}
+
+// Records are read in the order of `ingested_at`, which is set before the records are inserted.
+// Only records ingested before `until` are read, such that all inserts which could still add
+// records before the cursor have committed, see `ConsumerHorizon`. The `id` of
+// `auditor_accounting` orders records which were ingested at the same time.
+async fn get_next_records(
+    cursor: ConsumerCursor,
+    until: DateTime<Utc>,
+    limit: u32,
+    pool: &PgPool,
+) -> Result<ConsumerBatch, anyhow::Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id::BIGINT AS sequence,
+               record_id,
+               meta,
+               components,
+               start_time,
+               stop_time,
+               runtime,
+               ingested_at,
+               updated_at
+        FROM auditor_accounting
+        WHERE (ingested_at, id) > ($1, $2) AND ingested_at < $3 AND deleted_at IS NULL
+        ORDER BY ingested_at, id
+        LIMIT $4
+        "#,
+    )
+    .bind(cursor.ingested_at)
+    .bind(cursor.sequence)
+    .bind(until)
+    .bind(i64::from(limit))
+    .fetch_all(pool)
+    .await?;
+
+    let cursor = match rows.last() {
+        Some(row) => ConsumerCursor {
+            ingested_at: row.try_get("ingested_at")?,
+            sequence: row.try_get("sequence")?,
+        },
+        None => cursor,
+    };
+    let records = rows
+        .iter()
+        .map(|row| Record::try_from(RecordDatabase::from_row(row)?))
+        .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+    Ok(ConsumerBatch { records, cursor })
+}
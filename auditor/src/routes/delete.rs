@@ -0,0 +1,87 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::PgPool;
+
+/// Whether `DELETE /record/{record_id}` keeps the record as a tombstone instead of removing it.
+#[derive(Debug, Clone, Copy)]
+pub struct SoftDelete(pub bool);
+
+#[derive(thiserror::Error)]
+pub enum DeleteError {
+    #[error("Deleting unknown record {0} not possible.")]
+    UnknownRecord(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(DeleteError);
+responseerror_for_error!(
+    DeleteError,
+    UnknownRecord => NOT_FOUND, UnknownRecord;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Deletes a record.
+///
+/// With soft delete, the record is only marked as deleted and hidden from queries unless they set
+/// `include_deleted`. Deleting a record which is already marked as deleted fails.
+#[tracing::instrument(name = "Deleting a record", skip(pool, soft_delete))]
+pub async fn delete(
+    record_id: web::Path<String>,
+    pool: web::Data<PgPool>,
+    soft_delete: web::Data<SoftDelete>,
+) -> Result<HttpResponse, DeleteError> {
+    let record_id = record_id.into_inner();
+    let deleted = if soft_delete.0 {
+        mark_record_deleted(&record_id, &pool).await?
+    } else {
+        delete_record(&record_id, &pool).await?
+    };
+
+    if deleted {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Err(DeleteError::UnknownRecord(record_id))
+    }
+}
+
+#[tracing::instrument(name = "Marking a record as deleted in the database", skip(pool))]
+async fn mark_record_deleted(record_id: &str, pool: &PgPool) -> Result<bool, anyhow::Error> {
+    let now = Utc::now();
+    let result = sqlx::query(
+        r#"
+        UPDATE auditor_accounting
+        SET deleted_at = $2,
+            updated_at = $2
+        WHERE record_id = $1 AND deleted_at IS NULL
+        "#,
+    )
+    .bind(record_id)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[tracing::instrument(name = "Removing a record from the database", skip(pool))]
+async fn delete_record(record_id: &str, pool: &PgPool) -> Result<bool, anyhow::Error> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM auditor_accounting
+        WHERE record_id = $1
+        "#,
+    )
+    .bind(record_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
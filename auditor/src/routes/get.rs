@@ -15,7 +15,7 @@ pub enum GetError {
 }
 
 debug_for_error!(GetError);
-responseerror_for_error!(GetError, UnexpectedError => INTERNAL_SERVER_ERROR;);
+responseerror_for_error!(GetError, UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;);
 
 #[tracing::instrument(name = "Retrieving records from database", skip(pool))]
 pub async fn get_records(pool: &PgPool) -> Result<Vec<Record>, anyhow::Error> {
@@ -26,8 +26,11 @@ pub async fn get_records(pool: &PgPool) -> Result<Vec<Record>, anyhow::Error> {
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
+           WHERE deleted_at IS NULL
            ORDER BY stop_time
         "#
     )
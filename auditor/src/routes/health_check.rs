@@ -5,6 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::domain::{ComponentStatus, ServerStatus};
+use crate::routes::MaintenanceMode;
 use actix_web::{web, HttpResponse};
 use sqlx::PgPool;
 
@@ -14,3 +16,36 @@ pub async fn health_check(pool: web::Data<PgPool>) -> HttpResponse {
     }
     HttpResponse::Ok().finish()
 }
+
+/// Whether the server is serving HTTPS, reported by [`health_status`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TlsEnabled(pub bool);
+
+/// Reports the status of the individual components of the server.
+///
+/// Responds with `503 SERVICE UNAVAILABLE` if the database is not reachable, the body is the
+/// same in both cases.
+pub async fn health_status(
+    pool: web::Data<PgPool>,
+    tls: web::Data<TlsEnabled>,
+    maintenance: web::Data<MaintenanceMode>,
+) -> HttpResponse {
+    let database = match pool.acquire().await {
+        Ok(_) => ComponentStatus::Ok,
+        Err(_) => ComponentStatus::Unavailable,
+    };
+    let status = ServerStatus {
+        database,
+        tls: if tls.0 {
+            ComponentStatus::Ok
+        } else {
+            ComponentStatus::Disabled
+        },
+        maintenance_mode: maintenance.is_enabled(),
+    };
+
+    match database {
+        ComponentStatus::Ok => HttpResponse::Ok().json(status),
+        _ => HttpResponse::ServiceUnavailable().json(status),
+    }
+}
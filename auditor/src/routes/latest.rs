@@ -0,0 +1,73 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::domain::{Record, RecordDatabase, ValidName};
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::{FromRow, PgPool};
+
+#[derive(thiserror::Error)]
+pub enum LatestError {
+    #[error("Invalid query parameters: {0}")]
+    InvalidQuery(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(LatestError);
+responseerror_for_error!(
+    LatestError,
+    InvalidQuery => BAD_REQUEST, InvalidQuery;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+#[derive(serde::Deserialize, Debug)]
+pub struct LatestQuery {
+    /// Meta key whose values define the groups.
+    pub key: String,
+}
+
+/// Returns the record with the latest `stop_time` for every distinct value of a meta key.
+///
+/// Records are grouped by the complete list of values of the meta key. Records without the meta
+/// key are ignored. Records without `stop_time` are only returned if there is no other record in
+/// their group. The records are sorted by the value of the meta key.
+#[tracing::instrument(name = "Retrieving latest records per meta key", skip(request, pool))]
+pub async fn latest_records(
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, LatestError> {
+    let query: LatestQuery = serde_qs::from_str(request.query_string())
+        .map_err(|e| LatestError::InvalidQuery(e.to_string()))?;
+    let key = ValidName::parse(query.key).map_err(|e| LatestError::InvalidQuery(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(get_latest_records(&key, &pool).await?))
+}
+
+async fn get_latest_records(key: &ValidName, pool: &PgPool) -> Result<Vec<Record>, anyhow::Error> {
+    sqlx::query(
+        r#"
+        SELECT DISTINCT ON (meta ->> $1)
+               record_id,
+               meta,
+               components,
+               start_time,
+               stop_time,
+               runtime,
+               ingested_at,
+               updated_at
+        FROM auditor_accounting
+        WHERE meta ->> $1 IS NOT NULL AND deleted_at IS NULL
+        ORDER BY meta ->> $1, stop_time DESC NULLS LAST, record_id
+        "#,
+    )
+    .bind(key.as_ref())
+    .fetch_all(pool)
+    .await?
+    .iter()
+    .map(|row| Record::try_from(RecordDatabase::from_row(row)?))
+    .collect()
+}
@@ -0,0 +1,54 @@
+// Copyright 2021-2022 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use actix_web::{web, HttpResponse};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Read-only mode of the server. While enabled, all writing requests are rejected with
+/// `503 SERVICE UNAVAILABLE`, reads continue to work.
+#[derive(Debug, Default)]
+pub struct MaintenanceMode(AtomicBool);
+
+impl MaintenanceMode {
+    pub fn new(enabled: bool) -> Self {
+        MaintenanceMode(AtomicBool::new(enabled))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed)
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug)]
+pub struct MaintenanceState {
+    pub enabled: bool,
+}
+
+pub async fn get_maintenance(maintenance: web::Data<MaintenanceMode>) -> HttpResponse {
+    HttpResponse::Ok().json(MaintenanceState {
+        enabled: maintenance.is_enabled(),
+    })
+}
+
+#[tracing::instrument(name = "Setting maintenance mode", skip(maintenance))]
+pub async fn set_maintenance(
+    state: web::Json<MaintenanceState>,
+    maintenance: web::Data<MaintenanceMode>,
+) -> HttpResponse {
+    maintenance.set(state.enabled);
+    tracing::warn!(
+        "Maintenance mode {}",
+        if state.enabled { "enabled" } else { "disabled" }
+    );
+    HttpResponse::Ok().json(MaintenanceState {
+        enabled: maintenance.is_enabled(),
+    })
+}
@@ -0,0 +1,52 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+
+use crate::domain::ValidName;
+
+#[derive(thiserror::Error)]
+pub enum MetaValuesError {
+    #[error("Invalid meta key: {0}")]
+    InvalidKey(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(MetaValuesError);
+responseerror_for_error!(
+    MetaValuesError,
+    InvalidKey => BAD_REQUEST, InvalidQuery;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Returns the distinct values of a meta key over all records, sorted alphabetically.
+#[tracing::instrument(name = "Retrieving distinct values of meta key", skip(pool))]
+pub async fn meta_values(
+    key: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, MetaValuesError> {
+    let key = ValidName::parse(key.into_inner())
+        .map_err(|e| MetaValuesError::InvalidKey(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(get_meta_values(&key, &pool).await?))
+}
+
+async fn get_meta_values(key: &ValidName, pool: &PgPool) -> Result<Vec<String>, anyhow::Error> {
+    Ok(sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT jsonb_array_elements_text(meta -> $1) AS value
+        FROM auditor_accounting
+        WHERE jsonb_typeof(meta -> $1) = 'array' AND deleted_at IS NULL
+        ORDER BY value
+        "#,
+    )
+    .bind(key)
+    .fetch_all(pool)
+    .await?)
+}
@@ -6,15 +6,37 @@
 // copied, modified, or distributed except according to those terms.
 
 mod add;
+mod admin;
 mod advanced_record_filters;
+mod aggregate;
+mod by_ids;
+mod consumers;
+mod delete;
 mod get;
 mod health_check;
+mod latest;
+mod maintenance;
+mod meta_values;
+mod patch;
 mod record_handlers;
+mod single_flight;
+mod stream_limit;
 mod update;
 
 pub use add::*;
+pub use admin::*;
 pub use advanced_record_filters::*;
+pub use aggregate::*;
+pub use by_ids::*;
+pub use consumers::*;
+pub use delete::*;
 pub use get::*;
 pub use health_check::*;
+pub use latest::*;
+pub use maintenance::*;
+pub use meta_values::*;
+pub use patch::*;
 pub use record_handlers::*;
+pub use single_flight::*;
+pub use stream_limit::*;
 pub use update::*;
@@ -0,0 +1,231 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use crate::domain::{Component, Meta, ValidMeta};
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sqlx::PgPool;
+
+/// Fields of a record which can be changed with `PATCH /record/{record_id}`.
+const PATCHABLE_FIELDS: [&str; 2] = ["meta", "components"];
+
+/// Whether records may be changed with `PATCH /record/{record_id}`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecordPatchEnabled(pub bool);
+
+#[derive(thiserror::Error)]
+pub enum PatchError {
+    #[error("Patching records is disabled, record {0} was not changed.")]
+    Disabled(String),
+    #[error("Patching unknown record {0} not possible.")]
+    UnknownRecord(String),
+    #[error("Invalid patch: {0}")]
+    InvalidPatch(String),
+    #[error(transparent)]
+    UnexpectedError(#[from] anyhow::Error),
+}
+
+debug_for_error!(PatchError);
+responseerror_for_error!(
+    PatchError,
+    Disabled => FORBIDDEN, PatchDisabled;
+    UnknownRecord => NOT_FOUND, UnknownRecord;
+    InvalidPatch => BAD_REQUEST, ValidationFailed;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
+);
+
+/// Applies a JSON Merge Patch (RFC 7396) to the meta and components of a record.
+///
+/// Meta values are patched per key, keys set to `null` are removed. Components are replaced as a
+/// whole, as arrays cannot be merged.
+#[tracing::instrument(name = "Patching a record", skip(patch, pool, enabled))]
+pub async fn patch(
+    record_id: web::Path<String>,
+    patch: web::Json<Value>,
+    pool: web::Data<PgPool>,
+    enabled: web::Data<RecordPatchEnabled>,
+) -> Result<HttpResponse, PatchError> {
+    let record_id = record_id.into_inner();
+    if !enabled.0 {
+        return Err(PatchError::Disabled(record_id));
+    }
+
+    let patch = patch.into_inner();
+    check_patchable(&patch)?;
+    patch_record(&record_id, &patch, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+// Only meta and components may be patched, everything else is fixed once a record is added.
+fn check_patchable(patch: &Value) -> Result<(), PatchError> {
+    let Value::Object(fields) = patch else {
+        return Err(PatchError::InvalidPatch(
+            "Patch must be a JSON object".to_string(),
+        ));
+    };
+    match fields
+        .keys()
+        .find(|field| !PATCHABLE_FIELDS.contains(&field.as_str()))
+    {
+        Some(field) => Err(PatchError::InvalidPatch(format!(
+            "Field {field} cannot be patched"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Applies the JSON Merge Patch `patch` to `target` as specified in RFC 7396.
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+// Validates the patched fields the same way as the fields of a newly added record and returns
+// them in the form they are stored in the database.
+fn validate_patched(mut patched: Value) -> Result<(Value, Value), PatchError> {
+    let invalid = |e: &dyn std::fmt::Display| PatchError::InvalidPatch(e.to_string());
+
+    let meta = match patched.get_mut("meta").map(Value::take) {
+        None | Some(Value::Null) => Value::Null,
+        Some(meta) => {
+            let meta: Meta = serde_json::from_value(meta).map_err(|e| invalid(&e))?;
+            let meta = ValidMeta::try_from(meta).map_err(|e| invalid(&e))?;
+            serde_json::to_value(meta).map_err(|e| invalid(&e))?
+        }
+    };
+    let components: Vec<Component> = match patched.get_mut("components").map(Value::take) {
+        None | Some(Value::Null) => vec![],
+        Some(components) => serde_json::from_value(components).map_err(|e| invalid(&e))?,
+    };
+    let components = serde_json::to_value(components).map_err(|e| invalid(&e))?;
+
+    Ok((meta, components))
+}
+
+#[tracing::instrument(name = "Patching a record in the database", skip(patch, pool))]
+async fn patch_record(record_id: &str, patch: &Value, pool: &PgPool) -> Result<(), PatchError> {
+    let mut transaction = pool
+        .begin()
+        .await
+        .map_err(|e| PatchError::UnexpectedError(e.into()))?;
+
+    let (meta, components): (Option<Value>, Option<Value>) = sqlx::query_as(
+        r#"
+        SELECT meta, components
+        FROM auditor_accounting
+        WHERE record_id = $1 AND deleted_at IS NULL
+        FOR UPDATE
+        "#,
+    )
+    .bind(record_id)
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(|e| PatchError::UnexpectedError(e.into()))?
+    .ok_or_else(|| PatchError::UnknownRecord(record_id.to_string()))?;
+
+    let mut patched = json!({
+        "meta": meta.unwrap_or(Value::Null),
+        "components": components.unwrap_or(Value::Null),
+    });
+    merge_patch(&mut patched, patch);
+    let (meta, components) = validate_patched(patched)?;
+
+    sqlx::query(
+        r#"
+        UPDATE auditor_accounting
+        SET meta = $2,
+            components = $3,
+            updated_at = $4
+        WHERE record_id = $1
+        "#,
+    )
+    .bind(record_id)
+    .bind(meta)
+    .bind(components)
+    .bind(Utc::now())
+    .execute(&mut *transaction)
+    .await
+    .map_err(|e| PatchError::UnexpectedError(e.into()))?;
+
+    transaction
+        .commit()
+        .await
+        .map_err(|e| PatchError::UnexpectedError(e.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_patch_follows_rfc_7396() {
+        let cases = [
+            (json!({"a": "b"}), json!({"a": "c"}), json!({"a": "c"})),
+            (
+                json!({"a": "b"}),
+                json!({"b": "c"}),
+                json!({"a": "b", "b": "c"}),
+            ),
+            (json!({"a": "b"}), json!({"a": null}), json!({})),
+            (
+                json!({"a": ["b"]}),
+                json!({"a": ["c"]}),
+                json!({"a": ["c"]}),
+            ),
+            (
+                json!({"a": {"b": "c"}}),
+                json!({"a": {"b": "d", "c": null}}),
+                json!({"a": {"b": "d"}}),
+            ),
+            (json!(["a"]), json!({"a": "b"}), json!({"a": "b"})),
+            (json!({"a": "b"}), json!(["c"]), json!(["c"])),
+        ];
+        for (mut target, patch, expected) in cases {
+            merge_patch(&mut target, &patch);
+            assert_eq!(target, expected);
+        }
+    }
+
+    #[test]
+    fn only_meta_and_components_are_patchable() {
+        assert!(check_patchable(&json!({"meta": {"site_id": ["site-1"]}})).is_ok());
+        assert!(check_patchable(&json!({"components": []})).is_ok());
+        assert!(check_patchable(&json!({"stop_time": "2022-10-01T00:00:00Z"})).is_err());
+        assert!(check_patchable(&json!(["meta"])).is_err());
+    }
+
+    #[test]
+    fn patched_meta_is_validated() {
+        let patched = json!({"meta": {"site_id": ["site/1"]}, "components": []});
+
+        assert!(validate_patched(patched).is_err());
+    }
+
+    #[test]
+    fn removed_fields_are_stored_empty() {
+        let (meta, components) = validate_patched(json!({})).unwrap();
+
+        assert_eq!(meta, Value::Null);
+        assert_eq!(components, json!([]));
+    }
+}
@@ -1,7 +1,17 @@
-use crate::routes::{advanced_record_filtering, get_one_record, Filters};
-use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use crate::constants::{ErrorCode, ERROR_CODE_HEADER, NDJSON_CONTENT_TYPE};
+use crate::domain::{Filters, Record, RecordsEnvelope, ValidAmount};
+use crate::middleware::is_admin_request;
+use crate::routes::{
+    advanced_component_names, advanced_record_count, advanced_record_filtering,
+    advanced_record_ndjson_stream, get_one_record, SingleFlight, StreamLimit,
+};
+use crate::slow_query_log::SlowQueryLog;
+use actix_web::{http::header, web, HttpRequest, HttpResponse, ResponseError};
+use futures_util::StreamExt;
 use serde_json::json;
 use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -9,10 +19,19 @@ pub struct RecordQuery {
     pub record_id: String,
 }
 
-#[tracing::instrument(name = "Getting records", skip(query, pool))]
+/// Identical concurrent record queries share one database query.
+pub type RecordQueryFlights = SingleFlight<Result<Arc<Vec<Record>>, String>>;
+
+#[tracing::instrument(
+    name = "Getting records",
+    skip(query, pool, flights, slow_query_log, stream_limit)
+)]
 pub async fn query_records(
     query: HttpRequest,
     pool: web::Data<PgPool>,
+    flights: web::Data<RecordQueryFlights>,
+    slow_query_log: web::Data<Option<SlowQueryLog>>,
+    stream_limit: web::Data<StreamLimit>,
 ) -> Result<HttpResponse, GetFilterError> {
     let query_string = query.query_string();
 
@@ -21,24 +40,146 @@ pub async fn query_records(
         Err(_) => return Err(GetFilterError::InvalidQuery),
     };
 
-    if query_string.is_empty() {
-        // This case explicitly checks if the query is empty. Then it returns all records.
-        let records = advanced_record_filtering(filters, &pool)
-            .await
-            .map_err(|err| GetFilterError::UnexpectedError(err.to_string()))?;
+    check_include_deleted(&query, &filters)?;
+
+    // An empty query explicitly returns all records.
+    if !query_string.is_empty() && filters.is_all_none() {
+        return Err(GetFilterError::InvalidQuery);
+    }
+
+    // Streamed responses are neither shared between identical queries nor covered by the slow
+    // query log, because the query only finishes once the client has read all records.
+    if accepts_ndjson(&query) {
+        let Some(permit) = stream_limit.try_acquire() else {
+            tracing::warn!("Maximum number of concurrent streamed responses reached");
+            return Err(GetFilterError::TooManyStreams);
+        };
+        // The permit is released once the stream is dropped, i.e. after the last record has
+        // been sent or the client has disconnected.
+        let records =
+            advanced_record_ndjson_stream(filters, pool.get_ref().clone()).map(move |chunk| {
+                let _permit = &permit;
+                chunk
+            });
+        return Ok(HttpResponse::Ok()
+            .content_type(NDJSON_CONTENT_TYPE)
+            .streaming(records));
+    }
+
+    // One additional record is fetched to tell whether the records were truncated by `limit`.
+    let envelope = (filters.envelope == Some(true)).then(|| filters.clone());
+    let mut filters = filters;
+    if let Some(limit) = filters.limit.filter(|_| envelope.is_some()) {
+        filters.limit = ValidAmount::parse(limit.as_ref().saturating_add(1)).ok();
+    }
+
+    let start = Instant::now();
+    let records = flights
+        .run(query_string, || async {
+            advanced_record_filtering(filters, &pool)
+                .await
+                .map(Arc::new)
+                .map_err(|err| err.to_string())
+        })
+        .await
+        .map_err(GetFilterError::UnexpectedError)?;
+    if let Some(slow_query_log) = slow_query_log.as_ref() {
+        slow_query_log.check(query_string, start.elapsed());
+    }
+
+    if let Some(query) = envelope {
+        let mut records = records.to_vec();
+        let limit = query.limit.map(|limit| *limit.as_ref() as usize);
+        let truncated = limit.is_some_and(|limit| records.len() > limit);
+        records.truncate(limit.unwrap_or(records.len()));
+        return Ok(HttpResponse::Ok().json(RecordsEnvelope {
+            query,
+            count: records.len(),
+            records,
+            truncated,
+        }));
+    }
+
+    Ok(HttpResponse::Ok().json(&*records))
+}
 
-        return Ok(HttpResponse::Ok().json(records));
+// Soft-deleted records are only returned to clients with an admin token.
+fn check_include_deleted(request: &HttpRequest, filters: &Filters) -> Result<(), GetFilterError> {
+    if filters.include_deleted == Some(true) && !is_admin_request(request) {
+        return Err(GetFilterError::AdminTokenRequired);
     }
+    Ok(())
+}
 
-    if filters.is_all_none() {
+fn accepts_ndjson(request: &HttpRequest) -> bool {
+    request
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| {
+            accept
+                .split(',')
+                .any(|media_type| media_type.trim().starts_with(NDJSON_CONTENT_TYPE))
+        })
+}
+
+#[tracing::instrument(name = "Counting records", skip(query, pool, slow_query_log))]
+pub async fn count_records(
+    query: HttpRequest,
+    pool: web::Data<PgPool>,
+    slow_query_log: web::Data<Option<SlowQueryLog>>,
+) -> Result<HttpResponse, GetFilterError> {
+    let query_string = query.query_string();
+
+    let filters: Filters = match serde_qs::from_str(query_string) {
+        Ok(filters) => filters,
+        Err(_) => return Err(GetFilterError::InvalidQuery),
+    };
+
+    check_include_deleted(&query, &filters)?;
+
+    // An empty query counts all records.
+    if !query_string.is_empty() && filters.is_all_none() {
         return Err(GetFilterError::InvalidQuery);
     }
 
-    let records = advanced_record_filtering(filters, &pool)
+    let start = Instant::now();
+    let count = advanced_record_count(filters, &pool)
         .await
         .map_err(|err| GetFilterError::UnexpectedError(err.to_string()))?;
+    if let Some(slow_query_log) = slow_query_log.as_ref() {
+        slow_query_log.check(query_string, start.elapsed());
+    }
 
-    Ok(HttpResponse::Ok().json(records))
+    Ok(HttpResponse::Ok().json(json!({ "count": count })))
+}
+
+/// Returns the distinct names of the components of all records matching the query, sorted
+/// alphabetically.
+#[tracing::instrument(name = "Getting component names", skip(query, pool))]
+pub async fn component_names(
+    query: HttpRequest,
+    pool: web::Data<PgPool>,
+) -> Result<HttpResponse, GetFilterError> {
+    let query_string = query.query_string();
+
+    let filters: Filters = match serde_qs::from_str(query_string) {
+        Ok(filters) => filters,
+        Err(_) => return Err(GetFilterError::InvalidQuery),
+    };
+
+    check_include_deleted(&query, &filters)?;
+
+    // An empty query takes all records into account.
+    if !query_string.is_empty() && filters.is_all_none() {
+        return Err(GetFilterError::InvalidQuery);
+    }
+
+    let names = advanced_component_names(filters, &pool)
+        .await
+        .map_err(|err| GetFilterError::UnexpectedError(err.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(names))
 }
 
 #[tracing::instrument(name = "Getting one record", skip(record_query, pool))]
@@ -57,6 +198,12 @@ pub enum GetFilterError {
     #[error("Invalid query parameters")]
     InvalidQuery,
 
+    #[error("Too many concurrent streamed responses")]
+    TooManyStreams,
+
+    #[error("Including deleted records requires an admin token")]
+    AdminTokenRequired,
+
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
 }
@@ -64,12 +211,19 @@ pub enum GetFilterError {
 impl ResponseError for GetFilterError {
     fn error_response(&self) -> HttpResponse {
         match self {
-            GetFilterError::InvalidQuery => {
-                HttpResponse::BadRequest().json(json!({ "error": "Invalid query parameters" }))
-            }
-            GetFilterError::UnexpectedError(ref err) => {
-                HttpResponse::InternalServerError().json(json!({ "error": err }))
-            }
+            GetFilterError::InvalidQuery => HttpResponse::BadRequest()
+                .insert_header((ERROR_CODE_HEADER, ErrorCode::InvalidQuery.as_str()))
+                .json(json!({ "error": "Invalid query parameters" })),
+            GetFilterError::TooManyStreams => HttpResponse::ServiceUnavailable()
+                .insert_header((ERROR_CODE_HEADER, ErrorCode::TooManyStreams.as_str()))
+                .json(json!({ "error": "Too many concurrent streamed responses" })),
+            GetFilterError::AdminTokenRequired => HttpResponse::Unauthorized()
+                .insert_header((ERROR_CODE_HEADER, ErrorCode::Unauthorized.as_str()))
+                .insert_header((header::WWW_AUTHENTICATE, "Bearer"))
+                .json(json!({ "error": "Including deleted records requires an admin token" })),
+            GetFilterError::UnexpectedError(ref err) => HttpResponse::InternalServerError()
+                .insert_header((ERROR_CODE_HEADER, ErrorCode::UnexpectedError.as_str()))
+                .json(json!({ "error": err })),
         }
     }
 }
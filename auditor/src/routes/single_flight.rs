@@ -0,0 +1,128 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+
+/// Coalesces identical concurrent operations.
+///
+/// All callers of [`SingleFlight::run`] with the same key, which arrive while an operation for this
+/// key is in flight, wait for it and receive a clone of its result instead of running their own.
+/// Results are not cached: once an operation has finished, the next call runs a new one.
+pub struct SingleFlight<T> {
+    in_flight: Mutex<HashMap<String, Arc<OnceCell<T>>>>,
+}
+
+impl<T: Clone> SingleFlight<T> {
+    pub fn new() -> Self {
+        SingleFlight {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run<F, Fut>(&self, key: &str, operation: F) -> T
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let cell = self
+            .in_flight
+            .lock()
+            .unwrap()
+            .entry(key.to_string())
+            .or_default()
+            .clone();
+
+        // If the caller running the operation is cancelled, one of the waiting callers takes over.
+        let result = cell.get_or_init(operation).await.clone();
+
+        let mut in_flight = self.in_flight.lock().unwrap();
+        if in_flight
+            .get(key)
+            .is_some_and(|current| Arc::ptr_eq(current, &cell))
+        {
+            in_flight.remove(key);
+        }
+
+        result
+    }
+}
+
+impl<T: Clone> Default for SingleFlight<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    async fn slow_operation(calls: Arc<AtomicUsize>, value: usize) -> usize {
+        calls.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        value
+    }
+
+    #[tokio::test]
+    async fn identical_concurrent_calls_share_one_execution() {
+        let single_flight = Arc::new(SingleFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..50)
+            .map(|_| {
+                let single_flight = single_flight.clone();
+                let calls = calls.clone();
+                tokio::spawn(async move {
+                    single_flight
+                        .run("start_time[gte]=2022-10-01", || slow_operation(calls, 42))
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(single_flight.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn different_keys_are_executed_separately() {
+        let single_flight = SingleFlight::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (a, b) = tokio::join!(
+            single_flight.run("limit=1", || slow_operation(calls.clone(), 1)),
+            single_flight.run("limit=2", || slow_operation(calls.clone(), 2)),
+        );
+
+        assert_eq!((a, b), (1, 2));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn results_are_not_cached() {
+        let single_flight = SingleFlight::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        single_flight
+            .run("", || slow_operation(calls.clone(), 1))
+            .await;
+        let second = single_flight
+            .run("", || slow_operation(calls.clone(), 2))
+            .await;
+
+        assert_eq!(second, 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Limits the number of concurrent streamed record responses.
+///
+/// Each streamed response holds a database connection and buffers until the client has read all
+/// records. Clones share their permits.
+#[derive(Debug, Clone, Default)]
+pub struct StreamLimit {
+    permits: Option<Arc<Semaphore>>,
+}
+
+/// Permission to stream a response. The permit is returned to the [`StreamLimit`] on drop.
+#[derive(Debug)]
+pub struct StreamPermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl StreamLimit {
+    /// Allows at most `max_streams` concurrent streams, or any number if `None`.
+    pub fn new(max_streams: Option<usize>) -> Self {
+        StreamLimit {
+            permits: max_streams.map(|max| Arc::new(Semaphore::new(max))),
+        }
+    }
+
+    /// Returns `None` if the maximum number of concurrent streams is reached.
+    pub fn try_acquire(&self) -> Option<StreamPermit> {
+        let permit = match &self.permits {
+            Some(permits) => Some(permits.clone().try_acquire_owned().ok()?),
+            None => None,
+        };
+        Some(StreamPermit { _permit: permit })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlimited_by_default() {
+        let limit = StreamLimit::default();
+
+        let permits: Vec<_> = (0..100).map(|_| limit.try_acquire()).collect();
+
+        assert!(permits.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn rejects_streams_beyond_the_limit() {
+        let limit = StreamLimit::new(Some(2));
+
+        let first = limit.try_acquire();
+        let second = limit.clone().try_acquire();
+
+        assert!(first.is_some());
+        assert!(second.is_some());
+        assert!(limit.try_acquire().is_none());
+
+        drop(first);
+        assert!(limit.try_acquire().is_some());
+    }
+}
@@ -5,7 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::domain::RecordUpdate;
+use crate::domain::{RecordUpdate, RuntimeMismatch};
+use crate::routes::RuntimeTolerance;
 use actix_web::{web, HttpResponse};
 use chrono::Utc;
 use sqlx::PgPool;
@@ -14,6 +15,8 @@ use sqlx::PgPool;
 pub enum UpdateError {
     #[error("Updating unknown record {0} not possible.")]
     UnknownRecord(String),
+    #[error("Invalid update: {0}")]
+    RuntimeMismatch(RuntimeMismatch),
     #[error(transparent)]
     UnexpectedError(#[from] anyhow::Error),
 }
@@ -21,29 +24,38 @@ pub enum UpdateError {
 debug_for_error!(UpdateError);
 responseerror_for_error!(
     UpdateError,
-    UnknownRecord => NOT_FOUND;
-    UnexpectedError => INTERNAL_SERVER_ERROR;
+    UnknownRecord => NOT_FOUND, UnknownRecord;
+    RuntimeMismatch => UNPROCESSABLE_ENTITY, ValidationFailed;
+    UnexpectedError => INTERNAL_SERVER_ERROR, UnexpectedError;
 );
 
 #[tracing::instrument(
     name = "Updating a record",
-    skip(record, pool),
+    skip(record, pool, runtime_tolerance),
     fields(record_id = %record.record_id)
 )]
 pub async fn update(
     record: web::Json<RecordUpdate>,
     pool: web::Data<PgPool>,
+    runtime_tolerance: web::Data<RuntimeTolerance>,
 ) -> Result<HttpResponse, UpdateError> {
-    update_record(&record, &pool).await.map_err(|e| match e {
-        UpdateRecordError::RowNotFoundError(s) => UpdateError::UnknownRecord(s),
-        UpdateRecordError::OtherError(err) => UpdateError::UnexpectedError(err.into()),
-    })?;
+    update_record(&record, &pool, *runtime_tolerance.get_ref())
+        .await
+        .map_err(|e| match e {
+            UpdateRecordError::RowNotFoundError(s) => UpdateError::UnknownRecord(s),
+            UpdateRecordError::RuntimeMismatch(mismatch) => UpdateError::RuntimeMismatch(mismatch),
+            UpdateRecordError::OtherError(err) => UpdateError::UnexpectedError(err.into()),
+        })?;
 
     Ok(HttpResponse::Ok().finish())
 }
 
 #[tracing::instrument(name = "Updating a record in the database", skip(record, pool))]
-pub async fn update_record(record: &RecordUpdate, pool: &PgPool) -> Result<(), UpdateRecordError> {
+pub async fn update_record(
+    record: &RecordUpdate,
+    pool: &PgPool,
+    runtime_tolerance: RuntimeTolerance,
+) -> Result<(), UpdateRecordError> {
     let mut transaction = match pool.begin().await {
         Ok(transaction) => transaction,
         Err(e) => return Err(UpdateRecordError::OtherError(e)),
@@ -53,7 +65,7 @@ pub async fn update_record(record: &RecordUpdate, pool: &PgPool) -> Result<(), U
         r#"
         SELECT start_time
         FROM auditor_accounting
-        WHERE record_id = $1
+        WHERE record_id = $1 AND deleted_at IS NULL
         "#,
         record.record_id.as_ref(),
     )
@@ -67,6 +79,10 @@ pub async fn update_record(record: &RecordUpdate, pool: &PgPool) -> Result<(), U
     })?
     .start_time;
 
+    record
+        .check_runtime(start_time, runtime_tolerance.0)
+        .map_err(UpdateRecordError::RuntimeMismatch)?;
+
     sqlx::query_unchecked!(
         r#"
         UPDATE auditor_accounting
@@ -74,7 +90,7 @@ pub async fn update_record(record: &RecordUpdate, pool: &PgPool) -> Result<(), U
             runtime = $3,
             updated_at = $4
         WHERE
-            record_id = $1
+            record_id = $1 AND deleted_at IS NULL
         "#,
         record.record_id.as_ref(),
         record.stop_time,
@@ -97,6 +113,8 @@ pub enum UpdateRecordError {
     #[error("Entry {0} not found in database")]
     RowNotFoundError(String),
     #[error(transparent)]
+    RuntimeMismatch(RuntimeMismatch),
+    #[error(transparent)]
     OtherError(#[from] sqlx::Error),
 }
 
@@ -0,0 +1,144 @@
+// Copyright 2021-2022 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use std::time::Duration;
+
+/// Logs record queries which take longer than `threshold`.
+#[derive(Debug, Clone)]
+pub struct SlowQueryLog {
+    pub threshold: Duration,
+    /// Replace the values of meta filters by `<redacted>`.
+    pub redact_meta: bool,
+}
+
+impl SlowQueryLog {
+    /// Emits a warning with the decoded query parameters if `elapsed` exceeds the threshold.
+    /// Returns whether a warning was emitted.
+    pub fn check(&self, query_string: &str, elapsed: Duration) -> bool {
+        if elapsed <= self.threshold {
+            return false;
+        }
+        tracing::warn!(
+            duration_ms = elapsed.as_millis() as u64,
+            threshold_ms = self.threshold.as_millis() as u64,
+            query = %self.describe(query_string),
+            "Slow record query"
+        );
+        true
+    }
+
+    fn describe(&self, query_string: &str) -> String {
+        query_string
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let key = decode(key);
+                if self.redact_meta && key.starts_with("meta[") {
+                    format!("{key}=<redacted>")
+                } else {
+                    format!("{key}={}", decode(value))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+fn decode(s: &str) -> String {
+    urlencoding::decode(s)
+        .map(|decoded| decoded.into_owned())
+        .unwrap_or_else(|_| s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::get_subscriber;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::filter::LevelFilter;
+
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CapturedLogs {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn check_with_captured_logs(
+        log: &SlowQueryLog,
+        query_string: &str,
+        elapsed: Duration,
+    ) -> (bool, String) {
+        let logs = CapturedLogs::default();
+        let sink = logs.clone();
+        let subscriber = get_subscriber("test".into(), LevelFilter::WARN, move || sink.clone());
+        let emitted =
+            tracing::subscriber::with_default(subscriber, || log.check(query_string, elapsed));
+        (emitted, logs.contents())
+    }
+
+    const QUERY: &str =
+        "start_time%5Bgte%5D=2022-10-01T00%3A00%3A00%2B00%3A00&meta%5Buser_id%5D%5Bc%5D=secret_user";
+
+    #[test]
+    fn slow_query_is_logged_with_parameters() {
+        let log = SlowQueryLog {
+            threshold: Duration::from_millis(100),
+            redact_meta: false,
+        };
+
+        let (emitted, logs) = check_with_captured_logs(&log, QUERY, Duration::from_secs(3));
+
+        assert!(emitted);
+        assert!(logs.contains("Slow record query"));
+        assert!(logs.contains("\"duration_ms\":3000"));
+        assert!(logs.contains("start_time[gte]=2022-10-01T00:00:00+00:00"));
+        assert!(logs.contains("meta[user_id][c]=secret_user"));
+    }
+
+    #[test]
+    fn meta_values_can_be_redacted() {
+        let log = SlowQueryLog {
+            threshold: Duration::from_millis(100),
+            redact_meta: true,
+        };
+
+        let (emitted, logs) = check_with_captured_logs(&log, QUERY, Duration::from_secs(3));
+
+        assert!(emitted);
+        assert!(logs.contains("meta[user_id][c]=<redacted>"));
+        assert!(!logs.contains("secret_user"));
+    }
+
+    #[test]
+    fn fast_query_is_not_logged() {
+        let log = SlowQueryLog {
+            threshold: Duration::from_millis(100),
+            redact_meta: false,
+        };
+
+        let (emitted, logs) = check_with_captured_logs(&log, QUERY, Duration::from_millis(5));
+
+        assert!(!emitted);
+        assert!(!logs.contains("Slow record query"));
+    }
+}
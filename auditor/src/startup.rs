@@ -5,36 +5,197 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use crate::configuration::TLSParams;
+use crate::configuration::{DatabaseSettings, Settings, TLSParams};
 use crate::metrics::{DatabaseMetricsWatcher, PrometheusExporterBuilder, PrometheusExporterConfig};
-use crate::routes::{add, bulk_add, health_check, query_one_record, query_records, update};
+use crate::middleware::{
+    limit_request_rate, record_request_latency, reject_writes_in_maintenance, report_server_load,
+    require_admin_token, require_bearer_token, store_client_certificate, BearerAuth, RateLimiter,
+};
+use crate::publisher::RecordPublisher;
+use crate::routes::{
+    add, aggregate_records, analyze, bulk_add, component_names, consumer_ack, consumer_next,
+    count_records, delete, get_maintenance, health_check, health_status, latest_records,
+    meta_values, patch, query_one_record, query_records, records_by_ids, set_maintenance, update,
+    ConsumerHorizon, MaintenanceMode, MaxRecordAge, RecordPatchEnabled, RecordQueryFlights,
+    RuntimeTolerance, SoftDelete, StreamLimit, TlsEnabled,
+};
+use crate::slow_query_log::SlowQueryLog;
 use actix_web::dev::Server;
+use actix_web::middleware::{from_fn, Compress};
 use actix_web::{web, App, HttpServer};
 use actix_web_opentelemetry::{PrometheusMetricsHandler, RequestMetrics};
 use opentelemetry::global;
-use sqlx::PgPool;
+use sqlx::migrate::Migrate;
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use std::collections::HashSet;
 use std::net::TcpListener;
 use tracing_actix_web::TracingLogger;
 
+// Key of the advisory lock which is held while the database is created and migrated.
+const MIGRATION_LOCK_KEY: i64 = 0x0041_5544_4954_4f52;
+
+/// Creates the database if it does not exist yet and applies all pending migrations.
+///
+/// The database is only changed while holding an advisory lock, such that multiple instances can
+/// be started at the same time. Returns the versions of the applied migrations.
+pub async fn migrate_database(settings: &DatabaseSettings) -> Result<Vec<i64>, anyhow::Error> {
+    let mut connection = PgConnection::connect_with(&settings.without_db()).await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut connection)
+        .await?;
+
+    let applied = create_and_migrate_database(settings, &mut connection).await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut connection)
+        .await?;
+    connection.close().await?;
+
+    applied
+}
+
+async fn create_and_migrate_database(
+    settings: &DatabaseSettings,
+    connection: &mut PgConnection,
+) -> Result<Vec<i64>, anyhow::Error> {
+    let exists: bool =
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_database WHERE datname = $1)")
+            .bind(&settings.database_name)
+            .fetch_one(&mut *connection)
+            .await?;
+    if !exists {
+        tracing::info!("Creating database {}", settings.database_name);
+        connection
+            .execute(
+                format!(
+                    r#"CREATE DATABASE "{}";"#,
+                    settings.database_name.replace('"', r#""""#)
+                )
+                .as_str(),
+            )
+            .await?;
+    }
+
+    let mut connection = PgConnection::connect_with(&settings.with_db()).await?;
+    connection.ensure_migrations_table().await?;
+    let already_applied: HashSet<i64> = connection
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect();
+
+    let migrator = sqlx::migrate!("../migrations");
+    migrator.run(&mut connection).await?;
+    connection.close().await?;
+
+    let applied = migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .filter(|migration| !already_applied.contains(&migration.version))
+        .map(|migration| {
+            tracing::info!(
+                "Applied migration {} ({})",
+                migration.version,
+                migration.description
+            );
+            migration.version
+        })
+        .collect();
+
+    Ok(applied)
+}
+
+/// Behaviour of the server, which is shared with the request handlers and middlewares.
+pub struct ServerOptions {
+    pub slow_query_log: Option<SlowQueryLog>,
+    pub stream_limit: StreamLimit,
+    pub bearer_auth: BearerAuth,
+    pub record_patch: RecordPatchEnabled,
+    pub soft_delete: SoftDelete,
+    pub runtime_tolerance: RuntimeTolerance,
+    pub max_record_age: MaxRecordAge,
+    pub consumer_horizon: ConsumerHorizon,
+    pub rate_limiter: Option<RateLimiter>,
+    pub publisher: Option<RecordPublisher>,
+}
+
+impl ServerOptions {
+    /// Takes the options from the configuration. Inserted records are not published, see
+    /// [`ServerOptions::with_publisher`].
+    pub fn from_settings(settings: &Settings) -> Self {
+        ServerOptions {
+            slow_query_log: settings.application.slow_query_log(),
+            stream_limit: settings.application.stream_limit(),
+            bearer_auth: settings.auth.bearer_auth(),
+            record_patch: settings.application.record_patch(),
+            soft_delete: settings.application.soft_delete(),
+            runtime_tolerance: settings.application.runtime_tolerance(),
+            max_record_age: settings.application.max_record_age(),
+            consumer_horizon: settings.application.consumer_horizon(),
+            rate_limiter: settings.application.rate_limiter(),
+            publisher: None,
+        }
+    }
+
+    /// Publishes inserted records with `publisher`.
+    pub fn with_publisher(mut self, publisher: Option<RecordPublisher>) -> Self {
+        self.publisher = publisher;
+        self
+    }
+}
+
 /// Configures and starts the HttpServer
 pub fn run(
     listener: TcpListener,
     db_pool: PgPool,
     db_watcher: DatabaseMetricsWatcher,
     tls_params: Option<TLSParams>,
+    options: ServerOptions,
 ) -> Result<Server, anyhow::Error> {
     let request_metrics: PrometheusExporterConfig = PrometheusExporterBuilder::new()
         .with_database_watcher(db_watcher)
         .build()?;
     global::set_meter_provider(request_metrics.provider);
+    let request_latency = web::Data::new(request_metrics.request_latency);
 
     let db_pool = web::Data::new(db_pool);
+    let record_query_flights = web::Data::new(RecordQueryFlights::new());
+    let slow_query_log = web::Data::new(options.slow_query_log);
+    let stream_limit = web::Data::new(options.stream_limit);
+    let maintenance_mode = web::Data::new(MaintenanceMode::default());
+    let bearer_auth = web::Data::new(options.bearer_auth);
+    let record_patch = web::Data::new(options.record_patch);
+    let soft_delete = web::Data::new(options.soft_delete);
+    let runtime_tolerance = web::Data::new(options.runtime_tolerance);
+    let max_record_age = web::Data::new(options.max_record_age);
+    let consumer_horizon = web::Data::new(options.consumer_horizon);
+    let rate_limiter = web::Data::new(options.rate_limiter);
+    let publisher = web::Data::new(options.publisher);
+    let tls_enabled = web::Data::new(TlsEnabled(
+        tls_params.as_ref().is_some_and(|params| params.use_tls),
+    ));
 
     let app_config = move || {
         App::new()
+            // Rejects writes in maintenance mode. Registered first, so that these requests are
+            // still logged and counted by the middlewares below.
+            .wrap(from_fn(reject_writes_in_maintenance))
+            // Rejects requests of clients exceeding the rate limit, if enabled. Registered before
+            // the bearer token check, such that it only counts authenticated requests.
+            .wrap(from_fn(limit_request_rate))
+            // Rejects requests without a valid bearer token before they reach the maintenance
+            // mode check, if bearer token authentication is enabled.
+            .wrap(from_fn(require_bearer_token))
+            // Reports the load of the server, such that clients can slow down their ingest.
+            .wrap(from_fn(report_server_load))
             // Logging middleware
             .wrap(TracingLogger::default())
             .wrap(RequestMetrics::default())
+            // Records the latency per route, including the time spent in the middlewares above.
+            .wrap(from_fn(record_request_latency))
             .route(
                 "/metrics",
                 web::get().to(PrometheusMetricsHandler::new(
@@ -43,22 +204,62 @@ pub fn run(
             )
             // Routes
             .route("/health_check", web::get().to(health_check))
+            .route("/health_check/status", web::get().to(health_status))
+            .route("/maintenance", web::get().to(get_maintenance))
             .service(
                 web::resource("/record")
                     .route(web::post().to(add))
                     .route(web::put().to(update)),
             )
-            .route("/record/{record_id}", web::get().to(query_one_record))
+            .service(
+                web::resource("/record/{record_id}")
+                    .route(web::get().to(query_one_record))
+                    .route(web::patch().to(patch))
+                    .route(web::delete().to(delete)),
+            )
             // DB connection pool
             .service(
                 web::resource("/records")
+                    // Compresses responses if requested by the client (`Accept-Encoding`)
+                    .wrap(Compress::default())
                     .route(web::post().to(bulk_add))
                     .route(web::get().to(query_records)),
             )
+            .route("/records/by-ids", web::post().to(records_by_ids))
+            .route("/records/count", web::get().to(count_records))
+            .route("/records/components", web::get().to(component_names))
+            .route("/records/aggregate", web::get().to(aggregate_records))
+            .route("/records/latest", web::get().to(latest_records))
+            .route("/records/meta/{key}/values", web::get().to(meta_values))
+            .route("/consumers/{name}/next", web::get().to(consumer_next))
+            .route("/consumers/{name}/ack", web::post().to(consumer_ack))
+            // Admin endpoints, which require one of the admin tokens
+            .service(
+                web::scope("/admin")
+                    .wrap(from_fn(require_admin_token))
+                    .route("/maintenance", web::put().to(set_maintenance))
+                    .route("/maintenance/analyze", web::post().to(analyze)),
+            )
             .app_data(db_pool.clone())
+            .app_data(request_latency.clone())
+            .app_data(record_query_flights.clone())
+            .app_data(slow_query_log.clone())
+            .app_data(stream_limit.clone())
+            .app_data(maintenance_mode.clone())
+            .app_data(tls_enabled.clone())
+            .app_data(bearer_auth.clone())
+            .app_data(record_patch.clone())
+            .app_data(soft_delete.clone())
+            .app_data(runtime_tolerance.clone())
+            .app_data(max_record_age.clone())
+            .app_data(consumer_horizon.clone())
+            .app_data(rate_limiter.clone())
+            .app_data(publisher.clone())
     };
 
-    let server = HttpServer::new(app_config).listen(listener)?;
+    let server = HttpServer::new(app_config)
+        .on_connect(store_client_certificate)
+        .listen(listener)?;
 
     match tls_params {
         Some(params) if params.use_tls => {
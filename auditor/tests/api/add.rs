@@ -1,5 +1,6 @@
-use crate::helpers::spawn_app;
-use auditor::domain::{RecordDatabase, RecordTest};
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER, MAX_NAME_LENGTH};
+use auditor::domain::{RecordDatabase, RecordTest, RecordValidationError};
 use fake::{Fake, Faker};
 
 #[tokio::test]
@@ -22,7 +23,9 @@ async fn add_returns_a_200_for_valid_json_data() {
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
            WHERE record_id = $1
             "#,
@@ -72,6 +75,25 @@ async fn add_returns_a_400_for_invalid_json_data() {
     }
 }
 
+#[tokio::test]
+async fn add_rejects_names_longer_than_the_maximum_length() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let mut longest: RecordTest = Faker.fake();
+    longest.record_id = Some("a".repeat(MAX_NAME_LENGTH));
+    let mut too_long: RecordTest = Faker.fake();
+    too_long.record_id = Some("b".repeat(MAX_NAME_LENGTH + 1));
+
+    // Act
+    let longest_response = app.add_record(&longest).await;
+    let too_long_response = app.add_record(&too_long).await;
+
+    // Assert
+    assert_eq!(200, longest_response.status().as_u16());
+    assert_eq!(400, too_long_response.status().as_u16());
+}
+
 #[tokio::test]
 async fn add_returns_a_400_when_data_is_missing() {
     // Arrange
@@ -148,7 +170,9 @@ async fn bulk_insert_records() {
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
            WHERE record_id = $1
             "#,
@@ -197,6 +221,38 @@ async fn bulk_insert_returns_a_400_for_invalid_json_data() {
     }
 }
 
+#[tokio::test]
+async fn bulk_insert_reports_all_invalid_records() {
+    let app = spawn_app().await;
+
+    let mut records: Vec<RecordTest> = (0..5).map(|_| Faker.fake()).collect();
+    records[1].record_id = Some("test/test".to_string());
+    records[4].record_id = Some("test<test".to_string());
+    let mut records = serde_json::to_value(&records).unwrap();
+    records[2].as_object_mut().unwrap().remove("start_time");
+
+    let response = app.bulk_insert(&records).await;
+
+    assert_eq!(400, response.status().as_u16());
+
+    let errors: Vec<RecordValidationError> = response.json().await.unwrap();
+
+    assert_eq!(
+        errors.iter().map(|e| e.index).collect::<Vec<_>>(),
+        vec![1, 2, 4]
+    );
+    assert!(errors[0].reason.contains("test/test"));
+    assert!(errors[1].reason.contains("start_time"));
+    assert!(errors[2].reason.contains("test<test"));
+
+    let saved: Vec<_> = sqlx::query!(r#"SELECT record_id FROM auditor_accounting"#,)
+        .fetch_all(&app.db_pool)
+        .await
+        .expect("Failed to fetch data");
+
+    assert_eq!(saved.len(), 0);
+}
+
 #[tokio::test]
 async fn bulk_insert_returns_a_400_when_data_is_missing() {
     let app = spawn_app().await;
@@ -264,3 +320,150 @@ async fn bulk_insert_returns_a_500_for_duplicate_records() {
     let response = app.bulk_insert(&records).await;
     assert_eq!(500, response.status().as_u16());
 }
+
+async fn stored_runtime(app: &TestApp, record_id: &str) -> Option<i64> {
+    sqlx::query_scalar("SELECT runtime FROM auditor_accounting WHERE record_id = $1")
+        .bind(record_id)
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch data")
+}
+
+#[tokio::test]
+async fn stored_runtime_is_computed_from_timestamps() {
+    let app = spawn_app().await;
+
+    // Submitted runtimes within the tolerance are accepted, but not stored
+    let record = |record_id: &str, stop_time: Option<&str>, runtime: i64| {
+        serde_json::json!({
+            "record_id": record_id,
+            "meta": null,
+            "components": [],
+            "start_time": "2023-11-07T10:00:00Z",
+            "stop_time": stop_time,
+            "runtime": runtime,
+        })
+    };
+
+    let response = app
+        .add_record(&record("single", Some("2023-11-07T11:00:00Z"), 3601))
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    let response = app
+        .bulk_insert(&vec![
+            record("bulk", Some("2023-11-07T10:30:00Z"), 1799),
+            record("bulk-open", None, 42),
+        ])
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    assert_eq!(Some(3600), stored_runtime(&app, "single").await);
+    assert_eq!(Some(1800), stored_runtime(&app, "bulk").await);
+    // Records without stop time have no runtime yet
+    assert_eq!(None, stored_runtime(&app, "bulk-open").await);
+}
+
+fn record_with_runtime(record_id: &str, runtime: i64) -> serde_json::Value {
+    serde_json::json!({
+        "record_id": record_id,
+        "meta": null,
+        "components": [],
+        "start_time": "2023-11-07T10:00:00Z",
+        "stop_time": "2023-11-07T11:00:00Z",
+        "runtime": runtime,
+    })
+}
+
+#[tokio::test]
+async fn add_returns_a_422_for_mismatching_runtime() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.add_record(&record_with_runtime("r1", 42)).await;
+
+    // Assert
+    assert_eq!(422, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::ValidationFailed.as_str()
+    );
+    assert!(response.text().await.unwrap().contains("3600"));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM auditor_accounting")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch data");
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn runtime_tolerance_is_configurable() {
+    // Arrange
+    let app = spawn_app_with(|settings| settings.application.runtime_tolerance_secs = 60).await;
+
+    // Act
+    let within = app.add_record(&record_with_runtime("r1", 3660)).await;
+    let outside = app.add_record(&record_with_runtime("r2", 3661)).await;
+
+    // Assert
+    assert_eq!(200, within.status().as_u16());
+    assert_eq!(422, outside.status().as_u16());
+}
+
+#[tokio::test]
+async fn bulk_insert_returns_a_422_for_mismatching_runtime() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app
+        .bulk_insert(&vec![
+            record_with_runtime("r1", 3600),
+            record_with_runtime("r2", 42),
+        ])
+        .await;
+
+    // Assert
+    assert_eq!(422, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::ValidationFailed.as_str()
+    );
+    let errors: Vec<RecordValidationError> = response.json().await.unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].index, 1);
+    assert!(errors[0].reason.contains("3600"));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM auditor_accounting")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch data");
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn bulk_insert_with_other_invalid_records_returns_a_400() {
+    // Arrange
+    let app = spawn_app().await;
+    let mut invalid = record_with_runtime("r3", 3600);
+    invalid["record_id"] = serde_json::json!("r/3");
+
+    // Act
+    let response = app
+        .bulk_insert(&vec![record_with_runtime("r2", 42), invalid])
+        .await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::InvalidRecords.as_str()
+    );
+    let errors: Vec<RecordValidationError> = response.json().await.unwrap();
+    assert_eq!(
+        errors.iter().map(|e| e.index).collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+}
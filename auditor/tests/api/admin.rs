@@ -0,0 +1,142 @@
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use auditor::routes::AnalyzeResponse;
+use fake::{Fake, Faker};
+use secrecy::Secret;
+
+async fn spawn_app_with_admin_token() -> TestApp {
+    spawn_app_with(|settings| {
+        settings.auth.bearer_tokens = vec![Secret::new("token".to_string())];
+        settings.auth.admin_tokens = vec![Secret::new("admin".to_string())];
+    })
+    .await
+}
+
+async fn analyze(app: &TestApp, token: Option<&str>, reindex: bool) -> reqwest::Response {
+    let request = reqwest::Client::new().post(format!(
+        "{}/admin/maintenance/analyze?reindex={reindex}",
+        &app.address
+    ));
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+    request.send().await.expect("Failed to execute request.")
+}
+
+// Number of rows of the accounting table as estimated by the last `ANALYZE`
+async fn estimated_rows(app: &TestApp) -> f32 {
+    sqlx::query_scalar("SELECT reltuples FROM pg_class WHERE relname = 'auditor_accounting'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+}
+
+// The files of the indexes, which change when the indexes are rebuilt
+async fn index_files(app: &TestApp) -> Vec<i64> {
+    sqlx::query_scalar(
+        r#"
+        SELECT c.relfilenode::bigint
+        FROM pg_index i JOIN pg_class c ON c.oid = i.indexrelid
+        WHERE i.indrelid = 'auditor_accounting'::regclass
+        ORDER BY c.relname
+        "#,
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+async fn analyze_refreshes_table_statistics() {
+    // Arrange
+    let app = spawn_app_with_admin_token().await;
+    let records: Vec<RecordTest> = (0..3).map(|_| Faker.fake()).collect();
+    for record in records.iter() {
+        let response = reqwest::Client::new()
+            .post(format!("{}/record", &app.address))
+            .bearer_auth("token")
+            .json(record)
+            .send()
+            .await
+            .expect("Failed to execute request.");
+        assert_eq!(200, response.status().as_u16());
+    }
+    let indexes = index_files(&app).await;
+
+    // Act
+    let response = analyze(&app, Some("admin"), false).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let response: AnalyzeResponse = response.json().await.unwrap();
+    assert!(!response.reindexed);
+    assert_eq!(estimated_rows(&app).await, 3.0);
+    assert_eq!(index_files(&app).await, indexes);
+}
+
+#[tokio::test]
+async fn analyze_with_reindex_rebuilds_indexes() {
+    // Arrange
+    let app = spawn_app_with_admin_token().await;
+    let indexes = index_files(&app).await;
+    assert!(!indexes.is_empty());
+
+    // Act
+    let response = analyze(&app, Some("admin"), true).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let response: AnalyzeResponse = response.json().await.unwrap();
+    assert!(response.reindexed);
+    let rebuilt = index_files(&app).await;
+    assert_eq!(rebuilt.len(), indexes.len());
+    assert!(rebuilt.iter().all(|file| !indexes.contains(file)));
+}
+
+#[tokio::test]
+async fn analyze_requires_admin_token() {
+    // Arrange
+    let app = spawn_app_with_admin_token().await;
+
+    for token in [None, Some("token"), Some("other")] {
+        // Act
+        let response = analyze(&app, token, false).await;
+
+        // Assert
+        assert_eq!(401, response.status().as_u16(), "Token: {token:?}");
+        assert_eq!(
+            response.headers()[ERROR_CODE_HEADER],
+            ErrorCode::Unauthorized.as_str()
+        );
+    }
+}
+
+#[tokio::test]
+async fn analyze_is_disabled_without_admin_tokens() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = analyze(&app, None, false).await;
+
+    // Assert
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn analyze_is_allowed_in_maintenance_mode() {
+    // Arrange
+    let app = spawn_app_with(|settings| {
+        settings.auth.admin_tokens = vec![Secret::new("admin".to_string())];
+    })
+    .await;
+    assert_eq!(200, app.set_maintenance(true).await.status().as_u16());
+
+    // Act
+    let response = analyze(&app, Some("admin"), false).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+}
@@ -1,7 +1,8 @@
-use crate::helpers::spawn_app;
-use auditor::domain::{Record, RecordTest};
+use crate::helpers::{spawn_app, TestApp};
+use auditor::domain::{Record, RecordTest, ScoreTest};
 use chrono::{TimeZone, Utc};
 use fake::{Fake, Faker};
+use futures_util::future::join_all;
 use std::collections::HashMap;
 use urlencoding::encode;
 
@@ -102,7 +103,7 @@ async fn get_started_since_returns_a_list_of_sorted_records() {
             .skip(usize::try_from(i).unwrap() - 1)
             .cloned()
             .collect::<Vec<_>>();
-        tmp_test_cases.sort_by(|a, b| a.stop_time.cmp(&b.stop_time));
+        tmp_test_cases.sort_by_key(|a| a.stop_time);
 
         for (j, (record, received)) in tmp_test_cases
             .iter()
@@ -237,7 +238,7 @@ async fn get_stopped_since_returns_a_list_of_sorted_records() {
             .skip(usize::try_from(i).unwrap() - 1)
             .cloned()
             .collect::<Vec<_>>();
-        tmp_test_cases.sort_by(|a, b| a.stop_time.cmp(&b.stop_time));
+        tmp_test_cases.sort_by_key(|a| a.stop_time);
 
         for (j, (record, received)) in tmp_test_cases
             .iter()
@@ -532,3 +533,509 @@ async fn exact_record_id_returns_a_200_and_list_of_records() {
 
     assert_eq!(received_record.record_id, "r3".to_string());
 }
+
+#[tokio::test]
+async fn record_id_prefix_returns_matching_records() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let record_ids = ["site1-1", "site1-2", "site10-1", "site2-1", "xsite1-1"];
+    for record_id in record_ids {
+        let response = app
+            .add_record(&Faker.fake::<RecordTest>().with_record_id(record_id))
+            .await;
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let response = app.advanced_queries("record_id[prefix]=site1-").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let mut received_ids = response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect::<Vec<_>>();
+    received_ids.sort();
+
+    assert_eq!(received_ids, vec!["site1-1", "site1-2"]);
+}
+
+#[tokio::test]
+async fn record_id_prefix_matches_wildcards_literally() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let record_ids = ["site_1%a", "siteX1%b", "site_1xc", "site_1%"];
+    for record_id in record_ids {
+        let response = app
+            .add_record(&Faker.fake::<RecordTest>().with_record_id(record_id))
+            .await;
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let query = format!("record_id[prefix]={}", encode("site_1%"));
+    let response = app.advanced_queries(query).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let mut received_ids = response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect::<Vec<_>>();
+    received_ids.sort();
+
+    assert_eq!(received_ids, vec!["site_1%", "site_1%a"]);
+}
+
+#[tokio::test]
+async fn record_id_prefix_returns_a_400_for_unknown_operator() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.advanced_queries("record_id[suffix]=site1").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+// Adds records with the given GPU amount, records without an amount have no GPU component
+async fn add_gpu_fixture(app: &TestApp) {
+    for (i, gpus) in [Some(2), None, Some(8), Some(1), None]
+        .into_iter()
+        .enumerate()
+    {
+        let mut record = Faker.fake::<RecordTest>().with_record_id(format!("r{i}"));
+        record.components = None;
+        let mut record = record.with_component("CPU", 4, vec![]);
+        if let Some(gpus) = gpus {
+            record = record.with_component("GPU", gpus, vec![]);
+        }
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+async fn sorted_record_ids(app: &TestApp, query: &str) -> Vec<String> {
+    let response = app.advanced_queries(query).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect()
+}
+
+#[tokio::test]
+async fn sort_by_component_descending_sorts_missing_components_last() {
+    // Arrange
+    let app = spawn_app().await;
+    add_gpu_fixture(&app).await;
+
+    // Act
+    let received_ids = sorted_record_ids(&app, "sort_by[desc][component]=GPU").await;
+
+    // Assert
+    assert_eq!(received_ids, vec!["r2", "r0", "r3", "r1", "r4"]);
+}
+
+#[tokio::test]
+async fn sort_by_component_ascending_sorts_missing_components_last() {
+    // Arrange
+    let app = spawn_app().await;
+    add_gpu_fixture(&app).await;
+
+    // Act
+    let received_ids = sorted_record_ids(&app, "sort_by[asc][component]=GPU").await;
+
+    // Assert
+    assert_eq!(received_ids, vec!["r3", "r0", "r2", "r1", "r4"]);
+}
+
+#[tokio::test]
+async fn sort_by_component_can_be_combined_with_filters() {
+    // Arrange
+    let app = spawn_app().await;
+    add_gpu_fixture(&app).await;
+
+    // Act
+    let received_ids = sorted_record_ids(
+        &app,
+        "record_id[prefix]=r&sort_by[desc][component]=GPU&limit=2",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(received_ids, vec!["r2", "r0"]);
+}
+
+#[tokio::test]
+async fn updated_since_returns_added_and_updated_records() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let records = (0..3)
+        .map(|i| {
+            Faker
+                .fake::<RecordTest>()
+                .with_record_id(format!("r{i}"))
+                .with_start_time("2022-10-01T12:00:00-00:00")
+                .with_stop_time("2022-10-01T13:00:00-00:00")
+        })
+        .collect::<Vec<_>>();
+
+    for record in &records[..2] {
+        let response = app.add_record(record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let since = Utc::now();
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    // Update r0 and add r2 after `since`
+    let response = client
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(
+            &records[0]
+                .clone()
+                .with_stop_time("2022-10-01T14:00:00-00:00"),
+        )
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let response = app.add_record(&records[2]).await;
+
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let query = format!("updated_since={}", encode(&since.to_rfc3339()));
+    let response = app.advanced_queries(query).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let mut received_ids = response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect::<Vec<_>>();
+    received_ids.sort();
+
+    assert_eq!(received_ids, vec!["r0", "r2"]);
+}
+
+#[tokio::test]
+async fn stopped_returns_finished_or_unfinished_records() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for i in 0..5 {
+        let mut record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(HashMap::from([("site_id", vec!["site1"])]))
+            .with_start_time("2022-10-01T12:00:00-00:00")
+            .with_stop_time("2022-10-01T13:00:00-00:00");
+        // r1 and r3 are still running
+        if i % 2 == 1 {
+            record.stop_time = None;
+        }
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act + Assert
+    for (query, expected) in [
+        ("stopped=true", vec!["r0", "r2", "r4"]),
+        ("stopped=false", vec!["r1", "r3"]),
+        ("stopped=false&meta[site_id][c]=site1", vec!["r1", "r3"]),
+        ("stopped=false&meta[site_id][c]=site2", vec![]),
+    ] {
+        let response = app.advanced_queries(query).await;
+
+        assert_eq!(200, response.status().as_u16());
+
+        let mut received_ids = response
+            .json::<Vec<Record>>()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.record_id)
+            .collect::<Vec<_>>();
+        received_ids.sort();
+
+        assert_eq!(
+            received_ids, expected,
+            "Unexpected records for query {query}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn stopped_returns_a_400_for_invalid_value() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.advanced_queries("stopped=maybe").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn updated_since_returns_a_400_for_invalid_timestamp() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.advanced_queries("updated_since=yesterday").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+// Adds records with the given component and HEPSPEC06 score, records without a value have no score
+async fn add_score_fixture(app: &TestApp) {
+    for (i, (component, hepspec)) in [
+        ("CPU", Some(5.0)),
+        ("CPU", Some(10.0)),
+        ("CPU", Some(15.5)),
+        ("CPU", None),
+        ("GPU", Some(20.0)),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let scores = hepspec
+            .map(|value| {
+                vec![ScoreTest::new()
+                    .with_name("HEPSPEC06".to_string())
+                    .with_value(value)]
+            })
+            .unwrap_or_default();
+        let mut record = Faker.fake::<RecordTest>().with_record_id(format!("r{i}"));
+        record.components = None;
+        let record = record.with_component(component, 4, scores);
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+async fn matching_record_ids(app: &TestApp, query: &str) -> Vec<String> {
+    let mut received_ids = sorted_record_ids(app, query).await;
+    received_ids.sort();
+    received_ids
+}
+
+#[tokio::test]
+async fn component_score_query_returns_matching_records() {
+    // Arrange
+    let app = spawn_app().await;
+    add_score_fixture(&app).await;
+
+    // Act & Assert
+    for (query, expected) in [
+        ("component[CPU][score][HEPSPEC06][gt]=10", vec!["r2"]),
+        ("component[CPU][score][HEPSPEC06][gte]=10", vec!["r1", "r2"]),
+        ("component[CPU][score][HEPSPEC06][lt]=10", vec!["r0"]),
+        ("component[CPU][score][HEPSPEC06][equals]=15.5", vec!["r2"]),
+        (
+            "component[CPU][score][HEPSPEC06][gt]=5&component[CPU][score][HEPSPEC06][lt]=15",
+            vec!["r1"],
+        ),
+        ("component[GPU][score][HEPSPEC06][gt]=10", vec!["r4"]),
+        ("component[CPU][score][other][gt]=0", vec![]),
+    ] {
+        assert_eq!(
+            matching_record_ids(&app, query).await,
+            expected,
+            "Query {query} returned unexpected records"
+        );
+    }
+}
+
+#[tokio::test]
+async fn component_score_query_can_be_combined_with_amount() {
+    // Arrange
+    let app = spawn_app().await;
+    add_score_fixture(&app).await;
+
+    // Act
+    let received_ids = matching_record_ids(
+        &app,
+        "component[CPU][equals]=4&component[CPU][score][HEPSPEC06][lte]=10",
+    )
+    .await;
+
+    // Assert
+    assert_eq!(received_ids, vec!["r0", "r1"]);
+}
+
+#[tokio::test]
+async fn component_score_query_returns_a_400_for_invalid_value() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app
+        .advanced_queries("component[CPU][score][HEPSPEC06][gt]=high")
+        .await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+async fn add_sorted_records(app: &TestApp, count: u32) {
+    for i in 1..=count {
+        let record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_start_time(format!("2022-10-0{i}T12:00:00-00:00"));
+        assert_eq!(200, app.add_record(&record).await.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn envelope_echoes_query_and_counts_records() {
+    // Arrange
+    let app = spawn_app().await;
+    add_sorted_records(&app, 3).await;
+
+    // Act
+    let response = app
+        .advanced_queries("sort_by[asc]=start_time&envelope=true")
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let envelope: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(envelope["count"], 3);
+    assert_eq!(envelope["truncated"], false);
+    assert_eq!(envelope["query"]["sort_by"]["asc"], "start_time");
+    assert_eq!(envelope["query"]["envelope"], true);
+    let record_ids: Vec<_> = envelope["records"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|record| record["record_id"].as_str().unwrap())
+        .collect();
+    assert_eq!(record_ids, vec!["r1", "r2", "r3"]);
+}
+
+#[tokio::test]
+async fn envelope_is_truncated_if_limit_is_hit() {
+    // Arrange
+    let app = spawn_app().await;
+    add_sorted_records(&app, 3).await;
+
+    for (limit, expected_ids, truncated) in [
+        (2, vec!["r1", "r2"], true),
+        (3, vec!["r1", "r2", "r3"], false),
+        (5, vec!["r1", "r2", "r3"], false),
+    ] {
+        // Act
+        let response = app
+            .advanced_queries(format!(
+                "sort_by[asc]=start_time&limit={limit}&envelope=true"
+            ))
+            .await;
+
+        // Assert
+        assert_eq!(200, response.status().as_u16());
+        let envelope: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(envelope["query"]["limit"], limit);
+        assert_eq!(envelope["count"], expected_ids.len());
+        assert_eq!(envelope["truncated"], truncated, "limit {limit}");
+        let record_ids: Vec<_> = envelope["records"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|record| record["record_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(record_ids, expected_ids);
+    }
+}
+
+#[tokio::test]
+async fn records_are_a_bare_list_without_envelope() {
+    // Arrange
+    let app = spawn_app().await;
+    add_sorted_records(&app, 2).await;
+
+    // Act
+    let response = app.advanced_queries("limit=1").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let records = response.json::<Vec<Record>>().await.unwrap();
+    assert_eq!(records.len(), 1);
+}
+
+#[tokio::test]
+async fn identical_concurrent_queries_share_one_database_query() {
+    // Arrange
+    let app = spawn_app().await;
+    add_sorted_records(&app, 2).await;
+
+    // Queries wait for this lock until the transaction is finished
+    let mut lock = app.db_pool.begin().await.unwrap();
+    sqlx::query("LOCK TABLE auditor_accounting IN ACCESS EXCLUSIVE MODE")
+        .execute(&mut *lock)
+        .await
+        .unwrap();
+
+    // Act
+    let requests = (0..5).map(|_| app.advanced_queries("limit=2"));
+    let (responses, waiting_queries) = tokio::join!(join_all(requests), async {
+        // Give all requests time to arrive while the first one waits for the lock
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        let waiting_queries: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM pg_stat_activity
+            WHERE datname = current_database() AND wait_event_type = 'Lock'
+            "#,
+        )
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+        lock.commit().await.unwrap();
+        waiting_queries
+    });
+
+    // Assert
+    assert_eq!(waiting_queries, 1);
+    for response in responses {
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(response.json::<Vec<Record>>().await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::helpers::{spawn_app, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+use serde_json::json;
+use std::collections::HashMap;
+
+// Adds records with the given groups, runtime in seconds and CPU amount
+async fn add_fixture(app: &TestApp) {
+    for (i, (groups, runtime, cpus)) in [
+        (vec!["atlas"], 100, 4),
+        (vec!["atlas"], 200, 8),
+        (vec!["cms"], 50, 1),
+        (vec!["atlas", "cms"], 10, 2),
+        (vec![], 1000, 16),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut meta: HashMap<&str, Vec<&str>> = HashMap::new();
+        if !groups.is_empty() {
+            meta.insert("group_id", groups);
+        }
+        let mut record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(meta)
+            .with_start_time("2022-10-01T12:00:00-00:00");
+        record.stop_time = Some(record.start_time.unwrap() + chrono::Duration::seconds(runtime));
+        record.components = None;
+        let record =
+            record
+                .with_component("CPU", cpus, vec![])
+                .with_component("Memory", 1024, vec![]);
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn aggregate_sums_runtime_per_group() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.aggregate_records("group_by=group_id&sum=runtime").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        json!([
+            { "key": "atlas", "sum_runtime": 310, "count": 3 },
+            { "key": "cms", "sum_runtime": 60, "count": 2 },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn aggregate_sums_component_amount_per_group() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app
+        .aggregate_records("group_by=group_id&sum=component_amount&component=CPU")
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        json!([
+            { "key": "atlas", "sum_amount": 14, "count": 3 },
+            { "key": "cms", "sum_amount": 3, "count": 2 },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn aggregate_returns_empty_list_for_unknown_meta_key() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.aggregate_records("group_by=site_id&sum=runtime").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        json!([])
+    );
+}
+
+#[tokio::test]
+async fn aggregate_returns_a_400_for_invalid_query() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for query in [
+        "sum=runtime",
+        "group_by=group_id",
+        "group_by=group_id&sum=memory",
+        "group_by=group_id&sum=component_amount",
+        "group_by=group_id&sum=runtime&component=CPU",
+    ] {
+        // Act
+        let response = app.aggregate_records(query).await;
+
+        // Assert
+        assert_eq!(400, response.status().as_u16(), "Query: {query}");
+        assert_eq!(
+            Some(ErrorCode::InvalidQuery),
+            ErrorCode::from_code(response.headers()[ERROR_CODE_HEADER].to_str().unwrap()),
+            "Query: {query}"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+use secrecy::Secret;
+
+async fn spawn_app_with_tokens() -> TestApp {
+    spawn_app_with(|settings| {
+        settings.auth.bearer_tokens = vec![
+            Secret::new("token1".to_string()),
+            Secret::new("token2".to_string()),
+        ];
+    })
+    .await
+}
+
+async fn add_record(app: &TestApp, token: Option<&str>) -> reqwest::Response {
+    let record: RecordTest = Faker.fake();
+    let request = reqwest::Client::new()
+        .post(format!("{}/record", &app.address))
+        .json(&record);
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+    request.send().await.expect("Failed to execute request.")
+}
+
+#[tokio::test]
+async fn requests_with_valid_bearer_token_are_accepted() {
+    // Arrange
+    let app = spawn_app_with_tokens().await;
+
+    for token in ["token1", "token2"] {
+        // Act
+        let response = add_record(&app, Some(token)).await;
+
+        // Assert
+        assert_eq!(200, response.status().as_u16(), "Token: {token}");
+    }
+}
+
+#[tokio::test]
+async fn requests_without_valid_bearer_token_are_rejected() {
+    // Arrange
+    let app = spawn_app_with_tokens().await;
+
+    for token in [None, Some("token3"), Some("token"), Some("")] {
+        // Act
+        let response = add_record(&app, token).await;
+
+        // Assert
+        assert_eq!(401, response.status().as_u16(), "Token: {token:?}");
+        assert_eq!(
+            Some(ErrorCode::Unauthorized),
+            ErrorCode::from_code(response.headers()[ERROR_CODE_HEADER].to_str().unwrap()),
+            "Token: {token:?}"
+        );
+    }
+
+    let response = reqwest::Client::new()
+        .get(format!("{}/records", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(401, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn health_check_does_not_require_bearer_token() {
+    // Arrange
+    let app = spawn_app_with_tokens().await;
+
+    // Act
+    let response = reqwest::Client::new()
+        .get(format!("{}/health_check", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn bearer_token_is_not_required_without_configured_tokens() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = add_record(&app, None).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+}
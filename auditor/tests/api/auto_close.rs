@@ -0,0 +1,179 @@
+use crate::helpers::{spawn_app, TestApp};
+use auditor::auto_close::{close_orphaned_records, AUTO_CLOSED_META_KEY};
+use auditor::configuration::AutoCloseStopTime;
+use auditor::domain::{Record, RecordTest};
+use chrono::{DateTime, Duration, Utc};
+use fake::{Fake, Faker};
+
+async fn add_record(app: &TestApp, record_id: &str, start_time: &str, stop_time: Option<&str>) {
+    let mut record = Faker
+        .fake::<RecordTest>()
+        .with_record_id(record_id)
+        .with_start_time(start_time);
+    record.stop_time = stop_time.map(|t| t.parse().unwrap());
+    let response = app.add_record(&record).await;
+    assert_eq!(200, response.status().as_u16());
+}
+
+async fn get_record(app: &TestApp, record_id: &str) -> Record {
+    app.get_single_record(record_id)
+        .await
+        .json::<Record>()
+        .await
+        .unwrap()
+}
+
+fn is_auto_closed(record: &Record) -> bool {
+    record
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get(AUTO_CLOSED_META_KEY))
+        .is_some_and(|values| values == &vec!["true".to_string()])
+}
+
+#[tokio::test]
+async fn only_old_open_records_are_auto_closed() {
+    // Arrange
+    let app = spawn_app().await;
+    let now: DateTime<Utc> = "2024-01-10T12:00:00Z".parse().unwrap();
+    let max_runtime = Duration::days(2);
+
+    // Started before the maximum runtime and never finished
+    add_record(&app, "orphaned", "2024-01-01T12:00:00Z", None).await;
+    // Started within the maximum runtime
+    add_record(&app, "running", "2024-01-09T12:00:00Z", None).await;
+    // Finished records are never touched
+    add_record(
+        &app,
+        "finished",
+        "2024-01-01T12:00:00Z",
+        Some("2024-01-02T12:00:00Z"),
+    )
+    .await;
+    let finished = get_record(&app, "finished").await;
+
+    // Act
+    let closed = close_orphaned_records(
+        &app.db_pool,
+        now,
+        max_runtime,
+        AutoCloseStopTime::MaxRuntime,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(closed, 1);
+
+    let orphaned = get_record(&app, "orphaned").await;
+    assert_eq!(
+        orphaned.stop_time,
+        Some("2024-01-03T12:00:00Z".parse().unwrap())
+    );
+    assert_eq!(orphaned.runtime, Some(max_runtime.num_seconds()));
+    assert!(is_auto_closed(&orphaned));
+
+    let running = get_record(&app, "running").await;
+    assert_eq!(running.stop_time, None);
+    assert!(!is_auto_closed(&running));
+
+    assert_eq!(get_record(&app, "finished").await, finished);
+
+    // Running it again closes nothing
+    let closed = close_orphaned_records(
+        &app.db_pool,
+        now,
+        max_runtime,
+        AutoCloseStopTime::MaxRuntime,
+    )
+    .await
+    .unwrap();
+    assert_eq!(closed, 0);
+}
+
+#[tokio::test]
+async fn records_without_meta_are_auto_closed() {
+    // Arrange
+    let app = spawn_app().await;
+    let mut record = Faker
+        .fake::<RecordTest>()
+        .with_record_id("orphaned")
+        .with_start_time("2024-01-01T12:00:00Z");
+    record.meta = None;
+    record.stop_time = None;
+    assert_eq!(200, app.add_record(&record).await.status().as_u16());
+
+    // Act
+    let closed = close_orphaned_records(
+        &app.db_pool,
+        "2024-01-10T12:00:00Z".parse().unwrap(),
+        Duration::days(2),
+        AutoCloseStopTime::MaxRuntime,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(closed, 1);
+    let orphaned = get_record(&app, "orphaned").await;
+    assert_eq!(
+        orphaned.stop_time,
+        Some("2024-01-03T12:00:00Z".parse().unwrap())
+    );
+    assert!(is_auto_closed(&orphaned));
+}
+
+#[tokio::test]
+async fn auto_closed_records_can_stop_when_last_seen() {
+    // Arrange
+    let app = spawn_app().await;
+    let start_time = Utc::now() - Duration::days(10);
+    add_record(&app, "orphaned", &start_time.to_rfc3339(), None).await;
+    let last_seen = get_record(&app, "orphaned").await.updated_at.unwrap();
+
+    // Act
+    let closed = close_orphaned_records(
+        &app.db_pool,
+        Utc::now(),
+        Duration::days(2),
+        AutoCloseStopTime::LastSeen,
+    )
+    .await
+    .unwrap();
+
+    // Assert
+    assert_eq!(closed, 1);
+    let orphaned = get_record(&app, "orphaned").await;
+    assert_eq!(orphaned.stop_time, Some(last_seen));
+    assert!(is_auto_closed(&orphaned));
+
+    // Auto-closed records can be found by their meta key
+    let found = app
+        .advanced_queries(format!("meta[{AUTO_CLOSED_META_KEY}][c]=true"))
+        .await
+        .json::<Vec<Record>>()
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].record_id, "orphaned");
+}
+
+#[tokio::test]
+async fn non_positive_max_runtime_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    add_record(&app, "orphaned", "2024-01-01T12:00:00Z", None).await;
+
+    // Act
+    let result = close_orphaned_records(
+        &app.db_pool,
+        Utc::now(),
+        Duration::zero(),
+        AutoCloseStopTime::MaxRuntime,
+    )
+    .await;
+
+    // Assert
+    assert!(result.is_err());
+    assert_eq!(get_record(&app, "orphaned").await.stop_time, None);
+}
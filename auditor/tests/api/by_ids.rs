@@ -0,0 +1,120 @@
+use crate::helpers::{spawn_app, spawn_app_with, with_admin_token, TestApp};
+use auditor::domain::{Record, RecordTest};
+use fake::{Fake, Faker};
+
+async fn add_fixture(app: &TestApp) -> Vec<RecordTest> {
+    let mut records = vec![];
+    for i in 0..3 {
+        let record = Faker.fake::<RecordTest>().with_record_id(format!("r{i}"));
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+        records.push(record);
+    }
+    records
+}
+
+async fn record_ids(response: reqwest::Response) -> Vec<String> {
+    response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect()
+}
+
+#[tokio::test]
+async fn by_ids_returns_records_in_request_order() {
+    // Arrange
+    let app = spawn_app().await;
+    let records = add_fixture(&app).await;
+
+    // Act
+    let response = app.records_by_ids(&["r2", "r0", "r1"]).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let received = response.json::<Vec<Record>>().await.unwrap();
+    assert_eq!(3, received.len());
+    assert_eq!(records[2], received[0]);
+    assert_eq!(records[0], received[1]);
+    assert_eq!(records[1], received[2]);
+}
+
+#[tokio::test]
+async fn by_ids_skips_unknown_and_deleted_records() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+    assert_eq!(200, app.delete_record("r1").await.status().as_u16());
+
+    // Act
+    let response = app.records_by_ids(&["r3", "r1", "r0"]).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(record_ids(response).await, vec!["r0"]);
+}
+
+#[tokio::test]
+async fn by_ids_returns_duplicates_once() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.records_by_ids(&["r1", "r0", "r1"]).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(record_ids(response).await, vec!["r1", "r0"]);
+}
+
+#[tokio::test]
+async fn by_ids_returns_empty_list_for_empty_request() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.records_by_ids(&[]).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert!(record_ids(response).await.is_empty());
+}
+
+#[tokio::test]
+async fn by_ids_returns_a_400_for_invalid_body() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = reqwest::Client::new()
+        .post(format!("{}/records/by-ids", &app.address))
+        .header("Content-Type", "application/json")
+        .body(r#"{"record_ids": ["r0"]}"#)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn by_ids_is_allowed_in_maintenance_mode() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    add_fixture(&app).await;
+    assert_eq!(200, app.set_maintenance(true).await.status().as_u16());
+
+    // Act
+    let response = app.records_by_ids(&["r0"]).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(record_ids(response).await, vec!["r0"]);
+}
@@ -0,0 +1,87 @@
+use crate::helpers::{spawn_app, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+use std::collections::HashMap;
+
+// Adds records of two groups with different components
+async fn add_fixture(app: &TestApp) {
+    let fixture = [
+        ("group_1", vec!["CPU", "Memory"]),
+        ("group_1", vec!["CPU"]),
+        ("group_2", vec!["GPU", "CPU"]),
+        ("group_2", vec![]),
+    ];
+    for (i, (group, components)) in fixture.into_iter().enumerate() {
+        let mut record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(HashMap::from([("group_id", vec![group])]))
+            .with_start_time("2022-10-01T12:00:00-00:00")
+            .with_stop_time("2022-10-02T12:00:00-00:00");
+        record.components = Some(vec![]);
+        for name in components {
+            record = record.with_component(name, 1, vec![]);
+        }
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn component_names_returns_distinct_sorted_names_of_all_records() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.component_names("").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<Vec<String>>().await.unwrap(),
+        vec!["CPU", "GPU", "Memory"]
+    );
+}
+
+#[tokio::test]
+async fn component_names_only_takes_matching_records_into_account() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act + Assert
+    for (query, expected) in [
+        ("meta[group_id][c]=group_1", vec!["CPU", "Memory"]),
+        ("meta[group_id][c]=group_2", vec!["CPU", "GPU"]),
+        ("meta[group_id][c]=group_3", vec![]),
+    ] {
+        let response = app.component_names(query).await;
+
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!(
+            response.json::<Vec<String>>().await.unwrap(),
+            expected,
+            "Unexpected component names for query {query}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn component_names_returns_a_400_for_invalid_query() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.component_names("unknown=1").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::InvalidQuery.as_str()
+    );
+}
@@ -0,0 +1,198 @@
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::domain::RecordTest;
+use auditor::routes::{ConsumerBatch, ConsumerCursor};
+use fake::{Fake, Faker};
+
+async fn add_records(app: &TestApp, n: usize) {
+    for i in 0..n {
+        let record = Faker.fake::<RecordTest>().with_record_id(format!("r{i}"));
+        let response = app.add_record(&record).await;
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+fn record_ids(batch: &ConsumerBatch) -> Vec<&str> {
+    batch.records.iter().map(|r| r.record_id.as_str()).collect()
+}
+
+#[tokio::test]
+async fn next_returns_all_records_for_new_consumer() {
+    // Arrange
+    let app = spawn_app().await;
+    add_records(&app, 3).await;
+
+    // Act
+    let response = app.consumer_next("apel", None).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let batch = response.json::<ConsumerBatch>().await.unwrap();
+    assert_eq!(vec!["r0", "r1", "r2"], record_ids(&batch));
+}
+
+#[tokio::test]
+async fn ack_advances_cursor_to_next_batch() {
+    // Arrange
+    let app = spawn_app().await;
+    add_records(&app, 5).await;
+
+    let batch = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert_eq!(vec!["r0", "r1"], record_ids(&batch));
+
+    // Without acknowledgement the same records are returned again
+    let again = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert_eq!(record_ids(&batch), record_ids(&again));
+    assert_eq!(batch.cursor, again.cursor);
+
+    // Act
+    let response = app.consumer_ack("apel", batch.cursor).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        batch.cursor,
+        response.json::<ConsumerCursor>().await.unwrap()
+    );
+
+    let batch = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert_eq!(vec!["r2", "r3"], record_ids(&batch));
+
+    app.consumer_ack("apel", batch.cursor).await;
+    let batch = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert_eq!(vec!["r4"], record_ids(&batch));
+
+    // Everything has been consumed, the cursor stays where it is
+    app.consumer_ack("apel", batch.cursor).await;
+    let empty = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert!(empty.records.is_empty());
+    assert_eq!(batch.cursor, empty.cursor);
+}
+
+#[tokio::test]
+async fn ack_does_not_move_cursor_backwards() {
+    // Arrange
+    let app = spawn_app().await;
+    add_records(&app, 4).await;
+
+    let first = app
+        .consumer_next("apel", Some(1))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    let all = app
+        .consumer_next("apel", None)
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    app.consumer_ack("apel", all.cursor).await;
+
+    // Act
+    let response = app.consumer_ack("apel", first.cursor).await;
+
+    // Assert
+    assert_eq!(all.cursor, response.json::<ConsumerCursor>().await.unwrap());
+    let batch = app
+        .consumer_next("apel", None)
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert!(batch.records.is_empty());
+}
+
+#[tokio::test]
+async fn consumers_have_independent_cursors() {
+    // Arrange
+    let app = spawn_app().await;
+    add_records(&app, 3).await;
+
+    let batch = app
+        .consumer_next("apel", Some(2))
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+
+    // Act
+    app.consumer_ack("apel", batch.cursor).await;
+
+    // Assert
+    let apel = app
+        .consumer_next("apel", None)
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    let billing = app
+        .consumer_next("billing", None)
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+    assert_eq!(vec!["r2"], record_ids(&apel));
+    assert_eq!(vec!["r0", "r1", "r2"], record_ids(&billing));
+}
+
+#[tokio::test]
+async fn next_returns_a_400_for_invalid_limit() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.consumer_next("apel", Some(0)).await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn next_holds_back_recently_ingested_records() {
+    // Arrange
+    let app = spawn_app_with(|settings| settings.application.consumer_horizon_secs = 3600).await;
+    add_records(&app, 2).await;
+    sqlx::query(
+        "UPDATE auditor_accounting SET ingested_at = ingested_at - INTERVAL '2 hours' \
+         WHERE record_id = 'r1'",
+    )
+    .execute(&app.db_pool)
+    .await
+    .expect("Failed to update ingested_at");
+
+    // Act
+    let batch = app
+        .consumer_next("apel", None)
+        .await
+        .json::<ConsumerBatch>()
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(vec!["r1"], record_ids(&batch));
+}
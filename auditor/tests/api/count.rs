@@ -0,0 +1,84 @@
+use crate::helpers::spawn_app;
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+use std::collections::HashMap;
+
+#[derive(serde::Deserialize)]
+struct Count {
+    count: u64,
+}
+
+#[tokio::test]
+async fn count_returns_a_200_and_number_of_all_records() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let test_cases = (0..7)
+        .map(|i| Faker.fake::<RecordTest>().with_record_id(format!("r{i}")))
+        .collect::<Vec<_>>();
+
+    for case in test_cases.iter() {
+        let response = app.add_record(&case).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let response = app.count_records("").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(7, response.json::<Count>().await.unwrap().count);
+}
+
+#[tokio::test]
+async fn count_with_meta_filter_returns_number_of_matching_records() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for i in 0..9 {
+        let group = if i < 5 { "group_1" } else { "group_2" };
+        let mut meta: HashMap<String, Vec<String>> = HashMap::new();
+        meta.insert("group_id".to_string(), vec![group.to_string()]);
+
+        let record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(meta)
+            .with_start_time("2022-10-01T12:00:00-00:00")
+            .with_stop_time("2022-10-02T12:00:00-00:00");
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act + Assert
+    for (query, expected) in [
+        ("meta[group_id][c]=group_1", 5),
+        ("meta[group_id][c]=group_2", 4),
+        ("meta[group_id][dnc]=group_1", 4),
+        ("meta[group_id][c]=group_3", 0),
+    ] {
+        let response = app.count_records(query).await;
+
+        assert_eq!(200, response.status().as_u16(), "Query: {query}");
+        assert_eq!(
+            expected,
+            response.json::<Count>().await.unwrap().count,
+            "Query: {query}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn count_returns_a_400_for_invalid_query() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.count_records("invalid_filter=1").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
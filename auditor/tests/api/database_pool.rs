@@ -0,0 +1,25 @@
+use crate::helpers::spawn_app_with;
+
+#[tokio::test]
+async fn database_pool_uses_configured_size() {
+    let app = spawn_app_with(|settings| {
+        settings.database.max_connections = 3;
+        settings.database.min_connections = 2;
+    })
+    .await;
+
+    let options = app.db_pool.options();
+    assert_eq!(options.get_max_connections(), 3);
+    assert_eq!(options.get_min_connections(), 2);
+
+    // All configured connections can be acquired at the same time
+    let mut connections = Vec::new();
+    for _ in 0..3 {
+        connections.push(app.db_pool.acquire().await.unwrap());
+    }
+    assert_eq!(app.db_pool.size(), 3);
+    drop(connections);
+
+    let response = app.health_check().await;
+    assert!(response.status().is_success());
+}
@@ -0,0 +1,186 @@
+use crate::helpers::{spawn_app, spawn_app_with, with_admin_token, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::{Record, RecordTest};
+use fake::{Fake, Faker};
+
+// Adds the records `r1` and `r2`
+async fn add_fixtures(app: &TestApp) {
+    for record_id in ["r1", "r2"] {
+        let record = Faker.fake::<RecordTest>().with_record_id(record_id);
+        let response = app.add_record(&record).await;
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+async fn record_ids(response: reqwest::Response) -> Vec<String> {
+    assert_eq!(200, response.status().as_u16());
+    let mut record_ids: Vec<String> = response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| record.record_id)
+        .collect();
+    record_ids.sort();
+    record_ids
+}
+
+#[tokio::test]
+async fn soft_deleted_records_are_hidden_from_queries() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixtures(&app).await;
+
+    // Act
+    let response = app.delete_record("r1").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    assert_eq!(record_ids(app.get_records().await).await, vec!["r2"]);
+    assert!(record_ids(app.advanced_queries("record_id=r1").await)
+        .await
+        .is_empty());
+
+    let response = app.count_records("record_id[prefix]=r").await;
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap()["count"],
+        1
+    );
+}
+
+#[tokio::test]
+async fn soft_deleted_records_are_returned_with_include_deleted() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    add_fixtures(&app).await;
+
+    // Act
+    let response = app.delete_record("r1").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    assert_eq!(
+        record_ids(app.advanced_queries_as_admin("include_deleted=true").await).await,
+        vec!["r1", "r2"]
+    );
+    assert_eq!(
+        record_ids(
+            app.advanced_queries_as_admin("record_id=r1&include_deleted=true")
+                .await
+        )
+        .await,
+        vec!["r1"]
+    );
+}
+
+#[tokio::test]
+async fn include_deleted_requires_admin_token() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    add_fixtures(&app).await;
+
+    // Act
+    let response = app.advanced_queries("include_deleted=true").await;
+
+    // Assert
+    assert_eq!(401, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::Unauthorized.as_str()
+    );
+    assert_eq!(
+        401,
+        app.count_records("include_deleted=true")
+            .await
+            .status()
+            .as_u16()
+    );
+}
+
+#[tokio::test]
+async fn deleting_a_soft_deleted_record_again_returns_a_404() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixtures(&app).await;
+    let response = app.delete_record("r1").await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let response = app.delete_record("r1").await;
+
+    // Assert
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::UnknownRecord.as_str()
+    );
+}
+
+#[tokio::test]
+async fn delete_returns_a_404_for_non_existing_record() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.delete_record("r1").await;
+
+    // Assert
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::UnknownRecord.as_str()
+    );
+}
+
+#[tokio::test]
+async fn delete_removes_records_if_soft_delete_is_disabled() {
+    // Arrange
+    let app = spawn_app_with(|settings| {
+        settings.application.soft_delete = false;
+        with_admin_token(settings);
+    })
+    .await;
+    add_fixtures(&app).await;
+
+    // Act
+    let response = app.delete_record("r1").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    assert_eq!(
+        record_ids(app.advanced_queries_as_admin("include_deleted=true").await).await,
+        vec!["r2"]
+    );
+}
+
+#[tokio::test]
+async fn update_returns_a_404_for_soft_deleted_record() {
+    // Arrange
+    let app = spawn_app().await;
+    let mut record = Faker
+        .fake::<RecordTest>()
+        .with_record_id("r1")
+        .with_start_time("2022-03-01T12:00:00-00:00");
+    record.stop_time = None;
+    assert_eq!(200, app.add_record(&record).await.status().as_u16());
+    assert_eq!(200, app.delete_record("r1").await.status().as_u16());
+
+    // Act
+    let response = reqwest::Client::new()
+        .put(format!("{}/record", &app.address))
+        .json(&record.with_stop_time("2022-03-01T13:00:00-00:00"))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::UnknownRecord.as_str()
+    );
+}
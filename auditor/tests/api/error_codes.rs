@@ -0,0 +1,92 @@
+use crate::helpers::{spawn_app, spawn_app_with, with_admin_token};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+
+fn error_code(response: &reqwest::Response) -> Option<ErrorCode> {
+    ErrorCode::from_code(response.headers().get(ERROR_CODE_HEADER)?.to_str().ok()?)
+}
+
+#[tokio::test]
+async fn add_existing_record_returns_record_exists_code() {
+    // Arrange
+    let app = spawn_app().await;
+    let record: RecordTest = Faker.fake();
+    let response = app.add_record(&record).await;
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(None, error_code(&response));
+
+    // Act
+    let response = app.add_record(&record).await;
+
+    // Assert
+    assert_eq!(500, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::RecordExists), error_code(&response));
+
+    let response = app.bulk_insert(&vec![record]).await;
+    assert_eq!(500, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::RecordExists), error_code(&response));
+}
+
+#[tokio::test]
+async fn bulk_insert_of_invalid_records_returns_invalid_records_code() {
+    // Arrange
+    let app = spawn_app().await;
+    let mut records: Vec<RecordTest> = (0..2).map(|_| Faker.fake()).collect();
+    records[1].record_id = Some("test/test".to_string());
+
+    // Act
+    let response = app.bulk_insert(&records).await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::InvalidRecords), error_code(&response));
+}
+
+#[tokio::test]
+async fn update_of_unknown_record_returns_unknown_record_code() {
+    // Arrange
+    let app = spawn_app().await;
+    let record: RecordTest = Faker.fake();
+
+    // Act
+    let response = reqwest::Client::new()
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(&record)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::UnknownRecord), error_code(&response));
+}
+
+#[tokio::test]
+async fn invalid_query_returns_invalid_query_code() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.count_records("invalid_filter=1").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::InvalidQuery), error_code(&response));
+}
+
+#[tokio::test]
+async fn write_in_maintenance_mode_returns_maintenance_mode_code() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    let response = app.set_maintenance(true).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+
+    // Assert
+    assert_eq!(503, response.status().as_u16());
+    assert_eq!(Some(ErrorCode::MaintenanceMode), error_code(&response));
+}
@@ -1,6 +1,9 @@
-use crate::helpers::spawn_app;
+use crate::helpers::{spawn_app, spawn_app_with};
 use auditor::domain::{Record, RecordTest};
 use fake::{Fake, Faker};
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
 
 #[tokio::test]
 async fn get_returns_a_200_and_list_of_records() {
@@ -95,3 +98,227 @@ async fn get_returns_a_200_and_no_records() {
 
     assert!(received_records.is_empty());
 }
+
+#[tokio::test]
+async fn get_returns_ndjson_if_requested() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let mut test_cases: Vec<RecordTest> = (0..20).map(|_| Faker.fake::<RecordTest>()).collect();
+
+    for case in test_cases.iter() {
+        let response = app.add_record(&case).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let response = app.get_records_ndjson("").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        "application/x-ndjson",
+        response.headers()["Content-Type"].to_str().unwrap()
+    );
+
+    let body = response.text().await.unwrap();
+    assert!(body.ends_with('\n'));
+    let mut received_records = body
+        .lines()
+        .map(|line| serde_json::from_str::<Record>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(test_cases.len(), received_records.len());
+
+    test_cases.sort_by(|a, b| {
+        a.record_id
+            .as_ref()
+            .unwrap()
+            .cmp(b.record_id.as_ref().unwrap())
+    });
+    received_records.sort_by(|a, b| a.record_id.cmp(&b.record_id));
+
+    for (record, received) in test_cases.iter().zip(received_records.iter()) {
+        assert_eq!(record, received);
+    }
+}
+
+#[tokio::test]
+async fn get_ndjson_applies_filters() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let test_cases: Vec<RecordTest> = (0..10).map(|_| Faker.fake::<RecordTest>()).collect();
+
+    for case in test_cases.iter() {
+        let response = app.add_record(&case).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let response = app
+        .get_records_ndjson("sort_by[desc]=start_time&limit=3")
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let received_records = response
+        .text()
+        .await
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str::<Record>(line).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(3, received_records.len());
+    assert!(received_records
+        .windows(2)
+        .all(|w| w[0].start_time >= w[1].start_time));
+}
+
+#[tokio::test]
+async fn get_ndjson_returns_a_400_for_invalid_query() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app.get_records_ndjson("invalid_filter=1").await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn get_ndjson_rejects_streams_beyond_the_limit() {
+    // Arrange
+    let app = spawn_app_with(|configuration| {
+        configuration.application.max_streaming_responses = Some(1);
+    })
+    .await;
+
+    // The response must not fit into the socket buffers, such that the first stream stays open
+    // as long as it is not read.
+    let values = (0..500)
+        .map(|i| format!("value_{i:05}"))
+        .collect::<Vec<_>>();
+    for i in 0..20 {
+        let records: Vec<RecordTest> = (0..100)
+            .map(|j| {
+                Faker
+                    .fake::<RecordTest>()
+                    .with_record_id(format!("r{i}-{j}"))
+                    .with_meta(HashMap::from([("key".to_string(), values.clone())]))
+            })
+            .collect();
+        let response = app.bulk_insert(&records).await;
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let first = app.get_records_ndjson("").await;
+    let second = app.get_records_ndjson("").await;
+
+    // Assert
+    assert_eq!(200, first.status().as_u16());
+    assert_eq!(503, second.status().as_u16());
+    assert_eq!(
+        "TOO_MANY_STREAMS",
+        second.headers()["x-auditor-error-code"].to_str().unwrap()
+    );
+
+    // Responses which are not streamed are not limited
+    let response = app.advanced_queries("limit=1").await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Closing the first stream makes room for another one
+    drop(first);
+    let mut status = 0;
+    for _ in 0..50 {
+        status = app.get_records_ndjson("limit=1").await.status().as_u16();
+        if status == 200 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(200, status);
+}
+
+#[tokio::test]
+async fn get_returns_gzip_compressed_records_if_requested() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let mut test_cases: Vec<RecordTest> = (0..20).map(|_| Faker.fake::<RecordTest>()).collect();
+
+    for case in test_cases.iter() {
+        let response = app.add_record(&case).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let response = reqwest::Client::builder()
+        .no_gzip()
+        .build()
+        .unwrap()
+        .get(format!("{}/records", &app.address))
+        .header("Accept-Encoding", "gzip")
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        "gzip",
+        response.headers()["Content-Encoding"].to_str().unwrap()
+    );
+
+    let compressed = response.bytes().await.unwrap();
+    let mut decompressed = String::new();
+    flate2::read::GzDecoder::new(&compressed[..])
+        .read_to_string(&mut decompressed)
+        .unwrap();
+    let mut received_records = serde_json::from_str::<Vec<Record>>(&decompressed).unwrap();
+
+    assert_eq!(test_cases.len(), received_records.len());
+
+    test_cases.sort_by(|a, b| {
+        a.record_id
+            .as_ref()
+            .unwrap()
+            .cmp(b.record_id.as_ref().unwrap())
+    });
+    received_records.sort_by(|a, b| a.record_id.cmp(&b.record_id));
+
+    for (record, received) in test_cases.iter().zip(received_records.iter()) {
+        assert_eq!(record, received);
+    }
+}
+
+#[tokio::test]
+async fn get_returns_uncompressed_records_by_default() {
+    // Arrange
+    let app = spawn_app().await;
+
+    let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let response = reqwest::Client::builder()
+        .no_gzip()
+        .build()
+        .unwrap()
+        .get(format!("{}/records", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.headers().get("Content-Encoding").is_none());
+    assert_eq!(1, response.json::<Vec<Record>>().await.unwrap().len());
+}
@@ -1,4 +1,5 @@
-use crate::helpers::spawn_app;
+use crate::helpers::{spawn_app, spawn_app_with, with_admin_token};
+use auditor::domain::{ComponentStatus, ServerStatus};
 
 #[tokio::test]
 async fn health_check_works() {
@@ -9,3 +10,37 @@ async fn health_check_works() {
     assert!(response.status().is_success());
     assert_eq!(Some(0), response.content_length());
 }
+
+#[tokio::test]
+async fn health_status_reports_components() {
+    let app = spawn_app().await;
+
+    let response = app.health_status().await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<ServerStatus>().await.unwrap(),
+        ServerStatus {
+            database: ComponentStatus::Ok,
+            tls: ComponentStatus::Disabled,
+            maintenance_mode: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn health_status_reports_maintenance_mode() {
+    let app = spawn_app_with(with_admin_token).await;
+    app.set_maintenance(true).await;
+
+    let response = app.health_status().await;
+
+    assert_eq!(200, response.status().as_u16());
+    assert!(
+        response
+            .json::<ServerStatus>()
+            .await
+            .unwrap()
+            .maintenance_mode
+    );
+}
@@ -1,7 +1,11 @@
-use auditor::configuration::{get_configuration, DatabaseSettings};
+use auditor::configuration::{get_configuration, DatabaseSettings, Settings};
 use auditor::metrics::DatabaseMetricsWatcher;
+use auditor::publisher::RecordPublisher;
+use auditor::routes::ConsumerCursor;
+use auditor::startup::ServerOptions;
 use auditor::telemetry::{get_subscriber, init_subscriber};
 use once_cell::sync::Lazy;
+use secrecy::Secret;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 use std::net::TcpListener;
 use tracing_subscriber::filter::LevelFilter;
@@ -34,6 +38,14 @@ impl TestApp {
             .expect("Failed to execute request.")
     }
 
+    pub async fn health_status(&self) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/health_check/status", self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn add_record<T: serde::Serialize>(&self, record: &T) -> reqwest::Response {
         reqwest::Client::new()
             .post(format!("{}/record", &self.address))
@@ -62,6 +74,36 @@ impl TestApp {
             .expect("Failed to execute request.")
     }
 
+    pub async fn get_records_ndjson(&self, query_string: &str) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/records?{}", &self.address, query_string))
+            .header("Accept", "application/x-ndjson")
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn consumer_next(&self, name: &str, limit: Option<u32>) -> reqwest::Response {
+        let query = limit.map(|l| format!("?limit={l}")).unwrap_or_default();
+        reqwest::Client::new()
+            .get(format!(
+                "{}/consumers/{}/next{}",
+                &self.address, name, query
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn consumer_ack(&self, name: &str, cursor: ConsumerCursor) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/consumers/{}/ack", &self.address, name))
+            .json(&cursor)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_started_since_records<T: AsRef<str>>(
         &self,
         timestamp: T,
@@ -105,19 +147,169 @@ impl TestApp {
             .expect("Failed to execute queries.")
     }
 
+    /// Like [`TestApp::advanced_queries`], but authenticated with [`ADMIN_TOKEN`], e.g. to
+    /// include deleted records.
+    pub async fn advanced_queries_as_admin<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        query_string: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/records?{}", &self.address, query_string))
+            .bearer_auth(ADMIN_TOKEN)
+            .send()
+            .await
+            .expect("Failed to execute queries.")
+    }
+
+    pub async fn count_records<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        query_string: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/records/count?{}", &self.address, query_string))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn component_names<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        query_string: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!(
+                "{}/records/components?{}",
+                &self.address, query_string
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn aggregate_records<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        query_string: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!(
+                "{}/records/aggregate?{}",
+                &self.address, query_string
+            ))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn latest_records<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        query_string: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/records/latest?{}", &self.address, query_string))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn records_by_ids(&self, record_ids: &[&str]) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{}/records/by-ids", &self.address))
+            .json(record_ids)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn meta_values<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        key: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .get(format!("{}/records/meta/{}/values", &self.address, key))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    /// Requires the app to be spawned with [`with_admin_token`].
+    pub async fn set_maintenance(&self, enabled: bool) -> reqwest::Response {
+        reqwest::Client::new()
+            .put(format!("{}/admin/maintenance", &self.address))
+            .bearer_auth(ADMIN_TOKEN)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "enabled": enabled }))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
     pub async fn get_single_record<T: AsRef<str> + std::fmt::Display>(
         &self,
         record_id: T,
     ) -> reqwest::Response {
         reqwest::Client::new()
-            .get(format!("{}/record/{}", &self.address, record_id))
+            .get(format!(
+                "{}/record/{}",
+                &self.address,
+                encode(record_id.as_ref())
+            ))
             .send()
             .await
             .expect("Failed to execute queries.")
     }
+
+    pub async fn patch_record<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        record_id: T,
+        patch: &serde_json::Value,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .patch(format!("{}/record/{}", &self.address, record_id))
+            .header("Content-Type", "application/merge-patch+json")
+            .body(patch.to_string())
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn delete_record<T: AsRef<str> + std::fmt::Display>(
+        &self,
+        record_id: T,
+    ) -> reqwest::Response {
+        reqwest::Client::new()
+            .delete(format!("{}/record/{}", &self.address, record_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+}
+
+/// Admin token configured by [`with_admin_token`].
+pub const ADMIN_TOKEN: &str = "admin";
+
+/// Configures [`ADMIN_TOKEN`] as admin token, e.g. to toggle the maintenance mode.
+pub fn with_admin_token(settings: &mut Settings) {
+    settings.auth.admin_tokens = vec![Secret::new(ADMIN_TOKEN.to_string())];
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+/// Spawns the app after adjusting the configuration with `configure`.
+pub async fn spawn_app_with(configure: impl FnOnce(&mut Settings)) -> TestApp {
+    spawn(configure, None).await
+}
+
+/// Spawns the app which publishes inserted records with `publisher`.
+pub async fn spawn_app_with_publisher(publisher: RecordPublisher) -> TestApp {
+    spawn(|_| {}, Some(publisher)).await
+}
+
+async fn spawn(
+    configure: impl FnOnce(&mut Settings),
+    publisher: Option<RecordPublisher>,
+) -> TestApp {
     Lazy::force(&TRACING);
 
     let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
@@ -126,10 +318,19 @@ pub async fn spawn_app() -> TestApp {
 
     let mut configuration = get_configuration().expect("Failed to read configuration.");
     configuration.database.database_name = Uuid::new_v4().to_string();
+    // Records are handed out to consumers right away, as the tests insert records one by one
+    configuration.application.consumer_horizon_secs = 0;
+    configure(&mut configuration);
     let connection_pool = configure_database(&configuration.database).await;
     let db_watcher = DatabaseMetricsWatcher::new(connection_pool.clone(), &configuration).unwrap();
-    let server = auditor::startup::run(listener, connection_pool.clone(), db_watcher, None)
-        .expect("Failed to bind address");
+    let server = auditor::startup::run(
+        listener,
+        connection_pool.clone(),
+        db_watcher,
+        None,
+        ServerOptions::from_settings(&configuration).with_publisher(publisher),
+    )
+    .expect("Failed to bind address");
     tokio::spawn(server);
     TestApp {
         address,
@@ -147,7 +348,9 @@ async fn configure_database(config: &DatabaseSettings) -> PgPool {
         .expect("Failed to create database.");
 
     // Migrate database
-    let connection_pool = PgPool::connect_with(config.with_db())
+    let connection_pool = config
+        .pool_options()
+        .connect_with(config.with_db())
         .await
         .expect("Failed to connect to Postgres.");
     sqlx::migrate!("./../migrations")
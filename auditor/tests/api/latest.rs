@@ -0,0 +1,90 @@
+use crate::helpers::{spawn_app, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::{Record, RecordTest};
+use fake::{Fake, Faker};
+use std::collections::HashMap;
+
+// Adds records with the given groups and stop time offset in seconds
+async fn add_fixture(app: &TestApp) {
+    for (i, (groups, stop_offset)) in [
+        (vec!["atlas"], 100),
+        (vec!["atlas"], 300),
+        (vec!["atlas"], 200),
+        (vec!["cms"], 50),
+        (vec!["cms"], 10),
+        (vec![], 1000),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let mut meta: HashMap<&str, Vec<&str>> = HashMap::new();
+        if !groups.is_empty() {
+            meta.insert("group_id", groups);
+        }
+        let mut record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(meta)
+            .with_start_time("2022-10-01T12:00:00-00:00");
+        record.stop_time =
+            Some(record.start_time.unwrap() + chrono::Duration::seconds(stop_offset));
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn latest_returns_newest_record_per_group() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.latest_records("key=group_id").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let record_ids = response
+        .json::<Vec<Record>>()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.record_id)
+        .collect::<Vec<_>>();
+    assert_eq!(record_ids, vec!["r1", "r3"]);
+}
+
+#[tokio::test]
+async fn latest_returns_empty_list_for_unknown_meta_key() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.latest_records("key=site_id").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.json::<Vec<Record>>().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn latest_returns_a_400_for_invalid_query() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for query in ["", "group_by=group_id", "key=group/id"] {
+        // Act
+        let response = app.latest_records(query).await;
+
+        // Assert
+        assert_eq!(400, response.status().as_u16(), "Query: {query}");
+        assert_eq!(
+            Some(ErrorCode::InvalidQuery),
+            ErrorCode::from_code(response.headers()[ERROR_CODE_HEADER].to_str().unwrap()),
+            "Query: {query}"
+        );
+    }
+}
@@ -1,8 +1,30 @@
 mod add;
+mod admin;
 mod advanced_queries;
+mod aggregate;
+mod auto_close;
+mod auth;
+mod by_ids;
+mod component_names;
+mod consumers;
+mod count;
+mod database_pool;
+mod delete;
+mod error_codes;
 mod get;
 mod get_one_record;
 mod get_since;
 mod health_check;
 mod helpers;
+mod latest;
+mod maintenance;
+mod max_record_age;
+mod meta_values;
+mod migration;
+mod patch;
+mod publisher;
+mod rate_limit;
+mod request_metrics;
+mod retention;
+mod server_load;
 mod update;
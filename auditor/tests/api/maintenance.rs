@@ -0,0 +1,112 @@
+use crate::helpers::{spawn_app_with, with_admin_token, ADMIN_TOKEN};
+use auditor::constants::ERR_MAINTENANCE_MODE;
+use auditor::domain::{Record, RecordTest};
+use fake::{Fake, Faker};
+
+#[tokio::test]
+async fn maintenance_mode_rejects_writes_and_allows_reads() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+
+    // Fixed record id, as fake ones may contain characters which have to be percent-encoded
+    let record = Faker.fake::<RecordTest>().with_record_id("r1");
+    let response = app.add_record(&record).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let response = app.set_maintenance(true).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Assert
+    let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+    assert_eq!(503, response.status().as_u16());
+    assert_eq!(ERR_MAINTENANCE_MODE, response.text().await.unwrap());
+
+    let response = app
+        .bulk_insert(&(0..3).map(|_| Faker.fake()).collect::<Vec<RecordTest>>())
+        .await;
+    assert_eq!(503, response.status().as_u16());
+
+    let response = reqwest::Client::new()
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(&record)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(503, response.status().as_u16());
+
+    let response = app.get_records().await;
+    assert_eq!(200, response.status().as_u16());
+    let received = response.json::<Vec<Record>>().await.unwrap();
+    assert_eq!(1, received.len());
+    assert_eq!(record, received[0]);
+
+    let response = app
+        .get_single_record(record.record_id.as_ref().unwrap())
+        .await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Writes work again after disabling the maintenance mode
+    let response = app.set_maintenance(false).await;
+    assert_eq!(200, response.status().as_u16());
+
+    let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+    assert_eq!(200, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn maintenance_mode_can_be_queried() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    let client = reqwest::Client::new();
+
+    for enabled in [false, true, false] {
+        app.set_maintenance(enabled).await;
+
+        // Act
+        let response = client
+            .get(format!("{}/maintenance", &app.address))
+            .send()
+            .await
+            .expect("Failed to execute request.");
+
+        // Assert
+        assert_eq!(200, response.status().as_u16());
+        let state = response.json::<serde_json::Value>().await.unwrap();
+        assert_eq!(enabled, state["enabled"].as_bool().unwrap());
+    }
+}
+
+#[tokio::test]
+async fn setting_maintenance_mode_requires_admin_token() {
+    // Arrange
+    let app = spawn_app_with(with_admin_token).await;
+    let client = reqwest::Client::new();
+
+    for token in [None, Some("token"), Some(ADMIN_TOKEN)] {
+        // Act
+        let request = client
+            .put(format!("{}/admin/maintenance", &app.address))
+            .json(&serde_json::json!({ "enabled": true }));
+        let request = match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+        let response = request.send().await.expect("Failed to execute request.");
+
+        // Assert
+        let expected = if token == Some(ADMIN_TOKEN) { 200 } else { 401 };
+        assert_eq!(expected, response.status().as_u16(), "Token: {token:?}");
+    }
+
+    // The maintenance mode cannot be set outside of the admin scope
+    assert_eq!(200, app.set_maintenance(false).await.status().as_u16());
+    let response = client
+        .put(format!("{}/maintenance", &app.address))
+        .json(&serde_json::json!({ "enabled": false }))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert!(response.status().is_client_error());
+}
@@ -0,0 +1,148 @@
+use crate::helpers::{spawn_app_with, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordValidationError;
+use chrono::{Duration, Utc};
+use secrecy::Secret;
+
+async fn spawn_app_with_max_record_age() -> TestApp {
+    spawn_app_with(|settings| {
+        settings.application.max_record_age = Some(Duration::days(30));
+        settings.auth.backfill_tokens = vec![Secret::new("backfill".to_string())];
+    })
+    .await
+}
+
+// Record which stopped `age` ago
+fn record(record_id: &str, age: Duration) -> serde_json::Value {
+    let stop_time = Utc::now() - age;
+    serde_json::json!({
+        "record_id": record_id,
+        "meta": null,
+        "components": [],
+        "start_time": stop_time - Duration::hours(1),
+        "stop_time": stop_time,
+    })
+}
+
+async fn add_record(
+    app: &TestApp,
+    record: &serde_json::Value,
+    token: Option<&str>,
+) -> reqwest::Response {
+    let request = reqwest::Client::new()
+        .post(format!("{}/record", &app.address))
+        .json(record);
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+    request.send().await.expect("Failed to execute request.")
+}
+
+async fn count_records(app: &TestApp) -> i64 {
+    sqlx::query_scalar("SELECT COUNT(*) FROM auditor_accounting")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch data")
+}
+
+#[tokio::test]
+async fn recent_records_are_accepted() {
+    // Arrange
+    let app = spawn_app_with_max_record_age().await;
+    let mut open = record("open", Duration::days(60));
+    open["stop_time"] = serde_json::Value::Null;
+
+    // Act
+    let recent = add_record(&app, &record("recent", Duration::days(29)), None).await;
+    let open = add_record(&app, &open, None).await;
+
+    // Assert
+    assert_eq!(200, recent.status().as_u16());
+    assert_eq!(200, open.status().as_u16());
+    assert_eq!(2, count_records(&app).await);
+}
+
+#[tokio::test]
+async fn old_records_are_rejected() {
+    // Arrange
+    let app = spawn_app_with_max_record_age().await;
+
+    // Act
+    let response = add_record(&app, &record("old", Duration::days(31)), None).await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::ValidationFailed.as_str()
+    );
+    let body = response.text().await.unwrap();
+    assert!(body.contains("2592000s"), "{body}");
+    assert!(body.contains("backfill"), "{body}");
+    assert_eq!(0, count_records(&app).await);
+}
+
+#[tokio::test]
+async fn bulk_insert_reports_old_records() {
+    // Arrange
+    let app = spawn_app_with_max_record_age().await;
+
+    // Act
+    let response = app
+        .bulk_insert(&vec![
+            record("recent", Duration::days(1)),
+            record("old", Duration::days(365)),
+        ])
+        .await;
+
+    // Assert
+    assert_eq!(400, response.status().as_u16());
+    let errors: Vec<RecordValidationError> = response.json().await.unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].index, 1);
+    assert!(errors[0].reason.contains("maximum record age"));
+    assert_eq!(0, count_records(&app).await);
+}
+
+#[tokio::test]
+async fn old_records_are_accepted_with_backfill_token() {
+    // Arrange
+    let app = spawn_app_with_max_record_age().await;
+
+    // Act
+    let single = add_record(&app, &record("old", Duration::days(365)), Some("backfill")).await;
+    let bulk = reqwest::Client::new()
+        .post(format!("{}/records", &app.address))
+        .bearer_auth("backfill")
+        .json(&vec![record("old-bulk", Duration::days(365))])
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    let other_token = add_record(&app, &record("old-2", Duration::days(365)), Some("other")).await;
+
+    // Assert
+    assert_eq!(200, single.status().as_u16());
+    assert_eq!(200, bulk.status().as_u16());
+    assert_eq!(400, other_token.status().as_u16());
+    assert_eq!(2, count_records(&app).await);
+}
+
+#[tokio::test]
+async fn backfill_token_is_accepted_with_bearer_token_authentication() {
+    // Arrange
+    let app = spawn_app_with(|settings| {
+        settings.application.max_record_age = Some(Duration::days(30));
+        settings.auth.bearer_tokens = vec![Secret::new("token".to_string())];
+        settings.auth.backfill_tokens = vec![Secret::new("backfill".to_string())];
+    })
+    .await;
+
+    // Act
+    let backfill = add_record(&app, &record("old", Duration::days(365)), Some("backfill")).await;
+    let regular = add_record(&app, &record("old-2", Duration::days(365)), Some("token")).await;
+
+    // Assert
+    assert_eq!(200, backfill.status().as_u16());
+    assert_eq!(400, regular.status().as_u16());
+}
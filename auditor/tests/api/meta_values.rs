@@ -0,0 +1,94 @@
+use crate::helpers::{spawn_app, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+use std::collections::HashMap;
+
+// Adds records with the given sites
+async fn add_fixture(app: &TestApp) {
+    for (i, sites) in [vec!["site2"], vec!["site1", "site3"], vec!["site2"], vec![]]
+        .into_iter()
+        .enumerate()
+    {
+        let mut meta: HashMap<&str, Vec<&str>> = HashMap::new();
+        if !sites.is_empty() {
+            meta.insert("site_id", sites);
+        }
+        let record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(format!("r{i}"))
+            .with_meta(meta);
+
+        let response = app.add_record(&record).await;
+
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn meta_values_returns_distinct_sorted_values() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.meta_values("site_id").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(
+        response.json::<Vec<String>>().await.unwrap(),
+        vec!["site1", "site2", "site3"]
+    );
+}
+
+#[tokio::test]
+async fn meta_values_accepts_every_meta_key_of_a_record() {
+    // Arrange
+    let app = spawn_app().await;
+    let record = Faker
+        .fake::<RecordTest>()
+        .with_record_id("r1")
+        .with_meta(HashMap::from([("site id", vec!["site1"])]));
+    assert_eq!(200, app.add_record(&record).await.status().as_u16());
+
+    // Act
+    let response = app.meta_values("site%20id").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(response.json::<Vec<String>>().await.unwrap(), vec!["site1"]);
+}
+
+#[tokio::test]
+async fn meta_values_returns_empty_list_for_unknown_meta_key() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app.meta_values("group_id").await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert!(response.json::<Vec<String>>().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn meta_values_returns_a_400_for_invalid_meta_key() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for key in ["%20", "site(id)", "site%3Cid%3E", "site%27%29%3B"] {
+        // Act
+        let response = app.meta_values(key).await;
+
+        // Assert
+        assert_eq!(400, response.status().as_u16(), "Key: {key}");
+        assert_eq!(
+            Some(ErrorCode::InvalidQuery),
+            ErrorCode::from_code(response.headers()[ERROR_CODE_HEADER].to_str().unwrap()),
+            "Key: {key}"
+        );
+    }
+}
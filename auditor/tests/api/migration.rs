@@ -0,0 +1,45 @@
+use auditor::configuration::get_configuration;
+use auditor::startup::migrate_database;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn auto_migrate_creates_and_migrates_a_fresh_database() {
+    // Arrange
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    configuration.database.auto_migrate = true;
+    let all_migrations: Vec<i64> = sqlx::migrate!("./../migrations")
+        .iter()
+        .map(|migration| migration.version)
+        .collect();
+
+    // Act
+    // Two instances starting at the same time
+    let (first, second) = tokio::join!(
+        migrate_database(&configuration.database),
+        migrate_database(&configuration.database)
+    );
+
+    // Assert
+    let mut applied = [first.unwrap(), second.unwrap()].concat();
+    applied.sort();
+    assert_eq!(applied, all_migrations);
+
+    let pool = configuration
+        .database
+        .pool_options()
+        .connect_with(configuration.database.with_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM auditor_accounting")
+        .fetch_one(&pool)
+        .await
+        .expect("Table auditor_accounting was not created.");
+    assert_eq!(count, 0);
+
+    // Nothing is left to apply on the next start
+    assert!(migrate_database(&configuration.database)
+        .await
+        .unwrap()
+        .is_empty());
+}
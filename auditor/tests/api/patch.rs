@@ -0,0 +1,159 @@
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::{Record, RecordTest};
+use fake::{Fake, Faker};
+use serde_json::json;
+use std::collections::HashMap;
+
+async fn spawn_app_with_patch() -> TestApp {
+    spawn_app_with(|settings| settings.application.allow_record_patch = true).await
+}
+
+// Adds a record with the id `r1` and two meta keys
+async fn add_fixture(app: &TestApp) -> RecordTest {
+    let record = Faker
+        .fake::<RecordTest>()
+        .with_record_id("r1")
+        .with_meta(HashMap::from([
+            ("site_id", vec!["site1"]),
+            ("group_id", vec!["group1", "group2"]),
+        ]));
+
+    let response = app.add_record(&record).await;
+    assert_eq!(200, response.status().as_u16());
+
+    record
+}
+
+async fn get_fixture(app: &TestApp) -> Record {
+    app.get_single_record("r1").await.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn patch_changes_one_meta_value_and_leaves_others_intact() {
+    // Arrange
+    let app = spawn_app_with_patch().await;
+    add_fixture(&app).await;
+    let before = get_fixture(&app).await;
+
+    // Act
+    let response = app
+        .patch_record("r1", &json!({ "meta": { "site_id": ["site2"] } }))
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let after = get_fixture(&app).await;
+    let meta = after.meta.as_ref().unwrap();
+    assert_eq!(meta.get("site_id").unwrap(), &vec!["site2".to_string()]);
+    assert_eq!(
+        meta.get("group_id").unwrap(),
+        &vec!["group1".to_string(), "group2".to_string()]
+    );
+    assert_eq!(after.components, before.components);
+    assert_eq!(after.start_time, before.start_time);
+    assert_eq!(after.stop_time, before.stop_time);
+    assert!(after.updated_at >= before.updated_at);
+}
+
+#[tokio::test]
+async fn patch_removes_meta_keys_set_to_null() {
+    // Arrange
+    let app = spawn_app_with_patch().await;
+    add_fixture(&app).await;
+
+    // Act
+    let response = app
+        .patch_record("r1", &json!({ "meta": { "group_id": null } }))
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let after = get_fixture(&app).await;
+    let meta = after.meta.as_ref().unwrap();
+    assert_eq!(meta.get("site_id").unwrap(), &vec!["site1".to_string()]);
+    assert!(meta.get("group_id").is_none());
+}
+
+#[tokio::test]
+async fn patch_returns_a_403_if_disabled() {
+    // Arrange
+    let app = spawn_app().await;
+    add_fixture(&app).await;
+    let before = get_fixture(&app).await;
+
+    // Act
+    let response = app
+        .patch_record("r1", &json!({ "meta": { "site_id": ["site2"] } }))
+        .await;
+
+    // Assert
+    assert_eq!(403, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::PatchDisabled.as_str()
+    );
+    assert_eq!(get_fixture(&app).await, before);
+}
+
+#[tokio::test]
+async fn patch_returns_a_404_for_non_existing_record() {
+    // Arrange
+    let app = spawn_app_with_patch().await;
+
+    // Act
+    let response = app
+        .patch_record("r1", &json!({ "meta": { "site_id": ["site2"] } }))
+        .await;
+
+    // Assert
+    assert_eq!(404, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::UnknownRecord.as_str()
+    );
+}
+
+#[tokio::test]
+async fn patch_returns_a_400_for_invalid_patches() {
+    // Arrange
+    let app = spawn_app_with_patch().await;
+    add_fixture(&app).await;
+    let before = get_fixture(&app).await;
+
+    let test_cases = [
+        (
+            json!({ "stop_time": "2022-10-01T00:00:00Z" }),
+            "patching a fixed field",
+        ),
+        (
+            json!({ "meta": { "site_id": ["site/2"] } }),
+            "forbidden character in meta value",
+        ),
+        (
+            json!({ "components": [{ "name": "CPU", "amount": -1, "scores": [] }] }),
+            "negative component amount",
+        ),
+        (json!(["meta"]), "patch is not an object"),
+    ];
+
+    for (patch, description) in test_cases {
+        // Act
+        let response = app.patch_record("r1", &patch).await;
+
+        // Assert
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not fail with 400 Bad Request when the patch was {}.",
+            description
+        );
+        assert_eq!(
+            response.headers()[ERROR_CODE_HEADER],
+            ErrorCode::ValidationFailed.as_str()
+        );
+    }
+    assert_eq!(get_fixture(&app).await, before);
+}
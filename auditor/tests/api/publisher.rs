@@ -0,0 +1,92 @@
+use crate::helpers::spawn_app_with_publisher;
+use auditor::domain::{Record, RecordTest};
+use auditor::publisher::{Publish, RecordPublisher};
+use fake::{Fake, Faker};
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+// Forwards all published records to a channel
+struct MockPublisher(mpsc::UnboundedSender<(String, Vec<u8>)>);
+
+impl Publish for MockPublisher {
+    fn publish(&self, key: String, payload: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+        Box::pin(async move { Ok(self.0.send((key, payload))?) })
+    }
+}
+
+struct FailingPublisher;
+
+impl Publish for FailingPublisher {
+    fn publish(&self, _: String, _: Vec<u8>) -> BoxFuture<'_, Result<(), anyhow::Error>> {
+        Box::pin(async { anyhow::bail!("Message bus unavailable") })
+    }
+}
+
+async fn next_published(rx: &mut mpsc::UnboundedReceiver<(String, Vec<u8>)>) -> (String, Record) {
+    let (key, payload) = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("No record was published")
+        .unwrap();
+    (key, serde_json::from_slice(&payload).unwrap())
+}
+
+#[tokio::test]
+async fn inserted_record_is_published() {
+    // Arrange
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let publisher = RecordPublisher::new(MockPublisher(tx), 0, Duration::ZERO);
+    let app = spawn_app_with_publisher(publisher).await;
+    let record = Faker.fake::<RecordTest>().with_record_id("r1");
+
+    // Act
+    let response = app.add_record(&record).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let (key, published) = next_published(&mut rx).await;
+    assert_eq!(key, "r1");
+    assert_eq!(record, published);
+    assert!(published.ingested_at.is_some());
+    assert_eq!(published.updated_at, published.ingested_at);
+}
+
+#[tokio::test]
+async fn bulk_inserted_records_are_published() {
+    // Arrange
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let publisher = RecordPublisher::new(MockPublisher(tx), 0, Duration::ZERO);
+    let app = spawn_app_with_publisher(publisher).await;
+    let records: Vec<RecordTest> = (1..=3)
+        .map(|i| Faker.fake::<RecordTest>().with_record_id(format!("r{i}")))
+        .collect();
+
+    // Act
+    let response = app.bulk_insert(&records).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+
+    let mut keys = Vec::new();
+    for _ in 0..3 {
+        keys.push(next_published(&mut rx).await.0);
+    }
+    keys.sort();
+    assert_eq!(keys, vec!["r1", "r2", "r3"]);
+}
+
+#[tokio::test]
+async fn failed_publishing_does_not_fail_insert() {
+    // Arrange
+    let publisher = RecordPublisher::new(FailingPublisher, 1, Duration::ZERO);
+    let app = spawn_app_with_publisher(publisher).await;
+    let record = Faker.fake::<RecordTest>().with_record_id("r1");
+
+    // Act
+    let response = app.add_record(&record).await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    assert_eq!(200, app.get_single_record("r1").await.status().as_u16());
+}
@@ -0,0 +1,73 @@
+use crate::helpers::{spawn_app, spawn_app_with, TestApp};
+use auditor::configuration::RateLimitSettings;
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+
+const BURST: u32 = 3;
+
+// The bucket is refilled so slowly that it stays empty for the duration of the test
+async fn spawn_app_with_rate_limit() -> TestApp {
+    spawn_app_with(|settings| {
+        settings.application.rate_limit = Some(RateLimitSettings {
+            requests_per_second: 0.01,
+            burst: BURST,
+        })
+    })
+    .await
+}
+
+#[tokio::test]
+async fn requests_beyond_the_burst_are_rejected_with_429() {
+    // Arrange
+    let app = spawn_app_with_rate_limit().await;
+    for _ in 0..BURST {
+        let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let record: RecordTest = Faker.fake();
+    let response = app.add_record(&record).await;
+
+    // Assert
+    assert_eq!(429, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::RateLimited.as_str()
+    );
+    assert!(response.headers().contains_key("retry-after"));
+
+    let response = app
+        .get_single_record(record.record_id.as_ref().unwrap())
+        .await;
+    assert_eq!(429, response.status().as_u16());
+}
+
+#[tokio::test]
+async fn health_check_is_not_rate_limited() {
+    // Arrange
+    let app = spawn_app_with_rate_limit().await;
+
+    for _ in 0..=BURST {
+        // Act
+        let response = app.health_check().await;
+
+        // Assert
+        assert_eq!(200, response.status().as_u16());
+    }
+}
+
+#[tokio::test]
+async fn requests_are_not_rate_limited_by_default() {
+    // Arrange
+    let app = spawn_app().await;
+
+    for _ in 0..=BURST {
+        // Act
+        let response = app.add_record(&Faker.fake::<RecordTest>()).await;
+
+        // Assert
+        assert_eq!(200, response.status().as_u16());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::helpers::spawn_app;
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+
+#[tokio::test]
+async fn request_latency_is_exported_per_route_and_status() {
+    // Arrange
+    let app = spawn_app().await;
+    let record: RecordTest = Faker.fake();
+    assert_eq!(200, app.add_record(&record).await.status().as_u16());
+    let response = reqwest::Client::new()
+        .get(format!("{}/does/not/exist", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+    assert_eq!(404, response.status().as_u16());
+
+    // Act
+    let response = reqwest::Client::new()
+        .get(format!("{}/metrics", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert!(response.status().is_success());
+    let body = response.text().await.unwrap();
+    assert!(body.contains("# TYPE auditor_request_duration_seconds histogram"));
+    assert!(body.contains(
+        r#"auditor_request_duration_seconds_count{method="POST",route="/record",status="200"} 1"#
+    ));
+    assert!(body.contains(
+        r#"auditor_request_duration_seconds_count{method="GET",route="unmatched",status="404"} 1"#
+    ));
+    assert!(body.contains(r#"le="60""#));
+}
@@ -0,0 +1,80 @@
+use crate::helpers::spawn_app;
+use auditor::archive::delete_expired_records;
+use auditor::domain::{Record, RecordTest};
+use chrono::{DateTime, Duration, Utc};
+use fake::{Fake, Faker};
+
+#[tokio::test]
+async fn only_expired_finished_records_are_deleted() {
+    // Arrange
+    let app = spawn_app().await;
+    let now: DateTime<Utc> = "2024-01-10T12:00:00Z".parse().unwrap();
+    let retention_period = Duration::days(7);
+
+    let cases = [
+        // Finished before the retention period
+        ("expired", Some("2024-01-02T12:00:00Z")),
+        // Finished within the retention period
+        ("recent", Some("2024-01-04T12:00:00Z")),
+        // Never deleted, no matter how long ago it started
+        ("unfinished", None),
+    ];
+    for (record_id, stop_time) in cases {
+        let mut record = Faker
+            .fake::<RecordTest>()
+            .with_record_id(record_id)
+            .with_start_time("2024-01-01T12:00:00Z");
+        record.stop_time = stop_time.map(|t| t.parse().unwrap());
+        let response = app.add_record(&record).await;
+        assert_eq!(200, response.status().as_u16());
+    }
+
+    // Act
+    let deleted = delete_expired_records(&app.db_pool, now, retention_period)
+        .await
+        .unwrap();
+
+    // Assert
+    assert_eq!(deleted, 1);
+
+    let mut remaining = app
+        .advanced_queries("stopped=true")
+        .await
+        .json::<Vec<Record>>()
+        .await
+        .unwrap();
+    remaining.extend(
+        app.advanced_queries("stopped=false")
+            .await
+            .json::<Vec<Record>>()
+            .await
+            .unwrap(),
+    );
+    let mut remaining: Vec<String> = remaining.into_iter().map(|r| r.record_id).collect();
+    remaining.sort();
+    assert_eq!(remaining, vec!["recent", "unfinished"]);
+
+    // Running it again deletes nothing
+    let deleted = delete_expired_records(&app.db_pool, now, retention_period)
+        .await
+        .unwrap();
+    assert_eq!(deleted, 0);
+}
+
+#[tokio::test]
+async fn non_positive_retention_period_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    let record: RecordTest = Faker.fake();
+    assert_eq!(200, app.add_record(&record).await.status().as_u16());
+
+    // Act
+    let result = delete_expired_records(&app.db_pool, Utc::now(), Duration::zero()).await;
+
+    // Assert
+    assert!(result.is_err());
+    let response = app
+        .get_single_record(record.record_id.as_ref().unwrap())
+        .await;
+    assert_eq!(200, response.status().as_u16());
+}
@@ -0,0 +1,42 @@
+use crate::helpers::{spawn_app, spawn_app_with};
+use auditor::constants::SERVER_LOAD_HEADER;
+use auditor::domain::RecordTest;
+use fake::{Fake, Faker};
+
+fn server_load(response: &reqwest::Response) -> f64 {
+    response.headers()[SERVER_LOAD_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn responses_report_the_server_load() {
+    // Arrange
+    let app = spawn_app().await;
+
+    // Act
+    let response = app
+        .bulk_insert(&(0..3).map(|_| Faker.fake()).collect::<Vec<RecordTest>>())
+        .await;
+
+    // Assert
+    assert_eq!(200, response.status().as_u16());
+    let load = server_load(&response);
+    assert!((0.0..=1.0).contains(&load), "Invalid server load {load}");
+}
+
+#[tokio::test]
+async fn server_load_is_the_share_of_connections_in_use() {
+    // Arrange
+    let app = spawn_app_with(|settings| settings.database.max_connections = 2).await;
+    let _connection = app.db_pool.acquire().await.unwrap();
+
+    // Act
+    let response = app.health_check().await;
+
+    // Assert
+    assert!(response.status().is_success());
+    assert!(server_load(&response) >= 0.5);
+}
@@ -1,4 +1,5 @@
 use crate::helpers::spawn_app;
+use auditor::constants::{ErrorCode, ERROR_CODE_HEADER};
 use auditor::domain::{Record, RecordDatabase, RecordTest};
 use fake::{Fake, Faker};
 
@@ -64,7 +65,9 @@ async fn update_returns_a_200_for_valid_form_data() {
                   components,
                   start_time,
                   stop_time,
-                  runtime
+                  runtime,
+                  ingested_at,
+                  updated_at
            FROM auditor_accounting
            WHERE record_id = $1
         "#,
@@ -78,3 +81,138 @@ async fn update_returns_a_200_for_valid_form_data() {
 
     assert_eq!(saved, body);
 }
+
+#[tokio::test]
+async fn update_does_not_change_ingested_at() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    // first add a record without stop time
+    let mut body: RecordTest = Faker.fake();
+    body = body.with_start_time("2022-03-01T12:00:00-00:00");
+    body.stop_time = None;
+
+    let response = app.add_record(&body).await;
+    assert_eq!(200, response.status().as_u16());
+
+    let ingested_at_before_update = app
+        .get_single_record(body.record_id.as_ref().unwrap())
+        .await
+        .json::<Record>()
+        .await
+        .unwrap()
+        .ingested_at;
+    assert!(ingested_at_before_update.is_some());
+
+    // Update this record
+    let body = body.with_stop_time("2022-03-01T13:00:00-00:00");
+
+    let response = client
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let updated = app
+        .get_single_record(body.record_id.as_ref().unwrap())
+        .await
+        .json::<Record>()
+        .await
+        .unwrap();
+
+    assert!(updated.stop_time.is_some());
+    assert_eq!(ingested_at_before_update, updated.ingested_at);
+}
+
+#[tokio::test]
+async fn update_changes_updated_at() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    // first add a record without stop time
+    let mut body: RecordTest = Faker.fake();
+    body = body.with_start_time("2022-03-01T12:00:00-00:00");
+    body.stop_time = None;
+
+    let response = app.add_record(&body).await;
+    assert_eq!(200, response.status().as_u16());
+
+    let added = app
+        .get_single_record(body.record_id.as_ref().unwrap())
+        .await
+        .json::<Record>()
+        .await
+        .unwrap();
+    assert!(added.updated_at.is_some());
+
+    // Update this record
+    let body = body.with_stop_time("2022-03-01T13:00:00-00:00");
+
+    let response = client
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    assert_eq!(200, response.status().as_u16());
+
+    let updated = app
+        .get_single_record(body.record_id.as_ref().unwrap())
+        .await
+        .json::<Record>()
+        .await
+        .unwrap();
+
+    assert!(updated.updated_at > added.updated_at);
+    assert_eq!(added.ingested_at, updated.ingested_at);
+}
+
+#[tokio::test]
+async fn update_returns_a_422_for_mismatching_runtime() {
+    // Arrange
+    let app = spawn_app().await;
+    let client = reqwest::Client::new();
+
+    let mut body: RecordTest = Faker.fake();
+    body = body.with_start_time("2022-03-01T12:00:00-00:00");
+    body.stop_time = None;
+
+    let response = app.add_record(&body).await;
+    assert_eq!(200, response.status().as_u16());
+
+    // Act
+    let body = body.with_stop_time("2022-03-01T13:00:00-00:00");
+    let mut update = serde_json::to_value(&body).unwrap();
+    update["runtime"] = 42.into();
+
+    let response = client
+        .put(format!("{}/record", &app.address))
+        .header("Content-Type", "application/json")
+        .json(&update)
+        .send()
+        .await
+        .expect("Failed to execute request.");
+
+    // Assert
+    assert_eq!(422, response.status().as_u16());
+    assert_eq!(
+        response.headers()[ERROR_CODE_HEADER],
+        ErrorCode::ValidationFailed.as_str()
+    );
+
+    let saved = app
+        .get_single_record(body.record_id.as_ref().unwrap())
+        .await
+        .json::<Record>()
+        .await
+        .unwrap();
+    assert_eq!(saved.stop_time, None);
+}
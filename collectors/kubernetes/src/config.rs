@@ -6,10 +6,13 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
+use auditor::domain::ValidName;
 use chrono::{DateTime, Local, TimeDelta};
 use serde::Deserialize;
 use tracing_subscriber::filter::LevelFilter;
 
+use crate::constants::{COMPONENT_CPU, COMPONENT_MEM};
+
 #[derive(Debug)]
 pub enum ConfigError {
     FileOpenError(io::Error),
@@ -86,6 +89,12 @@ pub struct Config {
     pub database_path: PathBuf,
     #[serde(default)]
     pub job_filter: JobFilterSettings,
+    /// Kubernetes label selector, only matching pods are accounted (e.g. `accounting=true`)
+    #[serde(default)]
+    pub pod_label_selector: Option<String>,
+    /// Components which are obtained from Prometheus for every pod
+    #[serde(default = "default_prometheus_components")]
+    pub prometheus_components: Vec<PrometheusComponent>,
     //#[serde(default)] // bool defaults to false
     //pub delete_jobs: bool,
     #[serde(default = "default_backlog_interval")]
@@ -125,6 +134,26 @@ impl TLSConfig {
     }
 }
 
+/// Maps a PromQL query to a component of the record.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrometheusComponent {
+    pub name: ValidName,
+    /// Query template. The placeholders `{namespace}`, `{pod}` and `{duration}` (lifetime of the
+    /// pod in seconds) are replaced before the query is executed.
+    pub query: String,
+}
+
+impl PrometheusComponent {
+    /// Returns the query for the given pod.
+    pub fn query_for(&self, namespace: &str, pod: &str, duration: i64) -> String {
+        self.query
+            .replace("{namespace}", namespace)
+            .replace("{pod}", pod)
+            .replace("{duration}", &duration.to_string())
+    }
+}
+
 fn default_auditor_port() -> u16 {
     8000
 }
@@ -156,6 +185,25 @@ fn default_backlog_interval() -> Duration {
 fn default_backlog_maxtries() -> u16 {
     2
 }
+fn default_prometheus_components() -> Vec<PrometheusComponent> {
+    // TODO: restarted pods: should be done with max_over_time/increase?
+    vec![
+        PrometheusComponent {
+            name: COMPONENT_CPU.clone(),
+            query: r#"sum by (namespace,pod) (
+        max_over_time(increase(
+        pod_cpu_usage_seconds_total{namespace="{namespace}",pod="{pod}"}[{duration}s])[{duration}s:]
+        ))"#
+                .to_owned(),
+        },
+        PrometheusComponent {
+            name: COMPONENT_MEM.clone(),
+            query: r#"sum by (namespace,pod) (
+        max_over_time(pod_memory_working_set_bytes{namespace="{namespace}",pod="{pod}"}[{duration}s]))"#
+                .to_owned(),
+        },
+    ]
+}
 fn default_log_level() -> LevelFilter {
     LevelFilter::INFO
 }
@@ -117,7 +117,7 @@ impl Database {
             query_builder.push_values(chunk, |mut b, rec| {
                 let blob = bincode::serialize(rec).expect("Should never fail on a record");
                 b.push_bind(blob)
-                    .push_bind(&rec.record_id)
+                    .push_bind(rec.record_id.as_ref())
                     .push_bind(0)
                     .push_bind(0)
                     .push_bind(false);
@@ -3,7 +3,8 @@ use std::fmt::{self, Display};
 use std::time::Duration;
 
 use crate::{
-    constants::{COMPONENT_CPU, COMPONENT_MEM, KEY_NAMESPACE, KEY_PODNAME},
+    config::PrometheusComponent,
+    constants::{KEY_NAMESPACE, KEY_PODNAME},
     database::Database,
     CONFIG,
 };
@@ -142,13 +143,14 @@ async fn obtain_metric(
 }
 
 /// Takes a `RecordAdd` and tries to fill it with resource metrics from Prometheus,
-/// obtained through `client`.
+/// obtained through `client`. Every entry of `components` which is not yet part of
+/// the record is added with the result of its query.
 ///
 /// # Errors:
 /// - [`MergeError::RecordMalformed`] if `rec` is malformed
 /// - [`MergeError::NoConnection`] if there are connection problems to Prometheus
 /// - [`MergeError::Incomplete`] if Prometheus was reached but any of the queries
-///   returned an empty result
+///   returned an empty result. Components obtained up to this point are kept.
 /// - [`MergeError::Critical`] on any other error
 #[tracing::instrument(
     name = "Complete Record",
@@ -156,7 +158,11 @@ async fn obtain_metric(
     skip_all,
     fields(record_id = %rec.record_id),
 )]
-async fn fill_record(rec: &mut RecordAdd, client: &PClient) -> Result<(), MergeError> {
+async fn fill_record(
+    rec: &mut RecordAdd,
+    client: &PClient,
+    components: &[PrometheusComponent],
+) -> Result<(), MergeError> {
     // Stop time and duration
     // TODO: Timezones
     let starttime = rec.start_time;
@@ -168,48 +174,19 @@ async fn fill_record(rec: &mut RecordAdd, client: &PClient) -> Result<(), MergeE
     let namespace = get_meta(meta, &KEY_NAMESPACE).ok_or(MergeError::RecordMalformed)?;
     let pod = get_meta(meta, &KEY_PODNAME).ok_or(MergeError::RecordMalformed)?;
 
-    // Queries for cpu and mem
-    let labels = format!(r#"namespace="{namespace}",pod="{pod}""#);
-    // TODO: restarted pods: should be done with max_over_time/increase?
-    let cpu_query = format!(
-        r#"sum by (namespace,pod) (
-        max_over_time(increase(
-        pod_cpu_usage_seconds_total{{{0}}}[{1}s])[{1}s:]
-        ))"#,
-        labels, duration
-    );
-    let mem_query = format!(
-        r#"sum by (namespace,pod) (
-        max_over_time(pod_memory_working_set_bytes{{{}}}[{}s]))"#,
-        labels, duration
-    );
-
-    // Obtain CPU
-    if !component_exists(&rec.components, &COMPONENT_CPU) {
-        let cpu = obtain_metric(client, &cpu_query, &stoptime).await?;
-        let component = Component::new(&COMPONENT_CPU, cpu)
-            .context("Invalid component")
-            .map_err(|e| MergeError::Critical(e.to_string()))?;
-        rec.components.push(component);
-    };
-
-    // Obtain Mem
-    if !component_exists(&rec.components, &COMPONENT_MEM) {
-        let mem = obtain_metric(client, &mem_query, &stoptime).await?;
-        let component = Component::new(&COMPONENT_MEM, mem)
+    for pcomponent in components {
+        if component_exists(&rec.components, &pcomponent.name) {
+            continue;
+        }
+        let query = pcomponent.query_for(namespace.as_ref(), pod.as_ref(), duration);
+        let amount = obtain_metric(client, &query, &stoptime).await?;
+        let component = Component::new(&pcomponent.name, amount)
             .context("Invalid component")
             .map_err(|e| MergeError::Critical(e.to_string()))?;
         rec.components.push(component);
-    };
-
-    // Return
-    if component_exists(&rec.components, &COMPONENT_CPU)
-        && component_exists(&rec.components, &COMPONENT_MEM)
-    {
-        Ok(())
-    } else {
-        Err(MergeError::Incomplete)
     }
+
+    Ok(())
 }
 
 /// Tries to complete all mergeable records using Prometheus.
@@ -221,8 +198,9 @@ async fn merge(database: &Database, pclient: &PClient) -> anyhow::Result<()> {
         .get_mergequeue()
         .await
         .context("Failed reading from queue")?;
+    let components = &CONFIG.get().unwrap().prometheus_components;
     for mut r in records {
-        match fill_record(&mut r, pclient).await {
+        match fill_record(&mut r, pclient, components).await {
             Ok(_) => database
                 .replace_complete(&r)
                 .await
@@ -568,4 +546,65 @@ mod tests {
         let response = obtain_metric(&client, &query, &DateTime::<Utc>::default()).await;
         assert!(matches!(response.unwrap_err(), MergeError::NoConnection));
     }
+
+    #[tokio::test]
+    async fn test_fill_record_custom_component() {
+        crate::constants::ensure_lazies();
+        let mock_server = MockServer::start().await;
+        let uri = mock_server.uri();
+        let client = PClient::try_from(uri).unwrap();
+
+        let components = vec![PrometheusComponent {
+            name: ValidName::parse("gpu".to_owned()).unwrap(),
+            query: r#"sum(gpu_seconds_total{namespace="{namespace}",pod="{pod}"}[{duration}s])"#
+                .to_owned(),
+        }];
+        let response = r#"
+        {
+          "status": "success",
+          "data": {
+            "resultType": "vector",
+            "result": [
+              {
+                "metric": {},
+                "value": [
+                  1714910734.510,
+                  "42"
+                ]
+              }
+            ]
+          }
+        }
+        "#;
+
+        Mock::given(method("GET"))
+            .and(path("/api/v1/query"))
+            .and(query_param_contains(
+                "query",
+                r#"sum(gpu_seconds_total{namespace="default",pod="testpod"}[60s])"#,
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(response, "application/json"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let start_time = DateTime::<Utc>::default();
+        let meta = HashMap::from([
+            (KEY_PODNAME.to_string(), vec!["testpod".to_owned()]),
+            (KEY_NAMESPACE.to_string(), vec!["default".to_owned()]),
+        ]);
+        let mut record = RecordAdd::new("testrecord".to_owned(), meta, vec![], start_time)
+            .unwrap()
+            .with_stop_time(start_time + chrono::TimeDelta::try_seconds(59).unwrap());
+        fill_record(&mut record, &client, &components)
+            .await
+            .unwrap();
+        assert_eq!(record.components, vec![Component::new("gpu", 42).unwrap()]);
+
+        // Components already present are not queried again
+        fill_record(&mut record, &client, &components)
+            .await
+            .unwrap();
+        assert_eq!(record.components.len(), 1);
+    }
 }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use chrono::{DateTime, Utc};
 use k8s_openapi::api::core::v1::Pod;
@@ -6,6 +6,7 @@ use kube::api::ListParams;
 
 use super::RecordCollector;
 use crate::{
+    config::Config,
     constants::{KEY_NAMESPACE, KEY_PODNAME, KEY_STATUS},
     CONFIG,
 };
@@ -41,26 +42,120 @@ impl RecordCollector for KapiCollector {
         &self,
         lastcheck: &Option<DateTime<Utc>>,
     ) -> anyhow::Result<Vec<RecordAdd>> {
-        let labelfilter = CONFIG.get().unwrap().job_filter.labels.join(",");
-        let lp = ListParams::default().labels(&labelfilter);
+        let selector = label_selector(CONFIG.get().unwrap());
+        let lp = ListParams::default().labels(&selector);
         let pods = self.api.list(&lp).await?;
-        let mut records = Vec::with_capacity(pods.len());
-        for p in pods {
-            let r = match pod_to_record(p) {
-                Ok(Some(r)) => r,
-                Ok(None) => continue,
-                Err(e) => {
-                    tracing::error!("Cannot parse pod: {}", e);
-                    continue;
-                }
-            };
-            // Works since None < Some(_)
-            if &r.stop_time > lastcheck {
-                records.push(r);
+        Ok(pods_to_records(pods, &selector, lastcheck))
+    }
+}
+
+/// Converts all finished pods which match the label `selector` and stopped after `lastcheck`.
+fn pods_to_records(
+    pods: impl IntoIterator<Item = Pod>,
+    selector: &str,
+    lastcheck: &Option<DateTime<Utc>>,
+) -> Vec<RecordAdd> {
+    let pods = pods.into_iter();
+    let mut records = Vec::with_capacity(pods.size_hint().0);
+    for p in pods {
+        // The API server already filters by the selector, this makes sure that
+        // non-matching pods are never accounted.
+        if !matches_label_selector(p.metadata.labels.as_ref(), selector) {
+            continue;
+        }
+        let r = match pod_to_record(p) {
+            Ok(Some(r)) => r,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::error!("Cannot parse pod: {}", e);
+                continue;
             }
+        };
+        // Works since None < Some(_)
+        if &r.stop_time > lastcheck {
+            records.push(r);
         }
-        Ok(records)
     }
+    records
+}
+
+/// Combines `job_filter.labels` and `pod_label_selector` into a single label selector.
+fn label_selector(config: &Config) -> String {
+    config
+        .job_filter
+        .labels
+        .iter()
+        .chain(config.pod_label_selector.iter())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Checks whether `labels` satisfy all requirements of the label `selector`. See
+/// <https://kubernetes.io/docs/concepts/overview/working-with-objects/labels/#label-selectors>
+fn matches_label_selector(labels: Option<&BTreeMap<String, String>>, selector: &str) -> bool {
+    let empty = BTreeMap::new();
+    let labels = labels.unwrap_or(&empty);
+    split_requirements(selector)
+        .into_iter()
+        .all(|requirement| matches_requirement(labels, requirement))
+}
+
+/// Splits a selector at all commas which are not part of a set, e.g. `env in (prod, dev)`.
+fn split_requirements(selector: &str) -> Vec<&str> {
+    let mut requirements = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                requirements.push(&selector[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    requirements.push(&selector[start..]);
+    requirements
+        .into_iter()
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .collect()
+}
+
+fn matches_requirement(labels: &BTreeMap<String, String>, requirement: &str) -> bool {
+    let value = |key: &str| labels.get(key.trim()).map(String::as_str);
+    if let Some((key, expected)) = requirement.split_once("!=") {
+        return value(key) != Some(expected.trim());
+    }
+    if let Some((key, expected)) = requirement
+        .split_once("==")
+        .or_else(|| requirement.split_once('='))
+    {
+        return value(key) == Some(expected.trim());
+    }
+    if let Some(key) = requirement.strip_prefix('!') {
+        return value(key).is_none();
+    }
+
+    let (key, set) = requirement
+        .split_once(char::is_whitespace)
+        .unwrap_or((requirement, ""));
+    let set = set.trim();
+    fn values(set: &str) -> Option<Vec<&str>> {
+        let set = set.trim().strip_prefix('(')?.strip_suffix(')')?;
+        Some(set.split(',').map(str::trim).collect())
+    }
+    if let Some(values) = set.strip_prefix("notin").and_then(values) {
+        return !value(key).is_some_and(|v| values.contains(&v));
+    }
+    if let Some(values) = set.strip_prefix("in").and_then(values) {
+        return value(key).is_some_and(|v| values.contains(&v));
+    }
+    value(key).is_some()
 }
 
 #[tracing::instrument(
@@ -374,6 +469,10 @@ mod kubernetes {
             }
             None
         }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.len(), Some(self.len()))
+        }
     }
 
     impl<K> From<Vec<Vec<K>>> for ObjectIter<K> {
@@ -395,7 +494,6 @@ mod tests {
     use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta;
     use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
-    use std::collections::BTreeMap;
 
     fn testmeta() -> ObjectMeta {
         ObjectMeta {
@@ -514,6 +612,61 @@ mod tests {
         assert_eq!(components[1].amount.as_ref(), &200); // Two Containers
     }
 
+    #[test]
+    fn label_selector_matching() {
+        let labels = BTreeMap::from([
+            ("accounting".to_string(), "true".to_string()),
+            ("env".to_string(), "prod".to_string()),
+        ]);
+        for (selector, expected) in [
+            ("", true),
+            ("accounting=true", true),
+            ("accounting==true", true),
+            ("accounting=false", false),
+            ("accounting!=false", true),
+            ("accounting", true),
+            ("!accounting", false),
+            ("missing", false),
+            ("!missing", true),
+            ("missing!=true", true),
+            ("env in (prod, dev)", true),
+            ("env in (dev)", false),
+            ("env notin (dev, test)", true),
+            ("accounting=true, env in (prod, dev)", true),
+            ("accounting=true,env notin (prod)", false),
+        ] {
+            assert_eq!(
+                matches_label_selector(Some(&labels), selector),
+                expected,
+                "Selector: {selector}"
+            );
+        }
+        assert!(!matches_label_selector(None, "accounting=true"));
+        assert!(matches_label_selector(None, ""));
+    }
+
+    #[test]
+    fn only_labeled_pods_produce_records() {
+        crate::constants::ensure_lazies();
+        let _ = CONFIG.set(load_configuration("testconfig.yml").unwrap());
+        let mut labeled = testpod();
+        labeled.metadata.name = Some("labeled".to_string());
+        labeled.metadata.labels = Some(BTreeMap::from([(
+            "accounting".to_string(),
+            "true".to_string(),
+        )]));
+        let mut unlabeled = testpod();
+        unlabeled.metadata.name = Some("unlabeled".to_string());
+
+        let records = pods_to_records(vec![labeled, unlabeled], "accounting=true", &None);
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(
+            records[0].record_id.as_ref(),
+            "KUBE_-testns-labeled-testuuid"
+        );
+    }
+
     #[test]
     fn test_pod_to_record() {
         crate::constants::ensure_lazies();
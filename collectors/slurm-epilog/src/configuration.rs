@@ -5,8 +5,13 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use auditor::constants::FORBIDDEN_CHARACTERS;
+use auditor::domain::SanitizationMode;
 use auditor::telemetry::deserialize_log_level;
+use chrono::FixedOffset;
 use serde_aux::field_attributes::deserialize_number_from_string;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use tracing_subscriber::filter::LevelFilter;
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -18,10 +23,34 @@ pub struct Settings {
     pub port: u16,
     #[serde(default = "default_record_prefix")]
     pub record_prefix: String,
+    /// Template of the `record_id`, see [`RecordIdTemplate`].
+    #[serde(default)]
+    pub record_id_template: RecordIdTemplate,
     #[serde(default = "default_string")]
     pub site_id: String,
+    #[serde(default = "default_scontrol_path")]
+    pub scontrol_path: PathBuf,
+    /// Additional arguments for `scontrol`, e.g. `["--clusters", "cluster1"]`.
+    #[serde(default)]
+    pub scontrol_args: Vec<String>,
+    /// Used if the job is not known to `scontrol` anymore.
+    #[serde(default = "default_sacct_path")]
+    pub sacct_path: PathBuf,
     #[serde(default = "default_components")]
     pub components: Vec<ComponentConfig>,
+    /// Static meta which is added to every record. Cannot override `site_id`, `user_id` and
+    /// `group_id`.
+    #[serde(default)]
+    pub meta: HashMap<String, Vec<String>>,
+    /// Meta key under which the normalized job state (e.g. `COMPLETED`) is stored.
+    #[serde(default)]
+    pub job_state_meta_key: Option<String>,
+    /// How forbidden characters are removed from names and meta values.
+    #[serde(default)]
+    pub sanitization_mode: SanitizationMode,
+    /// Timezone of the timestamps reported by Slurm.
+    #[serde(default)]
+    pub slurm_timezone: SlurmTimezone,
     #[serde(default = "default_log_level")]
     #[serde(deserialize_with = "deserialize_log_level")]
     pub log_level: LevelFilter,
@@ -54,6 +83,88 @@ impl TLSConfig {
     }
 }
 
+/// Timezone of the timestamps reported by Slurm, which carry no offset themselves.
+///
+/// Configured as `Local`, `Utc` or a fixed offset like `+02:00`.
+#[derive(serde::Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub enum SlurmTimezone {
+    /// The current offset of the system the collector is running on.
+    #[default]
+    Local,
+    Utc,
+    Fixed(FixedOffset),
+}
+
+impl TryFrom<String> for SlurmTimezone {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "Local" | "local" => Ok(SlurmTimezone::Local),
+            "Utc" | "UTC" | "utc" => Ok(SlurmTimezone::Utc),
+            offset => offset
+                .parse()
+                .map(SlurmTimezone::Fixed)
+                .map_err(|_| format!("Invalid slurm_timezone: {offset}")),
+        }
+    }
+}
+
+/// Template of the `record_id` of a job, e.g. `{prefix}-{cluster}-{jobid}`.
+///
+/// The placeholders `{prefix}`, `{jobid}`, `{cluster}` and `{site_id}` are replaced with the
+/// (sanitized) values of the job. Defaults to `{prefix}-{jobid}`.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct RecordIdTemplate(String);
+
+impl RecordIdTemplate {
+    const PLACEHOLDERS: [&'static str; 4] = ["prefix", "jobid", "cluster", "site_id"];
+
+    /// Returns whether `placeholder` (without braces) occurs in the template.
+    pub fn uses(&self, placeholder: &str) -> bool {
+        self.0.contains(&format!("{{{placeholder}}}"))
+    }
+
+    /// Replaces all placeholders with the values of the job.
+    pub fn render(&self, prefix: &str, job_id: &str, cluster: &str, site_id: &str) -> String {
+        Self::PLACEHOLDERS
+            .iter()
+            .zip([prefix, job_id, cluster, site_id])
+            .fold(self.0.clone(), |record_id, (placeholder, value)| {
+                record_id.replace(&format!("{{{placeholder}}}"), value)
+            })
+    }
+}
+
+impl Default for RecordIdTemplate {
+    fn default() -> Self {
+        RecordIdTemplate("{prefix}-{jobid}".to_string())
+    }
+}
+
+impl TryFrom<String> for RecordIdTemplate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let template = RecordIdTemplate(value);
+        // Placeholders are delimited by braces, which are forbidden characters themselves. Hence
+        // everything that is left after removing the known placeholders must be valid.
+        let literal = template.render("", "", "", "");
+        if template.0.trim().is_empty() {
+            Err("record_id_template must not be empty".to_string())
+        } else if literal.contains(&FORBIDDEN_CHARACTERS[..]) {
+            Err(format!(
+                "record_id_template {} contains unknown placeholders or forbidden characters",
+                template.0
+            ))
+        } else {
+            Ok(template)
+        }
+    }
+}
+
 fn default_log_level() -> LevelFilter {
     LevelFilter::INFO
 }
@@ -92,6 +203,14 @@ fn default_record_prefix() -> String {
     "slurm".to_string()
 }
 
+fn default_scontrol_path() -> PathBuf {
+    "/usr/bin/scontrol".into()
+}
+
+fn default_sacct_path() -> PathBuf {
+    "/usr/bin/sacct".into()
+}
+
 fn default_string() -> String {
     "none".to_string()
 }
@@ -110,6 +229,13 @@ fn default_components() -> Vec<ComponentConfig> {
 }
 
 /// Loads the configuration from a file `configuration.{yaml,json,toml,...}`
+///
+/// The settings are layered with the following precedence (highest first):
+/// 1. Environment variables prefixed with `AUDITOR_`, nested keys are separated by `__`, e.g.
+///    `AUDITOR_TLS_CONFIG__USE_TLS` overrides `tls_config.use_tls`
+/// 2. The configuration file passed as first argument
+/// 3. `configuration/slurm-epilog-collector/base`
+/// 4. Default values
 #[tracing::instrument(name = "Loading configuration")]
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
@@ -127,11 +253,153 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
         ),
         None => settings,
     };
-    let settings = settings.add_source(
-        config::Environment::with_prefix("AUDITOR")
-            .separator("__")
-            .prefix_separator("_"),
-    );
+    with_environment(settings, environment_overrides())
+}
 
-    settings.build()?.try_deserialize()
+/// Environment variables which override the settings.
+fn environment_overrides() -> config::Environment {
+    config::Environment::with_prefix("AUDITOR")
+        .separator("__")
+        .prefix_separator("_")
+}
+
+/// Adds `environment` as source with the highest precedence and deserializes the settings.
+fn with_environment(
+    settings: config::ConfigBuilder<config::builder::DefaultState>,
+    environment: config::Environment,
+) -> Result<Settings, config::ConfigError> {
+    settings.add_source(environment).build()?.try_deserialize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings_from(
+        yaml: &str,
+        variables: &[(&str, &str)],
+    ) -> Result<Settings, config::ConfigError> {
+        let environment = environment_overrides().source(Some(
+            variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ));
+        with_environment(
+            config::Config::builder()
+                .add_source(config::File::from_str(yaml, config::FileFormat::Yaml)),
+            environment,
+        )
+    }
+
+    #[test]
+    fn environment_variable_overrides_nested_file_value() {
+        let settings = settings_from(
+            r#"
+addr: auditor.example.org
+port: 8000
+tls_config:
+  use_tls: false
+"#,
+            &[
+                ("AUDITOR_TLS_CONFIG__USE_TLS", "true"),
+                ("AUDITOR_PORT", "8443"),
+            ],
+        )
+        .unwrap();
+
+        assert!(settings.tls_config.use_tls);
+        assert_eq!(settings.port, 8443);
+        // Values which are not overridden are taken from the file
+        assert_eq!(settings.addr, "auditor.example.org");
+    }
+
+    #[test]
+    fn slurm_timezone_is_parsed() {
+        let settings = |timezone: &str| {
+            settings_from(
+                &format!("slurm_timezone: \"{timezone}\"\ntls_config:\n  use_tls: false\n"),
+                &[],
+            )
+        };
+
+        assert_eq!(
+            settings("Local").unwrap().slurm_timezone,
+            SlurmTimezone::Local
+        );
+        assert_eq!(settings("Utc").unwrap().slurm_timezone, SlurmTimezone::Utc);
+        assert_eq!(
+            settings("+02:00").unwrap().slurm_timezone,
+            SlurmTimezone::Fixed(FixedOffset::east_opt(2 * 3600).unwrap())
+        );
+        assert!(settings("Mars").is_err());
+    }
+
+    #[test]
+    fn slurm_timezone_defaults_to_local() {
+        let settings = settings_from("tls_config:\n  use_tls: false\n", &[]).unwrap();
+
+        assert_eq!(settings.slurm_timezone, SlurmTimezone::Local);
+    }
+
+    #[test]
+    fn record_id_template_defaults_to_prefix_and_job_id() {
+        let settings = settings_from("tls_config:\n  use_tls: false\n", &[]).unwrap();
+
+        assert_eq!(
+            settings
+                .record_id_template
+                .render("slurm", "1234", "cluster1", "site1"),
+            "slurm-1234"
+        );
+    }
+
+    #[test]
+    fn record_id_templates_are_rendered() {
+        let cases = [
+            ("{prefix}-{jobid}", "slurm-1234"),
+            ("{prefix}-{cluster}-{jobid}", "slurm-cluster1-1234"),
+            ("{site_id}_{jobid}", "site1_1234"),
+            ("job-{jobid}-{jobid}", "job-1234-1234"),
+            ("static", "static"),
+        ];
+        for (template, expected) in cases {
+            let template = RecordIdTemplate::try_from(template.to_string()).unwrap();
+
+            assert_eq!(
+                template.render("slurm", "1234", "cluster1", "site1"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_record_id_templates_are_rejected() {
+        for template in [
+            "",
+            "  ",
+            "{prefix}-{array_task}",
+            "{prefix}/{jobid}",
+            "{jobid",
+        ] {
+            assert!(
+                RecordIdTemplate::try_from(template.to_string()).is_err(),
+                "{template} was accepted"
+            );
+        }
+        let settings = settings_from(
+            "record_id_template: \"{user}-{jobid}\"\ntls_config:\n  use_tls: false\n",
+            &[],
+        );
+        assert!(settings.is_err());
+    }
+
+    #[test]
+    fn used_placeholders_are_detected() {
+        let template =
+            RecordIdTemplate::try_from("{prefix}-{cluster}-{jobid}".to_string()).unwrap();
+
+        assert!(template.uses("cluster"));
+        assert!(!template.uses("site_id"));
+    }
 }
@@ -5,9 +5,8 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use anyhow::Error;
-use auditor::constants::FORBIDDEN_CHARACTERS;
-use auditor::domain::{Component, RecordAdd, Score};
+use anyhow::{anyhow, bail, Error};
+use auditor::domain::{Component, RecordAdd, SanitizationMode, Score};
 use auditor::telemetry::{get_subscriber, init_subscriber};
 use auditor_client::AuditorClientBuilder;
 use chrono::{offset::FixedOffset, DateTime, Local, NaiveDateTime, Utc};
@@ -15,56 +14,194 @@ use regex::Regex;
 use std::collections::HashMap;
 use std::env;
 use std::fmt;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
 use std::process::Command;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 mod configuration;
+use configuration::SlurmTimezone;
 
 #[tracing::instrument(name = "Obtaining Slurm job id from environment")]
 fn get_slurm_job_id() -> Result<u64, Error> {
     Ok(env::var("SLURM_JOB_ID")?.parse()?)
 }
 
+#[tracing::instrument(name = "Obtaining Slurm cluster name from environment")]
+fn get_slurm_cluster_name() -> Result<String, Error> {
+    env::var("SLURM_CLUSTER_NAME").map_err(|e| anyhow!("Cannot determine cluster name: {e}"))
+}
+
+/// Renders the `record_id` of the job from `record_id_template`.
+fn construct_record_id(config: &configuration::Settings, job_id: u64, cluster: &str) -> String {
+    let valid = |value: &str| make_string_valid(config.sanitization_mode, value);
+    config.record_id_template.render(
+        &valid(&config.record_prefix),
+        &job_id.to_string(),
+        &valid(cluster),
+        &valid(&config.site_id),
+    )
+}
+
 type Job = HashMap<String, String>;
 
-#[tracing::instrument(name = "Getting Slurm job info via scontrol")]
-fn get_slurm_job_info(job_id: u64) -> Result<Job, Error> {
-    Ok(std::str::from_utf8(
-        &Command::new("/usr/bin/scontrol")
-            .arg("show")
-            .arg("job")
-            .arg(job_id.to_string())
-            .arg("--details")
-            .output()?
-            .stdout,
-    )?
-    .split_whitespace()
-    .filter_map(|s| {
-        if let Some((k, v)) = s.split_once('=') {
-            Some((k.to_string(), v.to_string()))
-        } else {
-            None
-        }
+#[tracing::instrument(name = "Checking scontrol binary")]
+fn validate_scontrol_path(path: &Path) -> Result<(), Error> {
+    let metadata = std::fs::metadata(path)
+        .map_err(|e| anyhow!("scontrol binary {} not found: {e}", path.display()))?;
+    if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+        bail!("scontrol binary {} is not executable", path.display());
+    }
+    Ok(())
+}
+
+fn scontrol_command(config: &configuration::Settings, job_id: u64) -> Command {
+    let mut command = Command::new(&config.scontrol_path);
+    command
+        .arg("show")
+        .arg("job")
+        .arg(job_id.to_string())
+        .arg("--details")
+        .args(&config.scontrol_args);
+    command
+}
+
+// Fields of `sacct` and the corresponding keys of `scontrol`
+const SACCT_FIELDS: [(&str, &str); 6] = [
+    ("User", "UserId"),
+    ("Group", "GroupId"),
+    ("Start", "StartTime"),
+    ("End", "EndTime"),
+    ("NCPUS", "NumCPUs"),
+    ("State", "JobState"),
+];
+
+fn sacct_command(config: &configuration::Settings, job_id: u64) -> Command {
+    let mut command = Command::new(&config.sacct_path);
+    command
+        .arg("-j")
+        .arg(job_id.to_string())
+        .arg("--allocations")
+        .arg("--noheader")
+        .arg("--parsable2")
+        .arg("--format")
+        .arg(SACCT_FIELDS.map(|(field, _)| field).join(","));
+    command
+}
+
+/// Runs `command` and returns its stdout, fails if the command does not exit successfully.
+fn run_command(mut command: Command) -> Result<String, Error> {
+    let output = command.output()?;
+    if !output.status.success() {
+        bail!(
+            "{:?} failed ({}): {}",
+            command.get_program(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+fn parse_scontrol_output(output: &str) -> Job {
+    output
+        .split_whitespace()
+        .filter_map(|s| {
+            if let Some((k, v)) = s.split_once('=') {
+                Some((k.to_string(), v.to_string()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn parse_sacct_output(output: &str) -> Result<Job, Error> {
+    let line = output
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .ok_or(anyhow!("sacct did not return the job"))?;
+    let values = line.trim().split('|').collect::<Vec<_>>();
+    if values.len() != SACCT_FIELDS.len() {
+        bail!("Cannot parse sacct output: {line}");
+    }
+    Ok(SACCT_FIELDS
+        .iter()
+        .zip(values)
+        .map(|((_, key), value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
+/// Checks that `job` contains all keys needed to construct the record.
+fn check_required_keys(config: &configuration::Settings, job: &Job) -> Result<(), Error> {
+    let component_keys = config.components.iter().flat_map(|c| {
+        std::iter::once(&c.key)
+            .chain(c.only_if.iter().map(|o| &o.key))
+            .chain(
+                c.scores
+                    .iter()
+                    .filter_map(|s| s.only_if.as_ref().map(|o| &o.key)),
+            )
+    });
+    let state_key = config.job_state_meta_key.as_ref().map(|_| "JobState");
+    let missing = ["UserId", "GroupId", "StartTime", "EndTime"]
+        .into_iter()
+        .chain(state_key)
+        .chain(component_keys.map(String::as_str))
+        .filter(|key| !job.contains_key(*key))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        bail!("Job info lacks the keys {}", missing.join(", "));
+    }
+    Ok(())
+}
+
+#[tracing::instrument(name = "Getting Slurm job info via scontrol", skip(config))]
+fn get_slurm_job_info(config: &configuration::Settings, job_id: u64) -> Result<Job, Error> {
+    let job = parse_scontrol_output(&run_command(scontrol_command(config, job_id))?);
+    check_required_keys(config, &job)?;
+    Ok(job)
+}
+
+#[tracing::instrument(name = "Getting Slurm job info via sacct", skip(config))]
+fn get_sacct_job_info(config: &configuration::Settings, job_id: u64) -> Result<Job, Error> {
+    let job = parse_sacct_output(&run_command(sacct_command(config, job_id))?)?;
+    check_required_keys(config, &job)?;
+    Ok(job)
+}
+
+/// Gets the job info from `scontrol`. Falls back to `sacct` if the job is not known to the
+/// controller anymore.
+fn get_job_info(config: &configuration::Settings, job_id: u64) -> Result<Job, Error> {
+    get_slurm_job_info(config, job_id).or_else(|e| {
+        warn!("Cannot get job info via scontrol, falling back to sacct: {e}");
+        get_sacct_job_info(config, job_id).map_err(|sacct_error| {
+            anyhow!("Cannot get job info via scontrol ({e}) or sacct ({sacct_error})")
+        })
     })
-    .collect())
 }
 
 #[tracing::instrument(name = "Parsing Slurm timestamp", level = "debug")]
 fn parse_slurm_timestamp<T: AsRef<str> + std::fmt::Debug>(
     timestamp: T,
+    timezone: SlurmTimezone,
 ) -> Result<DateTime<Utc>, Error> {
-    let local_offset = Local::now().offset().local_minus_utc();
+    let timestamp = NaiveDateTime::parse_from_str(timestamp.as_ref(), "%Y-%m-%dT%H:%M:%S")?;
+    let offset = match timezone {
+        SlurmTimezone::Local => FixedOffset::east_opt(Local::now().offset().local_minus_utc())
+            .expect("Local offset is out of bounds"),
+        SlurmTimezone::Utc => return Ok(timestamp.and_utc()),
+        SlurmTimezone::Fixed(offset) => offset,
+    };
     Ok(DateTime::<Utc>::from(
-        NaiveDateTime::parse_from_str(timestamp.as_ref(), "%Y-%m-%dT%H:%M:%S")?
-            .and_local_timezone(FixedOffset::east_opt(local_offset).unwrap())
-            .unwrap(),
+        timestamp.and_local_timezone(offset).unwrap(),
     ))
 }
 
 #[tracing::instrument(name = "Remove forbidden characters from string", level = "debug")]
-fn make_string_valid<T: AsRef<str> + fmt::Debug>(input: T) -> String {
-    input.as_ref().replace(&FORBIDDEN_CHARACTERS[..], "")
+fn make_string_valid<T: AsRef<str> + fmt::Debug>(mode: SanitizationMode, input: T) -> String {
+    mode.sanitize(input)
 }
 
 #[tracing::instrument(
@@ -86,7 +223,7 @@ fn construct_components(config: &configuration::Settings, job: &Job) -> Vec<Comp
         .cloned()
         .map(|c| {
             Component::new(
-                make_string_valid(c.name),
+                make_string_valid(config.sanitization_mode, c.name),
                 job[&c.key].parse().unwrap_or_else(|_| {
                     panic!(
                         "Cannot parse key {} (value: {}) into u64.",
@@ -113,10 +250,89 @@ fn construct_components(config: &configuration::Settings, job: &Job) -> Vec<Comp
                     })
                     .collect(),
             )
+            .unwrap_or_else(|e| panic!("{e}. Please check your configuration!"))
         })
         .collect()
 }
 
+/// Normalizes the various representations of a Slurm job state, e.g. `CANCELLED by 1000`,
+/// `CANCELLED+` and `CA` all become `CANCELLED`.
+fn normalize_job_state(state: &str) -> String {
+    let state = state
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('+')
+        .to_uppercase();
+    match state.as_str() {
+        "BF" => "BOOT_FAIL",
+        "CA" => "CANCELLED",
+        "CD" => "COMPLETED",
+        "DL" => "DEADLINE",
+        "F" => "FAILED",
+        "NF" => "NODE_FAIL",
+        "OOM" => "OUT_OF_MEMORY",
+        "PR" => "PREEMPTED",
+        "TO" => "TIMEOUT",
+        state => state,
+    }
+    .to_string()
+}
+
+#[tracing::instrument(
+    name = "Construct meta from job info and configuration",
+    level = "debug"
+)]
+fn construct_meta(config: &configuration::Settings, job: &Job) -> HashMap<String, Vec<String>> {
+    let mut meta = HashMap::from([
+        (
+            "site_id".to_string(),
+            vec![make_string_valid(config.sanitization_mode, &config.site_id)],
+        ),
+        (
+            "user_id".to_string(),
+            vec![make_string_valid(
+                config.sanitization_mode,
+                job["UserId"].split('(').take(1).collect::<Vec<_>>()[0],
+            )],
+        ),
+        (
+            "group_id".to_string(),
+            vec![make_string_valid(
+                config.sanitization_mode,
+                job["GroupId"].split('(').take(1).collect::<Vec<_>>()[0],
+            )],
+        ),
+    ]);
+
+    if let Some(ref key) = config.job_state_meta_key {
+        meta.insert(
+            make_string_valid(config.sanitization_mode, key),
+            vec![make_string_valid(
+                config.sanitization_mode,
+                normalize_job_state(&job["JobState"]),
+            )],
+        );
+    }
+
+    for (key, values) in &config.meta {
+        let key = make_string_valid(config.sanitization_mode, key);
+        if meta.contains_key(&key) {
+            warn!("Ignoring meta {key} from the configuration, it is derived from the job");
+            continue;
+        }
+        meta.insert(
+            key,
+            values
+                .iter()
+                .map(|v| make_string_valid(config.sanitization_mode, v))
+                .collect(),
+        );
+    }
+
+    meta
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     let config = configuration::get_configuration()?;
@@ -137,6 +353,8 @@ async fn main() -> Result<(), Error> {
 
     debug!(?config, "Loaded config");
 
+    validate_scontrol_path(&config.scontrol_path)?;
+
     let client = if config.tls_config.use_tls {
         let tls_config = &config.tls_config;
 
@@ -164,35 +382,28 @@ async fn main() -> Result<(), Error> {
 
     info!(slurm_job_id = job_id, "Acquired SLURM job ID");
 
-    let job = get_slurm_job_info(job_id)?;
+    let job = get_job_info(&config, job_id)?;
 
     debug!(?job, "Acquired SLURM job info");
 
+    // The cluster name is only required if it is part of the record id
+    let cluster = if config.record_id_template.uses("cluster") {
+        get_slurm_cluster_name()?
+    } else {
+        String::new()
+    };
+
     let record = RecordAdd::new(
-        format!("{}-{job_id}", make_string_valid(&config.record_prefix)),
-        HashMap::from([
-            (
-                "site_id".to_string(),
-                vec![make_string_valid(&config.site_id)],
-            ),
-            (
-                "user_id".to_string(),
-                vec![make_string_valid(
-                    job["UserId"].split('(').take(1).collect::<Vec<_>>()[0],
-                )],
-            ),
-            (
-                "group_id".to_string(),
-                vec![make_string_valid(
-                    job["GroupId"].split('(').take(1).collect::<Vec<_>>()[0],
-                )],
-            ),
-        ]),
+        construct_record_id(&config, job_id, &cluster),
+        construct_meta(&config, &job),
         construct_components(&config, &job),
-        parse_slurm_timestamp(&job["StartTime"])?,
+        parse_slurm_timestamp(&job["StartTime"], config.slurm_timezone)?,
     )
     .expect("Could not construct record")
-    .with_stop_time(parse_slurm_timestamp(&job["EndTime"])?);
+    .with_stop_time(parse_slurm_timestamp(
+        &job["EndTime"],
+        config.slurm_timezone,
+    )?);
 
     debug!(?record, "Constructed record.");
 
@@ -201,3 +412,312 @@ async fn main() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auditor::domain::Meta;
+    use chrono::TimeZone;
+    use configuration::{RecordIdTemplate, Settings, TLSConfig};
+    use tracing_subscriber::filter::LevelFilter;
+
+    fn settings(meta: HashMap<String, Vec<String>>) -> Settings {
+        Settings {
+            addr: "127.0.0.1".to_string(),
+            port: 8000,
+            record_prefix: "slurm".to_string(),
+            record_id_template: RecordIdTemplate::default(),
+            site_id: "site1".to_string(),
+            scontrol_path: "/usr/bin/scontrol".into(),
+            scontrol_args: vec![],
+            sacct_path: "/usr/bin/sacct".into(),
+            components: vec![],
+            meta,
+            job_state_meta_key: None,
+            sanitization_mode: SanitizationMode::Strip,
+            slurm_timezone: SlurmTimezone::Local,
+            log_level: LevelFilter::INFO,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+        }
+    }
+
+    fn job() -> Job {
+        HashMap::from([
+            ("UserId".to_string(), "user1(1000)".to_string()),
+            ("GroupId".to_string(), "group1(1000)".to_string()),
+        ])
+    }
+
+    #[test]
+    fn static_meta_is_added_to_record() {
+        let config = settings(HashMap::from([
+            ("tier".to_string(), vec!["2".to_string()]),
+            (
+                "region".to_string(),
+                vec!["eu".to_string(), "eu/west".to_string()],
+            ),
+        ]));
+
+        let record = RecordAdd::new(
+            "slurm-1".to_string(),
+            construct_meta(&config, &job()),
+            vec![],
+            Utc::now(),
+        )
+        .unwrap();
+
+        let meta = Meta::from(record.meta.unwrap());
+        assert_eq!(meta.get("site_id").unwrap(), &vec!["site1"]);
+        assert_eq!(meta.get("user_id").unwrap(), &vec!["user1"]);
+        assert_eq!(meta.get("group_id").unwrap(), &vec!["group1"]);
+        assert_eq!(meta.get("tier").unwrap(), &vec!["2"]);
+        assert_eq!(meta.get("region").unwrap(), &vec!["eu", "euwest"]);
+    }
+
+    #[test]
+    fn meta_is_sanitized_with_configured_mode() {
+        let meta = HashMap::from([(
+            "subject".to_string(),
+            vec!["/DC=org/CN=Jane (Doe)".to_string()],
+        )]);
+        let mut config = settings(meta);
+
+        let stripped = construct_meta(&config, &job());
+        assert_eq!(stripped["subject"], vec!["DC=orgCN=Jane Doe"]);
+
+        config.sanitization_mode = SanitizationMode::UrlEncode;
+        let encoded = construct_meta(&config, &job());
+        assert_eq!(encoded["subject"], vec!["%2FDC=org%2FCN=Jane %28Doe%29"]);
+        assert_eq!(
+            SanitizationMode::UrlEncode
+                .restore(&encoded["subject"][0])
+                .unwrap(),
+            "/DC=org/CN=Jane (Doe)"
+        );
+    }
+
+    #[test]
+    fn static_meta_does_not_override_derived_meta() {
+        let config = settings(HashMap::from([
+            ("site_id".to_string(), vec!["other".to_string()]),
+            ("user_id".to_string(), vec!["other".to_string()]),
+        ]));
+
+        let meta = construct_meta(&config, &job());
+
+        assert_eq!(meta.len(), 3);
+        assert_eq!(meta["site_id"], vec!["site1"]);
+        assert_eq!(meta["user_id"], vec!["user1"]);
+    }
+
+    #[test]
+    fn job_state_is_added_to_configured_meta_key() {
+        let mut config = settings(HashMap::new());
+        config.job_state_meta_key = Some("state".to_string());
+        let mut job = job();
+        job.insert("JobState".to_string(), "CANCELLED by 1000".to_string());
+
+        let meta = construct_meta(&config, &job);
+
+        assert_eq!(meta["state"], vec!["CANCELLED"]);
+    }
+
+    #[test]
+    fn missing_job_state_is_an_error_if_configured() {
+        let mut config = settings(HashMap::new());
+        let mut job = job();
+        job.insert("StartTime".to_string(), "2024-01-01T10:00:00".to_string());
+        job.insert("EndTime".to_string(), "2024-01-01T11:00:00".to_string());
+
+        assert!(check_required_keys(&config, &job).is_ok());
+        config.job_state_meta_key = Some("state".to_string());
+        assert!(check_required_keys(&config, &job).is_err());
+    }
+
+    #[test]
+    fn slurm_timestamp_is_parsed_in_configured_timezone() {
+        let timestamp = "2024-01-01T10:00:00";
+        let utc = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let local_offset = Local::now().offset().local_minus_utc();
+
+        assert_eq!(
+            parse_slurm_timestamp(timestamp, SlurmTimezone::Utc).unwrap(),
+            utc
+        );
+        assert_eq!(
+            parse_slurm_timestamp(timestamp, SlurmTimezone::Local).unwrap(),
+            utc - chrono::Duration::seconds(local_offset.into())
+        );
+        assert_eq!(
+            parse_slurm_timestamp(
+                timestamp,
+                SlurmTimezone::Fixed(FixedOffset::east_opt(2 * 3600).unwrap())
+            )
+            .unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_slurm_timestamp(
+                timestamp,
+                SlurmTimezone::Fixed(FixedOffset::west_opt(5 * 3600).unwrap())
+            )
+            .unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 15, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_slurm_timestamp_is_an_error() {
+        assert!(parse_slurm_timestamp("2024-01-01 10:00", SlurmTimezone::Utc).is_err());
+    }
+
+    #[test]
+    fn normalize_job_state_succeeds() {
+        assert_eq!(normalize_job_state("COMPLETED"), "COMPLETED");
+        assert_eq!(normalize_job_state("CANCELLED+"), "CANCELLED");
+        assert_eq!(normalize_job_state("OOM"), "OUT_OF_MEMORY");
+        assert_eq!(normalize_job_state("to"), "TIMEOUT");
+    }
+
+    #[test]
+    fn scontrol_command_uses_configured_path() {
+        let mut config = settings(HashMap::new());
+        config.scontrol_path = "/opt/slurm/bin/scontrol".into();
+
+        let command = scontrol_command(&config, 42);
+
+        assert_eq!(command.get_program(), "/opt/slurm/bin/scontrol");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["show", "job", "42", "--details"]
+        );
+    }
+
+    #[test]
+    fn scontrol_command_appends_configured_args() {
+        let mut config = settings(HashMap::new());
+        config.scontrol_args = vec!["--clusters".to_string(), "cluster1".to_string()];
+
+        let command = scontrol_command(&config, 42);
+
+        assert_eq!(command.get_program(), "/usr/bin/scontrol");
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            vec!["show", "job", "42", "--details", "--clusters", "cluster1"]
+        );
+    }
+
+    #[test]
+    fn missing_scontrol_binary_is_rejected() {
+        assert!(validate_scontrol_path(Path::new("/does/not/exist/scontrol")).is_err());
+    }
+
+    #[test]
+    fn non_executable_scontrol_binary_is_rejected() {
+        let path = std::env::temp_dir().join(format!("scontrol-{}", Uuid::new_v4()));
+        std::fs::write(&path, "").unwrap();
+
+        let non_executable = validate_scontrol_path(&path);
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let executable = validate_scontrol_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(non_executable.is_err());
+        assert!(executable.is_ok());
+    }
+
+    #[test]
+    fn failing_command_is_an_error() {
+        let mut command = Command::new("sh");
+        command.args(["-c", "echo 'Invalid job id specified' >&2; exit 1"]);
+
+        let error = run_command(command).unwrap_err().to_string();
+
+        assert!(error.contains("Invalid job id specified"), "{error}");
+    }
+
+    #[test]
+    fn empty_scontrol_output_lacks_required_keys() {
+        let config = settings(HashMap::new());
+
+        let job = parse_scontrol_output("");
+        let error = check_required_keys(&config, &job).unwrap_err().to_string();
+
+        assert!(
+            error.contains("UserId, GroupId, StartTime, EndTime"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn missing_component_key_is_an_error() {
+        let mut config = settings(HashMap::new());
+        config.components = vec![configuration::ComponentConfig {
+            name: "Cores".to_string(),
+            key: "NumCPUs".to_string(),
+            scores: vec![],
+            only_if: None,
+        }];
+        let mut job = job();
+        job.insert("StartTime".to_string(), "2024-01-01T10:00:00".to_string());
+        job.insert("EndTime".to_string(), "2024-01-01T11:00:00".to_string());
+
+        assert!(check_required_keys(&config, &job).is_err());
+        job.insert("NumCPUs".to_string(), "8".to_string());
+        assert!(check_required_keys(&config, &job).is_ok());
+    }
+
+    #[test]
+    fn sacct_output_is_parsed() {
+        let job = parse_sacct_output(
+            "user1|group1|2024-01-01T10:00:00|2024-01-01T11:00:00|8|COMPLETED\n",
+        )
+        .unwrap();
+
+        assert_eq!(job["UserId"], "user1");
+        assert_eq!(job["GroupId"], "group1");
+        assert_eq!(job["StartTime"], "2024-01-01T10:00:00");
+        assert_eq!(job["EndTime"], "2024-01-01T11:00:00");
+        assert_eq!(job["NumCPUs"], "8");
+        assert_eq!(job["JobState"], "COMPLETED");
+    }
+
+    #[test]
+    fn empty_sacct_output_is_an_error() {
+        assert!(parse_sacct_output("").is_err());
+        assert!(parse_sacct_output("user1|group1").is_err());
+    }
+
+    #[test]
+    fn scontrol_failure_falls_back_to_sacct() {
+        let mut config = settings(HashMap::new());
+        config.scontrol_path = "false".into();
+        config.sacct_path = "echo".into();
+
+        // `echo` prints its arguments, which is not a valid sacct output
+        let error = get_job_info(&config, 42).unwrap_err().to_string();
+
+        assert!(error.contains("scontrol"), "{error}");
+        assert!(error.contains("sacct"), "{error}");
+    }
+
+    #[test]
+    fn record_id_is_rendered_from_template() {
+        let mut config = settings(HashMap::new());
+        config.record_prefix = "slurm/".to_string();
+
+        assert_eq!(construct_record_id(&config, 1234, "cluster1"), "slurm-1234");
+
+        config.record_id_template =
+            RecordIdTemplate::try_from("{prefix}-{cluster}-{site_id}-{jobid}".to_string()).unwrap();
+        assert_eq!(
+            construct_record_id(&config, 1234, "cluster(1)"),
+            "slurm-cluster1-site1-1234"
+        );
+    }
+}
@@ -5,10 +5,12 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use std::sync::Mutex;
 use std::time::Duration;
 
 use auditor::domain::RecordAdd;
 use auditor_client::{AuditorClient, ClientError};
+use chrono::{DateTime, Utc};
 use color_eyre::eyre::{Result, WrapErr};
 use tokio::sync::{mpsc, oneshot};
 
@@ -36,7 +38,17 @@ impl AuditorSender {
         client: AuditorClient,
     ) -> Result<()> {
         let auditor_sender = AuditorSender {
-            sender: QueuedSender::new(database, CONFIG.sender_frequency.to_std()?, client).await?,
+            sender: QueuedSender::new(
+                database,
+                CONFIG.sender_frequency.to_std()?,
+                RetryPolicy {
+                    retries: CONFIG.sender_retries,
+                    backoff: CONFIG.sender_retry_backoff.to_std()?,
+                },
+                CONFIG.queue_report_frequency.to_std()?,
+                client,
+            )
+            .await?,
             rx,
             _shutdown_notifier: shutdown_notifier,
             shutdown: Some(shutdown),
@@ -76,11 +88,35 @@ impl AuditorSender {
     }
 }
 
+/// Retries of a failed send, before the record is left in the queue for the next round.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    pub(crate) retries: u32,
+    /// Waiting time before the first retry, doubled for every further retry.
+    pub(crate) backoff: Duration,
+}
+
+impl RetryPolicy {
+    fn backoff(&self, retry: u32) -> Duration {
+        self.backoff.saturating_mul(2u32.saturating_pow(retry))
+    }
+}
+
+/// Number of records waiting in the local queue and the time at which Auditor last confirmed a
+/// record.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct QueueStatus {
+    pub(crate) backlog: i64,
+    pub(crate) last_successful_send: Option<DateTime<Utc>>,
+}
+
 pub(crate) struct QueuedSender {
     database: Database,
     shutdown_tx: Option<oneshot::Sender<oneshot::Sender<()>>>,
     shutdown_rx: Option<oneshot::Receiver<oneshot::Sender<()>>>,
     frequency: Duration,
+    retry_policy: RetryPolicy,
+    report_frequency: Duration,
     client: Option<AuditorClient>,
 }
 
@@ -88,6 +124,8 @@ impl QueuedSender {
     pub(crate) async fn new(
         database: Database,
         frequency: Duration,
+        retry_policy: RetryPolicy,
+        report_frequency: Duration,
         client: AuditorClient,
     ) -> Result<QueuedSender> {
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
@@ -96,6 +134,8 @@ impl QueuedSender {
             shutdown_tx: Some(shutdown_tx),
             shutdown_rx: Some(shutdown_rx),
             frequency,
+            retry_policy,
+            report_frequency,
             client: Some(client),
         };
         sender.run().await;
@@ -125,15 +165,29 @@ impl QueuedSender {
 
     async fn run(&mut self) {
         let mut interval = tokio::time::interval(self.frequency);
+        let mut report_interval = tokio::time::interval(self.report_frequency);
         let mut shutdown_rx = self.shutdown_rx.take().expect("Bug.");
         let client = self.client.take().expect("Bug.");
 
         let database = self.database.clone();
+        let retry_policy = self.retry_policy;
+        let last_send = Mutex::new(None);
 
         tokio::spawn(async move {
             loop {
                 tokio::select! {
                     _ = interval.tick() => {},
+                    _ = report_interval.tick() => {
+                        match queue_status(&database, &last_send).await {
+                            Ok(status) => tracing::info!(
+                                backlog = status.backlog,
+                                last_successful_send = ?status.last_successful_send,
+                                "Sending queue status"
+                            ),
+                            Err(e) => tracing::error!("Getting queue status failed with error: {e}"),
+                        }
+                        continue;
+                    },
                     res = &mut shutdown_rx => {
                         tracing::info!("QueuedSender received shutdown signal. Shutting down.");
                         // shutdown properly
@@ -145,7 +199,7 @@ impl QueuedSender {
                         break;
                     },
                 }
-                if let Err(e) = process_queue(&database, &client).await {
+                if let Err(e) = process_queue(&database, &client, &retry_policy, &last_send).await {
                     tracing::error!("Processing queue failed with error: {e}");
                 };
             }
@@ -153,39 +207,237 @@ impl QueuedSender {
     }
 }
 
-#[tracing::instrument(name = "Processing queue", skip(database, client))]
-async fn process_queue(database: &Database, client: &AuditorClient) -> Result<()> {
+#[tracing::instrument(name = "Getting queue status", skip(database), level = "debug")]
+pub(crate) async fn queue_status(
+    database: &Database,
+    last_send: &Mutex<Option<DateTime<Utc>>>,
+) -> Result<QueueStatus> {
+    Ok(QueueStatus {
+        backlog: database.count_records().await?,
+        last_successful_send: *last_send.lock().unwrap(),
+    })
+}
+
+#[tracing::instrument(name = "Processing queue", skip(database, client, last_send))]
+async fn process_queue(
+    database: &Database,
+    client: &AuditorClient,
+    retry_policy: &RetryPolicy,
+    last_send: &Mutex<Option<DateTime<Utc>>>,
+) -> Result<()> {
     let entries = database.get_records().await?;
     for (id, record) in entries {
         tracing::info!("Sending record {}", id);
-        match client.add(&record).await {
-            Ok(_) => {
-                tracing::debug!("Successfully sent record {}", id);
-                database.delete(id).await?;
-            }
-            Err(ClientError::RecordExists) => {
-                tracing::debug!(
-                    "Failed sending record {} to Auditor instance. Record already exists.",
-                    id
-                );
-                database.delete(id).await?;
-            }
-            Err(ClientError::ReqwestError(e)) => {
-                tracing::error!(
-                    "Failed sending record {} to Auditor instance. Requeuing. Error: {:?}",
-                    id,
-                    e
-                );
-            }
-            Err(e) => {
-                tracing::error!(
-                    "Failed sending record {} to Auditor instance. Requeuing. Error: {:?}",
-                    id,
-                    e
-                );
+        let mut retry = 0;
+        // Records are only deleted from the queue once Auditor confirmed that it has them
+        loop {
+            match client.add(&record).await {
+                Ok(_) => {
+                    tracing::debug!("Successfully sent record {}", id);
+                    database.delete(id).await?;
+                    *last_send.lock().unwrap() = Some(Utc::now());
+                }
+                Err(ClientError::RecordExists) => {
+                    tracing::debug!(
+                        "Failed sending record {} to Auditor instance. Record already exists.",
+                        id
+                    );
+                    database.delete(id).await?;
+                    *last_send.lock().unwrap() = Some(Utc::now());
+                }
+                Err(e) if retry < retry_policy.retries => {
+                    let backoff = retry_policy.backoff(retry);
+                    tracing::warn!(
+                        "Failed sending record {} to Auditor instance. Retrying in {:?}. Error: {:?}",
+                        id,
+                        backoff,
+                        e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    retry += 1;
+                    continue;
+                }
+                Err(ClientError::ReqwestError(e)) => {
+                    // Auditor is most likely unavailable, don't retry the remaining records
+                    tracing::error!(
+                        "Failed sending record {} to Auditor instance. Requeuing all remaining records. Error: {:?}",
+                        id,
+                        e
+                    );
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::error!(
+                        "Failed sending record {} to Auditor instance. Requeuing. Error: {:?}",
+                        id,
+                        e
+                    );
+                }
             }
+            break;
         }
     }
     tokio::time::sleep(std::time::Duration::from_secs(3)).await;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use auditor::constants::ERR_RECORD_EXISTS;
+    use auditor::domain::RecordTest;
+    use auditor_client::AuditorClientBuilder;
+    use fake::{Fake, Faker};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const RETRY_POLICY: RetryPolicy = RetryPolicy {
+        retries: 2,
+        backoff: Duration::from_millis(10),
+    };
+
+    async fn queue_with_record() -> Database {
+        let database = Database::new("sqlite://:memory:").await.unwrap();
+        database
+            .insert(RecordAdd::try_from(Faker.fake::<RecordTest>()).unwrap())
+            .await
+            .unwrap();
+        database
+    }
+
+    #[test]
+    fn backoff_is_doubled_for_every_retry() {
+        assert_eq!(RETRY_POLICY.backoff(0), Duration::from_millis(10));
+        assert_eq!(RETRY_POLICY.backoff(1), Duration::from_millis(20));
+        assert_eq!(RETRY_POLICY.backoff(2), Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn failed_send_is_retried_and_removed_from_queue() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+        let database = queue_with_record().await;
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        process_queue(&database, &client, &RETRY_POLICY, &Mutex::new(None))
+            .await
+            .unwrap();
+
+        assert!(database.get_records().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn existing_record_is_removed_from_queue() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+        let database = queue_with_record().await;
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(500).set_body_string(ERR_RECORD_EXISTS))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        process_queue(&database, &client, &RETRY_POLICY, &Mutex::new(None))
+            .await
+            .unwrap();
+
+        assert!(database.get_records().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn record_stays_in_queue_when_retries_are_exhausted() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+        let database = queue_with_record().await;
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        process_queue(&database, &client, &RETRY_POLICY, &Mutex::new(None))
+            .await
+            .unwrap();
+
+        assert_eq!(database.get_records().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn queue_status_reports_unsent_records() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+        let database = Database::new("sqlite://:memory:").await.unwrap();
+        let last_send = Mutex::new(None);
+
+        Mock::given(method("POST"))
+            .and(path("/record"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        for _ in 0..2 {
+            database
+                .insert(RecordAdd::try_from(Faker.fake::<RecordTest>()).unwrap())
+                .await
+                .unwrap();
+        }
+        process_queue(&database, &client, &RETRY_POLICY, &last_send)
+            .await
+            .unwrap();
+        for _ in 0..3 {
+            database
+                .insert(RecordAdd::try_from(Faker.fake::<RecordTest>()).unwrap())
+                .await
+                .unwrap();
+        }
+
+        let status = queue_status(&database, &last_send).await.unwrap();
+        assert_eq!(status.backlog, 3);
+        assert!(status.last_successful_send.is_some());
+    }
+
+    #[tokio::test]
+    async fn queue_status_without_send() {
+        let database = queue_with_record().await;
+
+        let status = queue_status(&database, &Mutex::new(None)).await.unwrap();
+
+        assert_eq!(
+            status,
+            QueueStatus {
+                backlog: 1,
+                last_successful_send: None
+            }
+        );
+    }
+}
@@ -7,6 +7,7 @@
 
 use std::collections::HashMap;
 
+use auditor::constants::FORBIDDEN_CHARACTERS;
 use auditor::telemetry::deserialize_log_level;
 use chrono::{offset::FixedOffset, DateTime, Duration, Local, NaiveDateTime, Utc};
 use color_eyre::eyre::{eyre, Report, Result, WrapErr};
@@ -27,19 +28,38 @@ pub struct Settings {
     pub port: u16,
     #[serde(default = "default_record_prefix")]
     pub record_prefix: String,
+    /// Template of the `record_id`, see [`RecordIdTemplate`].
+    #[serde(default)]
+    pub record_id_template: RecordIdTemplate,
     #[serde(default = "default_sites")]
     pub sites: Vec<SiteConfig>,
     pub meta: Option<Vec<MetaConfig>>,
+    /// Meta key under which the normalized job state (e.g. `COMPLETED`) is stored.
+    #[serde(default)]
+    pub job_state_meta_key: Option<String>,
     #[serde(default = "default_earliest_datetime")]
     pub earliest_datetime: DateTime<Local>,
     #[serde(default = "default_components")]
     pub components: Vec<ComponentConfig>,
+    #[serde(default = "default_sacct_path")]
+    pub sacct_path: String,
+    /// Additional arguments for `sacct`, e.g. `["--clusters", "cluster1"]`.
+    #[serde(default)]
+    pub sacct_args: Vec<String>,
     #[serde(default = "default_sacct_frequency")]
     #[serde_as(as = "serde_with::DurationSeconds<i64>")]
     pub sacct_frequency: Duration,
     #[serde(default = "default_sender_frequency")]
     #[serde_as(as = "serde_with::DurationSeconds<i64>")]
     pub sender_frequency: Duration,
+    #[serde(default = "default_sender_retries")]
+    pub sender_retries: u32,
+    #[serde(default = "default_sender_retry_backoff")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub sender_retry_backoff: Duration,
+    #[serde(default = "default_queue_report_frequency")]
+    #[serde_as(as = "serde_with::DurationSeconds<i64>")]
+    pub queue_report_frequency: Duration,
     #[serde(default = "default_database_path")]
     pub database_path: String,
     #[serde(default = "default_job_filter_settings")]
@@ -76,6 +96,60 @@ impl TLSConfig {
     }
 }
 
+/// Template of the `record_id` of a job, e.g. `{prefix}-{cluster}-{jobid}`.
+///
+/// The placeholders `{prefix}`, `{jobid}`, `{cluster}` and `{site_id}` are replaced with the
+/// (sanitized) values of the job. Defaults to `{prefix}-{jobid}`.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct RecordIdTemplate(String);
+
+impl RecordIdTemplate {
+    const PLACEHOLDERS: [&'static str; 4] = ["prefix", "jobid", "cluster", "site_id"];
+
+    /// Returns whether `placeholder` (without braces) occurs in the template.
+    pub fn uses(&self, placeholder: &str) -> bool {
+        self.0.contains(&format!("{{{placeholder}}}"))
+    }
+
+    /// Replaces all placeholders with the values of the job.
+    pub fn render(&self, prefix: &str, job_id: &str, cluster: &str, site_id: &str) -> String {
+        Self::PLACEHOLDERS
+            .iter()
+            .zip([prefix, job_id, cluster, site_id])
+            .fold(self.0.clone(), |record_id, (placeholder, value)| {
+                record_id.replace(&format!("{{{placeholder}}}"), value)
+            })
+    }
+}
+
+impl Default for RecordIdTemplate {
+    fn default() -> Self {
+        RecordIdTemplate("{prefix}-{jobid}".to_string())
+    }
+}
+
+impl TryFrom<String> for RecordIdTemplate {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let template = RecordIdTemplate(value);
+        // Placeholders are delimited by braces, which are forbidden characters themselves. Hence
+        // everything that is left after removing the known placeholders must be valid.
+        let literal = template.render("", "", "", "");
+        if template.0.trim().is_empty() {
+            Err("record_id_template must not be empty".to_string())
+        } else if literal.contains(&FORBIDDEN_CHARACTERS[..]) {
+            Err(format!(
+                "record_id_template {} contains unknown placeholders or forbidden characters",
+                template.0
+            ))
+        } else {
+            Ok(template)
+        }
+    }
+}
+
 fn default_log_level() -> LevelFilter {
     LevelFilter::INFO
 }
@@ -229,10 +303,26 @@ fn default_sacct_frequency() -> Duration {
     Duration::try_seconds(10).expect("This should never fail")
 }
 
+fn default_sacct_path() -> String {
+    "/usr/bin/sacct".into()
+}
+
 fn default_sender_frequency() -> Duration {
     Duration::try_seconds(1).expect("This should never fail")
 }
 
+fn default_sender_retries() -> u32 {
+    3
+}
+
+fn default_sender_retry_backoff() -> Duration {
+    Duration::try_seconds(1).expect("This should never fail")
+}
+
+fn default_queue_report_frequency() -> Duration {
+    Duration::try_seconds(60).expect("This should never fail")
+}
+
 fn default_database_path() -> String {
     "sqlite://testdb.db".into()
 }
@@ -508,6 +598,13 @@ pub struct KeyConfig {
 }
 
 /// Loads the configuration from a file `configuration.{yaml,json,toml,...}`
+///
+/// The settings are layered with the following precedence (highest first):
+/// 1. Environment variables prefixed with `AUDITOR_SLURM_COLLECTOR_`, nested keys are separated
+///    by `__`, e.g. `AUDITOR_SLURM_COLLECTOR_TLS_CONFIG__USE_TLS` overrides `tls_config.use_tls`
+/// 2. The configuration file passed as first argument
+/// 3. `configuration/slurm-collector/base`
+/// 4. Default values
 #[tracing::instrument(name = "Loading configuration")]
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
@@ -526,19 +623,67 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
 
     // Should only be used for (temporarily) overwriting some configurations like addr or port.
     // This is definitely not meant to do the full configuration with.
-    let settings = settings.add_source(
-        config::Environment::with_prefix("AUDITOR_SLURM_COLLECTOR")
-            .separator("__")
-            .prefix_separator("_"),
-    );
+    with_environment(settings, environment_overrides())
+}
+
+/// Environment variables which override the settings.
+fn environment_overrides() -> config::Environment {
+    config::Environment::with_prefix("AUDITOR_SLURM_COLLECTOR")
+        .separator("__")
+        .prefix_separator("_")
+}
 
-    settings.build()?.try_deserialize()
+/// Adds `environment` as source with the highest precedence and deserializes the settings.
+fn with_environment(
+    settings: config::ConfigBuilder<config::builder::DefaultState>,
+    environment: config::Environment,
+) -> Result<Settings, config::ConfigError> {
+    settings.add_source(environment).build()?.try_deserialize()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn settings_from(
+        yaml: &str,
+        variables: &[(&str, &str)],
+    ) -> Result<Settings, config::ConfigError> {
+        let environment = environment_overrides().source(Some(
+            variables
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        ));
+        with_environment(
+            config::Config::builder()
+                .add_source(config::File::from_str(yaml, config::FileFormat::Yaml)),
+            environment,
+        )
+    }
+
+    #[test]
+    fn environment_variable_overrides_nested_file_value() {
+        let settings = settings_from(
+            r#"
+addr: auditor.example.org
+port: 8000
+tls_config:
+  use_tls: false
+"#,
+            &[
+                ("AUDITOR_SLURM_COLLECTOR_TLS_CONFIG__USE_TLS", "true"),
+                ("AUDITOR_SLURM_COLLECTOR_PORT", "8443"),
+            ],
+        )
+        .unwrap();
+
+        assert!(settings.tls_config.use_tls);
+        assert_eq!(settings.port, 8443);
+        // Values which are not overridden are taken from the file
+        assert_eq!(settings.addr, "auditor.example.org");
+    }
+
     #[test]
     fn correct_time_parsed() {
         let parsed = ParsableType::Time.parse("43:28.686").unwrap();
@@ -597,4 +742,45 @@ mod tests {
         let parsed = ParsableType::Json.parse("").unwrap();
         assert_eq!(parsed, expected);
     }
+
+    #[test]
+    fn record_id_templates_are_rendered() {
+        let cases = [
+            ("{prefix}-{jobid}", "slurm-1234_5"),
+            ("{prefix}-{cluster}-{jobid}", "slurm-cluster1-1234_5"),
+            ("{site_id}_{jobid}", "site1_1234_5"),
+            ("static", "static"),
+        ];
+        for (template, expected) in cases {
+            let template = RecordIdTemplate::try_from(template.to_string()).unwrap();
+
+            assert_eq!(
+                template.render("slurm", "1234_5", "cluster1", "site1"),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_record_id_templates_are_rejected() {
+        for template in ["", "{prefix}-{array_task}", "{prefix}/{jobid}", "jobid}"] {
+            assert!(
+                RecordIdTemplate::try_from(template.to_string()).is_err(),
+                "{template} was accepted"
+            );
+        }
+        let settings = settings_from(
+            "record_id_template: \"{user}-{jobid}\"\ntls_config:\n  use_tls: false\n",
+            &[],
+        );
+        assert!(settings.is_err());
+    }
+
+    #[test]
+    fn record_id_template_defaults_to_prefix_and_job_id() {
+        let settings = settings_from("tls_config:\n  use_tls: false\n", &[]).unwrap();
+
+        assert_eq!(settings.record_id_template, RecordIdTemplate::default());
+        assert!(!settings.record_id_template.uses("cluster"));
+    }
 }
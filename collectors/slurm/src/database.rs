@@ -35,7 +35,7 @@ impl Database {
 
     #[tracing::instrument(name = "Inserting record into database", level = "debug", skip(self))]
     pub(crate) async fn insert(&self, record: RecordAdd) -> Result<()> {
-        let record_id = record.record_id.clone();
+        let record_id = record.record_id.as_ref().to_string();
         let record = bincode::serialize(&record)?;
         sqlx::query!(
             r#"INSERT OR IGNORE INTO records (id, record) VALUES ($1, $2)"#,
@@ -70,6 +70,13 @@ impl Database {
             .collect())
     }
 
+    #[tracing::instrument(name = "Counting records in database", level = "debug", skip(self))]
+    pub(crate) async fn count_records(&self) -> Result<i64> {
+        Ok(sqlx::query_scalar(r#"SELECT COUNT(*) FROM records"#)
+            .fetch_one(&self.db_pool)
+            .await?)
+    }
+
     #[tracing::instrument(name = "Closing database connection", level = "info", skip(self))]
     pub(crate) async fn close(&self) {
         self.db_pool.close().await
@@ -36,6 +36,7 @@ const GROUP: &str = "Group";
 const START: &str = "Start";
 const END: &str = "End";
 const STATE: &str = "State";
+const CLUSTER: &str = "Cluster";
 static KEYS: Lazy<Vec<KeyConfig>> = Lazy::new(|| {
     let mut keys = CONFIG.get_keys();
     keys.push(KeyConfig {
@@ -68,6 +69,14 @@ static KEYS: Lazy<Vec<KeyConfig>> = Lazy::new(|| {
         key_type: ParsableType::String,
         allow_empty: false,
     });
+    // Only queried if needed, as it is not part of the records otherwise
+    if CONFIG.record_id_template.uses("cluster") {
+        keys.push(KeyConfig {
+            name: CLUSTER.to_owned(),
+            key_type: ParsableType::String,
+            allow_empty: false,
+        });
+    }
     keys
 });
 static CONFIG: Lazy<Settings> =
@@ -7,7 +7,7 @@
 
 use std::{collections::HashMap, fmt};
 
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use auditor::{
     constants::FORBIDDEN_CHARACTERS,
     domain::{Component, RecordAdd, Score},
@@ -23,7 +23,7 @@ use crate::{
     configuration::{AllowedTypes, ComponentConfig, KeyConfig, ParsableType, Settings},
     database::Database,
     shutdown::Shutdown,
-    CONFIG, END, GROUP, JOBID, KEYS, START, STATE, USER,
+    CLUSTER, CONFIG, END, GROUP, JOBID, KEYS, START, STATE, USER,
 };
 
 type SacctRow = HashMap<String, Option<AllowedTypes>>;
@@ -99,20 +99,13 @@ async fn place_records_on_queue(records: Vec<RecordAdd>, tx: &mpsc::Sender<Recor
     }
 }
 
-#[tracing::instrument(name = "Calling sacct and parsing output", skip(database))]
-async fn get_job_info(database: &Database) -> Result<Vec<RecordAdd>> {
-    let (lastcheck, last_record_id) = database.get_lastcheck().await?;
-    tracing::debug!("Last check: {:?}", lastcheck);
-    tracing::debug!("Last record id: {:?}", last_record_id);
-
-    tracing::debug!("Using CONFIG = {:?}", CONFIG);
-    tracing::debug!("Using KEYS = {:?}", KEYS);
-
-    let binary = "/usr/bin/sacct";
+/// Builds the `sacct` command for all jobs since `lastcheck`.
+fn sacct_command(config: &Settings, keys: &[KeyConfig], lastcheck: &DateTime<Local>) -> Command {
+    let binary = &config.sacct_path;
     let mut args = vec![
         "-a".to_string(),
         "--format".to_string(),
-        KEYS.iter().map(|k| k.name.clone()).join(","),
+        keys.iter().map(|k| k.name.clone()).join(","),
         "--noconvert".to_string(),
         "--noheader".to_string(),
         "-S".to_string(),
@@ -122,35 +115,51 @@ async fn get_job_info(database: &Database) -> Result<Vec<RecordAdd>> {
         "-P".to_string(),
     ];
 
-    if !CONFIG.job_filter.status.is_empty() {
+    if !config.job_filter.status.is_empty() {
         args.push("-s".to_string());
-        args.push(CONFIG.job_filter.status.join(","));
+        args.push(config.job_filter.status.join(","));
     }
 
-    if !CONFIG.job_filter.partition.is_empty() {
+    if !config.job_filter.partition.is_empty() {
         args.push("-r".to_string());
-        args.push(CONFIG.job_filter.partition.join(","));
+        args.push(config.job_filter.partition.join(","));
     }
 
-    if !CONFIG.job_filter.user.is_empty() {
+    if !config.job_filter.user.is_empty() {
         args.push("-u".to_string());
-        args.push(CONFIG.job_filter.user.join(","));
+        args.push(config.job_filter.user.join(","));
     }
 
-    if !CONFIG.job_filter.group.is_empty() {
+    if !config.job_filter.group.is_empty() {
         args.push("-g".to_string());
-        args.push(CONFIG.job_filter.group.join(","));
+        args.push(config.job_filter.group.join(","));
     }
 
-    if !CONFIG.job_filter.account.is_empty() {
+    if !config.job_filter.account.is_empty() {
         args.push("-A".to_string());
-        args.push(CONFIG.job_filter.account.join(","));
+        args.push(config.job_filter.account.join(","));
     }
 
+    args.extend(config.sacct_args.iter().cloned());
+
     let cmd = binary.to_owned() + " " + &args.join(" ");
     tracing::debug!("Executing the following command: {}", cmd);
 
-    let cmd_out = Command::new(binary).args(&args).output().await?;
+    let mut command = Command::new(binary);
+    command.args(&args);
+    command
+}
+
+#[tracing::instrument(name = "Calling sacct and parsing output", skip(database))]
+async fn get_job_info(database: &Database) -> Result<Vec<RecordAdd>> {
+    let (lastcheck, last_record_id) = database.get_lastcheck().await?;
+    tracing::debug!("Last check: {:?}", lastcheck);
+    tracing::debug!("Last record id: {:?}", last_record_id);
+
+    tracing::debug!("Using CONFIG = {:?}", CONFIG);
+    tracing::debug!("Using KEYS = {:?}", KEYS);
+
+    let cmd_out = sacct_command(&CONFIG, &KEYS, &lastcheck).output().await?;
 
     let cmd_out = std::str::from_utf8(&cmd_out.stdout)?;
     tracing::debug!("Got: {}", cmd_out);
@@ -307,7 +316,7 @@ fn construct_record(
         return Ok(None);
     };
 
-    let record_id = make_string_valid(format!("{}-{job_id}", &CONFIG.record_prefix));
+    let record_id = construct_record_id(map, &job_id, &site, config)?;
     // We don't want this record, we have already seen it in a previous run.
     if record_id == last_record_id {
         return Ok(None);
@@ -355,6 +364,7 @@ fn construct_record(
         "group_id".to_string(),
         vec![make_string_valid(map[GROUP].extract_string()?)],
     );
+    insert_job_state(&mut meta, map, config)?;
 
     let components = if let Ok(components) = construct_components(map, &config.components) {
         components
@@ -373,6 +383,64 @@ fn construct_record(
     ))
 }
 
+/// Renders the `record_id` of the job from `record_id_template`.
+fn construct_record_id(job: &Job, job_id: &str, site: &str, config: &Settings) -> Result<String> {
+    let cluster = if config.record_id_template.uses("cluster") {
+        job[CLUSTER].extract_string()?
+    } else {
+        String::new()
+    };
+    Ok(config.record_id_template.render(
+        &make_string_valid(&config.record_prefix),
+        &make_string_valid(job_id),
+        &make_string_valid(cluster),
+        &make_string_valid(site),
+    ))
+}
+
+/// Adds the normalized state of the job to `meta`, if `job_state_meta_key` is configured.
+fn insert_job_state(
+    meta: &mut HashMap<String, Vec<String>>,
+    job: &Job,
+    config: &Settings,
+) -> Result<()> {
+    if let Some(ref key) = config.job_state_meta_key {
+        let state = job
+            .get(STATE)
+            .ok_or(eyre!("Job has no state."))?
+            .extract_string()?;
+        meta.insert(
+            make_string_valid(key),
+            vec![make_string_valid(normalize_job_state(&state))],
+        );
+    }
+    Ok(())
+}
+
+/// Normalizes the various representations of a Slurm job state, e.g. `CANCELLED by 1000`,
+/// `CANCELLED+` and `CA` all become `CANCELLED`.
+fn normalize_job_state(state: &str) -> String {
+    let state = state
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('+')
+        .to_uppercase();
+    match state.as_str() {
+        "BF" => "BOOT_FAIL",
+        "CA" => "CANCELLED",
+        "CD" => "COMPLETED",
+        "DL" => "DEADLINE",
+        "F" => "FAILED",
+        "NF" => "NODE_FAIL",
+        "OOM" => "OUT_OF_MEMORY",
+        "PR" => "PREEMPTED",
+        "TO" => "TIMEOUT",
+        state => state,
+    }
+    .to_string()
+}
+
 #[tracing::instrument(name = "Remove forbidden characters from string", level = "debug")]
 fn make_string_valid<T: AsRef<str> + fmt::Debug>(input: T) -> String {
     input.as_ref().replace(&FORBIDDEN_CHARACTERS[..], "")
@@ -421,20 +489,19 @@ fn construct_components(
                 }))
             }
         })
-        .cloned()
         .map(|c| {
             if !job.contains_key(&c.key) {
                 if let Some(default_value) = c.default_value {
-                    Ok(Component::new(make_string_valid(&c.name), default_value)
+                    Component::new(make_string_valid(&c.name), default_value)
                         .expect("Cannot construct component")
-                        .with_scores(construct_component_scores(job, &c)))
+                        .with_scores(construct_component_scores(job, c)?)
                 } else {
                     // TODO we should probably create our own error type (enum) and return it here
                     // maybe this error type can also be used in other parts of this function
                     Err(anyhow!("Job information does not contain key {}", &c.key))
                 }
             } else {
-                Ok(Component::new(
+                Component::new(
                     make_string_valid(&c.name),
                     job[&c.key].extract_i64().unwrap_or_else(|_| {
                         panic!(
@@ -444,13 +511,16 @@ fn construct_components(
                     }),
                 )
                 .expect("Cannot construct component.")
-                .with_scores(construct_component_scores(job, &c)))
+                .with_scores(construct_component_scores(job, c)?)
             }
         })
         .collect()
 }
 
-fn construct_component_scores(job: &Job, component_config: &ComponentConfig) -> Vec<Score> {
+fn construct_component_scores(
+    job: &Job,
+    component_config: &ComponentConfig,
+) -> Result<Vec<Score>, anyhow::Error> {
     component_config
         .scores
         .iter()
@@ -468,7 +538,7 @@ fn construct_component_scores(job: &Job, component_config: &ComponentConfig) ->
         })
         .map(|s| {
             Score::new(s.name.clone(), s.value)
-                .unwrap_or_else(|_| panic!("Cannot construct score from {s:?}"))
+                .with_context(|| format!("Cannot construct score from {s:?}"))
         })
         .collect()
 }
@@ -476,7 +546,8 @@ fn construct_component_scores(job: &Job, component_config: &ComponentConfig) ->
 #[cfg(test)]
 mod tests {
     use auditor::domain::{ValidAmount, ValidName, ValidValue};
-    use chrono::{FixedOffset, NaiveDateTime};
+    use chrono::{FixedOffset, NaiveDateTime, TimeZone};
+    use std::ffi::OsStr;
 
     use super::*;
     use crate::{
@@ -1344,7 +1415,7 @@ mod tests {
             only_if: None,
         };
 
-        let component_scores = construct_component_scores(&job, &component_config);
+        let component_scores = construct_component_scores(&job, &component_config).unwrap();
 
         let expected = vec![
             Score {
@@ -1360,6 +1431,69 @@ mod tests {
         assert_eq!(component_scores, expected);
     }
 
+    #[test]
+    fn construct_component_scores_invalid_value_fails() {
+        let job = Job::from([
+            (
+                "JobID".to_owned(),
+                AllowedTypes::String("1234567".to_owned()),
+            ),
+            ("NCPUS".to_owned(), AllowedTypes::Integer(8)),
+        ]);
+
+        for value in [-1.0, f64::NAN, f64::INFINITY] {
+            let components_config = vec![ComponentConfig {
+                name: "NCPUS".to_owned(),
+                key: "NCPUS".to_owned(),
+                key_type: ParsableType::Integer,
+                key_allow_empty: false,
+                default_value: None,
+                scores: vec![ScoreConfig {
+                    name: "HEPSPEC06".to_owned(),
+                    value,
+                    only_if: None,
+                }],
+                only_if: None,
+            }];
+
+            assert!(construct_component_scores(&job, &components_config[0]).is_err());
+            assert!(construct_components(&job, &components_config).is_err());
+        }
+    }
+
+    #[test]
+    fn construct_components_duplicate_scores_fails() {
+        let job = Job::from([
+            (
+                "JobID".to_owned(),
+                AllowedTypes::String("1234567".to_owned()),
+            ),
+            ("NCPUS".to_owned(), AllowedTypes::Integer(8)),
+        ]);
+        let components_config = vec![ComponentConfig {
+            name: "NCPUS".to_owned(),
+            key: "NCPUS".to_owned(),
+            key_type: ParsableType::Integer,
+            key_allow_empty: false,
+            default_value: None,
+            scores: vec![
+                ScoreConfig {
+                    name: "HEPSPEC06".to_owned(),
+                    value: 10.0,
+                    only_if: None,
+                },
+                ScoreConfig {
+                    name: "HEPSPEC06".to_owned(),
+                    value: 11.0,
+                    only_if: None,
+                },
+            ],
+            only_if: None,
+        }];
+
+        assert!(construct_components(&job, &components_config).is_err());
+    }
+
     #[test]
     fn construct_component_scores_with_only_if_succeeds() {
         let job_1 = Job::from([
@@ -1409,8 +1543,8 @@ mod tests {
             only_if: None,
         };
 
-        let component_scores_1 = construct_component_scores(&job_1, &component_config);
-        let component_scores_2 = construct_component_scores(&job_2, &component_config);
+        let component_scores_1 = construct_component_scores(&job_1, &component_config).unwrap();
+        let component_scores_2 = construct_component_scores(&job_2, &component_config).unwrap();
 
         let expected_1 = vec![
             Score {
@@ -1431,4 +1565,116 @@ mod tests {
         assert_eq!(component_scores_1, expected_1);
         assert_eq!(component_scores_2, expected_2);
     }
+
+    #[test]
+    fn sacct_command_uses_configured_binary_and_args() {
+        let config: Settings = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                sacct_path: /opt/slurm/bin/sacct
+                sacct_args: ["--clusters", "cluster1"]
+                tls_config:
+                  use_tls: false
+                "#,
+                config::FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        let keys = config.get_keys();
+        let lastcheck = Local.with_ymd_and_hms(2022, 10, 1, 12, 0, 0).unwrap();
+
+        let command = sacct_command(&config, &keys, &lastcheck);
+        let command = command.as_std();
+
+        assert_eq!(command.get_program(), "/opt/slurm/bin/sacct");
+        let args = command.get_args().collect::<Vec<_>>();
+        assert_eq!(args[..2], ["-a", "--format"]);
+        assert!(args.contains(&OsStr::new("2022-10-01T12:00:00")));
+        assert_eq!(args[args.len() - 2..], ["--clusters", "cluster1"]);
+    }
+
+    #[test]
+    fn normalize_job_state_succeeds() {
+        assert_eq!(normalize_job_state("COMPLETED"), "COMPLETED");
+        assert_eq!(normalize_job_state("CANCELLED by 1000"), "CANCELLED");
+        assert_eq!(normalize_job_state("CANCELLED+"), "CANCELLED");
+        assert_eq!(normalize_job_state("OOM"), "OUT_OF_MEMORY");
+        assert_eq!(normalize_job_state("f"), "FAILED");
+    }
+
+    #[test]
+    fn insert_job_state_uses_configured_meta_key() {
+        let config: Settings = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                job_state_meta_key: state
+                tls_config:
+                  use_tls: false
+                "#,
+                config::FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        let job: Job = [(
+            STATE.to_string(),
+            AllowedTypes::String("OUT_OF_MEMORY".to_string()),
+        )]
+        .into();
+
+        let mut meta = HashMap::new();
+        insert_job_state(&mut meta, &job, &config).unwrap();
+
+        assert_eq!(meta["state"], vec!["OUT_OF_MEMORY"]);
+    }
+
+    #[test]
+    fn construct_record_id_renders_template() {
+        let config: Settings = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                record_prefix: "slurm/"
+                record_id_template: "{prefix}-{cluster}-{site_id}-{jobid}"
+                tls_config:
+                  use_tls: false
+                "#,
+                config::FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        let job: Job = [(
+            CLUSTER.to_string(),
+            AllowedTypes::String("cluster(1)".to_string()),
+        )]
+        .into();
+
+        let record_id = construct_record_id(&job, "1234_5", "site1", &config).unwrap();
+
+        assert_eq!(record_id, "slurm-cluster1-site1-1234_5");
+    }
+
+    #[test]
+    fn construct_record_id_fails_without_cluster() {
+        let config: Settings = config::Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+                record_id_template: "{cluster}-{jobid}"
+                tls_config:
+                  use_tls: false
+                "#,
+                config::FileFormat::Yaml,
+            ))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+        let job: Job = [(CLUSTER.to_string(), AllowedTypes::Integer(1))].into();
+
+        assert!(construct_record_id(&job, "1234", "site1", &config).is_err());
+    }
 }
@@ -0,0 +1,205 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! End-to-end test of the Slurm collector.
+//!
+//! Starts an Auditor server with a fresh database, runs the collector binary against a mock
+//! `sacct` and checks the record which ends up in the database.
+//!
+//! Requires a running PostgreSQL database (`./scripts/init_db.sh`) and is only compiled with
+//! the `e2e` feature:
+//!
+//! ```bash
+//! cargo test -p auditor-slurm-collector --features e2e
+//! ```
+#![cfg(feature = "e2e")]
+
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::Duration;
+
+use auditor::configuration::{get_configuration, DatabaseSettings};
+use auditor::domain::{Record, RecordDatabase};
+use auditor::metrics::DatabaseMetricsWatcher;
+use auditor::startup::ServerOptions;
+use chrono::{TimeZone, Utc};
+use sqlx::{Connection, Executor, PgConnection, PgPool};
+use uuid::Uuid;
+
+// Partition|Comment|NCPUS|JobID|Start|End|Group|User|State
+const SACCT_OUTPUT: &str = "part1|{ 'voms': '/atlas/Role=production', 'subject': '/some/thing' }|8|1234567|2023-11-07T10:14:01|2023-11-07T11:39:09|group1|user1|COMPLETED";
+
+async fn spawn_auditor() -> (u16, PgPool) {
+    // Auditor looks for its configuration relative to the current directory
+    std::env::set_current_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../.."))
+        .expect("Failed to change into the workspace directory");
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind random port");
+    let port = listener.local_addr().unwrap().port();
+    let mut configuration = get_configuration().expect("Failed to read configuration.");
+    configuration.database.database_name = Uuid::new_v4().to_string();
+    let connection_pool = configure_database(&configuration.database).await;
+    let db_watcher = DatabaseMetricsWatcher::new(connection_pool.clone(), &configuration).unwrap();
+    let server = auditor::startup::run(
+        listener,
+        connection_pool.clone(),
+        db_watcher,
+        None,
+        ServerOptions::from_settings(&configuration),
+    )
+    .expect("Failed to bind address");
+    tokio::spawn(server);
+
+    (port, connection_pool)
+}
+
+async fn configure_database(config: &DatabaseSettings) -> PgPool {
+    let mut connection = PgConnection::connect_with(&config.without_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    connection
+        .execute(format!(r#"CREATE DATABASE "{}";"#, config.database_name).as_str())
+        .await
+        .expect("Failed to create database.");
+
+    let connection_pool = PgPool::connect_with(config.with_db())
+        .await
+        .expect("Failed to connect to Postgres.");
+    sqlx::migrate!("./../../migrations")
+        .run(&connection_pool)
+        .await
+        .expect("Failed to migrate the database");
+    connection_pool
+}
+
+fn write_mock_sacct(dir: &Path) -> String {
+    let path = dir.join("sacct");
+    std::fs::write(
+        &path,
+        format!("#!/bin/sh\ncat <<'EOF'\n{SACCT_OUTPUT}\nEOF\n"),
+    )
+    .expect("Failed to write mock sacct");
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .expect("Failed to make mock sacct executable");
+    path.to_str().unwrap().to_string()
+}
+
+fn write_config(dir: &Path, port: u16, sacct_path: &str) -> String {
+    let path = dir.join("config.yaml");
+    let config = format!(
+        r#"addr: "127.0.0.1"
+port: {port}
+record_prefix: "slurm"
+sacct_path: "{sacct_path}"
+sacct_frequency: 1
+sender_frequency: 1
+earliest_datetime: "2023-11-01T00:00:00+00:00"
+database_path: "sqlite://{}"
+sites:
+  - name: "site1"
+    only_if:
+      key: "Partition"
+      matches: "^part1$"
+meta:
+  - name: Comment
+    key: "Comment"
+    key_type: Json
+components:
+  - name: "Cores"
+    key: "NCPUS"
+    scores:
+      - name: "HEPSPEC06"
+        value: 10.0
+tls_config:
+  use_tls: false
+"#,
+        dir.join("collector.db").display()
+    );
+    std::fs::write(&path, config).expect("Failed to write collector configuration");
+    path.to_str().unwrap().to_string()
+}
+
+async fn wait_for_records(db_pool: &PgPool) -> Vec<Record> {
+    for _ in 0..60 {
+        let records = sqlx::query_as::<_, RecordDatabase>(
+            "SELECT record_id, meta, components, start_time, stop_time, runtime, ingested_at, updated_at
+             FROM auditor_accounting",
+        )
+        .fetch_all(db_pool)
+        .await
+        .expect("Failed to fetch records")
+        .into_iter()
+        .map(Record::try_from)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Failed to convert records");
+        if !records.is_empty() {
+            return records;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    panic!("Collector did not send any record to Auditor");
+}
+
+#[tokio::test]
+async fn slurm_job_is_recorded_in_auditor() {
+    let (port, db_pool) = spawn_auditor().await;
+
+    let dir = std::env::temp_dir().join(format!("auditor-slurm-e2e-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).expect("Failed to create temporary directory");
+    let sacct_path = write_mock_sacct(&dir);
+    let config_path = write_config(&dir, port, &sacct_path);
+
+    let collector = env!("CARGO_BIN_EXE_auditor-slurm-collector");
+    let mut collector = tokio::process::Command::new(collector)
+        .arg(&config_path)
+        .current_dir(&dir)
+        // sacct reports times in local time, fix the offset to +01:00
+        .env("TZ", "CET-1")
+        .kill_on_drop(true)
+        .spawn()
+        .expect("Failed to start the collector");
+
+    let records = wait_for_records(&db_pool).await;
+
+    collector
+        .kill()
+        .await
+        .expect("Failed to stop the collector");
+    std::fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(record.record_id, "slurm-1234567");
+    assert_eq!(
+        record.start_time,
+        Some(Utc.with_ymd_and_hms(2023, 11, 7, 9, 14, 1).unwrap())
+    );
+    assert_eq!(
+        record.stop_time,
+        Some(Utc.with_ymd_and_hms(2023, 11, 7, 10, 39, 9).unwrap())
+    );
+    assert_eq!(record.runtime, Some(5108));
+
+    let meta = record.meta.as_ref().unwrap();
+    assert_eq!(meta.get("site_id").unwrap(), &vec!["site1"]);
+    assert_eq!(meta.get("user_id").unwrap(), &vec!["user1"]);
+    assert_eq!(meta.get("group_id").unwrap(), &vec!["group1"]);
+    assert_eq!(
+        meta.get("voms").unwrap(),
+        &vec!["%2Fatlas%2FRole=production"]
+    );
+    assert_eq!(meta.get("subject").unwrap(), &vec!["%2Fsome%2Fthing"]);
+
+    let components = record.components.as_ref().unwrap();
+    assert_eq!(components.len(), 1);
+    assert_eq!(components[0].name.as_ref(), "Cores");
+    assert_eq!(*components[0].amount.as_ref(), 8);
+    assert_eq!(components[0].scores.len(), 1);
+    assert_eq!(components[0].scores[0].name.as_ref(), "HEPSPEC06");
+    assert_eq!(*components[0].scores[0].value.as_ref(), 10.0);
+}
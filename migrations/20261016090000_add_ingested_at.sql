@@ -0,0 +1,11 @@
+BEGIN;
+
+ALTER TABLE auditor_accounting ADD COLUMN IF NOT EXISTS ingested_at TIMESTAMPTZ;
+
+-- Records inserted before this migration have no dedicated ingest timestamp.
+-- `updated_at` is the closest approximation that is available.
+UPDATE auditor_accounting SET ingested_at = updated_at WHERE ingested_at IS NULL;
+
+ALTER TABLE auditor_accounting ALTER COLUMN ingested_at SET NOT NULL;
+
+COMMIT;
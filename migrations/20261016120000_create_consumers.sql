@@ -0,0 +1,15 @@
+BEGIN;
+
+-- Named cursors of downstream consumers. `ingested_at` and `sequence` are the `ingested_at` and
+-- the `id` of the last record in `auditor_accounting` that was acknowledged by the consumer.
+CREATE TABLE IF NOT EXISTS auditor_consumers (
+    name TEXT PRIMARY KEY,
+    ingested_at TIMESTAMPTZ NOT NULL,
+    sequence BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS auditor_accounting_ingested_at_id_idx
+    ON auditor_accounting (ingested_at, id);
+
+COMMIT;
@@ -0,0 +1,6 @@
+BEGIN;
+
+-- Soft-deleted records are kept as tombstones with the time of their deletion.
+ALTER TABLE auditor_accounting ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+COMMIT;
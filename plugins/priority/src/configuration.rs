@@ -15,6 +15,29 @@ use tracing_subscriber::filter::LevelFilter;
 pub enum ComputationMode {
     FullSpread,
     ScaledBySum,
+    LogScaled,
+}
+
+/// How to combine multiple scores of a component which match the configured score name.
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreAggregation {
+    First,
+    Max,
+    Min,
+    Mean,
+}
+
+/// How to handle records without `group_id` in their meta information.
+#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MissingGroupPolicy {
+    /// Ignore the record.
+    Ignore,
+    /// Ignore the record and log a warning.
+    Warn,
+    /// Fail the update, such that the priorities are not changed.
+    Error,
+    /// Account the record to the given group.
+    AssignDefault(String),
 }
 
 #[serde_with::serde_as]
@@ -25,6 +48,8 @@ pub struct Settings {
     #[serde(default = "default_timeout")]
     pub timeout: i64,
     pub components: HashMap<String, String>,
+    #[serde(default = "default_score_aggregation")]
+    pub score_aggregation: ScoreAggregation,
     #[serde(deserialize_with = "deserialize_number_from_string")]
     #[serde(default = "default_min_priority")]
     pub min_priority: u64,
@@ -32,6 +57,8 @@ pub struct Settings {
     #[serde(default = "default_max_priority")]
     pub max_priority: u64,
     pub group_mapping: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub group_priority_bounds: HashMap<String, (u64, u64)>,
     #[serde(default = "default_command")]
     pub commands: Vec<String>,
     #[serde_as(as = "Option<serde_with::DurationSeconds<i64>>")]
@@ -46,6 +73,34 @@ pub struct Settings {
     pub log_level: LevelFilter,
     pub prometheus: Option<PrometheusSettings>,
     pub tls_config: TLSConfig,
+    #[serde(default)]
+    pub dry_run: bool,
+    pub max_consecutive_failures: Option<u32>,
+    /// Log the ids of records which were ignored while extracting the resources.
+    #[serde(default)]
+    pub log_dropped_record_ids: bool,
+    /// How to handle records without `group_id`.
+    #[serde(default = "default_on_missing_group")]
+    pub on_missing_group: MissingGroupPolicy,
+    /// Meta key holding the group of a record. Keys are matched literally, such that flattened
+    /// keys like `identity.group` can be used.
+    #[serde(default = "default_group_meta_key")]
+    pub group_meta_key: String,
+    /// Consume the records as a stream instead of fetching all of them at once, such that they
+    /// do not have to be kept in memory.
+    #[serde(default = "default_stream_records")]
+    pub stream_records: bool,
+}
+
+impl Settings {
+    /// Returns the `(min_priority, max_priority)` bounds of `group`, which are the global bounds
+    /// unless they are overridden in `group_priority_bounds`.
+    pub fn priority_bounds(&self, group: &str) -> (u64, u64) {
+        self.group_priority_bounds
+            .get(group)
+            .copied()
+            .unwrap_or((self.min_priority, self.max_priority))
+    }
 }
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -101,6 +156,7 @@ pub struct PrometheusSettings {
 pub enum PrometheusMetricsOptions {
     ResourceUsage,
     Priority,
+    DroppedRecords,
 }
 
 fn default_enable_option() -> bool {
@@ -151,15 +207,36 @@ fn default_computation_mode() -> ComputationMode {
     ComputationMode::ScaledBySum
 }
 
+fn default_score_aggregation() -> ScoreAggregation {
+    ScoreAggregation::First
+}
+
+fn default_on_missing_group() -> MissingGroupPolicy {
+    MissingGroupPolicy::Warn
+}
+
+fn default_group_meta_key() -> String {
+    "group_id".to_string()
+}
+
+fn default_stream_records() -> bool {
+    true
+}
+
 /// Loads the configuration from a file `configuration.{yaml,json,toml,...}`
+///
+/// Passing `--dry-run` on the command line enables `dry_run`, regardless of the configuration.
 #[tracing::instrument(name = "Loading configuration")]
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let configuration_directory = base_path.join("configuration").join("priority-plugin");
 
+    let (flags, args): (Vec<String>, Vec<String>) =
+        std::env::args().skip(1).partition(|arg| arg == "--dry-run");
+
     let settings = config::Config::builder()
         .add_source(config::File::from(configuration_directory.join("base")).required(false));
-    let settings = match std::env::args().nth(1) {
+    let settings = match args.into_iter().next() {
         Some(file) => settings.add_source(
             config::File::from(file.as_ref())
                 .required(false)
@@ -172,6 +249,11 @@ pub fn get_configuration() -> Result<Settings, config::ConfigError> {
             .separator("__")
             .prefix_separator("_"),
     );
+    let settings = if flags.is_empty() {
+        settings
+    } else {
+        settings.set_override("dry_run", true)?
+    };
 
     settings.build()?.try_deserialize()
 }
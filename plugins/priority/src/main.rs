@@ -5,17 +5,20 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-use anyhow::Error;
+use anyhow::{bail, Error};
 use auditor::domain::Record;
 use auditor::telemetry::{get_subscriber, init_subscriber};
-use auditor_client::{AuditorClientBuilder, Operator, QueryBuilder};
+use auditor_client::{AuditorClient, AuditorClientBuilder, ClientError, Operator, QueryBuilder};
 use chrono::Utc;
-use configuration::{ComputationMode, PrometheusMetricsOptions, Settings};
+use configuration::{
+    ComputationMode, MissingGroupPolicy, PrometheusMetricsOptions, ScoreAggregation, Settings,
+};
+use futures_util::{Stream, StreamExt};
 use num_traits::cast::FromPrimitive;
 use std::collections::HashMap;
 use std::net::TcpListener;
 use std::process::Command;
-use tracing::{debug, error, warn};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 mod configuration;
@@ -29,107 +32,348 @@ type ResourceName = String;
 type ResourceValue = f64;
 type PriorityName = String;
 type PriorityValue = i64;
+// Resources and priorities of all groups and the numbers of ignored records per reason.
+type PriorityUpdate = (
+    HashMap<ResourceName, ResourceValue>,
+    HashMap<PriorityName, PriorityValue>,
+    HashMap<String, u64>,
+);
 
-#[tracing::instrument(name = "Extracting resources from records", skip(records, config))]
-fn extract(records: Vec<Record>, config: &Settings) -> HashMap<ResourceName, ResourceValue> {
-    if config.components.is_empty() {
-        warn!(concat!(
-            "Not configured how to extract metrics to account for ",
-            "(components are missing). Will only account for time!"
-        ));
+/// Resources accounted to a group.
+///
+/// The resources of a record are its runtime multiplied by the amounts of the configured
+/// components and by the values of their scores. Without a score (or a score of exactly 1.0),
+/// this product is an integer and summed up exactly. Otherwise it is summed up as `f64`, which is
+/// exact up to 2^53.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct ResourceSum {
+    exact: i128,
+    weighted: f64,
+}
+
+impl ResourceSum {
+    fn add(&mut self, units: i128, factor: f64) {
+        match self.exact.checked_add(units) {
+            Some(exact) if factor == 1.0 => self.exact = exact,
+            _ => self.weighted += units as f64 * factor,
+        }
+    }
+
+    fn as_f64(&self) -> f64 {
+        self.exact as f64 + self.weighted
+    }
+}
+
+impl PartialEq<f64> for ResourceSum {
+    fn eq(&self, other: &f64) -> bool {
+        self.as_f64() == *other
+    }
+}
+
+/// Formats the exact integer if no weighted resources were added.
+impl std::fmt::Display for ResourceSum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.weighted == 0.0 {
+            write!(f, "{}", self.exact)
+        } else {
+            write!(f, "{}", self.as_f64())
+        }
+    }
+}
+
+/// Reason why a record was ignored while extracting the resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DropReason {
+    MissingRuntime,
+    MissingComponents,
+    MissingConfiguredComponent,
+    ResourcesOutOfRange,
+    MissingGroupId,
+    UnconfiguredGroup,
+}
+
+impl DropReason {
+    const ALL: [DropReason; 6] = [
+        DropReason::MissingRuntime,
+        DropReason::MissingComponents,
+        DropReason::MissingConfiguredComponent,
+        DropReason::ResourcesOutOfRange,
+        DropReason::MissingGroupId,
+        DropReason::UnconfiguredGroup,
+    ];
+
+    /// Label of the reason in the log and in the Prometheus metrics.
+    fn as_str(&self) -> &'static str {
+        match self {
+            DropReason::MissingRuntime => "missing_runtime",
+            DropReason::MissingComponents => "missing_components",
+            DropReason::MissingConfiguredComponent => "missing_configured_component",
+            DropReason::ResourcesOutOfRange => "resources_out_of_range",
+            DropReason::MissingGroupId => "missing_group_id",
+            DropReason::UnconfiguredGroup => "unconfigured_group",
+        }
+    }
+}
+
+/// Records which were ignored while extracting the resources, counted per reason.
+#[derive(Debug, Default)]
+struct DroppedRecords {
+    counts: HashMap<DropReason, u64>,
+    /// Only collected if `log_dropped_record_ids` is enabled.
+    record_ids: HashMap<DropReason, Vec<String>>,
+    keep_record_ids: bool,
+}
+
+impl DroppedRecords {
+    fn new(keep_record_ids: bool) -> Self {
+        DroppedRecords {
+            keep_record_ids,
+            ..Default::default()
+        }
     }
 
-    let mut resources: HashMap<String, f64> = HashMap::new();
+    fn add(&mut self, reason: DropReason, record_id: &str) {
+        *self.counts.entry(reason).or_default() += 1;
+        if self.keep_record_ids {
+            self.record_ids
+                .entry(reason)
+                .or_default()
+                .push(record_id.to_string());
+        }
+    }
+
+    fn count(&self, reason: DropReason) -> u64 {
+        self.counts.get(&reason).copied().unwrap_or_default()
+    }
+
+    /// Number of dropped records for every reason, including those without dropped records.
+    fn counts_by_label(&self) -> HashMap<String, u64> {
+        DropReason::ALL
+            .iter()
+            .map(|reason| (reason.as_str().to_string(), self.count(*reason)))
+            .collect()
+    }
 
-    for group in config.group_mapping.keys() {
-        resources.insert(group.to_string(), 0.0);
+    fn log_summary(&self) {
+        for reason in DropReason::ALL {
+            let count = self.count(reason);
+            if count == 0 {
+                continue;
+            }
+            match self.record_ids.get(&reason) {
+                Some(record_ids) => {
+                    info!(
+                        reason = reason.as_str(),
+                        count,
+                        ?record_ids,
+                        "Ignored records"
+                    )
+                }
+                None => info!(reason = reason.as_str(), count, "Ignored records"),
+            }
+        }
     }
+}
+
+/// Sums up the resources of the records per group, one record at a time, such that the records
+/// do not have to be kept in memory.
+struct ResourceAccumulator<'a> {
+    config: &'a Settings,
+    resources: HashMap<ResourceName, ResourceSum>,
+    dropped: DroppedRecords,
+}
 
-    for r in records {
-        let val: f64 = if let Some(runtime) = r.runtime {
-            f64::from_i64(runtime).unwrap()
-                * if r.components.is_none() {
-                    if !config.components.is_empty() {
-                        error!(
-                            record_id = %r.record_id,
-                            "Unexpectetely no components in record. Ignoring record."
-                        );
+impl<'a> ResourceAccumulator<'a> {
+    fn new(config: &'a Settings) -> Self {
+        if config.components.is_empty() {
+            warn!(concat!(
+                "Not configured how to extract metrics to account for ",
+                "(components are missing). Will only account for time!"
+            ));
+        }
+
+        // Only configured groups are considered, so they are filled beforehand
+        let resources = config
+            .group_mapping
+            .keys()
+            .map(|group| (group.to_string(), ResourceSum::default()))
+            .collect();
+
+        ResourceAccumulator {
+            config,
+            resources,
+            dropped: DroppedRecords::new(config.log_dropped_record_ids),
+        }
+    }
+
+    /// Adds the resources of a record to its group.
+    ///
+    /// Fails if the record has no group (`group_meta_key`) and `on_missing_group` is `Error`.
+    fn add(&mut self, r: Record) -> Result<(), Error> {
+        let Some(runtime) = r.runtime else {
+            error!(record_id = %r.record_id, "Record without runtime, ignoring.");
+            self.dropped.add(DropReason::MissingRuntime, &r.record_id);
+            return Ok(());
+        };
+        let (units, factor) = match r.components.as_ref() {
+            None if !self.config.components.is_empty() => {
+                error!(
+                    record_id = %r.record_id,
+                    "Unexpectetely no components in record. Ignoring record."
+                );
+                self.dropped
+                    .add(DropReason::MissingComponents, &r.record_id);
+                return Ok(());
+            }
+            None => (Some(i128::from(runtime)), 1.0),
+            Some(components) => {
+                let mut units = Some(i128::from(runtime));
+                let mut factor = 1.0;
+                let mut found = false;
+                for c in components {
+                    let Some(score_name) = self.config.components.get(c.name.as_ref()) else {
                         continue;
-                    }
-                    1.0
-                } else {
-                    match r.components.as_ref().unwrap().iter().fold(
-                        (1.0, false),
-                        |(acc, found), c| {
-                            if config.components.contains_key(c.name.as_ref()) {
-                                (
-                                    acc * f64::from_i64(*c.amount.as_ref()).unwrap()
-                                        * match c.scores.iter().fold(
-                                            (1.0, false),
-                                            |(acc, found), s| {
-                                                if s.name.as_ref()
-                                                    == config
-                                                        .components
-                                                        .get(c.name.as_ref())
-                                                        .unwrap()
-                                                {
-                                                    (*s.value.as_ref(), true)
-                                                } else {
-                                                    (acc, found)
-                                                }
-                                            },
-                                        ) {
-                                            (acc, true) => acc,
-                                            (_, false) => {
-                                                error!(
-                                                    record_id = %r.record_id,
-                                                    concat!(
-                                                        "Did not find configured score ",
-                                                        "in record! Assuming 1.0."
-                                                    )
-                                                );
-                                                1.0
-                                            }
-                                        },
-                                    true,
-                                )
-                            } else {
-                                (acc, found)
-                            }
-                        },
+                    };
+                    found = true;
+                    units = units.and_then(|u| u.checked_mul(i128::from(*c.amount.as_ref())));
+                    factor *= match aggregate_scores(
+                        c.scores
+                            .iter()
+                            .filter(|s| s.name.as_ref() == score_name)
+                            .map(|s| *s.value.as_ref()),
+                        self.config.score_aggregation,
                     ) {
-                        (acc, true) => acc,
-                        (_, false) => {
+                        Some(score) => score,
+                        None => {
                             error!(
                                 record_id = %r.record_id,
-                                "Did not find configured components in record! Ignoring record."
+                                concat!(
+                                    "Did not find configured score ",
+                                    "in record! Assuming 1.0."
+                                )
                             );
-                            continue;
+                            1.0
                         }
-                    }
+                    };
                 }
-        } else {
-            error!(record_id = %r.record_id, "Record without runtime, ignoring.");
-            continue;
-        };
-        // If no group_id is present in the record, then record will be silently ignored
-        if let Some(meta) = r.meta.as_ref() {
-            if let Some(groups) = meta.get("group_id") {
-                if let Some(group_id) = groups.first() {
-                    // Only consider configured groups
-                    if config.group_mapping.contains_key(group_id) {
-                        // we know that the key exists (we filled it beforehand), therefore we can unwrap
-                        *resources.get_mut(group_id).unwrap() += val;
-                        println!("Resources: {resources:?}");
-                    }
+                if !found {
+                    error!(
+                        record_id = %r.record_id,
+                        "Did not find configured components in record! Ignoring record."
+                    );
+                    self.dropped
+                        .add(DropReason::MissingConfiguredComponent, &r.record_id);
+                    return Ok(());
                 }
+                (units, factor)
             }
-        } else {
-            error!(record_id = %r.record_id, "Record without group_id, ignoring.");
+        };
+        let Some(units) = units else {
+            error!(
+                record_id = %r.record_id,
+                "Resources of record exceed the supported range. Ignoring record."
+            );
+            self.dropped
+                .add(DropReason::ResourcesOutOfRange, &r.record_id);
+            return Ok(());
+        };
+        let group_id = r
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.get(&self.config.group_meta_key))
+            .and_then(|groups| groups.first());
+        let group_id = match (group_id, &self.config.on_missing_group) {
+            (Some(group_id), _) => group_id,
+            (None, MissingGroupPolicy::AssignDefault(group)) => group,
+            (None, MissingGroupPolicy::Ignore) => {
+                debug!(
+                    record_id = %r.record_id,
+                    key = %self.config.group_meta_key,
+                    "Record without group, ignoring."
+                );
+                self.dropped.add(DropReason::MissingGroupId, &r.record_id);
+                return Ok(());
+            }
+            (None, MissingGroupPolicy::Warn) => {
+                warn!(
+                    record_id = %r.record_id,
+                    key = %self.config.group_meta_key,
+                    "Record without group, ignoring."
+                );
+                self.dropped.add(DropReason::MissingGroupId, &r.record_id);
+                return Ok(());
+            }
+            (None, MissingGroupPolicy::Error) => {
+                bail!(
+                    "Record {} has no meta key {}",
+                    r.record_id,
+                    self.config.group_meta_key
+                );
+            }
+        };
+        // Only consider configured groups, we filled them beforehand
+        match self.resources.get_mut(group_id) {
+            Some(resource) => resource.add(units, factor),
+            None => self
+                .dropped
+                .add(DropReason::UnconfiguredGroup, &r.record_id),
         }
+        Ok(())
     }
 
-    resources
+    fn finish(self) -> (HashMap<ResourceName, ResourceSum>, DroppedRecords) {
+        (self.resources, self.dropped)
+    }
+}
+
+/// Sums up the resources of the records per group.
+///
+/// Fails if a record has no group (`group_meta_key`) and `on_missing_group` is `Error`.
+#[tracing::instrument(name = "Extracting resources from records", skip(records, config))]
+fn extract(
+    records: impl IntoIterator<Item = Record>,
+    config: &Settings,
+) -> Result<(HashMap<ResourceName, ResourceSum>, DroppedRecords), Error> {
+    let mut accumulator = ResourceAccumulator::new(config);
+    for record in records {
+        accumulator.add(record)?;
+    }
+    Ok(accumulator.finish())
+}
+
+/// Same as [`extract`], but consumes the records as they are received, such that only the
+/// resources per group are kept in memory.
+///
+/// Fails if the stream yields an error.
+#[tracing::instrument(name = "Extracting resources from record stream", skip_all)]
+async fn extract_stream(
+    records: impl Stream<Item = Result<Record, ClientError>>,
+    config: &Settings,
+) -> Result<(HashMap<ResourceName, ResourceSum>, DroppedRecords), Error> {
+    let mut accumulator = ResourceAccumulator::new(config);
+    let mut records = std::pin::pin!(records);
+    while let Some(record) = records.next().await {
+        accumulator.add(record?)?;
+    }
+    Ok(accumulator.finish())
+}
+
+/// Combines the values of all scores of a component which match the configured score name.
+/// Returns `None` if no score matched.
+fn aggregate_scores(
+    scores: impl Iterator<Item = f64>,
+    aggregation: ScoreAggregation,
+) -> Option<f64> {
+    let scores: Vec<f64> = scores.collect();
+    if scores.is_empty() {
+        return None;
+    }
+    Some(match aggregation {
+        ScoreAggregation::First => scores[0],
+        ScoreAggregation::Max => scores.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        ScoreAggregation::Min => scores.iter().copied().fold(f64::INFINITY, f64::min),
+        ScoreAggregation::Mean => scores.iter().sum::<f64>() / scores.len() as f64,
+    })
 }
 
 #[tracing::instrument(name = "Computing priorities", skip(config))]
@@ -148,13 +392,19 @@ fn compute_priorities(
         },
     );
 
-    let max_priority = f64::from_u64(config.max_priority).unwrap();
-    let min_priority = f64::from_u64(config.min_priority).unwrap();
+    let bounds = |group: &str| {
+        let (min_priority, max_priority) = config.priority_bounds(group);
+        (
+            f64::from_u64(min_priority).unwrap(),
+            f64::from_u64(max_priority).unwrap(),
+        )
+    };
 
     match config.computation_mode {
         ComputationMode::FullSpread => resources
             .iter()
             .map(|(k, v)| {
+                let (min_priority, max_priority) = bounds(k);
                 (
                     k.clone(),
                     ((v - v_min) / (v_max - v_min) * (max_priority - min_priority) + min_priority)
@@ -165,20 +415,40 @@ fn compute_priorities(
         ComputationMode::ScaledBySum => resources
             .iter()
             .map(|(k, v)| {
+                let (min_priority, max_priority) = bounds(k);
                 (
                     k.clone(),
                     ((max_priority - min_priority) / v_sum * v + min_priority).round() as i64,
                 )
             })
             .collect(),
+        ComputationMode::LogScaled => {
+            let (l_min, l_max) = (v_min.max(0.0).ln_1p(), v_max.max(0.0).ln_1p());
+            resources
+                .iter()
+                .map(|(k, v)| {
+                    let (min_priority, max_priority) = bounds(k);
+                    // If all groups provided the same resources, they all get `max_priority`
+                    let scaled = if l_max > l_min {
+                        (v.max(0.0).ln_1p() - l_min) / (l_max - l_min)
+                    } else {
+                        1.0
+                    };
+                    (
+                        k.clone(),
+                        (scaled * (max_priority - min_priority) + min_priority).round() as i64,
+                    )
+                })
+                .collect()
+        }
     }
 }
 
 #[tracing::instrument(name = "Constructing command for setting priorities")]
-fn construct_command(
+fn construct_command<R: std::fmt::Display + std::fmt::Debug>(
     cmd: &[String],
     priority: i64,
-    resource: f64,
+    resource: R,
     group: &String,
     params: &[String],
 ) -> Vec<String> {
@@ -197,9 +467,9 @@ fn construct_command(
 }
 
 #[tracing::instrument(name = "Setting priorities", skip(config))]
-fn set_priorities(
+fn set_priorities<R: std::fmt::Display + std::fmt::Debug>(
     priorities: &HashMap<PriorityName, PriorityValue>,
-    resources: &HashMap<ResourceName, ResourceValue>,
+    resources: &HashMap<ResourceName, R>,
     config: &Settings,
 ) -> Result<(), Error> {
     for command in config.commands.iter() {
@@ -208,13 +478,18 @@ fn set_priorities(
             // Only set priority if group actually exists.
             if let Some(prio) = priorities.get(group) {
                 let resource = resources.get(group).unwrap();
-                let command = construct_command(&command.clone(), *prio, *resource, group, params);
+                let command = construct_command(&command.clone(), *prio, resource, group, params);
 
                 let mut cmd = Command::new(&command[0]);
                 cmd.args(&command[1..]);
 
                 debug!(?cmd, "Constructed command");
 
+                if config.dry_run {
+                    info!(?cmd, "Dry run, not executing command");
+                    continue;
+                }
+
                 let status = cmd
                     .status()
                     .inspect_err(|_x| error!("Executing command failed!"))?;
@@ -230,6 +505,41 @@ fn set_priorities(
     Ok(())
 }
 
+/// Retrieves the records from Auditor, computes the priorities and sets them.
+///
+/// Returns the resources and priorities of all groups and the number of ignored records per
+/// reason.
+#[tracing::instrument(name = "Updating priorities", skip_all)]
+async fn update_priorities(
+    client: &AuditorClient,
+    config: &Settings,
+) -> Result<PriorityUpdate, Error> {
+    let query = match config.duration {
+        Some(duration) => QueryBuilder::new()
+            .with_start_time(Operator::default().gte((Utc::now() - duration).into())),
+        None => QueryBuilder::new(),
+    };
+
+    let (resources, dropped) = if config.stream_records {
+        let records = client.get_ndjson_stream(query.build()?).await?;
+        extract_stream(records, config).await?
+    } else {
+        extract(query.get(client.clone()).await?, config)?
+    };
+    dropped.log_summary();
+    let values = resources
+        .iter()
+        .map(|(group, resource)| (group.clone(), resource.as_f64()))
+        .collect();
+
+    let priorities = compute_priorities(&values, config);
+
+    // The commands get the exact resources
+    let _ = set_priorities(&priorities, &resources, config);
+
+    Ok((values, priorities, dropped.counts_by_label()))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let config = configuration::get_configuration()?;
@@ -309,48 +619,52 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let main_task = tokio::spawn(async move {
         let configuration = config.clone();
+        let mut consecutive_failures = 0;
 
         loop {
-            tokio::select! {
-                _ = interval.tick() => {
-
-                let records = match config.duration {
-                    Some(duration) =>
-                        QueryBuilder::new()
-                        .with_start_time(Operator::default().gte((Utc::now() - duration).into()))
-                        .get(client.clone())
-                        .await
-                        .unwrap(),
-                    None => client.get().await.unwrap(),
-                };
-
-                let resources = extract(records, &configuration);
-
-                let priorities = compute_priorities(&resources, &configuration);
-
-                let _ = set_priorities(&priorities, &resources, &configuration);
-
-
-                     if enable_prometheus{
-                         cloned_request_metrics
-                             .update_prometheus_metrics(
-                                 &resources,
-                                 &priorities,
-                                 &prometheus_metrics,
-                             )
-                             .await
-                                 .unwrap();
-                    }
+            interval.tick().await;
 
+            match update_priorities(&client, &configuration).await {
+                Ok((resources, priorities, dropped)) => {
+                    consecutive_failures = 0;
+
+                    if enable_prometheus {
+                        cloned_request_metrics
+                            .update_prometheus_metrics(
+                                &resources,
+                                &priorities,
+                                &dropped,
+                                &prometheus_metrics,
+                            )
+                            .await
+                            .unwrap();
+                    }
                 }
+                Err(e) => {
+                    consecutive_failures += 1;
+                    error!(
+                        error = %e,
+                        consecutive_failures,
+                        "Updating priorities failed, retrying at next interval"
+                    );
 
+                    if configuration
+                        .max_consecutive_failures
+                        .is_some_and(|max| consecutive_failures >= max)
+                    {
+                        return Err::<(), Error>(e);
+                    }
+                }
             }
         }
     });
 
     tokio::select! {
-        _ = main_task => {
-            tracing::info!("starting main task");
+        result = main_task => {
+            if let Ok(Err(e)) = result {
+                error!("Giving up after too many consecutive failures");
+                return Err(e.into());
+            }
         }
         _ = tokio::signal::ctrl_c() => {
                     tracing::info!("CTRL-C received, shutting down priority plugin");
@@ -366,7 +680,634 @@ mod tests {
     use super::*;
     use crate::configuration::TLSConfig;
     use crate::configuration::{AuditorSettings, PrometheusSettings};
+    use auditor::domain::Component;
     use tracing_subscriber::filter::LevelFilter;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_extract_score_aggregation() {
+        let mut meta = auditor::domain::Meta::new();
+        meta.insert("group_id".to_string(), vec!["group1".to_string()]);
+        // Records sent directly to the REST API may still contain duplicate score names.
+        let component = auditor::domain::Component::new("NumCPUs", 2)
+            .unwrap()
+            .add_score(auditor::domain::Score::new("HEPSPEC", 2.0).unwrap())
+            .add_score(auditor::domain::Score::new("other", 100.0).unwrap())
+            .add_score(auditor::domain::Score::new("HEPSPEC", 4.0).unwrap());
+        let record = Record {
+            record_id: "record1".to_string(),
+            meta: Some(meta),
+            components: Some(vec![component]),
+            start_time: None,
+            stop_time: None,
+            runtime: Some(10),
+            ingested_at: None,
+            updated_at: None,
+        };
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::from([("NumCPUs".to_string(), "HEPSPEC".to_string())]),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([("group1".to_string(), vec![])]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        for (aggregation, expected) in [
+            (ScoreAggregation::First, 40.0),
+            (ScoreAggregation::Max, 80.0),
+            (ScoreAggregation::Min, 40.0),
+            (ScoreAggregation::Mean, 60.0),
+        ] {
+            config.score_aggregation = aggregation;
+
+            let (resources, _) = extract(vec![record.clone()], &config).unwrap();
+
+            assert_eq!(
+                *resources.get("group1").unwrap(),
+                expected,
+                "Wrong resources for {aggregation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_preserves_large_runtimes() {
+        let record = |id: &str, runtime: i64, components: Option<Vec<Component>>| {
+            let mut meta = auditor::domain::Meta::new();
+            meta.insert("group_id".to_string(), vec!["group1".to_string()]);
+            Record {
+                record_id: id.to_string(),
+                meta: Some(meta),
+                components,
+                start_time: None,
+                stop_time: None,
+                runtime: Some(runtime),
+                ingested_at: None,
+                updated_at: None,
+            }
+        };
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([("group1".to_string(), vec![])]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        // Only runtime, the sum exceeds i64::MAX
+        let (resources, _) = extract(
+            vec![
+                record("record1", i64::MAX - 1, None),
+                record("record2", 3, None),
+            ],
+            &config,
+        )
+        .unwrap();
+        let resource = resources.get("group1").unwrap();
+        assert_eq!(resource.to_string(), "9223372036854775809");
+        assert_eq!(
+            construct_command(
+                &["{resource}".to_string()],
+                1,
+                resource,
+                &"group1".to_string(),
+                &[]
+            ),
+            vec!["9223372036854775809"]
+        );
+
+        // Component without score
+        config.components = HashMap::from([("NumCPUs".to_string(), "HEPSPEC".to_string())]);
+        let (resources, _) = extract(
+            vec![record(
+                "record1",
+                i64::MAX - 1,
+                Some(vec![Component::new("NumCPUs", 2).unwrap()]),
+            )],
+            &config,
+        )
+        .unwrap();
+        assert_eq!(
+            resources.get("group1").unwrap().to_string(),
+            (i128::from(i64::MAX - 1) * 2).to_string()
+        );
+    }
+
+    #[test]
+    fn test_extract_counts_dropped_records() {
+        let record = |id: &str,
+                      group: Option<&str>,
+                      runtime: Option<i64>,
+                      components: Option<Vec<Component>>| {
+            let mut meta = auditor::domain::Meta::new();
+            if let Some(group) = group {
+                meta.insert("group_id".to_string(), vec![group.to_string()]);
+            }
+            Record {
+                record_id: id.to_string(),
+                meta: Some(meta),
+                components,
+                start_time: None,
+                stop_time: None,
+                runtime,
+                ingested_at: None,
+                updated_at: None,
+            }
+        };
+        let cpus = |amount| Some(vec![Component::new("NumCPUs", amount).unwrap()]);
+        let config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::from([("NumCPUs".to_string(), "HEPSPEC".to_string())]),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([("group1".to_string(), vec![])]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: true,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        let (resources, dropped) = extract(
+            vec![
+                record("valid", Some("group1"), Some(10), cpus(2)),
+                record("no-runtime-1", Some("group1"), None, cpus(2)),
+                record("no-runtime-2", Some("group1"), None, cpus(2)),
+                record("no-components", Some("group1"), Some(10), None),
+                record(
+                    "other-component",
+                    Some("group1"),
+                    Some(10),
+                    Some(vec![Component::new("Memory", 2).unwrap()]),
+                ),
+                // Exceeds i128 only with several components
+                record(
+                    "too-large",
+                    Some("group1"),
+                    Some(i64::MAX),
+                    Some(vec![Component::new("NumCPUs", i64::MAX).unwrap(); 2]),
+                ),
+                record("no-group", None, Some(10), cpus(2)),
+                record("other-group", Some("group2"), Some(10), cpus(2)),
+            ],
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(*resources.get("group1").unwrap(), 20.0);
+        assert_eq!(dropped.count(DropReason::MissingRuntime), 2);
+        assert_eq!(dropped.count(DropReason::MissingComponents), 1);
+        assert_eq!(dropped.count(DropReason::MissingConfiguredComponent), 1);
+        assert_eq!(dropped.count(DropReason::ResourcesOutOfRange), 1);
+        assert_eq!(dropped.count(DropReason::MissingGroupId), 1);
+        assert_eq!(dropped.count(DropReason::UnconfiguredGroup), 1);
+        assert_eq!(
+            dropped.record_ids[&DropReason::MissingRuntime],
+            vec!["no-runtime-1", "no-runtime-2"]
+        );
+
+        let counts = dropped.counts_by_label();
+        assert_eq!(counts.len(), DropReason::ALL.len());
+        assert_eq!(counts["missing_runtime"], 2);
+    }
+
+    #[test]
+    fn test_dropped_record_ids_are_only_kept_if_enabled() {
+        let mut dropped = DroppedRecords::new(false);
+        dropped.add(DropReason::MissingRuntime, "record1");
+
+        assert_eq!(dropped.count(DropReason::MissingRuntime), 1);
+        assert!(dropped.record_ids.is_empty());
+    }
+
+    #[test]
+    fn test_extract_handles_missing_group_id() {
+        let record = |id: &str, group: Option<&str>| {
+            let mut meta = auditor::domain::Meta::new();
+            if let Some(group) = group {
+                meta.insert("group_id".to_string(), vec![group.to_string()]);
+            }
+            Record {
+                record_id: id.to_string(),
+                meta: Some(meta),
+                components: None,
+                start_time: None,
+                stop_time: None,
+                runtime: Some(10),
+                ingested_at: None,
+                updated_at: None,
+            }
+        };
+        let records = || vec![record("valid", Some("group1")), record("no-group", None)];
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([
+                ("group1".to_string(), vec![]),
+                ("default".to_string(), vec![]),
+            ]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Ignore,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        // Ignore and Warn only differ in logging
+        for policy in [MissingGroupPolicy::Ignore, MissingGroupPolicy::Warn] {
+            config.on_missing_group = policy;
+            let (resources, dropped) = extract(records(), &config).unwrap();
+            assert_eq!(*resources.get("group1").unwrap(), 10.0);
+            assert_eq!(*resources.get("default").unwrap(), 0.0);
+            assert_eq!(dropped.count(DropReason::MissingGroupId), 1);
+        }
+
+        config.on_missing_group = MissingGroupPolicy::Error;
+        let error = extract(records(), &config).unwrap_err();
+        assert!(error.to_string().contains("no-group"));
+
+        config.on_missing_group = MissingGroupPolicy::AssignDefault("default".to_string());
+        let (resources, dropped) = extract(records(), &config).unwrap();
+        assert_eq!(*resources.get("group1").unwrap(), 10.0);
+        assert_eq!(*resources.get("default").unwrap(), 10.0);
+        assert_eq!(dropped.count(DropReason::MissingGroupId), 0);
+
+        // The default group has to be configured in `group_mapping` as well
+        config.on_missing_group = MissingGroupPolicy::AssignDefault("unknown".to_string());
+        let (resources, dropped) = extract(records(), &config).unwrap();
+        assert!(!resources.contains_key("unknown"));
+        assert_eq!(dropped.count(DropReason::UnconfiguredGroup), 1);
+    }
+
+    #[test]
+    fn test_extract_reads_group_from_configured_meta_key() {
+        let mut meta = auditor::domain::Meta::new();
+        meta.insert("identity.group".to_string(), vec!["group1".to_string()]);
+        meta.insert("group_id".to_string(), vec!["group2".to_string()]);
+        let record = Record {
+            record_id: "record1".to_string(),
+            meta: Some(meta),
+            components: None,
+            start_time: None,
+            stop_time: None,
+            runtime: Some(10),
+            ingested_at: None,
+            updated_at: None,
+        };
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([
+                ("group1".to_string(), vec![]),
+                ("group2".to_string(), vec![]),
+            ]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "identity.group".to_string(),
+            stream_records: true,
+        };
+
+        let (resources, _) = extract(vec![record.clone()], &config).unwrap();
+        assert_eq!(*resources.get("group1").unwrap(), 10.0);
+        assert_eq!(*resources.get("group2").unwrap(), 0.0);
+
+        config.group_meta_key = "identity.user".to_string();
+        let (resources, dropped) = extract(vec![record], &config).unwrap();
+        assert_eq!(*resources.get("group1").unwrap(), 0.0);
+        assert_eq!(dropped.count(DropReason::MissingGroupId), 1);
+    }
+
+    // Counts the bytes allocated by the current thread, such that the memory used while
+    // extracting the resources can be measured without interference from other tests.
+    mod allocations {
+        use std::alloc::{GlobalAlloc, Layout, System};
+        use std::cell::Cell;
+
+        thread_local! {
+            static CURRENT: Cell<isize> = const { Cell::new(0) };
+            static PEAK: Cell<isize> = const { Cell::new(0) };
+        }
+
+        struct CountingAllocator;
+
+        #[global_allocator]
+        static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+        unsafe impl GlobalAlloc for CountingAllocator {
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                let ptr = System.alloc(layout);
+                if !ptr.is_null() {
+                    track(layout.size() as isize);
+                }
+                ptr
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                System.dealloc(ptr, layout);
+                track(-(layout.size() as isize));
+            }
+        }
+
+        fn track(delta: isize) {
+            let _ = CURRENT.try_with(|current| {
+                current.set(current.get() + delta);
+                let _ = PEAK.try_with(|peak| peak.set(peak.get().max(current.get())));
+            });
+        }
+
+        /// Resets the peak to the current allocation and returns it.
+        pub fn reset_peak() -> isize {
+            let current = CURRENT.with(Cell::get);
+            PEAK.with(|peak| peak.set(current));
+            current
+        }
+
+        pub fn peak() -> isize {
+            PEAK.with(Cell::get)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_stream_keeps_memory_bounded() {
+        const NUM_RECORDS: i64 = 100_000;
+        let config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::from([("NumCPUs".to_string(), "HEPSPEC".to_string())]),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([("group1".to_string(), vec![])]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+        // The records are only created when they are polled
+        let records = futures_util::stream::iter(0..NUM_RECORDS).map(|i| {
+            let mut meta = auditor::domain::Meta::new();
+            meta.insert("group_id".to_string(), vec!["group1".to_string()]);
+            Ok(Record {
+                record_id: format!("record-{i}"),
+                meta: Some(meta),
+                components: Some(vec![Component::new("NumCPUs", 2).unwrap()]),
+                start_time: None,
+                stop_time: None,
+                runtime: Some(10),
+                ingested_at: None,
+                updated_at: None,
+            })
+        });
+
+        let baseline = allocations::reset_peak();
+        let (resources, _) = extract_stream(records, &config).await.unwrap();
+        let peak = allocations::peak() - baseline;
+
+        assert_eq!(
+            *resources.get("group1").unwrap(),
+            (NUM_RECORDS * 10 * 2) as f64
+        );
+        // Keeping all records in memory would take far more than their size on the stack
+        let all_records = NUM_RECORDS as isize * std::mem::size_of::<Record>() as isize;
+        assert!(
+            peak < all_records / 100,
+            "Peak allocation of {peak} bytes while extracting"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_priorities() {
+        let mock_server = MockServer::start().await;
+        let client = AuditorClientBuilder::new()
+            .connection_string(&mock_server.uri())
+            .build()
+            .unwrap();
+
+        let records: Vec<Record> = [("group1", 10), ("group2", 30)]
+            .into_iter()
+            .map(|(group, runtime)| {
+                let mut meta = auditor::domain::Meta::new();
+                meta.insert("group_id".to_string(), vec![group.to_string()]);
+                Record {
+                    record_id: format!("record-{group}"),
+                    meta: Some(meta),
+                    components: None,
+                    start_time: None,
+                    stop_time: None,
+                    runtime: Some(runtime),
+                    ingested_at: None,
+                    updated_at: None,
+                }
+            })
+            .collect();
+
+        let config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([
+                ("group1".to_string(), vec![]),
+                ("group2".to_string(), vec![]),
+            ]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: true,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        let ndjson: String = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect();
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .and(header("accept", "application/x-ndjson"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ndjson))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        let (resources, priorities, _) = update_priorities(&client, &config).await.unwrap();
+
+        assert_eq!(*resources.get("group1").unwrap(), 10.0);
+        assert_eq!(*resources.get("group2").unwrap(), 30.0);
+        assert_eq!(*priorities.get("group1").unwrap(), 1i64);
+        assert_eq!(*priorities.get("group2").unwrap(), 10i64);
+
+        // Without streaming, all records are fetched at once
+        let mut config = config;
+        config.stream_records = false;
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(&records))
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+
+        let (resources, _, _) = update_priorities(&client, &config).await.unwrap();
+
+        assert_eq!(*resources.get("group1").unwrap(), 10.0);
+        assert_eq!(*resources.get("group2").unwrap(), 30.0);
+
+        // A failing Auditor server must not panic
+        Mock::given(method("GET"))
+            .and(path("/records"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        assert!(update_priorities(&client, &config).await.is_err());
+    }
 
     #[test]
     fn test_compute_priorities_fullspread() {
@@ -385,6 +1326,8 @@ mod tests {
             min_priority: 1,
             max_priority: 10,
             group_mapping: HashMap::new(),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
             commands: vec!["whatever".to_string()],
             duration: None,
             computation_mode: ComputationMode::FullSpread,
@@ -405,6 +1348,12 @@ mod tests {
                 client_cert_path: None,
                 client_key_path: None,
             },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
         };
 
         let prios = compute_priorities(&resources, &config);
@@ -431,6 +1380,8 @@ mod tests {
             min_priority: 1,
             max_priority: 10,
             group_mapping: HashMap::new(),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
             commands: vec!["whatever".to_string()],
             duration: None,
             computation_mode: ComputationMode::ScaledBySum,
@@ -451,6 +1402,12 @@ mod tests {
                 client_cert_path: None,
                 client_key_path: None,
             },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
         };
 
         let prios = compute_priorities(&resources, &config);
@@ -460,6 +1417,176 @@ mod tests {
         assert_eq!(*prios.get("blah3").unwrap(), 5i64);
     }
 
+    #[test]
+    fn test_compute_priorities_group_bounds() {
+        let resources = HashMap::from([
+            ("blah1".to_string(), 2.0),
+            ("blah3".to_string(), 4.0),
+            ("blah2".to_string(), 3.0),
+        ]);
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::new(),
+            group_priority_bounds: HashMap::from([
+                ("blah2".to_string(), (10, 19)),
+                ("blah3".to_string(), (20, 40)),
+            ]),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        let prios = compute_priorities(&resources, &config);
+
+        assert_eq!(*prios.get("blah1").unwrap(), 1i64);
+        assert_eq!(*prios.get("blah2").unwrap(), 15i64);
+        assert_eq!(*prios.get("blah3").unwrap(), 40i64);
+
+        config.computation_mode = ComputationMode::ScaledBySum;
+        let prios = compute_priorities(&resources, &config);
+
+        assert_eq!(*prios.get("blah1").unwrap(), 3i64);
+        assert_eq!(*prios.get("blah2").unwrap(), 13i64);
+        assert_eq!(*prios.get("blah3").unwrap(), 29i64);
+    }
+
+    #[test]
+    fn test_compute_priorities_logscaled() {
+        let resources = HashMap::from([
+            ("blah1".to_string(), 0.0),
+            ("blah3".to_string(), 99.0),
+            ("blah2".to_string(), 9.0),
+            ("blah4".to_string(), 999.0),
+        ]);
+        let config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 0,
+            max_priority: 12,
+            group_mapping: HashMap::new(),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec!["whatever".to_string()],
+            duration: None,
+            computation_mode: ComputationMode::LogScaled,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: Some(PrometheusSettings {
+                enable: true,
+                addr: "whatever".to_string(),
+                port: 1234,
+                metrics: vec![
+                    PrometheusMetricsOptions::ResourceUsage,
+                    PrometheusMetricsOptions::Priority,
+                ],
+            }),
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: false,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        let prios = compute_priorities(&resources, &config);
+
+        assert_eq!(*prios.get("blah1").unwrap(), 0i64);
+        assert_eq!(*prios.get("blah2").unwrap(), 4i64);
+        assert_eq!(*prios.get("blah3").unwrap(), 8i64);
+        assert_eq!(*prios.get("blah4").unwrap(), 12i64);
+
+        // All groups provided the same resources
+        for value in [0.0, 5.0] {
+            let resources =
+                HashMap::from([("blah1".to_string(), value), ("blah2".to_string(), value)]);
+
+            let prios = compute_priorities(&resources, &config);
+
+            assert_eq!(*prios.get("blah1").unwrap(), 12i64);
+            assert_eq!(*prios.get("blah2").unwrap(), 12i64);
+        }
+    }
+
+    #[test]
+    fn test_set_priorities_dry_run() {
+        let marker = std::env::temp_dir().join(format!("priority-plugin-{}", Uuid::new_v4()));
+        let priorities = HashMap::from([("blah1".to_string(), 1i64)]);
+        let resources = HashMap::from([("blah1".to_string(), 2.0)]);
+        let mut config = Settings {
+            auditor: AuditorSettings {
+                addr: "whatever".to_string(),
+                port: 1234,
+            },
+            timeout: 30,
+            components: HashMap::new(),
+            min_priority: 1,
+            max_priority: 10,
+            group_mapping: HashMap::from([("blah1".to_string(), vec![])]),
+            group_priority_bounds: HashMap::new(),
+            score_aggregation: ScoreAggregation::First,
+            commands: vec![format!("/usr/bin/touch {}", marker.display())],
+            duration: None,
+            computation_mode: ComputationMode::FullSpread,
+            frequency: chrono::Duration::try_seconds(3600).expect("This should never fail"),
+            log_level: LevelFilter::INFO,
+            prometheus: None,
+            tls_config: TLSConfig {
+                use_tls: false,
+                ca_cert_path: None,
+                client_cert_path: None,
+                client_key_path: None,
+            },
+            dry_run: true,
+            max_consecutive_failures: None,
+            log_dropped_record_ids: false,
+            on_missing_group: MissingGroupPolicy::Warn,
+            group_meta_key: "group_id".to_string(),
+            stream_records: true,
+        };
+
+        set_priorities(&priorities, &resources, &config).unwrap();
+        assert!(!marker.exists());
+
+        config.dry_run = false;
+        set_priorities(&priorities, &resources, &config).unwrap();
+        assert!(marker.exists());
+
+        std::fs::remove_file(marker).unwrap();
+    }
+
     #[test]
     fn test_construct_command() {
         let cmd = vec![
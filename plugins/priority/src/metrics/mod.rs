@@ -10,6 +10,7 @@ pub struct PrometheusExporterConfig {
     pub prom_registry: Registry,
     pub resource_metric: IntGaugeVec,
     pub priority_metric: IntGaugeVec,
+    pub dropped_records_metric: IntGaugeVec,
 }
 
 impl PrometheusExporterConfig {
@@ -29,8 +30,17 @@ impl PrometheusExporterConfig {
         let priority_metric =
             IntGaugeVec::new(Opts::new("priority", "Priority metrics"), &["group"])?;
 
+        let dropped_records_metric = IntGaugeVec::new(
+            Opts::new(
+                "dropped_records",
+                "Number of records ignored in the last update",
+            ),
+            &["reason"],
+        )?;
+
         prom_registry.register(Box::new(resource_metric.clone()))?;
         prom_registry.register(Box::new(priority_metric.clone()))?;
+        prom_registry.register(Box::new(dropped_records_metric.clone()))?;
 
         let provider = SdkMeterProvider::builder()
             .with_reader(metrics_exporter)
@@ -41,6 +51,7 @@ impl PrometheusExporterConfig {
             prom_registry,
             resource_metric,
             priority_metric,
+            dropped_records_metric,
         })
     }
 
@@ -48,6 +59,7 @@ impl PrometheusExporterConfig {
         &self,
         resources: &HashMap<String, f64>,
         priorities: &HashMap<String, i64>,
+        dropped_records: &HashMap<String, u64>,
         metrics: &[PrometheusMetricsOptions],
     ) -> Result<(), anyhow::Error> {
         for metric in metrics.iter() {
@@ -66,6 +78,13 @@ impl PrometheusExporterConfig {
                             .set(*value);
                     }
                 }
+                PrometheusMetricsOptions::DroppedRecords => {
+                    for (reason, count) in dropped_records {
+                        self.dropped_records_metric
+                            .with_label_values(&[reason])
+                            .set(*count as i64);
+                    }
+                }
             };
         }
         Ok(())
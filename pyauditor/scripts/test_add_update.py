@@ -30,6 +30,7 @@ async def main():
         2021, 12, 6, 16, 29, 43, 79043, tzinfo=local_tz
     ).astimezone(datetime.timezone.utc)
     record = Record(record_id, start)
+    assert record.updated_at is None
 
     await client.add(record)
 
@@ -39,6 +40,8 @@ async def main():
     record = records[0]
     assert record.record_id == record_id
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
+    assert record.updated_at is not None
+    updated_at = record.updated_at
 
     print("Updating record: Adding stop time")
     stop = datetime.datetime.now(tz=local_tz).astimezone(datetime.timezone.utc)
@@ -53,6 +56,7 @@ async def main():
     assert record.record_id == record_id
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
     assert record.stop_time.replace(tzinfo=datetime.timezone.utc) == stop
+    assert record.updated_at > updated_at
 
     print("Script test_add_update.py finished.")
 
@@ -32,6 +32,7 @@ def main():
     ).astimezone(datetime.timezone.utc)
 
     record = Record(record_id, start)
+    assert record.updated_at is None
 
     client.add(record)
 
@@ -41,6 +42,8 @@ def main():
     record = records[0]
     assert record.record_id == record_id
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
+    assert record.updated_at is not None
+    updated_at = record.updated_at
 
     print("Updating record: Adding stop time")
     stop = datetime.datetime.now(tz=local_tz).astimezone(datetime.timezone.utc)
@@ -55,6 +58,7 @@ def main():
     assert record.record_id == record_id
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
     assert record.stop_time.replace(tzinfo=datetime.timezone.utc) == stop
+    assert record.updated_at > updated_at
 
     print("Script test_add_update.py finished.")
 
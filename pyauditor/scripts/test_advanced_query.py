@@ -158,6 +158,10 @@ async def main():
     records = await client.advanced_query(records)
     assert len(records) == 10
 
+    count = await QueryBuilder().with_meta_query(meta_query).count(client)
+    assert count == 10
+    assert isinstance(count, int)
+
     meta_operator = MetaOperator().contains("placeholder")
     meta_query = MetaQuery().meta_operator("group_id", meta_operator)
     records = QueryBuilder().with_meta_query(meta_query).build()
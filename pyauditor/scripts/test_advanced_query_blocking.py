@@ -160,6 +160,10 @@ def main():
     records = client.advanced_query(records)
     assert len(records) == 10
 
+    count = QueryBuilder().with_meta_query(meta_query).count(client)
+    assert count == 10
+    assert isinstance(count, int)
+
     meta_operator = MetaOperator().contains("placeholder")
     meta_query = MetaQuery().meta_operator("group_id", meta_operator)
     records = QueryBuilder().with_meta_query(meta_query).build()
@@ -4,7 +4,7 @@ import datetime
 
 from tzlocal import get_localzone
 
-from pyauditor import AuditorClientBuilder, Record
+from pyauditor import AuditorClientBuilder, Record, RecordExistsError
 
 
 def main():
@@ -44,6 +44,13 @@ def main():
     assert record.record_id == record_id
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
 
+    print("Inserting the same record again should fail")
+    try:
+        client.bulk_insert([Record(record_id, start)])
+        raise AssertionError("bulk_insert of an existing record did not fail")
+    except RecordExistsError:
+        pass
+
     print("Updating record: Adding stop time")
     stop = datetime.datetime.now(tz=local_tz).astimezone(datetime.timezone.utc)
 
@@ -58,6 +65,13 @@ def main():
     assert record.start_time.replace(tzinfo=datetime.timezone.utc) == start
     assert record.stop_time.replace(tzinfo=datetime.timezone.utc) == stop
 
+    print("Adding a list of records to Auditor")
+    client.bulk_insert([Record(f"record-{i}", start) for i in range(2, 102)])
+
+    print("Asserting that all records are in auditor db")
+    records = client.get()
+    assert len(records) == 101
+
     print("Script test_add_update.py finished.")
 
 
@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use crate::domain::Record;
+use crate::error::to_py_err;
 use chrono::{DateTime, Utc};
 use pyo3::prelude::*;
 use pyo3::types::PyDateTime;
@@ -69,7 +70,8 @@ impl AuditorClientBlocking {
         let since: DateTime<Utc> = timestamp.extract()?;
         let query_string = auditor_client::QueryBuilder::new()
             .with_start_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
         Ok(self_
             .inner
             .advanced_query(query_string.to_string())
@@ -109,7 +111,8 @@ impl AuditorClientBlocking {
         let since: DateTime<Utc> = timestamp.extract()?;
         let query_string = auditor_client::QueryBuilder::new()
             .with_stop_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
         Ok(self_
             .inner
             .advanced_query(query_string.to_string())
@@ -147,14 +150,18 @@ impl AuditorClientBlocking {
 
     /// add(record: Record)
     /// Push a record to the Auditor instance
+    ///
+    /// Raises ``RecordExistsError`` if the record already exists.
     fn add(&self, record: Record) -> PyResult<()> {
         self.inner
             .add(&auditor::domain::RecordAdd::try_from(record.inner)?)
-            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e}")))
+            .map_err(to_py_err)
     }
 
-    /// add(record: Record)
+    /// bulk_insert(records: list[Record])
     /// Push a list of records to the Auditor instance
+    ///
+    /// Raises ``RecordExistsError`` if one of the records already exists.
     fn bulk_insert(&self, records: Vec<Record>) -> PyResult<()> {
         let bulk_insert_records: Result<Vec<auditor::domain::RecordAdd>, anyhow::Error> = records
             .into_iter()
@@ -163,7 +170,7 @@ impl AuditorClientBlocking {
 
         self.inner
             .bulk_insert(&bulk_insert_records?)
-            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e}")))
+            .map_err(to_py_err)
     }
 
     /// update(record: Record)
@@ -5,7 +5,9 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
+use crate::blocking_client::AuditorClientBlocking;
 use crate::domain::Record;
+use crate::error::to_py_err;
 use anyhow::Error;
 use chrono::{DateTime, Utc};
 use pyo3::prelude::*;
@@ -321,9 +323,7 @@ impl ComponentQuery {
     #[new]
     fn new() -> Self {
         ComponentQuery {
-            inner: auditor_client::ComponentQuery {
-                component_query: HashMap::new(),
-            },
+            inner: auditor_client::ComponentQuery::new(),
         }
     }
 
@@ -368,16 +368,13 @@ impl SortBy {
     #[new]
     fn new() -> Self {
         Self {
-            inner: auditor_client::SortBy {
-                asc: None,
-                desc: None,
-            },
+            inner: auditor_client::SortBy::new(),
         }
     }
 
     /// Specify the column by which the query records must be sorted in ascending order
     ///
-    /// :param column: Name of the column by which the records must be sorted. One of four values (`start_time`, `stop_time`, `runtime`, `record_id`).
+    /// :param column: Name of the column by which the records must be sorted. One of five values (`start_time`, `stop_time`, `runtime`, `record_id`, `ingested_at`).
     /// :type column: string
     ///
     /// **Example**
@@ -392,7 +389,7 @@ impl SortBy {
 
     /// Specify the column by which the query records must be sorted in descending order
     ///
-    /// :param column: Name of the column by which the records must be sorted. One of three values (`start_time`, `stop_time`, `runtime`, `record_id`).
+    /// :param column: Name of the column by which the records must be sorted. One of five values (`start_time`, `stop_time`, `runtime`, `record_id`, `ingested_at`).
     /// :type column: string
     ///
     /// **Example**
@@ -415,8 +412,12 @@ impl QueryBuilder {
             inner: auditor_client::QueryBuilder {
                 query_params: auditor_client::QueryParameters {
                     record_id: None,
+                    record_id_prefix: None,
                     start_time: None,
                     stop_time: None,
+                    ingested_at: None,
+                    updated_since: None,
+                    stopped: None,
                     runtime: None,
                     meta: None,
                     component: None,
@@ -494,6 +495,28 @@ impl QueryBuilder {
         self_
     }
 
+    /// Sets the ingestion time in the query parameters
+    ///
+    /// :param operator: Operator object containing `DateTime<Utc>`
+    /// :type operator: Operator object
+    ///
+    ///
+    /// **Example**
+    ///
+    /// .. code-block:: python
+    ///     
+    ///     ingested_at = datetime.datetime(
+    ///      2022, 8, 8, 11, 30, 0, 0, tzinfo=datetime.timezone.utc
+    ///     )
+    ///     
+    ///     value = Value.set_datetime(ingested_at)
+    ///     operator = Operator().gt(value)
+    ///     query_string = QueryBuilder().with_ingested_at(operator).build()
+    fn with_ingested_at(mut self_: PyRefMut<Self>, operator: Operator) -> PyRefMut<Self> {
+        self_.inner.query_params.ingested_at = Some(operator.inner);
+        self_
+    }
+
     /// Sets the runtime in the query parameters
     ///
     /// :param operator: Operator object containing runtime value
@@ -587,9 +610,48 @@ impl QueryBuilder {
     }
 
     /// Builds the query string for the given query parameters
-    fn build(self_: PyRef<Self>, py: Python) -> Py<PyAny> {
-        let query_string: String = self_.inner.clone().build();
-        query_string.into_py(py)
+    fn build(self_: PyRef<Self>, py: Python) -> PyResult<Py<PyAny>> {
+        let query_string: String = self_.inner.build().map_err(to_py_err)?;
+        Ok(query_string.into_py(py))
+    }
+
+    /// count(client: AuditorClient | AuditorClientBlocking)
+    /// Counts the records matching the query parameters without retrieving them.
+    ///
+    /// ``sort_by`` and ``limit`` are ignored when counting. With an ``AuditorClient``, the count
+    /// has to be awaited.
+    ///
+    /// :param client: Client used to send the query
+    /// :type client: AuditorClient or AuditorClientBlocking
+    ///
+    /// **Example**
+    ///
+    /// .. code-block:: python
+    ///
+    ///     meta_operator = MetaOperator().contains("group_1")
+    ///     meta_query = MetaQuery().meta_operator("group_id", meta_operator)
+    ///     count = await QueryBuilder().with_meta_query(meta_query).count(client)
+    ///     count = QueryBuilder().with_meta_query(meta_query).count(blocking_client)
+    fn count<'a>(
+        self_: PyRef<'a, Self>,
+        client: &Bound<'a, PyAny>,
+        py: Python<'a>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let query_string = self_.inner.build().map_err(to_py_err)?;
+        if let Ok(client) = client.extract::<AuditorClientBlocking>() {
+            let count = client
+                .inner
+                .count(query_string)
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e}")))?;
+            return Ok(count.into_py(py).into_bound(py));
+        }
+        let inner = client.extract::<AuditorClient>()?.inner;
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            inner
+                .count(query_string)
+                .await
+                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(format!("{e}")))
+        })
     }
 }
 
@@ -664,7 +726,8 @@ impl AuditorClient {
         let inner = self_.inner.clone();
         let query_string = auditor_client::QueryBuilder::new()
             .with_start_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             Ok(inner
@@ -712,7 +775,8 @@ impl AuditorClient {
         let inner = self_.inner.clone();
         let query_string = auditor_client::QueryBuilder::new()
             .with_stop_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             Ok(inner
                 .advanced_query(query_string.to_string())
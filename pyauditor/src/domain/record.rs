@@ -66,6 +66,8 @@ impl Record {
                 start_time: Some(start_time),
                 stop_time: None,
                 runtime: None,
+                ingested_at: None,
+                updated_at: None,
             },
         })
     }
@@ -165,6 +167,28 @@ impl Record {
         self.inner.runtime
     }
 
+    /// Returns the time at which the record was received by Auditor
+    ///
+    /// Returns None for records that have not been retrieved from Auditor.
+    #[getter]
+    fn ingested_at(&self, py: Python) -> Option<Py<PyAny>> {
+        self.inner
+            .ingested_at
+            .as_ref()
+            .map(|ingested_at| ingested_at.naive_utc().into_py(py))
+    }
+
+    /// Returns the time at which the record was last added or updated in Auditor
+    ///
+    /// Returns None for records that have not been retrieved from Auditor.
+    #[getter]
+    fn updated_at(&self, py: Python) -> Option<Py<PyAny>> {
+        self.inner
+            .updated_at
+            .as_ref()
+            .map(|updated_at| updated_at.naive_utc().into_py(py))
+    }
+
     /// Output content of Record as JSON-encoded string
     fn to_json(&self) -> Result<String, Error> {
         Ok(format!("{}", serde_json::to_value(&self.inner)?))
@@ -0,0 +1,26 @@
+// Copyright 2021-2024 AUDITOR developers
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+use auditor_client::ClientError;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::PyErr;
+
+pyo3::create_exception!(
+    pyauditor,
+    RecordExistsError,
+    PyRuntimeError,
+    "Raised when a record with the same ``record_id`` already exists in the Auditor instance."
+);
+
+/// Converts a `ClientError` into the matching Python exception.
+pub(crate) fn to_py_err(error: ClientError) -> PyErr {
+    match error {
+        ClientError::RecordExists => RecordExistsError::new_err(format!("{error}")),
+        ClientError::QuerySerialization(_) => PyValueError::new_err(format!("{error}")),
+        _ => PyRuntimeError::new_err(format!("{error}")),
+    }
+}
@@ -6,6 +6,8 @@
 // copied, modified, or distributed except according to those terms.
 
 #![allow(clippy::borrow_deref_ref)]
+// Both are raised in code generated by pyo3 macros
+#![allow(clippy::useless_conversion, unexpected_cfgs)]
 
 use pyo3::prelude::*;
 
@@ -13,11 +15,12 @@ mod blocking_client;
 mod builder;
 mod client;
 mod domain;
+mod error;
 mod queued_client;
 
 /// pyauditor is a client for interacting with an Auditor instance via Python.
 #[pymodule]
-fn pyauditor(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn pyauditor(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<crate::builder::AuditorClientBuilder>()?;
     m.add_class::<crate::client::AuditorClient>()?;
     m.add_class::<crate::client::Value>()?;
@@ -33,5 +36,9 @@ fn pyauditor(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<crate::domain::Meta>()?;
     m.add_class::<crate::domain::Component>()?;
     m.add_class::<crate::domain::Score>()?;
+    m.add(
+        "RecordExistsError",
+        py.get_type_bound::<crate::error::RecordExistsError>(),
+    )?;
     Ok(())
 }
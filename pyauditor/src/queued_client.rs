@@ -6,6 +6,7 @@
 // copied, modified, or distributed except according to those terms.
 
 use crate::domain::Record;
+use crate::error::to_py_err;
 use chrono::{DateTime, Utc};
 use pyo3::prelude::*;
 use pyo3::types::PyDateTime;
@@ -98,7 +99,8 @@ impl QueuedAuditorClient {
         let inner = self_.inner.clone();
         let query_string = auditor_client::QueryBuilder::new()
             .with_start_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
 
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             Ok(inner
@@ -146,7 +148,8 @@ impl QueuedAuditorClient {
         let inner = self_.inner.clone();
         let query_string = auditor_client::QueryBuilder::new()
             .with_stop_time(auditor_client::Operator::default().gte(since.into()))
-            .build();
+            .build()
+            .map_err(to_py_err)?;
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
             Ok(inner
                 .advanced_query(query_string.to_string())